@@ -0,0 +1,39 @@
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion, Throughput};
+use tweep::StoryPassages;
+
+/// Builds a story of roughly `size` bytes, with passages of several paragraphs
+/// of prose and links, and a stylesheet full of `::` that isn't a header
+fn story(size: usize) -> String {
+    let mut story = String::from(":: StoryTitle\nBenchmark\n\n:: StoryData\n{\"ifid\": \"D674C58C-DEFA-4F70-B7A2-27742230C0FC\"}\n\n");
+    story.push_str(":: Style [stylesheet]\na::before { content: \"::\"; }\np::first-line { color: red; }\n\n");
+    let mut i = 0;
+    while story.len() < size {
+        story.push_str(&format!(":: Passage {} [tag{}]\n", i, i % 10));
+        for _ in 0..4 {
+            story.push_str("The corridor stretches on into the dark, and the air smells of dust and old paper. ");
+            story.push_str(&format!("You could go on to [[Passage {}]] or back to [[Passage {}]].\n\n", i + 1, i / 2));
+        }
+        i += 1;
+    }
+    story
+}
+
+fn parse(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parse");
+    group.sample_size(10);
+    for &megabytes in &[1, 4] {
+        let input = story(megabytes * 1024 * 1024);
+        group.throughput(Throughput::Bytes(input.len() as u64));
+        group.bench_function(format!("{}MB", megabytes), |b| {
+            b.iter_batched(
+                || input.clone(),
+                |input| StoryPassages::from_string(input).take(),
+                BatchSize::LargeInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, parse);
+criterion_main!(benches);
@@ -1,16 +1,31 @@
 use crate::context::Position;
 use crate::context::PositionKind;
-use std::borrow::Borrow;
+use crate::context::SharedStr;
+use std::path::Path;
+use std::path::PathBuf;
 use std::sync::Arc;
 
 /// A context that represents a span of twee code with a beginning, end, and
-/// contents, along with a file name and some helper functions
+/// contents, along with a file path and some helper functions
+///
+/// A `FullContext` is `Send` and `Sync`. Its source is shared between all
+/// contexts created from it, so cloning a context or taking a subcontext is
+/// cheap, and contexts can be freely passed to other threads.
+///
+/// # Examples
+/// ```
+/// # use tweep::{FullContext, Position};
+/// let c = FullContext::from(None, "Hail Eris\nAll hail Discordia".to_string());
+/// let sub = c.subcontext(Position::rel(2, 1)..);
+/// let handle = std::thread::spawn(move || sub.get_contents().len());
+/// assert_eq!(handle.join().unwrap(), 18);
+/// ```
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct FullContext {
-    file_name: Option<String>,
+    file_path: Option<Arc<Path>>,
     start_position: Position,
     end_position: Position,
-    contents: Arc<String>,
+    contents: Arc<str>,
     line_starts: Arc<Vec<usize>>,
 }
 
@@ -18,7 +33,7 @@ mod util {
     use super::Position;
 
     pub(crate) fn line_starts<'a>(s: &'a str) -> impl 'a + Iterator<Item = usize> {
-        std::iter::once(0).chain(s.match_indices('\n').map(|(i, _)| i + 1))
+        std::iter::once(0).chain(memchr::memchr_iter(b'\n', s.as_bytes()).map(|i| i + 1))
     }
 
     pub(crate) fn to_byte_index(p: &Position, line_starts: &[usize], inclusive: bool) -> usize {
@@ -43,14 +58,14 @@ mod util {
 
 impl FullContext {
     pub(crate) fn new_with_line_starts(
-        file_name: Option<String>,
+        file_path: Option<Arc<Path>>,
         start_position: Position,
         end_position: Position,
-        contents: Arc<String>,
+        contents: Arc<str>,
         line_starts: Arc<Vec<usize>>,
     ) -> Self {
         FullContext {
-            file_name,
+            file_path,
             start_position,
             end_position,
             contents,
@@ -66,7 +81,7 @@ impl FullContext {
                 util::end_of_line(
                     line,
                     self.get_line_starts(),
-                    self.contents.as_str().borrow(),
+                    &self.contents,
                 )
                 .column,
             ),
@@ -77,7 +92,7 @@ impl FullContext {
                     util::end_of_line(
                         line,
                         self.get_line_starts(),
-                        self.contents.as_str().borrow(),
+                        &self.contents,
                     )
                     .column,
                 )
@@ -108,22 +123,58 @@ impl FullContext {
     }
 
     /// Creates a new context from the given file name and string
+    ///
+    /// To create a context for a file whose path may not be valid UTF-8, use
+    /// [`from_file_path`](#method.from_file_path)
     pub fn from(file_name: Option<String>, contents: String) -> Self {
+        Self::from_file_path(file_name.map(PathBuf::from), contents)
+    }
+
+    /// Creates a new context from the given file path and string
+    ///
+    /// # Examples
+    /// ```
+    /// # use tweep::FullContext;
+    /// # use std::path::{Path, PathBuf};
+    /// let c = FullContext::from_file_path(Some(PathBuf::from("dir/story.twee")), "::A".to_string());
+    /// assert_eq!(c.get_file_path(), Some(Path::new("dir/story.twee")));
+    /// ```
+    pub fn from_file_path(file_path: Option<PathBuf>, contents: String) -> Self {
         let line_starts = util::line_starts(&contents).collect::<Vec<usize>>();
         let start = Position::abs(1, 1);
         let end = util::end_of_line(line_starts.len(), &line_starts, &contents);
         Self::new_with_line_starts(
-            file_name,
+            file_path.map(Arc::from),
             start,
             end,
-            Arc::new(contents),
+            Arc::from(contents),
             Arc::new(line_starts),
         )
     }
 
-    /// Gets a reference to the optional file name
-    pub fn get_file_name(&self) -> &Option<String> {
-        &self.file_name
+    /// Gets the path of the file this context belongs to, if any
+    pub fn get_file_path(&self) -> Option<&Path> {
+        self.file_path.as_deref()
+    }
+
+    /// Gets the optional file name, which may involve a lossy conversion of
+    /// the file path
+    #[deprecated(note = "use `get_file_path` instead")]
+    pub fn get_file_name(&self) -> Option<String> {
+        self.get_file_path().map(|p| p.to_string_lossy().into_owned())
+    }
+
+    /// Returns an object that implements `Display` for printing the file path
+    /// of this context, if any, which may involve a lossy conversion
+    ///
+    /// # Examples
+    /// ```
+    /// # use tweep::FullContext;
+    /// let c = FullContext::from(Some("story.twee".to_string()), String::new());
+    /// assert_eq!(c.display_file_path().unwrap().to_string(), "story.twee");
+    /// ```
+    pub fn display_file_path(&self) -> Option<std::path::Display<'_>> {
+        self.file_path.as_ref().map(|p| p.display())
     }
 
     /// Gets a reference to the 1-indexed start position of this context
@@ -153,8 +204,132 @@ impl FullContext {
         &self.contents[start..end]
     }
 
+    /// Gets the contents of this context as a `SharedStr`, which refers to
+    /// the shared source text rather than copying it
+    pub(crate) fn get_shared_contents(&self) -> SharedStr {
+        self.shared_slice(0..self.get_contents().len())
+    }
+
+    /// Gets the given byte range of the contents of this context as a
+    /// `SharedStr`, which refers to the shared source text rather than
+    /// copying it
+    pub(crate) fn shared_slice(&self, range: Range<usize>) -> SharedStr {
+        let base = self.get_byte_range().start;
+        SharedStr::new(self.contents.clone(), base + range.start..base + range.end)
+    }
+
+    /// Gets the full source text this context was created from
+    pub(crate) fn get_source(&self) -> &str {
+        &self.contents
+    }
+
+    /// Returns `true` if this context was created from the same source text as
+    /// `other`
+    pub(crate) fn shares_source(&self, other: &FullContext) -> bool {
+        Arc::ptr_eq(&self.contents, &other.contents)
+    }
+
     pub(crate) fn get_line_starts(&self) -> &Vec<usize> {
-        self.line_starts.borrow()
+        &self.line_starts
+    }
+
+    /// Converts a position that may be relative to this context into an
+    /// absolute position
+    fn to_absolute(&self, position: Position) -> Position {
+        match position.kind {
+            PositionKind::Absolute => position,
+            PositionKind::Relative => self
+                .start_position
+                .subposition(position.line, position.column),
+        }
+    }
+
+    /// Returns `true` if `other` refers to the same file as this context and
+    /// its span lies entirely within this context's span
+    ///
+    /// # Examples
+    /// ```
+    /// # use tweep::{FullContext, Position};
+    /// let c = FullContext::from(None, "Hail Eris\nAll hail Discordia".to_string());
+    /// let line = c.subcontext(Position::rel(2, 1)..=Position::rel(2, 18));
+    /// let word = c.subcontext(Position::rel(2, 5)..=Position::rel(2, 8));
+    /// assert!(c.contains(&line));
+    /// assert!(line.contains(&word));
+    /// assert!(!word.contains(&line));
+    /// ```
+    pub fn contains(&self, other: &FullContext) -> bool {
+        self.file_path == other.file_path
+            && self.start_position <= other.start_position
+            && other.end_position <= self.end_position
+    }
+
+    /// Returns `true` if the given position, which may be relative to this
+    /// context, lies within this context's span
+    ///
+    /// # Examples
+    /// ```
+    /// # use tweep::{FullContext, Position};
+    /// let c = FullContext::from(None, "Hail Eris\nAll hail Discordia".to_string());
+    /// let word = c.subcontext(Position::rel(2, 5)..=Position::rel(2, 8));
+    /// assert!(word.contains_position(Position::abs(2, 8)));
+    /// assert!(word.contains_position(Position::rel(1, 1)));
+    /// assert!(!word.contains_position(Position::abs(1, 5)));
+    /// ```
+    pub fn contains_position(&self, position: Position) -> bool {
+        let position = self.to_absolute(position);
+        self.start_position <= position && position <= self.end_position
+    }
+
+    /// Returns `true` if the given range of positions, which may be relative to
+    /// this context, overlaps this context's span
+    ///
+    /// # Examples
+    /// ```
+    /// # use tweep::{FullContext, Position};
+    /// let c = FullContext::from(None, "Hail Eris\nAll hail Discordia".to_string());
+    /// let word = c.subcontext(Position::rel(2, 5)..=Position::rel(2, 8));
+    /// assert!(word.overlaps(Position::abs(1, 1)..=Position::abs(2, 5)));
+    /// assert!(!word.overlaps(Position::abs(2, 9)..));
+    /// ```
+    pub fn overlaps<T>(&self, range: T) -> bool
+    where
+        T: SubContextRange,
+    {
+        let (start, end) = range.into(self).into_inner();
+        start <= self.end_position && self.start_position <= end
+    }
+
+    /// Converts a position, which may be relative to this context, into a byte
+    /// offset within the underlying source
+    ///
+    /// # Examples
+    /// ```
+    /// # use tweep::{FullContext, Position};
+    /// let c = FullContext::from(None, "Hail Eris\nAll hail Discordia".to_string());
+    /// assert_eq!(c.byte_offset(Position::abs(2, 5)), 14);
+    /// let sub = c.subcontext(Position::rel(2, 5)..);
+    /// assert_eq!(sub.byte_offset(Position::rel(1, 2)), 15);
+    /// ```
+    pub fn byte_offset(&self, position: Position) -> usize {
+        util::to_byte_index(&self.to_absolute(position), &self.line_starts, false)
+    }
+
+    /// Converts a byte offset within the underlying source into an absolute
+    /// position
+    ///
+    /// # Examples
+    /// ```
+    /// # use tweep::{FullContext, Position};
+    /// let c = FullContext::from(None, "Hail Eris\nAll hail Discordia".to_string());
+    /// assert_eq!(c.position_at(14), Position::abs(2, 5));
+    /// assert_eq!(c.position_at(0), Position::abs(1, 1));
+    /// ```
+    pub fn position_at(&self, offset: usize) -> Position {
+        let line = match self.line_starts.binary_search(&offset) {
+            Ok(line) => line,
+            Err(line) => line - 1,
+        };
+        Position::abs(line + 1, offset - self.line_starts[line] + 1)
     }
 
     /// Creates a subcontext out of the current context from the inclusive,
@@ -167,6 +342,62 @@ impl FullContext {
         self.inner_subcontext(start, end)
     }
 
+    /// Creates a subcontext out of the current context from a range of bytes
+    /// within the contents of this context. An empty range creates an empty
+    /// context whose end position is the column before its start position, so
+    /// `slice(0..0)` ends at column 0
+    ///
+    /// # Panics
+    /// Panics if the start of the range is after its end, or if the range
+    /// extends past the end of this context's contents
+    ///
+    /// # Examples
+    /// ```
+    /// # use tweep::{FullContext, Position};
+    /// let c = FullContext::from(None, "Hail Eris\nAll hail Discordia".to_string());
+    /// let sub = c.slice(5..13);
+    /// assert_eq!(sub.get_contents(), "Eris\nAll");
+    /// assert_eq!(*sub.get_start_position(), Position::abs(1, 6));
+    /// assert_eq!(sub.slice(5..8).get_contents(), "All");
+    /// let empty = c.slice(0..0);
+    /// assert_eq!(empty.get_contents(), "");
+    /// assert_eq!(*empty.get_end_position(), Position::abs(1, 0));
+    /// ```
+    pub fn slice(&self, range: Range<usize>) -> Self {
+        assert!(range.start <= range.end, "Byte range {:?} starts after its end", range);
+        assert!(
+            range.end <= self.get_contents().len(),
+            "Byte range {:?} out of bounds for context of length {}",
+            range,
+            self.get_contents().len()
+        );
+        let base = self.get_byte_range().start;
+        let start = self.position_at(base + range.start);
+        let end = if range.end > range.start {
+            self.position_at(base + range.end - 1)
+        } else {
+            Position::abs(start.line, start.column - 1)
+        };
+        self.inner_subcontext(start, end)
+    }
+
+    /// Creates a subcontext out of the current context from an inclusive range
+    /// of 1-indexed line numbers, relative to the start of this context
+    ///
+    /// # Examples
+    /// ```
+    /// # use tweep::FullContext;
+    /// let c = FullContext::from(None, "Hail Eris\nAll hail\nDiscordia".to_string());
+    /// assert_eq!(c.slice_lines(2..=3).get_contents(), "All hail\nDiscordia");
+    /// let sub = c.slice_lines(2..=3);
+    /// assert_eq!(sub.slice_lines(1..=1).get_contents(), "All hail");
+    /// ```
+    pub fn slice_lines(&self, lines: RangeInclusive<usize>) -> Self {
+        let start = self.to_absolute(Position::rel(*lines.start(), 1));
+        let end = self.end_of_line(*lines.end(), PositionKind::Relative);
+        self.inner_subcontext(start, std::cmp::min(end, self.end_position))
+    }
+
     pub(crate) fn inner_subcontext(
         &self,
         start_position: Position,
@@ -174,20 +405,10 @@ impl FullContext {
     ) -> Self {
         let contents = self.contents.clone();
         let line_starts = self.line_starts.clone();
-        let start_position = match start_position.kind {
-            PositionKind::Absolute => start_position,
-            PositionKind::Relative => self
-                .start_position
-                .subposition(start_position.line, start_position.column),
-        };
-        let end_position = match end_position.kind {
-            PositionKind::Absolute => end_position,
-            PositionKind::Relative => self
-                .start_position
-                .subposition(end_position.line, end_position.column),
-        };
+        let start_position = self.to_absolute(start_position);
+        let end_position = self.to_absolute(end_position);
         Self::new_with_line_starts(
-            self.file_name.clone(),
+            self.file_path.clone(),
             start_position,
             end_position,
             contents,
@@ -252,17 +473,50 @@ where
     }
 }
 
+/// Serializes as a struct with the fields `file_path`, which is a string or
+/// `null`, `start_position`, and `end_position`. The contents are not included
+///
+/// Enabled with "serialize-issues" feature
+#[cfg(feature = "serialize-issues")]
+impl serde::Serialize for FullContext {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+        let mut s = serializer.serialize_struct("FullContext", 3)?;
+        s.serialize_field("file_path", &self.file_path.as_ref().map(|p| p.to_string_lossy()))?;
+        s.serialize_field("start_position", &self.start_position)?;
+        s.serialize_field("end_position", &self.end_position)?;
+        s.end()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::FullContext;
     use super::Position;
 
+    #[cfg(feature = "serialize-issues")]
+    #[test]
+    fn serialize() {
+        let c = FullContext::from(None, "foo\nbar".to_string());
+        assert_eq!(
+            serde_json::to_value(&c).unwrap(),
+            serde_json::json!({
+                "file_path": null,
+                "start_position": { "line": 1, "column": 1, "kind": "Absolute" },
+                "end_position": { "line": 2, "column": 3, "kind": "Absolute" },
+            })
+        );
+    }
+
     #[test]
     fn test_construction() {
         let owned = "hello".to_string();
         let c = FullContext::from(None, owned);
         assert_eq!(c.get_contents(), "hello");
-        assert_eq!(*c.get_file_name(), None);
+        assert_eq!(c.get_file_path(), None);
+        #[allow(deprecated)]
+        let file_name = c.get_file_name();
+        assert_eq!(file_name, None);
         assert_eq!(*c.get_start_position(), Position::abs(1, 1));
         assert_eq!(*c.get_end_position(), Position::abs(1, 5));
     }
@@ -272,14 +526,97 @@ mod tests {
         let owned = "Hail Eris".to_string();
         let c = FullContext::from(None, owned);
         assert_eq!(c.get_contents(), "Hail Eris");
-        assert_eq!(*c.get_file_name(), None);
+        assert_eq!(c.get_file_path(), None);
         assert_eq!(*c.get_start_position(), Position::abs(1, 1));
         assert_eq!(*c.get_end_position(), Position::abs(1, 9));
 
         let sub = c.subcontext(Position::rel(1, 6)..=Position::rel(1, 9));
         assert_eq!(sub.get_contents(), "Eris");
-        assert_eq!(*sub.get_file_name(), None);
+        assert_eq!(sub.get_file_path(), None);
         assert_eq!(*sub.get_start_position(), Position::abs(1, 6));
         assert_eq!(*sub.get_end_position(), Position::abs(1, 9));
     }
+
+    #[test]
+    fn thread_safe() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<FullContext>();
+
+        let c = FullContext::from(Some("a.twee".to_string()), "foo\nbar".to_string());
+        let sub = c.subcontext(Position::rel(2, 1)..);
+        assert!(std::sync::Arc::ptr_eq(&c.contents, &sub.contents));
+        assert!(std::sync::Arc::ptr_eq(&c.line_starts, &sub.clone().line_starts));
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let sub = sub.clone();
+                std::thread::spawn(move || sub.get_contents().to_string())
+            })
+            .collect();
+        for handle in handles {
+            assert_eq!(handle.join().unwrap(), "bar");
+        }
+    }
+
+    #[test]
+    fn slices() {
+        let c = FullContext::from(None, "foo\nbar baz\nqux".to_string());
+        let sub = c.slice(4..11);
+        assert_eq!(sub.get_contents(), "bar baz");
+        assert_eq!(sub.get_byte_range(), 4..11);
+        assert_eq!(sub.slice(4..7).get_contents(), "baz");
+        assert_eq!(sub.slice(4..7).get_byte_range(), 8..11);
+        assert_eq!(c.slice(2..5).get_contents(), "o\nb");
+        assert_eq!(c.slice(3..4).get_contents(), "\n");
+        assert_eq!(c.slice(4..4).get_contents(), "");
+        assert_eq!(c.slice(4..4).get_byte_range(), 4..4);
+        assert_eq!(c.slice(0..c.get_contents().len()), c);
+
+        let word = c.slice(8..11);
+        assert_eq!(word.slice_lines(1..=1).get_contents(), "baz");
+        assert_eq!(c.slice_lines(1..=2).get_contents(), "foo\nbar baz");
+        assert_eq!(c.slice_lines(3..=3).get_contents(), "qux");
+    }
+
+    #[test]
+    #[should_panic]
+    fn slice_out_of_bounds() {
+        let c = FullContext::from(None, "foo\nbar".to_string());
+        c.slice_lines(2..=2).slice(0..4);
+    }
+
+    #[test]
+    #[should_panic]
+    fn slice_reversed() {
+        let c = FullContext::from(None, "foo\nbar".to_string());
+        let (start, end) = (3, 1);
+        c.slice(start..end);
+    }
+
+    #[test]
+    fn spans() {
+        let c = FullContext::from(None, "foo\nbar baz\nqux".to_string());
+        let line = c.subcontext(Position::rel(2, 1)..=Position::rel(2, 7));
+        let word = line.subcontext(Position::rel(1, 5)..=Position::rel(1, 7));
+        assert_eq!(word.get_contents(), "baz");
+        assert!(c.contains(&word));
+        assert!(line.contains(&word));
+        assert!(!word.contains(&line));
+        assert!(!FullContext::from(Some("other".to_string()), "foo\nbar baz\nqux".to_string()).contains(&word));
+        assert!(word.overlaps(Position::abs(2, 7)..=Position::abs(3, 1)));
+        assert!(!word.overlaps(Position::abs(1, 1)..=Position::abs(2, 4)));
+        assert!(word.overlaps(..));
+    }
+
+    #[test]
+    fn byte_offsets() {
+        let c = FullContext::from(None, "foo\nbar baz\nqux".to_string());
+        for offset in 0..c.get_contents().len() {
+            assert_eq!(c.byte_offset(c.position_at(offset)), offset);
+        }
+        assert_eq!(c.position_at(4), Position::abs(2, 1));
+        assert_eq!(c.position_at(12), Position::abs(3, 1));
+        let word = c.subcontext(Position::rel(2, 5)..=Position::rel(2, 7));
+        assert_eq!(word.byte_offset(Position::rel(1, 1)), 8);
+        assert_eq!(word.get_byte_range(), 8..11);
+    }
 }
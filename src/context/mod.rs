@@ -5,5 +5,8 @@ pub use position::PositionKind;
 mod full_context;
 pub use full_context::FullContext;
 
+mod shared_str;
+pub use shared_str::SharedStr;
+
 mod partial_context;
 pub use partial_context::PartialContext;
@@ -1,6 +1,8 @@
 use crate::context::{Position, FullContext};
+use std::path::Path;
+use std::sync::Arc;
 
-/// A Context that holds only an optional file name and 1-indexed start position
+/// A Context that holds only an optional file path and 1-indexed start position
 ///
 /// Intended to be constructed only from a [`FullContext`] as a way of
 /// discarding additional, unwanted information.
@@ -8,14 +10,27 @@ use crate::context::{Position, FullContext};
 /// [`FullContext`]: struct.FullContext.html
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct PartialContext {
-    file_name: Option<String>,
+    file_path: Option<Arc<Path>>,
     start_position: Position,
 }
 
 impl PartialContext {
-    /// Returns a reference to the optional file name
-    pub fn get_file_name(&self) -> &Option<String> {
-        &self.file_name
+    /// Returns the optional file path
+    pub fn get_file_path(&self) -> Option<&Path> {
+        self.file_path.as_deref()
+    }
+
+    /// Returns the optional file name, which may involve a lossy conversion
+    /// of the file path
+    #[deprecated(note = "use `get_file_path` instead")]
+    pub fn get_file_name(&self) -> Option<String> {
+        self.get_file_path().map(|p| p.to_string_lossy().into_owned())
+    }
+
+    /// Returns an object that implements `Display` for printing the file path,
+    /// if any, which may involve a lossy conversion
+    pub fn display_file_path(&self) -> Option<std::path::Display<'_>> {
+        self.file_path.as_ref().map(|p| p.display())
     }
 
     /// Returns a reference to the 1-indexed start position
@@ -27,7 +42,7 @@ impl PartialContext {
 impl std::convert::From<FullContext> for PartialContext {
     fn from(full: FullContext) -> PartialContext {
         PartialContext {
-            file_name: full.get_file_name().clone(),
+            file_path: full.get_file_path().map(Arc::from),
             start_position: *full.get_start_position(),
         }
     }
@@ -35,7 +50,25 @@ impl std::convert::From<FullContext> for PartialContext {
 
 impl std::fmt::Display for PartialContext {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{:?}: {}", self.file_name, self.start_position)
+        match self.display_file_path() {
+            Some(path) => write!(f, "{}: {}", path, self.start_position),
+            None => write!(f, "{}", self.start_position),
+        }
+    }
+}
+
+/// Serializes as a struct with the fields `file_path`, which is a string or
+/// `null`, and `start_position`
+///
+/// Enabled with "serialize-issues" feature
+#[cfg(feature = "serialize-issues")]
+impl serde::Serialize for PartialContext {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+        let mut s = serializer.serialize_struct("PartialContext", 2)?;
+        s.serialize_field("file_path", &self.file_path.as_ref().map(|p| p.to_string_lossy()))?;
+        s.serialize_field("start_position", &self.start_position)?;
+        s.end()
     }
 }
 
@@ -43,12 +76,35 @@ impl std::fmt::Display for PartialContext {
 mod tests {
     use super::*;
 
+    #[cfg(feature = "serialize-issues")]
+    #[test]
+    fn serialize() {
+        let c = FullContext::from(Some("a.twee".to_string()), "hail eris".to_string());
+        let c = PartialContext::from(c);
+        assert_eq!(
+            serde_json::to_value(&c).unwrap(),
+            serde_json::json!({
+                "file_path": "a.twee",
+                "start_position": { "line": 1, "column": 1, "kind": "Absolute" },
+            })
+        );
+    }
+
+    #[test]
+    fn thread_safe() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<PartialContext>();
+        assert_send_sync::<crate::Warning>();
+        assert_send_sync::<crate::Error>();
+        assert_send_sync::<crate::ErrorList>();
+    }
+
     #[test]
     fn test_conversion() {
         let contents = "hail eris".to_string();
         let c = FullContext::from(None, contents);
         let partial: PartialContext = c.into();
-        assert_eq!(*partial.get_file_name(), None);
+        assert_eq!(partial.get_file_path(), None);
         assert_eq!(*partial.get_start_position(), Position::abs(1, 1));
     }
 
@@ -59,7 +115,11 @@ mod tests {
         let c = FullContext::from(Some(name), contents);
         let sub = c.subcontext(Position::rel(1, 6)..=Position::rel(2, 3));
         let partial: PartialContext = sub.into();
-        assert_eq!(*partial.get_file_name(), Some("name.ext".to_string()));
+        assert_eq!(partial.get_file_path(), Some(Path::new("name.ext")));
+        #[allow(deprecated)]
+        let file_name = partial.get_file_name();
+        assert_eq!(file_name, Some("name.ext".to_string()));
+        assert_eq!(partial.to_string(), "name.ext: line: 1 column: 6");
         assert_eq!(*partial.get_start_position(), Position::abs(1, 6));
     }
 }
@@ -1,5 +1,6 @@
 /// Indicates absolute/relative position
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serialize-issues", derive(serde::Serialize))]
 pub enum PositionKind {
     /// Absolute position
     Absolute,
@@ -10,15 +11,20 @@ pub enum PositionKind {
 
 /// One-indexed, line and column numbers to be used within a [`Context`]
 ///
+/// Positions are ordered by line, then by column.
+///
 /// # Examples
 /// ```
 /// # use tweep::Position;
 /// let c = Position::abs(1, 3);
 /// assert_eq!((c.line, c.column), (1, 3));
+/// assert!(c < Position::abs(1, 4));
+/// assert!(c > Position::abs(0, 5));
 /// ```
 ///
 /// [`Context`]: struct.Context.html
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serialize-issues", derive(serde::Serialize))]
 pub struct Position {
     /// The one-indexed line number
     pub line: usize,
@@ -119,4 +125,25 @@ mod tests {
         let sub = p.subposition(1, 1);
         assert_eq!(sub, p);
     }
+
+    #[test]
+    fn ordering() {
+        let mut positions = vec![
+            Position::abs(2, 1),
+            Position::abs(1, 10),
+            Position::abs(2, 3),
+            Position::abs(1, 2),
+        ];
+        positions.sort();
+        assert_eq!(
+            positions,
+            vec![
+                Position::abs(1, 2),
+                Position::abs(1, 10),
+                Position::abs(2, 1),
+                Position::abs(2, 3),
+            ]
+        );
+        assert!(Position::abs(3, 1) > Position::abs(2, 100));
+    }
 }
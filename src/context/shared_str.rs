@@ -0,0 +1,138 @@
+use std::ops::Deref;
+use std::ops::Range;
+use std::sync::Arc;
+
+/// A slice of a shared source buffer, such as the contents of a parsed file,
+/// that can be stored without copying the text it refers to. Dereferences to
+/// `str`, and can be created from a `String`
+///
+/// # Examples
+/// ```
+/// use tweep::SharedStr;
+/// let text = SharedStr::from("Hail Eris".to_string());
+/// assert_eq!(text, "Hail Eris");
+/// assert!(text.starts_with("Hail"));
+/// assert_eq!(String::from(text), "Hail Eris");
+/// ```
+#[derive(Clone, Default, Eq)]
+pub struct SharedStr {
+    source: Arc<str>,
+    range: Range<usize>,
+}
+
+impl SharedStr {
+    /// Creates a slice of the given byte range of `source`
+    pub(crate) fn new(source: Arc<str>, range: Range<usize>) -> Self {
+        assert!(source.is_char_boundary(range.start) && source.is_char_boundary(range.end));
+        assert!(range.start <= range.end && range.end <= source.len());
+        SharedStr { source, range }
+    }
+
+    /// Returns this slice followed by a line break. If the line break follows
+    /// the slice in its source, the result refers to the source as well;
+    /// otherwise, the text is copied
+    pub(crate) fn with_line_break(self) -> Self {
+        if self.source[self.range.end..].starts_with('\n') {
+            SharedStr::new(self.source, self.range.start..self.range.end + 1)
+        } else {
+            SharedStr::from(format!("{}\n", &*self))
+        }
+    }
+}
+
+impl Deref for SharedStr {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.source[self.range.clone()]
+    }
+}
+
+impl AsRef<str> for SharedStr {
+    fn as_ref(&self) -> &str {
+        self
+    }
+}
+
+impl From<String> for SharedStr {
+    fn from(s: String) -> Self {
+        let len = s.len();
+        SharedStr::new(Arc::from(s), 0..len)
+    }
+}
+
+impl From<&str> for SharedStr {
+    fn from(s: &str) -> Self {
+        SharedStr::from(s.to_string())
+    }
+}
+
+impl From<SharedStr> for String {
+    fn from(s: SharedStr) -> Self {
+        s.to_string()
+    }
+}
+
+impl PartialEq for SharedStr {
+    fn eq(&self, other: &SharedStr) -> bool {
+        **self == **other
+    }
+}
+
+impl PartialEq<str> for SharedStr {
+    fn eq(&self, other: &str) -> bool {
+        &**self == other
+    }
+}
+
+impl PartialEq<&str> for SharedStr {
+    fn eq(&self, other: &&str) -> bool {
+        &**self == *other
+    }
+}
+
+impl PartialEq<String> for SharedStr {
+    fn eq(&self, other: &String) -> bool {
+        **self == **other
+    }
+}
+
+impl std::fmt::Display for SharedStr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Display::fmt(&**self, f)
+    }
+}
+
+impl std::fmt::Debug for SharedStr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Debug::fmt(&**self, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slices_share_source() {
+        let source: Arc<str> = Arc::from("Hail Eris");
+        let eris = SharedStr::new(source.clone(), 5..9);
+        assert_eq!(&*eris, "Eris");
+        assert_eq!(Arc::strong_count(&source), 2);
+        assert_eq!(format!("{:?}", eris), "\"Eris\"");
+        assert_eq!(&*SharedStr::from("owned".to_string()), "owned");
+        assert_eq!(&*SharedStr::default(), "");
+    }
+
+    #[test]
+    fn line_break() {
+        let source: Arc<str> = Arc::from("Hail\nEris");
+        let hail = SharedStr::new(source.clone(), 0..4).with_line_break();
+        assert_eq!(hail, "Hail\n");
+        assert_eq!(Arc::strong_count(&source), 2);
+        let eris = SharedStr::new(source.clone(), 5..9).with_line_break();
+        assert_eq!(eris, "Eris\n");
+        assert_eq!(Arc::strong_count(&source), 2);
+        assert_eq!(SharedStr::new(source, 0..4), SharedStr::from("Hail"));
+    }
+}
@@ -0,0 +1,27 @@
+//! A lossless concrete syntax tree (CST) for twee source, for formatters and
+//! refactoring tools that need to make surgical edits.
+//!
+//! Unlike the typed [`StoryPassages`] API, the CST keeps every character of
+//! the source, including whitespace, escapes, and link syntax, as a token
+//! with a byte span. Concatenating the text of every token reproduces the
+//! source exactly, so an edit to one token leaves the rest of the file
+//! untouched.
+//!
+//! [`StoryPassages`]: ../struct.StoryPassages.html
+
+mod parser;
+
+mod syntax_element;
+pub use syntax_element::SyntaxElement;
+
+mod syntax_kind;
+pub use syntax_kind::SyntaxKind;
+
+mod syntax_node;
+pub use syntax_node::SyntaxNode;
+
+mod syntax_token;
+pub use syntax_token::SyntaxToken;
+
+mod syntax_tree;
+pub use syntax_tree::SyntaxTree;
@@ -0,0 +1,271 @@
+use crate::cst::SyntaxElement;
+use crate::cst::SyntaxKind;
+use crate::cst::SyntaxNode;
+use crate::cst::SyntaxToken;
+
+/// Parses twee source into a lossless `Document` node
+pub(crate) fn parse(source: &str) -> SyntaxNode {
+    let mut passages = Vec::new();
+    let mut current: Option<(SyntaxNode, Vec<SyntaxElement>, usize)> = None;
+    let mut preamble = Vec::new();
+
+    let mut offset = 0;
+    while offset < source.len() {
+        let line_end = source[offset..].find('\n').map_or(source.len(), |i| offset + i + 1);
+        let line = &source[offset..line_end];
+        if line.trim_start().starts_with("::") {
+            if let Some(passage) = current.take() {
+                passages.push(finish_passage(passage));
+            }
+            current = Some((header(line, offset), Vec::new(), line_end));
+        } else {
+            let elements = body_line(line, offset);
+            match current.as_mut() {
+                Some((_, body, _)) => body.extend(elements),
+                None => preamble.extend(elements),
+            }
+        }
+        offset = line_end;
+    }
+    if let Some(passage) = current.take() {
+        passages.push(finish_passage(passage));
+    }
+
+    let mut children = Vec::new();
+    if !preamble.is_empty() {
+        children.push(SyntaxElement::Node(SyntaxNode::new(SyntaxKind::Preamble, 0, preamble)));
+    }
+    children.extend(passages.into_iter().map(SyntaxElement::Node));
+    SyntaxNode::new(SyntaxKind::Document, 0, children)
+}
+
+fn finish_passage((header, body, body_start): (SyntaxNode, Vec<SyntaxElement>, usize)) -> SyntaxNode {
+    let start = header.range.start;
+    let body = SyntaxNode::new(SyntaxKind::Body, body_start, body);
+    SyntaxNode::new(
+        SyntaxKind::Passage,
+        start,
+        vec![SyntaxElement::Node(header), SyntaxElement::Node(body)],
+    )
+}
+
+/// Accumulates tokens for a single line, with ranges relative to the source
+struct Tokens {
+    base: usize,
+    elements: Vec<SyntaxElement>,
+}
+
+impl Tokens {
+    fn push(&mut self, kind: SyntaxKind, start: usize, end: usize) {
+        if start < end {
+            self.elements.push(SyntaxElement::Token(SyntaxToken {
+                kind,
+                range: self.base + start..self.base + end,
+            }));
+        }
+    }
+}
+
+/// Returns a line without its line break
+fn split_newline(line: &str) -> &str {
+    let content = line.strip_suffix('\n').unwrap_or(line);
+    content.strip_suffix('\r').unwrap_or(content)
+}
+
+/// Returns the end of the run of spaces and tabs starting at `pos`
+fn whitespace_end(line: &str, pos: usize) -> usize {
+    line[pos..]
+        .find(|c| c != ' ' && c != '\t')
+        .map_or(line.len(), |i| pos + i)
+}
+
+fn header(line: &str, offset: usize) -> SyntaxNode {
+    let content = split_newline(line);
+    let mut tokens = Tokens {
+        base: offset,
+        elements: Vec::new(),
+    };
+
+    let mut pos = whitespace_end(content, 0);
+    tokens.push(SyntaxKind::Whitespace, 0, pos);
+    tokens.push(SyntaxKind::Sigil, pos, pos + 2);
+    pos += 2;
+    let name_start = whitespace_end(content, pos);
+    tokens.push(SyntaxKind::Whitespace, pos, name_start);
+
+    // The name runs until the first unescaped `[` or `{`
+    let mut name_end = content.len();
+    let mut escaped = false;
+    for (i, c) in content[name_start..].char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '[' | '{' => {
+                name_end = name_start + i;
+                break;
+            }
+            _ => (),
+        }
+    }
+    let trimmed_end = name_start + content[name_start..name_end].trim_end().len();
+    tokens.push(SyntaxKind::Name, name_start, trimmed_end);
+    tokens.push(SyntaxKind::Whitespace, trimmed_end, name_end);
+    pos = name_end;
+
+    let mut children = tokens.elements;
+    while pos < content.len() {
+        let rest = &content[pos..];
+        let mut tokens = Tokens {
+            base: offset,
+            elements: Vec::new(),
+        };
+        if rest.starts_with(' ') || rest.starts_with('\t') {
+            let end = whitespace_end(content, pos);
+            tokens.push(SyntaxKind::Whitespace, pos, end);
+            pos = end;
+        } else if rest.starts_with('[') {
+            let start = pos;
+            tokens.push(SyntaxKind::TagOpen, pos, pos + 1);
+            pos += 1;
+            while pos < content.len() {
+                let c = content[pos..].chars().next().unwrap();
+                if c == ']' {
+                    tokens.push(SyntaxKind::TagClose, pos, pos + 1);
+                    pos += 1;
+                    break;
+                } else if c == ' ' || c == '\t' {
+                    let end = whitespace_end(content, pos);
+                    tokens.push(SyntaxKind::Whitespace, pos, end);
+                    pos = end;
+                } else {
+                    let end = content[pos..]
+                        .find([' ', '\t', ']'])
+                        .map_or(content.len(), |i| pos + i);
+                    tokens.push(SyntaxKind::Tag, pos, end);
+                    pos = end;
+                }
+            }
+            children.push(SyntaxElement::Node(SyntaxNode::new(
+                SyntaxKind::TagBlock,
+                offset + start,
+                tokens.elements,
+            )));
+            continue;
+        } else if rest.starts_with('{') {
+            let end = metadata_end(rest).map_or(content.len(), |len| pos + len);
+            tokens.push(SyntaxKind::Metadata, pos, end);
+            pos = end;
+        } else {
+            let end = content[pos..]
+                .find([' ', '\t', '[', '{'])
+                .map_or(content.len(), |i| pos + i);
+            tokens.push(SyntaxKind::Unknown, pos, end);
+            pos = end;
+        }
+        children.extend(tokens.elements);
+    }
+
+    let mut tokens = Tokens {
+        base: offset,
+        elements: children,
+    };
+    tokens.push(SyntaxKind::Newline, content.len(), line.len());
+    SyntaxNode::new(SyntaxKind::Header, offset, tokens.elements)
+}
+
+/// Returns the length of the metadata block at the start of `input`,
+/// skipping over braces within JSON strings, or `None` if it is unclosed
+fn metadata_end(input: &str) -> Option<usize> {
+    let mut depth = 0;
+    let mut in_string = false;
+    let mut escaped = false;
+    for (i, c) in input.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if in_string => escaped = true,
+            '"' => in_string = !in_string,
+            '{' if !in_string => depth += 1,
+            '}' if !in_string => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i + 1);
+                }
+            }
+            _ => (),
+        }
+    }
+    None
+}
+
+fn body_line(line: &str, offset: usize) -> Vec<SyntaxElement> {
+    let content = split_newline(line);
+    let mut tokens = Tokens {
+        base: offset,
+        elements: Vec::new(),
+    };
+
+    let mut text_start = 0;
+    let mut pos = 0;
+    while pos < content.len() {
+        let rest = &content[pos..];
+        let special = if rest.starts_with("[[") {
+            rest.find("]]").map(|end| (SyntaxKind::Link, end + 2))
+        } else if rest.starts_with("<<") {
+            rest.find(">>").map(|end| (SyntaxKind::Macro, end + 2))
+        } else if rest.starts_with('<')
+            && rest[1..].starts_with(|c: char| c.is_ascii_alphabetic() || c == '/')
+        {
+            rest.find('>').map(|end| (SyntaxKind::HtmlTag, end + 1))
+        } else {
+            None
+        };
+
+        match special {
+            Some((kind, len)) => {
+                tokens.push(SyntaxKind::Text, text_start, pos);
+                if kind == SyntaxKind::Link {
+                    let link = link(&content[pos..pos + len], offset + pos);
+                    tokens.elements.push(SyntaxElement::Node(link));
+                } else {
+                    tokens.push(kind, pos, pos + len);
+                }
+                pos += len;
+                text_start = pos;
+            }
+            None => pos += rest.chars().next().unwrap().len_utf8(),
+        }
+    }
+    tokens.push(SyntaxKind::Text, text_start, content.len());
+    tokens.push(SyntaxKind::Newline, content.len(), line.len());
+    tokens.elements
+}
+
+/// Tokenizes a link, using the same separator precedence as [`TwineLink`]
+///
+/// [`TwineLink`]: ../struct.TwineLink.html
+fn link(source: &str, offset: usize) -> SyntaxNode {
+    let mut tokens = Tokens {
+        base: offset,
+        elements: Vec::new(),
+    };
+    let inner_end = source.len() - 2;
+    let inner = &source[2..inner_end];
+    tokens.push(SyntaxKind::LinkOpen, 0, 2);
+    if let Some(i) = inner.find('|') {
+        tokens.push(SyntaxKind::LinkText, 2, 2 + i);
+        tokens.push(SyntaxKind::LinkSeparator, 2 + i, 3 + i);
+        tokens.push(SyntaxKind::LinkTarget, 3 + i, inner_end);
+    } else if let Some(i) = inner.find("<-") {
+        tokens.push(SyntaxKind::LinkTarget, 2, 2 + i);
+        tokens.push(SyntaxKind::LinkSeparator, 2 + i, 4 + i);
+        tokens.push(SyntaxKind::LinkText, 4 + i, inner_end);
+    } else if let Some(i) = inner.find("->") {
+        tokens.push(SyntaxKind::LinkText, 2, 2 + i);
+        tokens.push(SyntaxKind::LinkSeparator, 2 + i, 4 + i);
+        tokens.push(SyntaxKind::LinkTarget, 4 + i, inner_end);
+    } else {
+        tokens.push(SyntaxKind::LinkTarget, 2, inner_end);
+    }
+    tokens.push(SyntaxKind::LinkClose, inner_end, source.len());
+    SyntaxNode::new(SyntaxKind::Link, offset, tokens.elements)
+}
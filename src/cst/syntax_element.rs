@@ -0,0 +1,34 @@
+use crate::cst::SyntaxKind;
+use crate::cst::SyntaxNode;
+use crate::cst::SyntaxToken;
+use std::ops::Range;
+
+/// A child of a [`SyntaxNode`], which is either another node or a token
+///
+/// [`SyntaxNode`]: struct.SyntaxNode.html
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum SyntaxElement {
+    /// An interior node
+    Node(SyntaxNode),
+
+    /// A leaf token
+    Token(SyntaxToken),
+}
+
+impl SyntaxElement {
+    /// Returns the kind of the element
+    pub fn kind(&self) -> SyntaxKind {
+        match self {
+            SyntaxElement::Node(node) => node.kind,
+            SyntaxElement::Token(token) => token.kind,
+        }
+    }
+
+    /// Returns the byte range of the element within the source
+    pub fn range(&self) -> Range<usize> {
+        match self {
+            SyntaxElement::Node(node) => node.range.clone(),
+            SyntaxElement::Token(token) => token.range.clone(),
+        }
+    }
+}
@@ -0,0 +1,86 @@
+/// The kind of a [`SyntaxNode`] or [`SyntaxToken`]
+///
+/// [`SyntaxNode`]: struct.SyntaxNode.html
+/// [`SyntaxToken`]: struct.SyntaxToken.html
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum SyntaxKind {
+    /// Node: the whole source
+    Document,
+
+    /// Node: text before the first passage header
+    Preamble,
+
+    /// Node: a passage, made up of a `Header` and a `Body`
+    Passage,
+
+    /// Node: a passage header line, including its line break
+    Header,
+
+    /// Token: the `::` sigil of a header
+    Sigil,
+
+    /// Token: a passage name, including any escapes
+    Name,
+
+    /// Node: a tag block, such as `[tag1 tag2]`
+    TagBlock,
+
+    /// Token: the `[` opening a tag block
+    TagOpen,
+
+    /// Token: a single tag within a tag block
+    Tag,
+
+    /// Token: the `]` closing a tag block
+    TagClose,
+
+    /// Token: a metadata block, such as `{"position":"5,5"}`
+    Metadata,
+
+    /// Node: the lines of a passage following its header
+    Body,
+
+    /// Token: prose within a passage body
+    Text,
+
+    /// Node: a link, such as `[[Text->Target]]`
+    Link,
+
+    /// Token: the `[[` opening a link
+    LinkOpen,
+
+    /// Token: the display text of a link
+    LinkText,
+
+    /// Token: the `|`, `->`, or `<-` separating the text and target of a link
+    LinkSeparator,
+
+    /// Token: the target of a link
+    LinkTarget,
+
+    /// Token: the `]]` closing a link
+    LinkClose,
+
+    /// Token: a macro, such as `<<set $x to 1>>`
+    Macro,
+
+    /// Token: an HTML tag, such as `<b>` or `</b>`
+    HtmlTag,
+
+    /// Token: spaces and tabs
+    Whitespace,
+
+    /// Token: a line break
+    Newline,
+
+    /// Token: text that could not be parsed, such as trailing text after a
+    /// metadata block
+    Unknown,
+}
+
+impl SyntaxKind {
+    /// Returns `true` if this kind is trivia: whitespace or a line break
+    pub fn is_trivia(self) -> bool {
+        matches!(self, SyntaxKind::Whitespace | SyntaxKind::Newline)
+    }
+}
@@ -0,0 +1,56 @@
+use crate::cst::SyntaxElement;
+use crate::cst::SyntaxKind;
+use crate::cst::SyntaxToken;
+use std::ops::Range;
+
+/// An interior node of a [`SyntaxTree`], spanning its children
+///
+/// [`SyntaxTree`]: struct.SyntaxTree.html
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SyntaxNode {
+    /// The kind of the node
+    pub kind: SyntaxKind,
+
+    /// The byte range of the node within the source
+    pub range: Range<usize>,
+
+    /// The children of the node, in source order
+    pub children: Vec<SyntaxElement>,
+}
+
+impl SyntaxNode {
+    /// Creates a node of the given kind spanning the given children
+    pub(crate) fn new(kind: SyntaxKind, start: usize, children: Vec<SyntaxElement>) -> Self {
+        let end = children.last().map_or(start, |c| c.range().end);
+        SyntaxNode {
+            kind,
+            range: start..end,
+            children,
+        }
+    }
+
+    /// Returns the child nodes of this node
+    pub fn child_nodes(&self) -> impl Iterator<Item = &SyntaxNode> {
+        self.children.iter().filter_map(|c| match c {
+            SyntaxElement::Node(node) => Some(node),
+            SyntaxElement::Token(_) => None,
+        })
+    }
+
+    /// Returns the first child node or token of the given kind, if any
+    pub fn child(&self, kind: SyntaxKind) -> Option<&SyntaxElement> {
+        self.children.iter().find(|c| c.kind() == kind)
+    }
+
+    /// Returns every token within this node, in source order
+    pub fn tokens(&self) -> Vec<&SyntaxToken> {
+        let mut tokens = Vec::new();
+        for child in &self.children {
+            match child {
+                SyntaxElement::Node(node) => tokens.extend(node.tokens()),
+                SyntaxElement::Token(token) => tokens.push(token),
+            }
+        }
+        tokens
+    }
+}
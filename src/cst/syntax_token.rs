@@ -0,0 +1,14 @@
+use crate::cst::SyntaxKind;
+use std::ops::Range;
+
+/// A leaf of a [`SyntaxTree`]: a span of source text of a single kind
+///
+/// [`SyntaxTree`]: struct.SyntaxTree.html
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SyntaxToken {
+    /// The kind of the token
+    pub kind: SyntaxKind,
+
+    /// The byte range of the token within the source
+    pub range: Range<usize>,
+}
@@ -0,0 +1,192 @@
+use crate::cst::parser;
+use crate::cst::SyntaxElement;
+use crate::cst::SyntaxKind;
+use crate::cst::SyntaxNode;
+use crate::cst::SyntaxToken;
+use std::fmt;
+use std::ops::Range;
+
+/// A lossless concrete syntax tree of twee source
+///
+/// Every byte of the source belongs to exactly one [`SyntaxToken`], so the
+/// tree can be used to locate and rewrite a single name, tag, or link target
+/// while leaving the surrounding formatting untouched.
+///
+/// # Examples
+/// ```
+/// use tweep::cst::{SyntaxKind, SyntaxTree};
+/// let source = ":: Start [tag]\nGo [[North->Hall]]\n";
+/// let tree = SyntaxTree::parse(source);
+/// assert_eq!(tree.to_string(), source);
+///
+/// let target = tree
+///     .tokens()
+///     .into_iter()
+///     .find(|t| t.kind == SyntaxKind::LinkTarget)
+///     .unwrap();
+/// assert_eq!(tree.text(target.range.clone()), "Hall");
+///
+/// let edited = tree.edit(target.range.clone(), "Kitchen");
+/// assert_eq!(edited.source(), ":: Start [tag]\nGo [[North->Kitchen]]\n");
+/// ```
+///
+/// [`SyntaxToken`]: struct.SyntaxToken.html
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SyntaxTree {
+    source: String,
+    root: SyntaxNode,
+}
+
+impl SyntaxTree {
+    /// Parses the given twee source into a syntax tree
+    pub fn parse<S: Into<String>>(source: S) -> Self {
+        let source = source.into();
+        let root = parser::parse(&source);
+        SyntaxTree { source, root }
+    }
+
+    /// Returns the source the tree was parsed from
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Returns the root `Document` node
+    pub fn root(&self) -> &SyntaxNode {
+        &self.root
+    }
+
+    /// Returns the source text within the given byte range
+    pub fn text(&self, range: Range<usize>) -> &str {
+        &self.source[range]
+    }
+
+    /// Returns every token in the tree, in source order
+    pub fn tokens(&self) -> Vec<&SyntaxToken> {
+        self.root.tokens()
+    }
+
+    /// Returns the `Passage` nodes of the tree, in source order
+    pub fn passages(&self) -> impl Iterator<Item = &SyntaxNode> {
+        self.root
+            .child_nodes()
+            .filter(|node| node.kind == SyntaxKind::Passage)
+    }
+
+    /// Returns the name of the given `Passage` node, as written in the source
+    pub fn passage_name(&self, passage: &SyntaxNode) -> Option<&str> {
+        let header = match passage.child(SyntaxKind::Header)? {
+            SyntaxElement::Node(header) => header,
+            SyntaxElement::Token(_) => return None,
+        };
+        header
+            .child(SyntaxKind::Name)
+            .map(|name| self.text(name.range()))
+    }
+
+    /// Replaces the given byte range with `replacement` and returns the
+    /// re-parsed tree. All source outside of `range` is preserved exactly
+    pub fn edit(&self, range: Range<usize>, replacement: &str) -> SyntaxTree {
+        let mut source = self.source.clone();
+        source.replace_range(range, replacement);
+        SyntaxTree::parse(source)
+    }
+}
+
+impl fmt::Display for SyntaxTree {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for token in self.tokens() {
+            write!(f, "{}", self.text(token.range.clone()))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(tree: &SyntaxTree) -> Vec<(SyntaxKind, &str)> {
+        tree.tokens()
+            .into_iter()
+            .map(|t| (t.kind, tree.text(t.range.clone())))
+            .collect()
+    }
+
+    #[test]
+    fn lossless() {
+        let source = "Leading text\r\n  ::  A \\[name\\]  [ a  b ] {\"position\":\"1,1\", \"x\":\"}\"} junk \r\n\
+            Body <<set $x to 1>> <b>bold</b> [[unclosed\n\
+            :: Next\n[[T<-x]] [[a|b]] < not a tag\n::Empty";
+        let tree = SyntaxTree::parse(source);
+        assert_eq!(tree.to_string(), source);
+
+        let mut end = 0;
+        for token in tree.tokens() {
+            assert_eq!(token.range.start, end);
+            assert!(token.range.start < token.range.end);
+            end = token.range.end;
+        }
+        assert_eq!(end, source.len());
+    }
+
+    #[test]
+    fn header_tokens() {
+        let tree = SyntaxTree::parse(":: A \\[b\\] [t1  t2] {\"size\":\"100,100\"}\n");
+        assert_eq!(
+            kinds(&tree),
+            vec![
+                (SyntaxKind::Sigil, "::"),
+                (SyntaxKind::Whitespace, " "),
+                (SyntaxKind::Name, "A \\[b\\]"),
+                (SyntaxKind::Whitespace, " "),
+                (SyntaxKind::TagOpen, "["),
+                (SyntaxKind::Tag, "t1"),
+                (SyntaxKind::Whitespace, "  "),
+                (SyntaxKind::Tag, "t2"),
+                (SyntaxKind::TagClose, "]"),
+                (SyntaxKind::Whitespace, " "),
+                (SyntaxKind::Metadata, "{\"size\":\"100,100\"}"),
+                (SyntaxKind::Newline, "\n"),
+            ]
+        );
+        let passage = tree.passages().next().unwrap();
+        assert_eq!(tree.passage_name(passage), Some("A \\[b\\]"));
+    }
+
+    #[test]
+    fn body_tokens() {
+        let tree = SyntaxTree::parse(":: A\nGo [[Target<-Text]] <<x>> <i>now</i>");
+        let body: Vec<_> = kinds(&tree).into_iter().skip(4).collect();
+        assert_eq!(
+            body,
+            vec![
+                (SyntaxKind::Text, "Go "),
+                (SyntaxKind::LinkOpen, "[["),
+                (SyntaxKind::LinkTarget, "Target"),
+                (SyntaxKind::LinkSeparator, "<-"),
+                (SyntaxKind::LinkText, "Text"),
+                (SyntaxKind::LinkClose, "]]"),
+                (SyntaxKind::Text, " "),
+                (SyntaxKind::Macro, "<<x>>"),
+                (SyntaxKind::Text, " "),
+                (SyntaxKind::HtmlTag, "<i>"),
+                (SyntaxKind::Text, "now"),
+                (SyntaxKind::HtmlTag, "</i>"),
+            ]
+        );
+    }
+
+    #[test]
+    fn structure() {
+        let tree = SyntaxTree::parse("preamble\n:: A\ntext\n:: B\n");
+        let kinds: Vec<_> = tree.root().child_nodes().map(|n| n.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![SyntaxKind::Preamble, SyntaxKind::Passage, SyntaxKind::Passage]
+        );
+        let names: Vec<_> = tree.passages().map(|p| tree.passage_name(p)).collect();
+        assert_eq!(names, vec![Some("A"), Some("B")]);
+        let a = tree.passages().next().unwrap();
+        assert_eq!(tree.text(a.range.clone()), ":: A\ntext\n");
+    }
+}
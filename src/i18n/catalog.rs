@@ -0,0 +1,726 @@
+use crate::i18n::CatalogEntry;
+use crate::Error;
+use crate::ErrorKind;
+use crate::ErrorList;
+use crate::FullContext;
+use crate::Output;
+use crate::ParseOptions;
+use crate::Passage;
+use crate::PassageOrder;
+use crate::PassageContent;
+use crate::Position;
+use crate::StoryPassages;
+use crate::Warning;
+use crate::WarningKind;
+use std::collections::HashMap;
+use std::ops::Range;
+use std::path::Path;
+
+/// A catalog of the translatable strings of a story, which can be written as
+/// a gettext PO file, an XLIFF 1.2 document, or JSON
+///
+/// # Examples
+/// ```
+/// use tweep::StoryPassages;
+/// use tweep::i18n::Catalog;
+/// let input = r#":: Start
+/// <<set $gold to 5>>You have $gold coins.
+/// [[Spend them|Shop]]
+///
+/// :: Shop
+/// Nothing for sale.
+/// "#.to_string();
+/// let story = StoryPassages::from_string(input).take().0.ok().unwrap();
+/// let catalog = Catalog::extract(&story);
+/// let sources: Vec<&str> = catalog.entries.iter().map(|e| e.source.as_str()).collect();
+/// assert_eq!(sources, vec!["Nothing for sale.", "You have $gold coins.", "Spend them"]);
+/// assert!(catalog.to_po().contains("msgctxt \"Start#1\"\nmsgid \"Spend them\"\nmsgstr \"\""));
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Catalog {
+    /// The entries of the catalog, ordered by passage and then by segment.
+    /// Passages are in order of name, unless extracted with
+    /// [`extract_in_order`](#method.extract_in_order)
+    pub entries: Vec<CatalogEntry>,
+}
+
+impl Catalog {
+    /// Extracts the prose of every normal passage of the story into a new
+    /// `Catalog` with no translations
+    pub fn extract(story: &StoryPassages) -> Self {
+        Catalog::extract_in_order(story, PassageOrder::default())
+    }
+
+    /// Extracts the prose of every normal passage of the story like
+    /// [`extract`], with the passages in the given [`PassageOrder`]
+    ///
+    /// [`extract`]: #method.extract
+    /// [`PassageOrder`]: ../enum.PassageOrder.html
+    pub fn extract_in_order(story: &StoryPassages, order: PassageOrder) -> Self {
+        let mut entries = Vec::new();
+        for name in story.ordered_names(order) {
+            if let PassageContent::Normal(twine) = &story.passages[name].content {
+                let source = twine.trimmed();
+                for (segment, range) in twine.prose_segments().into_iter().enumerate() {
+                    entries.push(CatalogEntry {
+                        passage: name.clone(),
+                        segment,
+                        source: source[range].to_string(),
+                        translation: None,
+                    });
+                }
+            }
+        }
+        Catalog { entries }
+    }
+
+    /// Writes the catalog as a gettext PO file. Each entry's key is used as
+    /// its `msgctxt`, so identical source text in different places can be
+    /// translated differently
+    pub fn to_po(&self) -> String {
+        let mut out = String::from("msgid \"\"\nmsgstr \"\"\n\"Content-Type: text/plain; charset=UTF-8\\n\"\n");
+        for entry in &self.entries {
+            out.push_str(&format!(
+                "\nmsgctxt \"{}\"\nmsgid \"{}\"\nmsgstr \"{}\"\n",
+                escape_po(&entry.key()),
+                escape_po(&entry.source),
+                escape_po(entry.translation.as_deref().unwrap_or_default()),
+            ));
+        }
+        out
+    }
+
+    /// Writes the catalog as an XLIFF 1.2 document with the given source
+    /// language, e.g. `en`. Each entry's key is used as its `trans-unit` id
+    pub fn to_xliff(&self, source_language: &str) -> String {
+        let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        out.push_str("<xliff version=\"1.2\" xmlns=\"urn:oasis:names:tc:xliff:document:1.2\">\n");
+        out.push_str(&format!(
+            "  <file original=\"story\" source-language=\"{}\" datatype=\"plaintext\">\n    <body>\n",
+            escape_xml(source_language)
+        ));
+        for entry in &self.entries {
+            out.push_str(&format!("      <trans-unit id=\"{}\">\n", escape_xml(&entry.key())));
+            out.push_str(&format!("        <source>{}</source>\n", escape_xml(&entry.source)));
+            if let Some(translation) = &entry.translation {
+                out.push_str(&format!("        <target>{}</target>\n", escape_xml(translation)));
+            }
+            out.push_str("      </trans-unit>\n");
+        }
+        out.push_str("    </body>\n  </file>\n</xliff>\n");
+        out
+    }
+
+    /// Writes the catalog as a JSON array of entries
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(&self.entries).expect("Catalog entries are always serializable")
+    }
+
+    /// Reads a catalog written by [`to_json`]
+    ///
+    /// # Errors
+    /// Returns a list holding a [`BadCatalog`] error if the input is not a
+    /// valid catalog
+    ///
+    /// [`to_json`]: #method.to_json
+    /// [`BadCatalog`]: ../enum.ErrorKind.html#variant.BadCatalog
+    pub fn from_json(input: &str) -> Result<Self, ErrorList> {
+        serde_json::from_str(input)
+            .map(|entries| Catalog { entries })
+            .map_err(|e| bad_catalog(e.to_string()))
+    }
+
+    /// Reads a gettext PO file in the form written by [`to_po`]. Entries
+    /// with an empty `msgstr` have no translation
+    ///
+    /// # Errors
+    /// Returns a list holding a [`BadCatalog`] error if the input is not a
+    /// valid catalog
+    ///
+    /// [`to_po`]: #method.to_po
+    /// [`BadCatalog`]: ../enum.ErrorKind.html#variant.BadCatalog
+    pub fn from_po(input: &str) -> Result<Self, ErrorList> {
+        // Each field being read, and its value so far
+        let mut fields: HashMap<&str, String> = HashMap::new();
+        let mut current: Option<&str> = None;
+        let mut entries = Vec::new();
+        for (i, line) in input.lines().chain(std::iter::once("")).enumerate() {
+            let line = line.trim();
+            let (field, value) = if let Some(value) = line.strip_prefix('"') {
+                (current, value)
+            } else if let Some((field, value)) = line.split_once(' ') {
+                if !["msgctxt", "msgid", "msgstr"].contains(&field) {
+                    return Err(bad_catalog(format!("Unknown keyword on line {}", i + 1)));
+                }
+                if field != "msgstr" && fields.contains_key("msgstr") {
+                    push_po_entry(&mut entries, &mut fields)?;
+                }
+                (Some(field), value.strip_prefix('"').unwrap_or(value))
+            } else if line.is_empty() || line.starts_with('#') {
+                push_po_entry(&mut entries, &mut fields)?;
+                current = None;
+                continue;
+            } else {
+                return Err(bad_catalog(format!("Unexpected text on line {}", i + 1)));
+            };
+
+            let field = field.ok_or_else(|| bad_catalog(format!("Unexpected string on line {}", i + 1)))?;
+            let value = value
+                .strip_suffix('"')
+                .ok_or_else(|| bad_catalog(format!("Unterminated string on line {}", i + 1)))?;
+            fields.entry(field).or_default().push_str(&unescape_po(value));
+            current = Some(field);
+        }
+        Ok(Catalog { entries })
+    }
+
+    /// Reads an XLIFF 1.2 document in the form written by [`to_xliff`].
+    /// Units without a `target` have no translation
+    ///
+    /// # Errors
+    /// Returns a list holding a [`BadCatalog`] error if the input is not a
+    /// valid catalog
+    ///
+    /// [`to_xliff`]: #method.to_xliff
+    /// [`BadCatalog`]: ../enum.ErrorKind.html#variant.BadCatalog
+    pub fn from_xliff(input: &str) -> Result<Self, ErrorList> {
+        let mut entries = Vec::new();
+        let mut rest = input;
+        while let Some(start) = rest.find("<trans-unit") {
+            let unit = &rest[start..];
+            let end = unit
+                .find("</trans-unit>")
+                .ok_or_else(|| bad_catalog("Unclosed trans-unit".to_string()))?;
+            let unit = &unit[..end];
+            rest = &rest[start + end..];
+
+            let id = unit
+                .split_once("id=\"")
+                .and_then(|(_, s)| s.split_once('"'))
+                .map(|(id, _)| unescape_xml(id))
+                .ok_or_else(|| bad_catalog("trans-unit without an id".to_string()))?;
+            let source = xml_element(unit, "source")
+                .ok_or_else(|| bad_catalog(format!("trans-unit {} has no source", id)))?;
+            let (passage, segment) = parse_key(&id)?;
+            entries.push(CatalogEntry {
+                passage,
+                segment,
+                source,
+                translation: xml_element(unit, "target"),
+            });
+        }
+        Ok(Catalog { entries })
+    }
+
+    /// Produces a new story with the prose of each passage of `story`
+    /// replaced by its translation from this catalog. Links, macros, and
+    /// markup are left intact, as are passage names, so links still resolve
+    ///
+    /// Each source file of the story is rewritten and parsed again, so the
+    /// positions in the new story refer to the translated text. Any line
+    /// breaks in a translation are replaced by spaces. The story is parsed
+    /// with default options apart from its special tags; use
+    /// [`translate_with_options`] to parse it the way the original was.
+    ///
+    /// # Errors
+    /// Any errors from parsing the translated text, such as warnings denied by
+    /// the options it's parsed with
+    ///
+    /// # Warnings
+    /// * [`MissingTranslation`] - A segment has no translation; the original
+    ///   text is kept
+    /// * [`StaleTranslation`] - A translation's source text no longer matches
+    ///   the story, or its segment no longer exists; the translation is not
+    ///   used
+    /// * [`UnsafeTranslation`] - A translation contains markup, such as a link,
+    ///   macro, HTML tag, or passage header; the original text is kept
+    ///
+    /// # Examples
+    /// ```
+    /// use tweep::{PassageContent, StoryPassages};
+    /// use tweep::i18n::Catalog;
+    /// let input = ":: Start\nHello! [[Leave->End]]\n\n:: End\nGoodbye".to_string();
+    /// let story = StoryPassages::from_string(input).take().0.ok().unwrap();
+    /// let mut catalog = Catalog::extract(&story);
+    /// for entry in catalog.entries.iter_mut() {
+    ///     entry.translation = match entry.source.as_str() {
+    ///         "Hello!" => Some("Bonjour !".to_string()),
+    ///         "Leave" => Some("Partir".to_string()),
+    ///         _ => None,
+    ///     };
+    /// }
+    /// let (translated, warnings) = catalog.translate(&story).take();
+    /// let translated = translated.ok().unwrap();
+    /// assert_eq!(warnings.len(), 1);
+    /// if let PassageContent::Normal(twine) = &translated.passages["Start"].content {
+    ///     assert_eq!(twine.trimmed(), "Bonjour ! [[Partir->End]]");
+    ///     assert_eq!(twine.get_links()[0].target, "End");
+    /// }
+    /// # else { panic!(); }
+    /// ```
+    ///
+    /// [`MissingTranslation`]: ../enum.WarningKind.html#variant.MissingTranslation
+    /// [`StaleTranslation`]: ../enum.WarningKind.html#variant.StaleTranslation
+    /// [`UnsafeTranslation`]: ../enum.WarningKind.html#variant.UnsafeTranslation
+    /// [`translate_with_options`]: #method.translate_with_options
+    pub fn translate(&self, story: &StoryPassages) -> Output<Result<StoryPassages, ErrorList>> {
+        self.translate_with_options(story, &ParseOptions::default())
+    }
+
+    /// Produces a new story like [`translate`], parsing the translated text
+    /// with the given options. These should be the options the original story
+    /// was parsed with. The story's special tags are always registered, so
+    /// its tagged passages stay out of the normal passage map. As when
+    /// parsing, author notes, passages excluded by the build profile and the
+    /// configuration passage are set aside
+    ///
+    /// # Examples
+    /// ```
+    /// use tweep::{ParseOptions, StoryPassages, WarningKind};
+    /// use tweep::i18n::Catalog;
+    /// let input = ":: Start\nHello [[Go->End ]]\n\n:: End\nBye".to_string();
+    /// let options = ParseOptions::new().with_normalize_link_whitespace(true);
+    /// let story = StoryPassages::from_string_with_options(input, &options).take().0.ok().unwrap();
+    /// let mut catalog = Catalog::extract(&story);
+    /// for entry in catalog.entries.iter_mut() {
+    ///     entry.translation = Some(format!("{}!", entry.source));
+    /// }
+    /// catalog.entries[0].translation = Some("::".to_string());
+    /// let (translated, warnings) = catalog.translate_with_options(&story, &options).take();
+    /// assert_eq!(warnings[0].kind, WarningKind::UnsafeTranslation("End#0".to_string()));
+    /// assert_eq!(warnings.len(), 1);
+    /// let translated = translated.ok().unwrap();
+    /// assert!(translated.passages.contains_key("End"));
+    /// // The link whitespace is still normalized, so the link isn't dead
+    /// assert!(!translated.check().iter().any(|w| matches!(w.kind, WarningKind::DeadLink(_))));
+    /// ```
+    ///
+    /// [`translate`]: #method.translate
+    pub fn translate_with_options(
+        &self,
+        story: &StoryPassages,
+        options: &ParseOptions,
+    ) -> Output<Result<StoryPassages, ErrorList>> {
+        let mut warnings = Vec::new();
+        let mut translations: HashMap<(&str, usize), &CatalogEntry> = self
+            .entries
+            .iter()
+            .map(|e| ((e.passage.as_str(), e.segment), e))
+            .collect();
+
+        // Every passage of the story, including those set aside when it was
+        // parsed, so that their sources are parsed again as well
+        let every_passage: Vec<&Passage> = story
+            .all_passages()
+            .chain(story.notes.values())
+            .chain(story.excluded_passages.values())
+            .chain(story.config_passage.iter())
+            .collect();
+
+        // Find the distinct sources of the story, and the edits to make to each
+        let mut sources: Vec<(&FullContext, Vec<Edit>)> = Vec::new();
+        for passage in &every_passage {
+            if !sources.iter().any(|(s, _)| s.shares_source(&passage.context)) {
+                sources.push((&passage.context, Vec::new()));
+            }
+        }
+
+        let mut names: Vec<&String> = story.passages.keys().collect();
+        names.sort();
+        for name in names {
+            let passage = &story.passages[name];
+            let twine = match &passage.content {
+                PassageContent::Normal(twine) => twine,
+                _ => continue,
+            };
+            let body_start = match passage.context.get_contents().find('\n') {
+                Some(pos) => passage.context.byte_offset(Position::rel(1, 1)) + pos + 1,
+                None => continue,
+            };
+            let edits = &mut sources
+                .iter_mut()
+                .find(|(s, _)| s.shares_source(&passage.context))
+                .expect("Every passage's source was collected")
+                .1;
+            let text = twine.trimmed();
+            for (segment, range) in twine.prose_segments().into_iter().enumerate() {
+                let key = format!("{}#{}", name, segment);
+                let context = passage.context.position_at(body_start + range.start);
+                let context = passage.context.subcontext(context..=passage.context.position_at(body_start + range.end - 1));
+                match translations.remove(&(name.as_str(), segment)) {
+                    Some(entry) if entry.source != text[range.clone()] => {
+                        warnings.push(Warning::new(WarningKind::StaleTranslation(key), Some(context)));
+                    }
+                    Some(CatalogEntry { translation: Some(translation), .. }) => {
+                        let translation = translation.replace(['\r', '\n'], " ");
+                        if has_markup(&translation) {
+                            warnings.push(Warning::new(WarningKind::UnsafeTranslation(key), Some(context)));
+                            continue;
+                        }
+                        let range = body_start + range.start..body_start + range.end;
+                        edits.push((range, translation));
+                    }
+                    _ => warnings.push(Warning::new(WarningKind::MissingTranslation(key), Some(context))),
+                }
+            }
+        }
+
+        // Any remaining translations refer to segments that no longer exist
+        let mut leftover: Vec<&CatalogEntry> = translations.into_values().collect();
+        leftover.sort_by(|a, b| (&a.passage, a.segment).cmp(&(&b.passage, b.segment)));
+        for entry in leftover {
+            warnings.push(Warning::new::<FullContext>(WarningKind::StaleTranslation(entry.key()), None));
+        }
+
+        // Lines are never added or removed, so each passage can be matched
+        // with its original by file and line
+        let originals: HashMap<(Option<&Path>, usize), &Passage> = every_passage
+            .iter()
+            .map(|p| ((p.context.get_file_path(), p.context.get_start_position().line), *p))
+            .collect();
+
+        // Passages with registered special tags must be recognized again
+        let options = &ParseOptions { special_tags: story.special_tags.clone(), ..options.clone() };
+        let mut translated: Option<StoryPassages> = None;
+        let mut parse_warnings = Vec::new();
+        let mut errors = ErrorList::default();
+        for (context, mut edits) in sources {
+            let mut text = context.get_source().to_string();
+            edits.sort_by_key(|(range, _)| range.start);
+            for (range, translation) in edits.into_iter().rev() {
+                text.replace_range(range, &translation);
+            }
+            let path = context.get_file_path().map(Path::to_path_buf);
+            let new_context = FullContext::from_file_path(path, text);
+            // Warnings are left out, as they were already reported for the
+            // original story
+            let mut sub_story = match StoryPassages::from_context(new_context, options).take().0 {
+                Ok(sub_story) => sub_story,
+                Err(e) => {
+                    #[cfg(feature = "full-context")]
+                    let e = e.error_list;
+                    e.errors.into_iter().for_each(|error| errors.push(error));
+                    continue;
+                }
+            };
+
+            // Restore the original names, which may have been namespaced
+            let passages = std::mem::take(&mut sub_story.passages);
+            sub_story.passages = passages
+                .into_values()
+                .map(|mut passage| {
+                    let key = (passage.context.get_file_path(), passage.context.get_start_position().line);
+                    if let Some(original) = originals.get(&key) {
+                        passage.header.name = original.header.name.clone();
+                    }
+                    (passage.header.name.clone(), passage)
+                })
+                .collect();
+
+            match &mut translated {
+                Some(story) => parse_warnings.append(&mut story.merge_from(sub_story)),
+                None => translated = Some(sub_story),
+            }
+        }
+
+        if !errors.is_empty() {
+            return Output::new(Err(errors)).with_warnings(warnings);
+        }
+
+        // Set aside notes, excluded passages and the configuration as parsing
+        // the original did. Only denied warnings are reported, as the rest
+        // were already reported for the original story
+        let mut translated = translated.unwrap_or_default();
+        translated.preprocess(options, None, &mut parse_warnings);
+        let mut translated = match translated.deny_duplicates(&mut parse_warnings) {
+            Ok(translated) => translated,
+            Err(e) => {
+                #[cfg(feature = "full-context")]
+                let e = e.error_list;
+                return Output::new(Err(e)).with_warnings(warnings);
+            }
+        };
+
+        // Restore the original pids, which merging files renumbers
+        for passage in translated.passages.values_mut() {
+            let original = story.passages.get(&passage.header.name).map(|p| &p.content);
+            if let (PassageContent::Normal(new), Some(PassageContent::Normal(old))) = (&mut passage.content, original) {
+                new.pid = old.pid;
+            }
+        }
+
+        translated.namespaced = story.namespaced;
+        translated.link_resolver = story.link_resolver.clone();
+        translated.special_tags = story.special_tags.clone();
+        Output::new(Ok(translated)).with_warnings(warnings)
+    }
+}
+
+/// Returns `true` if a translation contains markup that would be parsed as
+/// something other than prose: link, macro, or HTML syntax, or a `::` that
+/// would start a new passage
+fn has_markup(translation: &str) -> bool {
+    const MARKUP: [&str; 7] = ["[[", "]]", "<<", ">>", "|", "->", "<-"];
+    translation.trim_start().starts_with("::")
+        || MARKUP.iter().any(|m| translation.contains(m))
+        || translation
+            .match_indices('<')
+            .any(|(i, _)| translation[i + 1..].starts_with(|c: char| c.is_ascii_alphabetic() || c == '/' || c == '!'))
+}
+
+/// A replacement of a byte range of a source with a translation
+type Edit = (Range<usize>, String);
+
+/// Creates a list holding a [`BadCatalog`] error with the given message
+///
+/// [`BadCatalog`]: ../enum.ErrorKind.html#variant.BadCatalog
+fn bad_catalog(message: String) -> ErrorList {
+    Error::new::<FullContext>(ErrorKind::BadCatalog(message), None).into()
+}
+
+/// Splits an entry key of the form `passage#segment` into its parts
+fn parse_key(key: &str) -> Result<(String, usize), ErrorList> {
+    key.rsplit_once('#')
+        .and_then(|(passage, segment)| Some((passage.to_string(), segment.parse().ok()?)))
+        .ok_or_else(|| bad_catalog(format!("Invalid key {}", key)))
+}
+
+/// Adds an entry built from the fields read from a PO file, if any, to
+/// `entries`. The PO header entry, which has an empty `msgid`, is skipped
+fn push_po_entry(entries: &mut Vec<CatalogEntry>, fields: &mut HashMap<&str, String>) -> Result<(), ErrorList> {
+    let fields = std::mem::take(fields);
+    let source = match fields.get("msgid") {
+        Some(source) if !source.is_empty() => source.clone(),
+        _ => return Ok(()),
+    };
+    let key = fields
+        .get("msgctxt")
+        .ok_or_else(|| bad_catalog(format!("No msgctxt for {}", source)))?;
+    let (passage, segment) = parse_key(key)?;
+    entries.push(CatalogEntry {
+        passage,
+        segment,
+        source,
+        translation: fields.get("msgstr").filter(|s| !s.is_empty()).cloned(),
+    });
+    Ok(())
+}
+
+/// Escapes a string for use within double quotes in a PO file
+fn escape_po(input: &str) -> String {
+    let mut out = String::with_capacity(input.len());
+    for c in input.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '"' => out.push_str("\\\""),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Reverses `escape_po`
+fn unescape_po(input: &str) -> String {
+    let mut out = String::with_capacity(input.len());
+    let mut chars = input.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            match chars.next() {
+                Some('n') => out.push('\n'),
+                Some('t') => out.push('\t'),
+                Some(c) => out.push(c),
+                None => (),
+            }
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// Gets the unescaped text of the first element named `name` in `input`
+fn xml_element(input: &str, name: &str) -> Option<String> {
+    let open = format!("<{}", name);
+    let close = format!("</{}>", name);
+    let (_, rest) = input.split_once(&open)?;
+    // Skip any attributes
+    let (_, rest) = rest.split_once('>')?;
+    let (text, _) = rest.split_once(&close)?;
+    Some(unescape_xml(text))
+}
+
+/// Reverses `escape_xml`
+fn unescape_xml(input: &str) -> String {
+    input
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+/// Escapes a string for use as XML text or an attribute value
+fn escape_xml(input: &str) -> String {
+    let mut out = String::with_capacity(input.len());
+    for c in input.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BuildProfile;
+
+    fn catalog() -> Catalog {
+        let input = ":: Start\n<b>Say \"hi\"</b> & [[go->Next]]\n\n:: Next\nThe end\n\n:: Code [script]\nvar x;".to_string();
+        let story = StoryPassages::from_string(input).take().0.ok().unwrap();
+        Catalog::extract(&story)
+    }
+
+    #[test]
+    fn extract() {
+        let entries = catalog().entries;
+        let keys: Vec<String> = entries.iter().map(|e| e.key()).collect();
+        assert_eq!(keys, vec!["Next#0", "Start#0", "Start#1", "Start#2"]);
+        let sources: Vec<&str> = entries.iter().map(|e| e.source.as_str()).collect();
+        assert_eq!(sources, vec!["The end", "Say \"hi\"", "&", "go"]);
+    }
+
+    #[test]
+    fn formats() {
+        let mut catalog = catalog();
+        catalog.entries[1].translation = Some("Dis \"salut\"".to_string());
+
+        let po = catalog.to_po();
+        assert!(po.contains("msgctxt \"Start#0\"\nmsgid \"Say \\\"hi\\\"\"\nmsgstr \"Dis \\\"salut\\\"\"\n"));
+
+        let xliff = catalog.to_xliff("en");
+        assert!(xliff.contains("source-language=\"en\""));
+        assert!(xliff.contains("<trans-unit id=\"Start#1\">\n        <source>&amp;</source>\n      </trans-unit>"));
+        assert!(xliff.contains("<target>Dis &quot;salut&quot;</target>"));
+
+        let json: Vec<CatalogEntry> = serde_json::from_str(&catalog.to_json()).unwrap();
+        assert_eq!(json, catalog.entries);
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut catalog = catalog();
+        catalog.entries[0].translation = Some("La fin\t\\ \"<&>\"".to_string());
+        catalog.entries[3].translation = Some("aller".to_string());
+        assert_eq!(Catalog::from_po(&catalog.to_po()).unwrap(), catalog);
+        assert_eq!(Catalog::from_xliff(&catalog.to_xliff("en")).unwrap(), catalog);
+        assert_eq!(Catalog::from_json(&catalog.to_json()).unwrap(), catalog);
+
+        let po = "msgctxt \"A#0\"\nmsgid \"\"\n\"Multi \"\n\"line\"\nmsgstr \"Plusieurs\"\n\"\"\n";
+        let entries = Catalog::from_po(po).unwrap().entries;
+        assert_eq!(entries[0].source, "Multi line");
+        assert_eq!(entries[0].translation.as_deref(), Some("Plusieurs"));
+
+        assert!(Catalog::from_po("msgid \"Unterminated").is_err());
+        assert!(Catalog::from_po("msgctxt \"NoSegment\"\nmsgid \"x\"\nmsgstr \"\"").is_err());
+        assert!(Catalog::from_xliff("<trans-unit id=\"A#0\"><source>x</source>").is_err());
+        assert!(Catalog::from_json("{}").is_err());
+    }
+
+    fn text_of(story: &StoryPassages, name: &str) -> String {
+        match &story.passages[name].content {
+            PassageContent::Normal(twine) => twine.trimmed().to_string(),
+            _ => panic!("Expected normal passage"),
+        }
+    }
+
+    #[test]
+    fn translate() {
+        let input = ":: Start\n<b>Say \"hi\"</b> & [[go->Next]]\n\n:: Next\nThe end\n\n:: Code [script]\nvar x;".to_string();
+        let story = StoryPassages::from_string(input).take().0.ok().unwrap();
+        let mut catalog = Catalog::extract(&story);
+        catalog.entries[0].translation = Some("Das\nEnde".to_string());
+        catalog.entries[1].translation = Some("Sag \"hallo\"".to_string());
+        catalog.entries[2].source = "and".to_string();
+        catalog.entries[2].translation = Some("und".to_string());
+        catalog.entries.push(CatalogEntry {
+            passage: "Gone".to_string(),
+            segment: 0,
+            source: "Old".to_string(),
+            translation: Some("Alt".to_string()),
+        });
+
+        let (translated, warnings) = catalog.translate(&story).take();
+        let translated = translated.ok().unwrap();
+        let kinds: Vec<WarningKind> = warnings.into_iter().map(|w| w.kind).collect();
+        assert_eq!(kinds, vec![
+            WarningKind::StaleTranslation("Start#1".to_string()),
+            WarningKind::MissingTranslation("Start#2".to_string()),
+            WarningKind::StaleTranslation("Gone#0".to_string()),
+        ]);
+
+        assert_eq!(text_of(&translated, "Start"), "<b>Sag \"hallo\"</b> & [[go->Next]]");
+        assert_eq!(text_of(&translated, "Next"), "Das Ende");
+        assert_eq!(translated.scripts.len(), 1);
+        assert!(!translated.check().iter().any(|w| matches!(w.kind, WarningKind::DeadLink(_))));
+
+        for unsafe_text in ["::", "  :: Other", "[[Elsewhere]]", "<<set $x to 1>>", "<i>Hi</i>", "a|b", "x->y"] {
+            let mut catalog = Catalog::extract(&story);
+            catalog.entries[0].translation = Some(unsafe_text.to_string());
+            let (translated, warnings) = catalog.translate(&story).take();
+            assert_eq!(warnings[0].kind, WarningKind::UnsafeTranslation("Next#0".to_string()));
+            assert_eq!(text_of(&translated.ok().unwrap(), "Next"), "The end");
+        }
+    }
+
+    #[test]
+    fn translate_preprocesses() {
+        let input = r#":: StoryConfig
+{"special-tags": ["module"]}
+
+:: Start
+Hello [[Continue->Later]]
+
+:: Later [debug]
+Later
+
+:: Aside [notes]
+Remember this
+
+:: Setup [module]
+setup();
+"#
+        .to_string();
+        let options = ParseOptions::new()
+            .with_author_notes(true)
+            .with_profile(BuildProfile::new("release").with_excluded_tag("debug"));
+        let story = StoryPassages::from_string_with_options(input, &options).take().0.ok().unwrap();
+        let mut catalog = Catalog::extract(&story);
+        catalog.entries[0].translation = Some("Bonjour".to_string());
+        catalog.entries[1].translation = Some("Continuer".to_string());
+
+        let (translated, warnings) = catalog.translate_with_options(&story, &options).take();
+        assert_eq!(warnings, vec![]);
+        let translated = translated.ok().unwrap();
+        assert_eq!(text_of(&translated, "Start"), "Bonjour [[Continuer->Later]]");
+        let mut names: Vec<&String> = translated.passages.keys().collect();
+        names.sort();
+        assert_eq!(names, vec!["Start"]);
+        assert!(translated.config_passage.is_some());
+        assert!(translated.notes.contains_key("Aside"));
+        assert!(translated.excluded_passages.contains_key("Later"));
+        assert_eq!(translated.tagged_passages["module"].len(), 1);
+
+        // The story's special tags are recognized even with default options
+        let translated = catalog.translate(&story).take().0.ok().unwrap();
+        assert_eq!(translated.tagged_passages["module"].len(), 1);
+        assert!(!translated.passages.contains_key("Setup"));
+    }
+}
@@ -0,0 +1,40 @@
+use serde::{Deserialize, Serialize};
+
+/// A single translatable segment of passage prose in a [`Catalog`]
+///
+/// [`Catalog`]: struct.Catalog.html
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct CatalogEntry {
+    /// The name of the passage containing the segment
+    pub passage: String,
+
+    /// The index of the segment within the passage, starting at 0
+    pub segment: usize,
+
+    /// The source text of the segment
+    pub source: String,
+
+    /// The translated text of the segment, if any
+    #[serde(default)]
+    pub translation: Option<String>,
+}
+
+impl CatalogEntry {
+    /// Returns the key uniquely identifying this entry within a catalog, in
+    /// the form `passage#segment`
+    ///
+    /// # Examples
+    /// ```
+    /// use tweep::i18n::CatalogEntry;
+    /// let entry = CatalogEntry {
+    ///     passage: "Start".to_string(),
+    ///     segment: 2,
+    ///     source: "Hello".to_string(),
+    ///     translation: None,
+    /// };
+    /// assert_eq!(entry.key(), "Start#2");
+    /// ```
+    pub fn key(&self) -> String {
+        format!("{}#{}", self.passage, self.segment)
+    }
+}
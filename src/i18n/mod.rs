@@ -0,0 +1,16 @@
+//! Extraction of translatable strings from a story into string catalogs, for
+//! teams localizing Twine games through standard translation pipelines.
+//!
+//! The prose of each normal passage is split into segments at markup and line
+//! breaks, so that macros, HTML tags, and link targets are never exposed to
+//! translators. The display text of a link is its own segment. Once
+//! translated, a catalog can be read back and applied to the story with
+//! [`Catalog::translate`].
+//!
+//! [`Catalog::translate`]: struct.Catalog.html#method.translate
+
+mod catalog;
+pub use catalog::Catalog;
+
+mod catalog_entry;
+pub use catalog_entry::CatalogEntry;
@@ -1,17 +1,28 @@
 use crate::ErrorKind;
+use crate::Explanation;
 use crate::Context;
+use crate::TextEdit;
 
 /// An error with an owned [`ErrorKind`] and [`Position`]
 ///
 /// [`ErrorKind`]: enum.ErrorKind.html
 /// [`Position`]: enum.Position.html
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serialize-issues", derive(serde::Serialize))]
 pub struct Error {
     /// The type of error
     pub kind: ErrorKind,
 
     /// The context of the error
     pub context: Option<Context>,
+
+    /// An edit to the source that fixes the error, if one is known. See
+    /// [`FixSession::from_errors`] for applying fixes. Boxed so that results
+    /// carrying errors stay small
+    ///
+    /// [`FixSession::from_errors`]: struct.FixSession.html#method.from_errors
+    #[cfg_attr(feature = "serialize-issues", serde(skip))]
+    pub fix: Option<Box<TextEdit>>,
 }
 
 impl Error {
@@ -33,8 +44,16 @@ impl Error {
         Error {
             kind,
             context: context.map(|t| t.into()),
+            fix: None,
         }
     }
+
+    /// Moves the object, sets the edit that fixes the error, and returns the
+    /// modified object
+    pub fn with_fix(mut self, fix: TextEdit) -> Self {
+        self.fix = Some(Box::new(fix));
+        self
+    }
 }
 
 #[cfg(feature = "issue-names")]
@@ -47,6 +66,28 @@ impl Error {
     }
 }
 
+impl Error {
+    /// Returns a longer [`Explanation`] of this error's [`ErrorKind`]
+    ///
+    /// The explanation is also included when formatting an `Error` with the
+    /// alternate flag (`{:#}`).
+    ///
+    /// # Examples
+    /// ```
+    /// use tweep::{Error, ErrorKind, FullContext};
+    /// # let context = FullContext::from(None, "::".to_string());
+    /// let error = Error::new(ErrorKind::EmptyName, Some(context));
+    /// assert_eq!(error.explanation(), ErrorKind::EmptyName.explanation());
+    /// assert!(format!("{:#}", error).ends_with(&error.explanation().url));
+    /// ```
+    ///
+    /// [`Explanation`]: struct.Explanation.html
+    /// [`ErrorKind`]: enum.ErrorKind.html
+    pub fn explanation(&self) -> Explanation {
+        self.kind.explanation()
+    }
+}
+
 impl std::error::Error for Error {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
         None
@@ -55,6 +96,10 @@ impl std::error::Error for Error {
 
 impl std::fmt::Display for Error {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{} at {:?}", self.kind, self.context)
+        write!(f, "{} at {:?}", self.kind, self.context)?;
+        if f.alternate() {
+            write!(f, "\n{}", self.explanation())?;
+        }
+        Ok(())
     }
 }
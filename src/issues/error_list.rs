@@ -4,6 +4,7 @@ use crate::Error;
 ///
 /// [`Error`]: struct.Error.html
 #[derive(Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serialize-issues", derive(serde::Serialize))]
 pub struct ErrorList {
     /// The list of `Error`s
     pub errors: Vec<Error>,
@@ -52,7 +53,7 @@ impl ErrorList {
     /// * `Ok(())` if both inputs are `Ok`
     /// * The `ErrorList` contained by the `Err` input if one input is `Err`
     /// * The `ErrorList` of `right` appended to the `ErrorList` of `left` if
-    /// both inputs are `Err`
+    ///   both inputs are `Err`
     ///
     /// Note that `T` and `U` do not need to have any relation to each other.
     ///
@@ -125,10 +126,12 @@ impl std::fmt::Display for ErrorList {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let mut res = Ok(());
         for error in &self.errors {
-            res = writeln!(f, "{}", error);
-            if res.is_err() {
-                return res;
-            }
+            res = if f.alternate() {
+                writeln!(f, "{:#}", error)
+            } else {
+                writeln!(f, "{}", error)
+            };
+            res?;
         }
         res
     }
@@ -164,13 +167,13 @@ mod tests {
         let mut errs = ErrorList::default();
         assert!(errs.is_empty());
         errs.push(Error::new(ErrorKind::EmptyName, Some(FullContext::from(None, "::".to_string()))));
-        errs.push(Error::new(ErrorKind::MissingSigil, Some(FullContext::from(None, "Blah".to_string()))));
+        errs.push(Error::new(ErrorKind::MissingSigil("Blah".to_string()), Some(FullContext::from(None, "Blah".to_string()))));
         assert!(!errs.is_empty());
         assert_eq!(
             errs.errors,
             vec![
                 Error::new(ErrorKind::EmptyName, Some(FullContext::from(None, "::".to_string()))),
-                Error::new(ErrorKind::MissingSigil, Some(FullContext::from(None, "Blah".to_string())))
+                Error::new(ErrorKind::MissingSigil("Blah".to_string()), Some(FullContext::from(None, "Blah".to_string())))
             ]
         );
     }
@@ -184,12 +187,12 @@ mod tests {
             ErrorList {
                 errors: vec![Error::new(ErrorKind::EmptyName, Some(FullContext::from(None, "::".to_string())))],
             }
-        };
+        }
         fn error_list_right() -> ErrorList {
             ErrorList {
-                errors: vec![Error::new(ErrorKind::MissingSigil, Some(FullContext::from(None, "Blah".to_string())))],
+                errors: vec![Error::new(ErrorKind::MissingSigil("Blah".to_string()), Some(FullContext::from(None, "Blah".to_string())))],
             }
-        };
+        }
 
         assert!(ErrorList::merge(&mut ok_left, &mut ok_right).is_ok());
 
@@ -214,4 +217,17 @@ mod tests {
         expected.append(&mut error_list_right().errors);
         assert_eq!(errs.errors, expected);
     }
+
+    #[test]
+    #[cfg(feature = "serialize-issues")]
+    fn serialize() {
+        let mut errors = ErrorList::new();
+        errors.push(Error::new(ErrorKind::EmptyName, Some(FullContext::from(None, "::".to_string()))));
+        errors.push(Error::new::<FullContext>(ErrorKind::BadInputPath("a".into(), "b".to_string()), None));
+        let value = serde_json::to_value(&errors).unwrap();
+        assert_eq!(value["errors"][0]["kind"], "EmptyName");
+        assert_eq!(value["errors"][0]["context"]["start_position"]["column"], 1);
+        assert_eq!(value["errors"][1]["kind"], serde_json::json!({ "BadInputPath": ["a", "b"] }));
+        assert_eq!(value["errors"][1]["context"], serde_json::Value::Null);
+    }
 }
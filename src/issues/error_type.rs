@@ -1,5 +1,11 @@
+use crate::Explanation;
+use crate::WarningKind;
+use std::path::PathBuf;
+
 /// An enum of the types of errors that can be generated by `tweep`
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serialize-issues", derive(serde::Serialize))]
+#[non_exhaustive]
 pub enum ErrorKind {
     /// Passage header has no name specified
     EmptyName,
@@ -10,27 +16,54 @@ pub enum ErrorKind {
     /// Passage header has metadata and tags in wrong order
     MetadataBeforeTags,
 
-    /// Passage header is missing sigil `::`
-    MissingSigil,
+    /// Passage header is missing sigil `::`. Contains the text found where
+    /// the sigil was expected, up to the first whitespace
+    MissingSigil(String),
+
+    /// Passage name has an unescaped `[` character. Contains the passage name
+    /// as written in the header
+    UnescapedOpenSquare(String),
 
-    /// Passage name has an unescaped `[` character
-    UnescapedOpenSquare,
+    /// Passage name has an unescaped `{` character. Contains the passage name
+    /// as written in the header
+    UnescapedOpenCurly(String),
 
-    /// Passage name has an unescaped `{` character
-    UnescapedOpenCurly,
+    /// Passage name has an unescaped `]` character. Contains the passage name
+    /// as written in the header
+    UnescapedCloseSquare(String),
 
-    /// Passage name has an unescaped `]` character
-    UnescapedCloseSquare,
+    /// Passage name has an unescaped `}` character. Contains the passage name
+    /// as written in the header
+    UnescapedCloseCurly(String),
 
-    /// Passage name has an unescaped `}` character
-    UnescapedCloseCurly,
+    /// Passage header has an unclosed tag block. Contains the text of the
+    /// block after its opening `[`
+    UnclosedTagBlock(String),
 
-    /// Passage header has an unclosed tag block
-    UnclosedTagBlock,
+    /// Passage header has an unclosed metadata block. Only generated when
+    /// parsing with [`strict_headers`](struct.ParseOptions.html#structfield.strict_headers)
+    UnclosedMetadataBlock,
 
     /// An error was encountered when attempting to parse from the given [`Path`](std::path::Path).
-    /// Contains the path string and the error string
-    BadInputPath(String, String),
+    /// Contains the path and the error string
+    BadInputPath(PathBuf, String),
+
+    /// A [`Warning`](struct.Warning.html) that was treated as an error due to a
+    /// [`WarningPolicy`](enum.WarningPolicy.html). Contains the kind of the
+    /// original warning
+    DeniedWarning(WarningKind),
+
+    /// Binary story data could not be decoded. Contains a description of the
+    /// problem
+    BadBinaryData(String),
+
+    /// A translation catalog could not be read. Contains a description of the
+    /// problem
+    BadCatalog(String),
+
+    /// The operation was abandoned because its
+    /// [`CancellationToken`](struct.CancellationToken.html) was cancelled
+    Cancelled,
 }
 
 #[cfg(feature = "issue-names")]
@@ -43,17 +76,85 @@ impl ErrorKind {
             ErrorKind::EmptyName => "EmptyName",
             ErrorKind::LeadingWhitespace => "LeadingWhitespace",
             ErrorKind::MetadataBeforeTags => "MetadataBeforeTags",
-            ErrorKind::MissingSigil => "MissingSigil",
-            ErrorKind::UnescapedOpenSquare => "UnescapedOpenSquare",
-            ErrorKind::UnescapedOpenCurly => "UnescapedOpenCurly",
-            ErrorKind::UnescapedCloseSquare => "UnescapedCloseSquare",
-            ErrorKind::UnescapedCloseCurly => "UnescapedCloseCurly",
-            ErrorKind::UnclosedTagBlock => "UnclosedTagBlock",
+            ErrorKind::MissingSigil(_) => "MissingSigil",
+            ErrorKind::UnescapedOpenSquare(_) => "UnescapedOpenSquare",
+            ErrorKind::UnescapedOpenCurly(_) => "UnescapedOpenCurly",
+            ErrorKind::UnescapedCloseSquare(_) => "UnescapedCloseSquare",
+            ErrorKind::UnescapedCloseCurly(_) => "UnescapedCloseCurly",
+            ErrorKind::UnclosedTagBlock(_) => "UnclosedTagBlock",
+            ErrorKind::UnclosedMetadataBlock => "UnclosedMetadataBlock",
             ErrorKind::BadInputPath(_, _) => "BadInputPath",
+            ErrorKind::DeniedWarning(_) => "DeniedWarning",
+            ErrorKind::BadBinaryData(_) => "BadBinaryData",
+            ErrorKind::BadCatalog(_) => "BadCatalog",
+            ErrorKind::Cancelled => "Cancelled",
         }
     }
 }
 
+impl ErrorKind {
+    /// Returns a longer [`Explanation`] of this kind of error and how to fix
+    /// it, along with a URL to its documentation
+    ///
+    /// # Examples
+    /// ```
+    /// use tweep::ErrorKind;
+    /// let explanation = ErrorKind::MissingSigil("Start".to_string()).explanation();
+    /// assert!(explanation.text.contains("::"));
+    /// ```
+    ///
+    /// [`Explanation`]: struct.Explanation.html
+    pub fn explanation(&self) -> Explanation {
+        let (code, text) = match self {
+            ErrorKind::EmptyName => ("EmptyName",
+                "Every passage header must give the passage a name after the `::` sigil. \
+                 Add a name, e.g. `:: My Passage`."),
+            ErrorKind::LeadingWhitespace => ("LeadingWhitespace",
+                "A passage header must begin with the `::` sigil at the very start of the \
+                 line. Remove any spaces or tabs before it."),
+            ErrorKind::MetadataBeforeTags => ("MetadataBeforeTags",
+                "A passage header's tag block (`[...]`) must come before its metadata \
+                 block (`{...}`), e.g. `:: Name [tags] {\"position\":\"100,100\"}`."),
+            ErrorKind::MissingSigil(_) => ("MissingSigil",
+                "A passage header must begin with the `::` sigil."),
+            ErrorKind::UnescapedOpenSquare(_) => ("UnescapedOpenSquare",
+                "`[` begins a passage's tag block, so it must be escaped as `\\[` when \
+                 used within a passage name."),
+            ErrorKind::UnescapedOpenCurly(_) => ("UnescapedOpenCurly",
+                "`{` begins a passage's metadata block, so it must be escaped as `\\{` \
+                 when used within a passage name."),
+            ErrorKind::UnescapedCloseSquare(_) => ("UnescapedCloseSquare",
+                "`]` ends a passage's tag block, so it must be escaped as `\\]` when used \
+                 within a passage name."),
+            ErrorKind::UnescapedCloseCurly(_) => ("UnescapedCloseCurly",
+                "`}` ends a passage's metadata block, so it must be escaped as `\\}` when \
+                 used within a passage name."),
+            ErrorKind::UnclosedTagBlock(_) => ("UnclosedTagBlock",
+                "A passage's tag block was opened with `[` but never closed. Add a \
+                 closing `]` after the last tag."),
+            ErrorKind::UnclosedMetadataBlock => ("UnclosedMetadataBlock",
+                "A passage's metadata block was opened with `{` but never closed. Add a \
+                 closing `}` after the metadata, or escape the `{` as `\\{`."),
+            ErrorKind::BadInputPath(_, _) => ("BadInputPath",
+                "The given file or directory could not be read. Check that it exists and \
+                 that its contents are valid UTF-8."),
+            ErrorKind::DeniedWarning(_) => ("DeniedWarning",
+                "A warning was treated as an error because of the warning policy in use. \
+                 See the explanation of the original warning for details."),
+            ErrorKind::BadBinaryData(_) => ("BadBinaryData",
+                "Binary story data is truncated, corrupt, or was written by an \
+                 incompatible version of tweep. Re-export it from the twee source."),
+            ErrorKind::BadCatalog(_) => ("BadCatalog",
+                "A translation catalog is malformed or isn't in the expected format. \
+                 Check the file against the format written by tweep."),
+            ErrorKind::Cancelled => ("Cancelled",
+                "The operation was cancelled before it finished, so it produced no result. \
+                 Run it again to get one."),
+        };
+        Explanation::new("ErrorKind", code, text)
+    }
+}
+
 impl std::fmt::Display for ErrorKind {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -65,19 +166,74 @@ impl std::fmt::Display for ErrorKind {
                     "Passage header has whitespace before sigil (::)".to_string(),
                 ErrorKind::MetadataBeforeTags =>
                     "Passage header has metadata before tags".to_string(),
-                ErrorKind::MissingSigil => "Passage header missing sigil (::)".to_string(),
-                ErrorKind::UnescapedOpenSquare =>
-                    "Unescaped [ character in passage header".to_string(),
-                ErrorKind::UnescapedOpenCurly =>
-                    "Unescaped { character in passage header".to_string(),
-                ErrorKind::UnescapedCloseSquare =>
-                    "Unescaped ] character in passage header".to_string(),
-                ErrorKind::UnescapedCloseCurly =>
-                    "Unescaped } character in passage header".to_string(),
-                ErrorKind::UnclosedTagBlock => "Unclosed tag block in passage header".to_string(),
+                ErrorKind::MissingSigil(found) =>
+                    format!("Passage header missing sigil (::), found \"{}\"", found),
+                ErrorKind::UnescapedOpenSquare(name) =>
+                    format!("Unescaped [ character in passage header: {}", name),
+                ErrorKind::UnescapedOpenCurly(name) =>
+                    format!("Unescaped {{ character in passage header: {}", name),
+                ErrorKind::UnescapedCloseSquare(name) =>
+                    format!("Unescaped ] character in passage header: {}", name),
+                ErrorKind::UnescapedCloseCurly(name) =>
+                    format!("Unescaped }} character in passage header: {}", name),
+                ErrorKind::UnclosedTagBlock(block) =>
+                    format!("Unclosed tag block in passage header: [{}", block),
+                ErrorKind::UnclosedMetadataBlock =>
+                    "Unclosed metadata block in passage header".to_string(),
                 ErrorKind::BadInputPath(path, err_str) =>
-                    format!("Error opening path {}: {}", path, err_str),
+                    format!("Error opening path {}: {}", path.display(), err_str),
+                ErrorKind::DeniedWarning(kind) => format!("Denied warning: {}", kind),
+                ErrorKind::BadBinaryData(err_str) => format!("Invalid binary story data: {}", err_str),
+                ErrorKind::BadCatalog(err_str) =>
+                    format!("Invalid translation catalog: {}", err_str),
+                ErrorKind::Cancelled => "Operation cancelled".to_string(),
             }
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "issue-names")]
+    #[test]
+    fn explanation_codes() {
+        let kinds = vec![
+            ErrorKind::EmptyName,
+            ErrorKind::LeadingWhitespace,
+            ErrorKind::MetadataBeforeTags,
+            ErrorKind::MissingSigil("x".to_string()),
+            ErrorKind::UnescapedOpenSquare("x".to_string()),
+            ErrorKind::UnescapedOpenCurly("x".to_string()),
+            ErrorKind::UnescapedCloseSquare("x".to_string()),
+            ErrorKind::UnescapedCloseCurly("x".to_string()),
+            ErrorKind::UnclosedTagBlock("x".to_string()),
+            ErrorKind::UnclosedMetadataBlock,
+            ErrorKind::BadInputPath(PathBuf::from("x"), "y".to_string()),
+            ErrorKind::DeniedWarning(WarningKind::LegacyEncoding),
+            ErrorKind::BadBinaryData("x".to_string()),
+            ErrorKind::BadCatalog("x".to_string()),
+            ErrorKind::Cancelled,
+        ];
+        for kind in kinds {
+            assert_eq!(kind.explanation().code, kind.get_name());
+        }
+    }
+
+    #[test]
+    fn payload_display() {
+        assert_eq!(
+            ErrorKind::MissingSigil("An".to_string()).to_string(),
+            "Passage header missing sigil (::), found \"An\""
+        );
+        assert_eq!(
+            ErrorKind::UnescapedCloseCurly("A}b".to_string()).to_string(),
+            "Unescaped } character in passage header: A}b"
+        );
+        assert_eq!(
+            ErrorKind::UnclosedTagBlock("a b".to_string()).to_string(),
+            "Unclosed tag block in passage header: [a b"
+        );
+    }
+}
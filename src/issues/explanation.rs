@@ -0,0 +1,59 @@
+/// A longer explanation of an [`ErrorKind`] or [`WarningKind`], along with a
+/// URL where more documentation can be found
+///
+/// # Examples
+/// ```
+/// use tweep::ErrorKind;
+/// let explanation = ErrorKind::EmptyName.explanation();
+/// assert_eq!(explanation.code, "EmptyName");
+/// assert!(explanation.url.ends_with("enum.ErrorKind.html#variant.EmptyName"));
+/// ```
+///
+/// [`ErrorKind`]: enum.ErrorKind.html
+/// [`WarningKind`]: enum.WarningKind.html
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Explanation {
+    /// The name of the issue variant being explained
+    pub code: &'static str,
+
+    /// The explanation text
+    pub text: &'static str,
+
+    /// A URL pointing to the documentation for the issue
+    pub url: String,
+}
+
+/// The base URL for the tweep API documentation
+const DOCS_URL: &str = "https://docs.rs/tweep/latest/tweep";
+
+impl Explanation {
+    /// Creates a new `Explanation` for the variant `code` of the enum named
+    /// `kind`
+    pub(crate) fn new(kind: &str, code: &'static str, text: &'static str) -> Self {
+        Explanation {
+            code,
+            text,
+            url: format!("{}/enum.{}.html#variant.{}", DOCS_URL, kind, code),
+        }
+    }
+}
+
+impl std::fmt::Display for Explanation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}\nFor more information, see {}", self.text, self.url)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display() {
+        let explanation = Explanation::new("WarningKind", "DeadLink", "Foo.");
+        assert_eq!(
+            format!("{}", explanation),
+            "Foo.\nFor more information, see https://docs.rs/tweep/latest/tweep/enum.WarningKind.html#variant.DeadLink"
+        );
+    }
+}
@@ -0,0 +1,41 @@
+use crate::ErrorKind;
+use crate::WarningKind;
+
+/// The kind of an issue, which is either an error or a warning
+///
+/// # Examples
+/// ```
+/// use tweep::{ErrorKind, IssueKind, WarningKind};
+/// let kind: IssueKind = WarningKind::UnclosedLink("Cellar".to_string()).into();
+/// assert_eq!(kind, IssueKind::Warning(WarningKind::UnclosedLink("Cellar".to_string())));
+/// assert_eq!(IssueKind::from(ErrorKind::EmptyName).to_string(), ErrorKind::EmptyName.to_string());
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum IssueKind {
+    /// The kind of an [`Error`](struct.Error.html)
+    Error(ErrorKind),
+
+    /// The kind of a [`Warning`](struct.Warning.html)
+    Warning(WarningKind),
+}
+
+impl From<ErrorKind> for IssueKind {
+    fn from(kind: ErrorKind) -> Self {
+        IssueKind::Error(kind)
+    }
+}
+
+impl From<WarningKind> for IssueKind {
+    fn from(kind: WarningKind) -> Self {
+        IssueKind::Warning(kind)
+    }
+}
+
+impl std::fmt::Display for IssueKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IssueKind::Error(kind) => write!(f, "{}", kind),
+            IssueKind::Warning(kind) => write!(f, "{}", kind),
+        }
+    }
+}
@@ -7,8 +7,20 @@ pub use error_type::ErrorKind;
 mod error_list;
 pub use error_list::ErrorList;
 
+mod explanation;
+pub use explanation::Explanation;
+
+mod issue_kind;
+pub use issue_kind::IssueKind;
+
+mod severity;
+pub use severity::Severity;
+
 mod warning;
 pub use warning::Warning;
 
 mod warning_type;
 pub use warning_type::WarningKind;
+
+mod warning_policy;
+pub use warning_policy::WarningPolicy;
@@ -0,0 +1,48 @@
+/// How serious a [`Warning`] is
+///
+/// Severities are ordered from least to most serious. Every [`WarningKind`]
+/// is either a `Note` or a `Warning`; [`Hint`] and [`Error`] are for
+/// applications that present some warnings as more or less serious than
+/// their kinds are, as with [`Output::render_warnings_with`]
+///
+/// # Examples
+/// ```
+/// use tweep::{Severity, WarningKind};
+/// assert_eq!(WarningKind::DeadLink("x".to_string()).severity(), Severity::Warning);
+/// assert_eq!(WarningKind::GeneratedStoryData("x".to_string()).severity(), Severity::Note);
+/// assert!(Severity::Note < Severity::Warning);
+/// assert!(Severity::Warning < Severity::Error);
+/// ```
+///
+/// [`Warning`]: struct.Warning.html
+/// [`WarningKind`]: enum.WarningKind.html
+/// [`Hint`]: #variant.Hint
+/// [`Error`]: #variant.Error
+/// [`Output::render_warnings_with`]: struct.Output.html#method.render_warnings_with
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serialize-issues", derive(serde::Serialize))]
+pub enum Severity {
+    /// A suggestion that's even less pressing than a note
+    Hint,
+
+    /// Information that doesn't need to be acted on, such as a note that a
+    /// missing passage was generated
+    Note,
+
+    /// A likely mistake or bad practice
+    Warning,
+
+    /// A problem that should be treated as an error
+    Error,
+}
+
+impl std::fmt::Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Severity::Hint => write!(f, "hint"),
+            Severity::Note => write!(f, "note"),
+            Severity::Warning => write!(f, "warning"),
+            Severity::Error => write!(f, "error"),
+        }
+    }
+}
@@ -1,5 +1,7 @@
 use crate::Context;
 use crate::WarningKind;
+use crate::Explanation;
+use crate::Severity;
 
 /// A warning with a [`WarningKind`], [`Position`], and optionally a reference
 /// to another [`Position`]
@@ -17,6 +19,7 @@ use crate::WarningKind;
 /// [`WarningKind`]: enum.WarningKind.html
 /// [`Position`]: enum.Position.html
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serialize-issues", derive(serde::Serialize))]
 pub struct Warning {
     /// The warning type
     pub kind: WarningKind,
@@ -52,7 +55,7 @@ impl Warning {
     /// ```
     /// use tweep::{FullContext, Warning, WarningKind};
     /// # let context = FullContext::from(None, String::new());
-    /// let mut warning = Warning::new(WarningKind::UnclosedLink, Some(context));
+    /// let mut warning = Warning::new(WarningKind::UnclosedLink("x".to_string()), Some(context));
     /// assert!(!warning.has_referent());
     /// # let referent = FullContext::from(None, String::new());
     /// warning.set_referent(referent);
@@ -110,6 +113,36 @@ impl Warning {
     }
 }
 
+impl Warning {
+    /// Returns a longer [`Explanation`] of this warning's [`WarningKind`]
+    ///
+    /// The explanation is also included when formatting a `Warning` with the
+    /// alternate flag (`{:#}`).
+    ///
+    /// # Examples
+    /// ```
+    /// use tweep::{FullContext, Warning, WarningKind};
+    /// # let context = FullContext::from(None, String::new());
+    /// let warning = Warning::new(WarningKind::UnclosedLink("x".to_string()), Some(context));
+    /// assert_eq!(warning.explanation().code, "UnclosedLink");
+    /// assert!(format!("{:#}", warning).ends_with(&warning.explanation().url));
+    /// ```
+    ///
+    /// [`Explanation`]: struct.Explanation.html
+    /// [`WarningKind`]: enum.WarningKind.html
+    pub fn explanation(&self) -> Explanation {
+        self.kind.explanation()
+    }
+
+    /// Returns the [`Severity`] of this warning's [`WarningKind`]
+    ///
+    /// [`Severity`]: enum.Severity.html
+    /// [`WarningKind`]: enum.WarningKind.html
+    pub fn severity(&self) -> Severity {
+        self.kind.severity()
+    }
+}
+
 #[cfg(feature = "issue-names")]
 impl Warning {
     /// Gets a string representation of a `Warning`'s `WarningKind` variant name
@@ -123,16 +156,24 @@ impl Warning {
 impl std::fmt::Display for Warning {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let cause = if self.has_referent() {
+            // Without full-context, the referent is already a PartialContext
+            #[allow(clippy::useless_conversion)]
             let p: crate::PartialContext = self.get_referent().unwrap().clone().into();
             format!(", caused by: {}", p)
         } else {
             String::new()
         };
-        write!(f, "{} at {:?}{}", self.kind, self.context, cause)
+        write!(f, "{} at {:?}{}", self.kind, self.context, cause)?;
+        if f.alternate() {
+            write!(f, "\n{}", self.explanation())?;
+        }
+        Ok(())
     }
 }
 
 #[cfg(test)]
+// Contexts only need converting without full-context
+#[allow(clippy::useless_conversion)]
 mod tests {
     use super::*;
     use crate::FullContext;
@@ -140,7 +181,7 @@ mod tests {
     #[test]
     fn incremental() {
         let context: Context = FullContext::from(None, "[[".to_string()).into();
-        let mut warning = Warning::new(WarningKind::UnclosedLink, Some(context));
+        let mut warning = Warning::new(WarningKind::UnclosedLink("x".to_string()), Some(context));
         assert!(!warning.has_referent());
         assert!(warning.get_referent().is_none());
 
@@ -154,17 +195,28 @@ mod tests {
     fn unchanged_referent() {
         let context = FullContext::from(None, "[[".to_string());
         let ref_context = FullContext::from(None, "foo bar".to_string());
-        let warning = Warning::new(WarningKind::UnclosedLink, Some(context))
+        let warning = Warning::new(WarningKind::UnclosedLink("x".to_string()), Some(context))
             .with_referent(ref_context.clone());
         // Prove changing the Warning's Position doesn't change the referent
         assert_eq!(warning.get_referent(), Some(&ref_context.into()));
     }
 
+    #[test]
+    #[cfg(feature = "serialize-issues")]
+    fn serialize() {
+        let context = FullContext::from(None, "[[foo]]".to_string());
+        let warning = Warning::new(WarningKind::DeadLink("foo".to_string()), Some(context));
+        let value = serde_json::to_value(&warning).unwrap();
+        assert_eq!(value["kind"], serde_json::json!({ "DeadLink": "foo" }));
+        assert_eq!(value["context"]["start_position"]["line"], 1);
+        assert_eq!(value["referent"], serde_json::Value::Null);
+    }
+
     #[test]
     #[cfg(feature = "issue-names")]
     fn test_name() {
         let context = FullContext::from(None, "[[".to_string());
-        let warning = Warning::new(WarningKind::UnclosedLink, Some(context));
+        let warning = Warning::new(WarningKind::UnclosedLink("x".to_string()), Some(context));
         assert_eq!(warning.get_name(), "UnclosedLink");
     }
 }
@@ -0,0 +1,53 @@
+use crate::Warning;
+
+/// Controls which [`Warning`]s are treated as errors by
+/// [`Output::into_result_with`]
+///
+/// Warnings are selected by their code, which is the name of their
+/// [`WarningKind`] variant, as given by [`Explanation::code`].
+///
+/// # Examples
+/// ```
+/// use tweep::{FullContext, Warning, WarningKind, WarningPolicy};
+/// # let context = FullContext::from(None, String::new());
+/// let warning = Warning::new(WarningKind::DeadLink("x".to_string()), Some(context));
+/// assert!(!WarningPolicy::Allow.denies(&warning));
+/// assert!(WarningPolicy::Deny.denies(&warning));
+/// assert!(WarningPolicy::DenyCodes(vec!["DeadLink".to_string()]).denies(&warning));
+/// assert!(!WarningPolicy::AllowCodes(vec!["DeadLink".to_string()]).denies(&warning));
+/// ```
+///
+/// [`Warning`]: struct.Warning.html
+/// [`WarningKind`]: enum.WarningKind.html
+/// [`Output::into_result_with`]: struct.Output.html#method.into_result_with
+/// [`Explanation::code`]: struct.Explanation.html#structfield.code
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub enum WarningPolicy {
+    /// No warnings are treated as errors. This is the default
+    #[default]
+    Allow,
+
+    /// All warnings are treated as errors
+    Deny,
+
+    /// Only warnings with the contained codes are treated as errors
+    DenyCodes(Vec<String>),
+
+    /// All warnings except those with the contained codes are treated as errors
+    AllowCodes(Vec<String>),
+}
+
+impl WarningPolicy {
+    /// Returns `true` if this policy treats the given [`Warning`] as an error
+    ///
+    /// [`Warning`]: struct.Warning.html
+    pub fn denies(&self, warning: &Warning) -> bool {
+        let code = || warning.explanation().code;
+        match self {
+            WarningPolicy::Allow => false,
+            WarningPolicy::Deny => true,
+            WarningPolicy::DenyCodes(codes) => codes.iter().any(|c| c == code()),
+            WarningPolicy::AllowCodes(codes) => !codes.iter().any(|c| c == code()),
+        }
+    }
+}
@@ -1,17 +1,27 @@
+use crate::Explanation;
+use crate::Severity;
+use std::path::PathBuf;
+
 /// An enum of the types of warnings that can be produced by `tweep`
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serialize-issues", derive(serde::Serialize))]
+#[non_exhaustive]
 pub enum WarningKind {
-    /// `\[` in a passage title
-    EscapedOpenSquare,
+    /// `\[` in a passage title. Contains the passage name as written in the
+    /// header
+    EscapedOpenSquare(String),
 
-    /// `\]` in a passage title
-    EscapedCloseSquare,
+    /// `\]` in a passage title. Contains the passage name as written in the
+    /// header
+    EscapedCloseSquare(String),
 
-    /// `\{` in a passage title
-    EscapedOpenCurly,
+    /// `\{` in a passage title. Contains the passage name as written in the
+    /// header
+    EscapedOpenCurly(String),
 
-    /// `\}` in a passage title
-    EscapedCloseCurly,
+    /// `\}` in a passage title. Contains the passage name as written in the
+    /// header
+    EscapedCloseCurly(String),
 
     /// Error encountered while parsing JSON. Contains the text of the error
     JsonError(String),
@@ -28,11 +38,13 @@ pub enum WarningKind {
     /// No `StoryData` passage parsed while parsing a [`Story`](struct.Story.html)
     MissingStoryData,
 
-    /// Encountered a link in a [`TwineContent`](struct.TwineContent.html) passage that was unterminated
-    UnclosedLink,
+    /// Encountered a link in a [`TwineContent`](struct.TwineContent.html) passage that was unterminated.
+    /// Contains the target the link appears to point to
+    UnclosedLink(String),
 
-    /// Encountered errant whitespace in a Twine link (e.g., `[[Text | Link]]`)
-    WhitespaceInLink,
+    /// Encountered errant whitespace in a Twine link (e.g., `[[Text | Link]]`).
+    /// Contains the link target, including the whitespace
+    WhitespaceInLink(String),
 
     /// Encountered a link to a passage name that does not match any parsed
     /// passage. Contains the passage name content of the dead link.
@@ -46,6 +58,164 @@ pub enum WarningKind {
 
     /// Encountered a duplicated passage name
     DuplicatePassage(String),
+
+    /// Two files parsed into the same story both define a passage with the same
+    /// name. Contains the passage name
+    DuplicatePassageAcrossFiles(String),
+
+    /// Both sides of a three-way merge changed the same passage in different
+    /// ways. Contains the name of the conflicting passage
+    MergeConflict(String),
+
+    /// Tags separated by commas rather than whitespace. Only generated when
+    /// parsing in [`lenient`](struct.ParseOptions.html#structfield.lenient) mode.
+    /// Contains the separator found
+    LegacyTagSeparator(char),
+
+    /// Text that appears to be in the Windows-1252 encoding was converted. Only
+    /// generated when parsing in [`lenient`](struct.ParseOptions.html#structfield.lenient) mode
+    LegacyEncoding,
+
+    /// A finding reported by a [`ProseChecker`](trait.ProseChecker.html), such as a
+    /// spelling mistake. Contains the message from the checker
+    ProseIssue(String),
+
+    /// A segment of passage prose has no translation in the catalog being applied.
+    /// Contains the key of the segment
+    MissingTranslation(String),
+
+    /// A translation in the catalog being applied was made from different source
+    /// text than the story now contains. Contains the key of the segment
+    StaleTranslation(String),
+
+    /// A translation in the catalog being applied contains markup, such as a link
+    /// or a passage header, that would change the structure of the story.
+    /// Contains the key of the segment
+    UnsafeTranslation(String),
+
+    /// A construct that can't be expressed in the format a story is being exported
+    /// to was found. Contains a description of the construct
+    UnsupportedExport(String),
+
+    /// An occurrence found by a find-and-replace couldn't be replaced without
+    /// affecting text outside the requested scope. Contains the name of the
+    /// passage with the occurrence
+    AmbiguousReplacement(String),
+
+    /// A `<` or `&` in passage prose that most story formats will interpret as HTML,
+    /// such as an unclosed tag or a character entity. Contains the offending text
+    UnescapedHtml(String),
+
+    /// A link target that doesn't match any passage as written, but does once
+    /// trailing punctuation is removed, such as `[[Go->Hall.]]` when there is a
+    /// `Hall` passage. Contains the target as written and the matching passage name
+    TrimmedLinkMatch(String, String),
+
+    /// A tag containing a character that suggests it was meant to be several tags,
+    /// such as `my,tag` or `foo/bar`. Contains the tag
+    SuspiciousTag(String),
+
+    /// An asset referenced by a passage, such as an image, doesn't exist relative to
+    /// the project root. Contains the path of the asset
+    MissingAsset(String),
+
+    /// A tag that looks like it was meant to be the special `script` or `stylesheet`
+    /// tag, such as `Script` or `styles`, but isn't recognized, so the passage is
+    /// treated as a normal passage. Contains the tag and the special tag it resembles
+    UnknownSpecialTag(String, String),
+
+    /// A template instantiation, such as `{{greeting name=Ann}}`, that names a template
+    /// which doesn't exist. The instantiation is left unexpanded. Contains the name of the
+    /// template
+    UnknownTemplate(String),
+
+    /// A template instantiation that doesn't provide an argument used by the template.
+    /// The placeholder is replaced with nothing. Contains the name of the template and of
+    /// the missing argument
+    MissingTemplateArgument(String, String),
+
+    /// A link to a passage that was excluded by the active build profile, such as a
+    /// link to a passage tagged `debug` in a release build. Contains the link target and
+    /// the name of the profile
+    ExcludedLink(String, String),
+
+    /// A note that no `StoryTitle` passage was found, so one was generated from the name
+    /// of the parsed file or directory, as enabled by
+    /// [`ParseOptions::fallback_title`](struct.ParseOptions.html#structfield.fallback_title).
+    /// Contains the generated title
+    GeneratedStoryTitle(String),
+
+    /// A note that no `StoryData` passage was found, so one was generated with a new IFID,
+    /// as enabled by
+    /// [`ParseOptions::generate_story_data`](struct.ParseOptions.html#structfield.generate_story_data).
+    /// Contains the generated IFID
+    GeneratedStoryData(String),
+
+    /// A name listed in
+    /// [`ParseOptions::dynamic_targets`](struct.ParseOptions.html#structfield.dynamic_targets)
+    /// as a possible target of a dynamic link, but no passage with that name exists. Contains
+    /// the listed name
+    DeadDynamicTarget(String),
+
+    /// A link from an exported passage to a passage left out of exports by the story's
+    /// [`ExportFilter`](struct.ExportFilter.html). Contains the link target
+    UnexportedLink(String),
+
+    /// An image with no alternative text for screen readers, such as an `<img>` tag without an
+    /// `alt` attribute or `[img[picture.png]]`. Contains the image markup
+    MissingAltText(String),
+
+    /// A link whose display text, such as "here" or "click", doesn't describe where it leads.
+    /// Contains the display text
+    VagueLinkText(String),
+
+    /// Prose that identifies something by its color alone, such as "the red link". Contains the
+    /// phrase
+    ColorOnlyReference(String),
+
+    /// A passage declares a field registered in the
+    /// [`MetadataRegistry`](struct.MetadataRegistry.html) being used with a value of the
+    /// wrong type, or one rejected by its validator. Contains the field and the problem
+    InvalidMetadata(String, String),
+
+    /// Two passage names that differ only by case or whitespace, or by a single edit, which
+    /// usually means one of them is an accidental near-duplicate. Contains the later name and
+    /// the earlier one
+    SimilarPassageNames(String, String),
+
+    /// A twee file in a parsed directory that was skipped because of the
+    /// [`exclude`](struct.ParseOptions.html#structfield.exclude) patterns or filter. Only
+    /// generated when [`report_orphan_files`](struct.ParseOptions.html#structfield.report_orphan_files)
+    /// is enabled. Contains the path of the file
+    ExcludedFile(PathBuf),
+
+    /// A directory given to a parse method in which no passages were found, because it has no
+    /// twee files or only excluded or empty ones. Only
+    /// generated when [`report_orphan_files`](struct.ParseOptions.html#structfield.report_orphan_files)
+    /// is enabled. Contains the path of the directory
+    UnmatchedPath(PathBuf),
+
+    /// A `StoryData` passage with fields that aren't part of the Twee 3 specification,
+    /// such as ones added by other tools. The fields are kept in
+    /// [`StoryData::unknown`](struct.StoryData.html#structfield.unknown). Contains the
+    /// names of the fields, in sorted order
+    UnknownStoryDataKeys(Vec<String>),
+}
+
+impl WarningKind {
+    /// Returns the [`Severity`] of this kind of warning. Notes about passages
+    /// generated at the user's request are [`Severity::Note`]; every other
+    /// kind is a [`Severity::Warning`]
+    ///
+    /// [`Severity`]: enum.Severity.html
+    /// [`Severity::Note`]: enum.Severity.html#variant.Note
+    /// [`Severity::Warning`]: enum.Severity.html#variant.Warning
+    pub fn severity(&self) -> Severity {
+        match self {
+            WarningKind::GeneratedStoryTitle(_) | WarningKind::GeneratedStoryData(_) => Severity::Note,
+            _ => Severity::Warning,
+        }
+    }
 }
 
 #[cfg(feature = "issue-names")]
@@ -55,39 +225,255 @@ impl WarningKind {
     /// Enabled with "issue-names" feature
     pub fn get_name(&self) -> &str {
         match self {
-            WarningKind::EscapedOpenSquare => "EscapedOpenSquare",
-            WarningKind::EscapedCloseSquare => "EscapedCloseSquare",
-            WarningKind::EscapedOpenCurly => "EscapedOpenCurly",
-            WarningKind::EscapedCloseCurly => "EscapedCloseCurly",
+            WarningKind::EscapedOpenSquare(_) => "EscapedOpenSquare",
+            WarningKind::EscapedCloseSquare(_) => "EscapedCloseSquare",
+            WarningKind::EscapedOpenCurly(_) => "EscapedOpenCurly",
+            WarningKind::EscapedCloseCurly(_) => "EscapedCloseCurly",
             WarningKind::JsonError(_) => "JsonError",
             WarningKind::DuplicateStoryData => "DuplicateStoryData",
             WarningKind::DuplicateStoryTitle => "DuplicateStoryTitle",
             WarningKind::MissingStoryData => "MissingStoryData",
             WarningKind::MissingStoryTitle => "MissingStoryTitle",
-            WarningKind::UnclosedLink => "UnclosedLink",
-            WarningKind::WhitespaceInLink => "WhitespaceInLink",
+            WarningKind::UnclosedLink(_) => "UnclosedLink",
+            WarningKind::WhitespaceInLink(_) => "WhitespaceInLink",
             WarningKind::DeadLink(_) => "DeadLink",
             WarningKind::MissingStartPassage => "MissingStartPassage",
             WarningKind::DeadStartPassage(_) => "DeadStartPassage",
             WarningKind::DuplicatePassage(_) => "DuplicatePassage",
+            WarningKind::DuplicatePassageAcrossFiles(_) => "DuplicatePassageAcrossFiles",
+            WarningKind::MergeConflict(_) => "MergeConflict",
+            WarningKind::LegacyTagSeparator(_) => "LegacyTagSeparator",
+            WarningKind::LegacyEncoding => "LegacyEncoding",
+            WarningKind::ProseIssue(_) => "ProseIssue",
+            WarningKind::MissingTranslation(_) => "MissingTranslation",
+            WarningKind::StaleTranslation(_) => "StaleTranslation",
+            WarningKind::UnsafeTranslation(_) => "UnsafeTranslation",
+            WarningKind::UnsupportedExport(_) => "UnsupportedExport",
+            WarningKind::AmbiguousReplacement(_) => "AmbiguousReplacement",
+            WarningKind::UnescapedHtml(_) => "UnescapedHtml",
+            WarningKind::TrimmedLinkMatch(_, _) => "TrimmedLinkMatch",
+            WarningKind::SuspiciousTag(_) => "SuspiciousTag",
+            WarningKind::MissingAsset(_) => "MissingAsset",
+            WarningKind::UnknownSpecialTag(_, _) => "UnknownSpecialTag",
+            WarningKind::UnknownTemplate(_) => "UnknownTemplate",
+            WarningKind::MissingTemplateArgument(_, _) => "MissingTemplateArgument",
+            WarningKind::ExcludedLink(_, _) => "ExcludedLink",
+            WarningKind::GeneratedStoryTitle(_) => "GeneratedStoryTitle",
+            WarningKind::GeneratedStoryData(_) => "GeneratedStoryData",
+            WarningKind::DeadDynamicTarget(_) => "DeadDynamicTarget",
+            WarningKind::UnexportedLink(_) => "UnexportedLink",
+            WarningKind::MissingAltText(_) => "MissingAltText",
+            WarningKind::VagueLinkText(_) => "VagueLinkText",
+            WarningKind::ColorOnlyReference(_) => "ColorOnlyReference",
+            WarningKind::InvalidMetadata(_, _) => "InvalidMetadata",
+            WarningKind::SimilarPassageNames(_, _) => "SimilarPassageNames",
+            WarningKind::ExcludedFile(_) => "ExcludedFile",
+            WarningKind::UnmatchedPath(_) => "UnmatchedPath",
+            WarningKind::UnknownStoryDataKeys(_) => "UnknownStoryDataKeys",
         }
     }
 }
 
+impl WarningKind {
+    /// Returns a longer [`Explanation`] of this kind of warning and how to
+    /// address it, along with a URL to its documentation
+    ///
+    /// # Examples
+    /// ```
+    /// use tweep::WarningKind;
+    /// let explanation = WarningKind::DeadLink("Nowhere".to_string()).explanation();
+    /// assert_eq!(explanation.code, "DeadLink");
+    /// ```
+    ///
+    /// [`Explanation`]: struct.Explanation.html
+    pub fn explanation(&self) -> Explanation {
+        let (code, text) = match self {
+            WarningKind::EscapedOpenSquare(_) => ("EscapedOpenSquare",
+                "An escaped `\\[` in a passage name is valid, but will appear in the name \
+                 as `[`, which may be confusing when linking to the passage."),
+            WarningKind::EscapedCloseSquare(_) => ("EscapedCloseSquare",
+                "An escaped `\\]` in a passage name is valid, but will appear in the name \
+                 as `]`, which may be confusing when linking to the passage."),
+            WarningKind::EscapedOpenCurly(_) => ("EscapedOpenCurly",
+                "An escaped `\\{` in a passage name is valid, but will appear in the name \
+                 as `{`, which may be confusing when linking to the passage."),
+            WarningKind::EscapedCloseCurly(_) => ("EscapedCloseCurly",
+                "An escaped `\\}` in a passage name is valid, but will appear in the name \
+                 as `}`, which may be confusing when linking to the passage."),
+            WarningKind::JsonError(_) => ("JsonError",
+                "Passage metadata and the contents of the StoryData passage must be valid \
+                 JSON objects. The invalid JSON was ignored."),
+            WarningKind::DuplicateStoryTitle => ("DuplicateStoryTitle",
+                "A story may only have one StoryTitle passage. Only the first one found \
+                 is used."),
+            WarningKind::DuplicateStoryData => ("DuplicateStoryData",
+                "A story may only have one StoryData passage. Only the first one found \
+                 is used."),
+            WarningKind::MissingStoryTitle => ("MissingStoryTitle",
+                "A story should have a StoryTitle passage containing the name of the \
+                 story."),
+            WarningKind::MissingStoryData => ("MissingStoryData",
+                "A story should have a StoryData passage containing a JSON object with at \
+                 least the story's IFID."),
+            WarningKind::UnclosedLink(_) => ("UnclosedLink",
+                "A link was opened with `[[` but never closed with `]]`."),
+            WarningKind::WhitespaceInLink(_) => ("WhitespaceInLink",
+                "Whitespace around a link's separator (`|`, `->`, or `<-`) becomes part of \
+                 the target passage name, which is rarely intended."),
+            WarningKind::DeadLink(_) => ("DeadLink",
+                "A link points to a passage name that doesn't exist. Check the spelling \
+                 and capitalization of the target, or add the missing passage."),
+            WarningKind::MissingStartPassage => ("MissingStartPassage",
+                "The story has no passage named `Start` and StoryData doesn't name a start \
+                 passage, so there's no way to know where the story begins."),
+            WarningKind::DeadStartPassage(_) => ("DeadStartPassage",
+                "The start passage named in StoryData doesn't exist."),
+            WarningKind::DuplicatePassage(_) => ("DuplicatePassage",
+                "Passage names must be unique within a story. Only the first passage with \
+                 a given name is used."),
+            WarningKind::DuplicatePassageAcrossFiles(_) => ("DuplicatePassageAcrossFiles",
+                "Passage names must be unique within a story, even when it's split across \
+                 several files. Two files define a passage with the same name, and only the one \
+                 parsed first is used. Rename one of them, or delete the copy that's no longer \
+                 needed."),
+            WarningKind::MergeConflict(_) => ("MergeConflict",
+                "Both sides of a merge changed the same passage in different ways. The \
+                 passage must be reconciled by hand."),
+            WarningKind::LegacyTagSeparator(_) => ("LegacyTagSeparator",
+                "Tags in a passage header should be separated by whitespace. Commas are \
+                 accepted in lenient mode to ease migration of old projects."),
+            WarningKind::LegacyEncoding => ("LegacyEncoding",
+                "Twee source should be encoded as UTF-8. Text that appeared to be \
+                 Windows-1252 was converted; re-save the file as UTF-8 to silence this."),
+            WarningKind::ProseIssue(_) => ("ProseIssue",
+                "A spellchecker or style checker flagged the prose of a passage. See the \
+                 message for details."),
+            WarningKind::MissingTranslation(_) => ("MissingTranslation",
+                "A segment of passage prose has no translation in the catalog, so the \
+                 original text was kept. Extract a fresh catalog and translate it."),
+            WarningKind::StaleTranslation(_) => ("StaleTranslation",
+                "The source text of a segment changed after it was translated, or the \
+                 segment no longer exists, so the translation was not used. Extract a \
+                 fresh catalog and update the translation."),
+            WarningKind::UnsafeTranslation(_) => ("UnsafeTranslation",
+                "A translation contains link, macro, or HTML syntax, or starts with `::`, \
+                 so applying it would add markup or passages to the story rather than \
+                 prose. The original text was kept. Remove the markup from the translation."),
+            WarningKind::UnsupportedExport(_) => ("UnsupportedExport",
+                "The story contains something the export format has no equivalent for. \
+                 It was removed or converted to a comment, and may need to be recreated \
+                 by hand."),
+            WarningKind::AmbiguousReplacement(_) => ("AmbiguousReplacement",
+                "The text to replace overlaps more than one part of a passage, such as \
+                 the text of a `[[Target]]` link, which is also its target. Edit it by \
+                 hand, e.g. by rewriting the link as `[[Text->Target]]`."),
+            WarningKind::UnescapedHtml(_) => ("UnescapedHtml",
+                "Story formats render passage text as HTML, so a `<` that starts an unclosed \
+                 tag can swallow the text after it, and an `&` sequence such as `&copy;` is \
+                 shown as a single character. Write `&lt;` or `&amp;` to show the \
+                 characters literally."),
+            WarningKind::TrimmedLinkMatch(_, _) => ("TrimmedLinkMatch",
+                "A link target ends in punctuation that isn't part of any passage name, which \
+                 is usually left over from copying text into the link. Remove the \
+                 punctuation from the target, or move it outside the link."),
+            WarningKind::SuspiciousTag(_) => ("SuspiciousTag",
+                "Tags in a passage header are separated by spaces, and may not contain spaces \
+                 themselves. A tag containing `,`, `;`, `|`, `/`, or `\\` is usually a list of \
+                 tags written with the wrong separator; separate them with spaces instead."),
+            WarningKind::MissingAsset(_) => ("MissingAsset",
+                "A passage refers to an image, audio, or video file that couldn't be found \
+                 relative to the project root. Check the spelling and capitalization of \
+                 the path, or add the missing file."),
+            WarningKind::UnknownSpecialTag(_, _) => ("UnknownSpecialTag",
+                "Only the exact, lowercase tags `script` and `stylesheet` mark a passage as a \
+                 script or stylesheet. A passage whose tag differs in case, spelling, or \
+                 invisible characters is treated as a normal passage, so its code is displayed \
+                 as story text instead. Rename the tag to the special tag it resembles."),
+            WarningKind::UnknownTemplate(_) => ("UnknownTemplate",
+                "When template expansion is enabled, `{{name ...}}` in a passage is replaced with \
+                 the body of the passage named `name` that is tagged `template`. No such \
+                 template was found, so the text was left as is. Check the spelling of the \
+                 template name, or add the missing template."),
+            WarningKind::MissingTemplateArgument(_, _) => ("MissingTemplateArgument",
+                "A template refers to a parameter with a `{{parameter}}` placeholder, but the \
+                 instantiation doesn't provide a value for it, so it was replaced with nothing. \
+                 Add `parameter=value` to the instantiation."),
+            WarningKind::ExcludedLink(_, _) => ("ExcludedLink",
+                "The linked passage exists, but carries a tag that the active build profile \
+                 excludes, so the link is dead in this build. Remove the link, guard it so it \
+                 only appears in builds that include the passage, or remove the tag from the \
+                 passage."),
+            WarningKind::GeneratedStoryTitle(_) => ("GeneratedStoryTitle",
+                "No StoryTitle passage was found, so a title was generated from the name of the \
+                 project's file or directory. This is only a note; add a StoryTitle passage to \
+                 choose the title yourself."),
+            WarningKind::GeneratedStoryData(_) => ("GeneratedStoryData",
+                "No StoryData passage was found, so one was generated with a new IFID. This is \
+                 only a note, but the IFID will change every time the story is built. Add a \
+                 StoryData passage containing the IFID to keep it stable."),
+            WarningKind::DeadDynamicTarget(_) => ("DeadDynamicTarget",
+                "A dynamic link, whose target is a variable or expression, may lead to this passage at \
+                 runtime, but no passage with this name exists. Fix the name in the list of \
+                 dynamic targets, or add the passage."),
+            WarningKind::UnexportedLink(_) => ("UnexportedLink",
+                "The linked passage exists, but the export filter leaves it out of exported output, \
+                 so the link will be dead once the story is exported. Remove the link, or change \
+                 the passage's tags or the export filter so that the passage is exported."),
+            WarningKind::MissingAltText(_) => ("MissingAltText",
+                "Screen readers can't describe an image without alternative text, so players who \
+                 can't see it miss what it shows. Add an `alt` attribute to the `<img>` tag, or \
+                 a title to image markup, such as `[img[A locked door|door.png]]`."),
+            WarningKind::VagueLinkText(_) => ("VagueLinkText",
+                "Screen reader users often navigate by listing the links on a page, where text like \
+                 \"here\" or \"click here\" gives no hint of where each link leads. Use display \
+                 text that describes the choice, such as `[[Open the door->Hall]]`."),
+            WarningKind::ColorOnlyReference(_) => ("ColorOnlyReference",
+                "Players who are color blind or use a screen reader can't tell which element is \
+                 meant when it's identified only by its color. Refer to it by its text or \
+                 position as well, or instead."),
+            WarningKind::InvalidMetadata(_, _) => ("InvalidMetadata",
+                "A tool processing this story registered a custom metadata field that passages may \
+                 declare in their header, and the value given doesn't fit it. Fix the value in \
+                 the passage header; until then, the tool uses the field's default, if any."),
+            WarningKind::SimilarPassageNames(_, _) => ("SimilarPassageNames",
+                "Two passages have names that are almost the same, such as \"The Cellar\" and \
+                 \"The  Cellar\". Links written with one spelling won't reach the other, so this \
+                 is usually a typo that leads to dead links later. Rename one of the passages, or \
+                 merge them if they're meant to be the same."),
+            WarningKind::ExcludedFile(_) => ("ExcludedFile",
+                "A twee file in the story's directory was skipped because it matches one of the \
+                 exclude patterns, so its passages aren't part of the story. If the file belongs \
+                 to the story, change the patterns so they don't match it. Otherwise, move it out \
+                 of the story's directory."),
+            WarningKind::UnmatchedPath(_) => ("UnmatchedPath",
+                "No passages were found in a directory given to be parsed, because it has no twee \
+                 files, or only ones that are excluded or empty, so nothing from it is part of the \
+                 story. Check that the path is right and that the exclude patterns don't skip \
+                 everything in it."),
+            WarningKind::UnknownStoryDataKeys(_) => ("UnknownStoryDataKeys",
+                "The StoryData passage has fields that aren't part of the Twee 3 specification. \
+                 They're often added by other tools and are kept as they are, but story formats \
+                 and compilers ignore them. Check the field names for typos, such as \
+                 \"formatVersion\" for \"format-version\"."),
+        };
+        Explanation::new("WarningKind", code, text)
+    }
+}
+
 impl std::fmt::Display for WarningKind {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
             "{}",
             match self {
-                WarningKind::EscapedOpenSquare =>
-                    "Escaped [ character in passage header".to_string(),
-                WarningKind::EscapedCloseSquare =>
-                    "Escaped ] character in passage header".to_string(),
-                WarningKind::EscapedOpenCurly =>
-                    "Escaped { character in passage header".to_string(),
-                WarningKind::EscapedCloseCurly =>
-                    "Escaped } character in passage header".to_string(),
+                WarningKind::EscapedOpenSquare(name) =>
+                    format!("Escaped [ character in passage header: {}", name),
+                WarningKind::EscapedCloseSquare(name) =>
+                    format!("Escaped ] character in passage header: {}", name),
+                WarningKind::EscapedOpenCurly(name) =>
+                    format!("Escaped {{ character in passage header: {}", name),
+                WarningKind::EscapedCloseCurly(name) =>
+                    format!("Escaped }} character in passage header: {}", name),
                 WarningKind::JsonError(error_str) =>
                     format!("Error encountered while parsing JSON: {}", error_str),
                 WarningKind::DuplicateStoryData => "Multiple StoryData passages found".to_string(),
@@ -95,8 +481,9 @@ impl std::fmt::Display for WarningKind {
                     "Multiple StoryTitle passages found".to_string(),
                 WarningKind::MissingStoryData => "No StoryData passage found".to_string(),
                 WarningKind::MissingStoryTitle => "No StoryTitle passage found".to_string(),
-                WarningKind::UnclosedLink => "Unclosed passage link".to_string(),
-                WarningKind::WhitespaceInLink => "Whitespace in passage link".to_string(),
+                WarningKind::UnclosedLink(target) => format!("Unclosed passage link to {}", target),
+                WarningKind::WhitespaceInLink(target) =>
+                    format!("Whitespace in passage link: \"{}\"", target),
                 WarningKind::DeadLink(target) =>
                     format!("Dead link to nonexistant passage: {}", target),
                 WarningKind::MissingStartPassage =>
@@ -105,6 +492,63 @@ impl std::fmt::Display for WarningKind {
                 WarningKind::DeadStartPassage(start) =>
                     format!("Start passage set to {}, but no such passage found", start),
                 WarningKind::DuplicatePassage(name) => format!("Found duplicate passage named {}", name),
+                WarningKind::DuplicatePassageAcrossFiles(name) =>
+                    format!("Found duplicate passage named {} in another file", name),
+                WarningKind::MergeConflict(name) =>
+                    format!("Conflicting changes to passage {} during merge", name),
+                WarningKind::LegacyTagSeparator(separator) =>
+                    format!("Tags separated by {:?} in passage header", separator),
+                WarningKind::LegacyEncoding =>
+                    "Converted Windows-1252 text to UTF-8".to_string(),
+                WarningKind::ProseIssue(message) => format!("Prose issue: {}", message),
+                WarningKind::MissingTranslation(key) =>
+                    format!("No translation for {}", key),
+                WarningKind::StaleTranslation(key) =>
+                    format!("Stale translation for {}", key),
+                WarningKind::UnsafeTranslation(key) =>
+                    format!("Translation for {} contains markup", key),
+                WarningKind::UnsupportedExport(construct) =>
+                    format!("Can't export {}", construct),
+                WarningKind::AmbiguousReplacement(passage) =>
+                    format!("Ambiguous replacement in passage {}", passage),
+                WarningKind::UnescapedHtml(text) =>
+                    format!("Unescaped HTML in prose: {}", text),
+                WarningKind::TrimmedLinkMatch(target, name) =>
+                    format!("Link target \"{}\" only matches passage {} after trimming", target, name),
+                WarningKind::SuspiciousTag(tag) =>
+                    format!("Suspicious tag {}", tag),
+                WarningKind::MissingAsset(path) =>
+                    format!("Missing asset: {}", path),
+                WarningKind::UnknownSpecialTag(tag, special) =>
+                    format!("Tag {} is not recognized as the special tag {}", tag, special),
+                WarningKind::UnknownTemplate(name) =>
+                    format!("Unknown template {}", name),
+                WarningKind::MissingTemplateArgument(template, argument) =>
+                    format!("Template {} is missing argument {}", template, argument),
+                WarningKind::ExcludedLink(target, profile) =>
+                    format!("Link to {} excluded by profile {}", target, profile),
+                WarningKind::GeneratedStoryTitle(title) =>
+                    format!("Generated StoryTitle {}", title),
+                WarningKind::GeneratedStoryData(ifid) =>
+                    format!("Generated StoryData with IFID {}", ifid),
+                WarningKind::DeadDynamicTarget(name) =>
+                    format!("Dynamic link target {} does not exist", name),
+                WarningKind::UnexportedLink(target) =>
+                    format!("Link to {} is not exported", target),
+                WarningKind::MissingAltText(markup) =>
+                    format!("Image {} has no alternative text", markup),
+                WarningKind::VagueLinkText(text) =>
+                    format!("Link text {} does not describe the link", text),
+                WarningKind::ColorOnlyReference(phrase) =>
+                    format!("{} relies on color alone", phrase),
+                WarningKind::InvalidMetadata(field, problem) =>
+                    format!("Invalid metadata field {}: {}", field, problem),
+                WarningKind::SimilarPassageNames(name, similar) =>
+                    format!("Passage name {} is very similar to {}", name, similar),
+                WarningKind::ExcludedFile(path) => format!("Twee file {} was excluded from the story", path.display()),
+                WarningKind::UnmatchedPath(path) => format!("No passages found in {}", path.display()),
+                WarningKind::UnknownStoryDataKeys(keys) =>
+                    format!("Unknown fields in StoryData: {}", keys.join(", ")),
             }
         )
     }
@@ -117,20 +561,115 @@ mod tests {
     #[cfg(feature = "issue-names")]
     #[test]
     fn test_names() {
-        assert_eq!(WarningKind::EscapedOpenSquare.get_name(), "EscapedOpenSquare");
-        assert_eq!(WarningKind::EscapedCloseSquare.get_name(), "EscapedCloseSquare");
-        assert_eq!(WarningKind::EscapedOpenCurly.get_name(), "EscapedOpenCurly");
-        assert_eq!(WarningKind::EscapedCloseCurly.get_name(), "EscapedCloseCurly");
+        assert_eq!(WarningKind::EscapedOpenSquare("x".to_string()).get_name(), "EscapedOpenSquare");
+        assert_eq!(WarningKind::EscapedCloseSquare("x".to_string()).get_name(), "EscapedCloseSquare");
+        assert_eq!(WarningKind::EscapedOpenCurly("x".to_string()).get_name(), "EscapedOpenCurly");
+        assert_eq!(WarningKind::EscapedCloseCurly("x".to_string()).get_name(), "EscapedCloseCurly");
         assert_eq!(WarningKind::JsonError("x".to_string()).get_name(), "JsonError");
         assert_eq!(WarningKind::DuplicateStoryData.get_name(), "DuplicateStoryData");
         assert_eq!(WarningKind::DuplicateStoryTitle.get_name(), "DuplicateStoryTitle");
         assert_eq!(WarningKind::MissingStoryData.get_name(), "MissingStoryData");
         assert_eq!(WarningKind::MissingStoryTitle.get_name(), "MissingStoryTitle");
-        assert_eq!(WarningKind::UnclosedLink.get_name(), "UnclosedLink");
-        assert_eq!(WarningKind::WhitespaceInLink.get_name(), "WhitespaceInLink");
+        assert_eq!(WarningKind::UnclosedLink("x".to_string()).get_name(), "UnclosedLink");
+        assert_eq!(WarningKind::WhitespaceInLink("x".to_string()).get_name(), "WhitespaceInLink");
         assert_eq!(WarningKind::DeadLink("x".to_string()).get_name(), "DeadLink");
         assert_eq!(WarningKind::MissingStartPassage.get_name(), "MissingStartPassage");
         assert_eq!(WarningKind::DeadStartPassage("x".to_string()).get_name(), "DeadStartPassage");
         assert_eq!(WarningKind::DuplicatePassage("x".to_string()).get_name(), "DuplicatePassage");
+        assert_eq!(WarningKind::DuplicatePassageAcrossFiles("x".to_string()).get_name(), "DuplicatePassageAcrossFiles");
+        assert_eq!(WarningKind::MergeConflict("x".to_string()).get_name(), "MergeConflict");
+        assert_eq!(WarningKind::LegacyTagSeparator(',').get_name(), "LegacyTagSeparator");
+        assert_eq!(WarningKind::LegacyEncoding.get_name(), "LegacyEncoding");
+        assert_eq!(WarningKind::ProseIssue("x".to_string()).get_name(), "ProseIssue");
+        assert_eq!(WarningKind::MissingTranslation("x".to_string()).get_name(), "MissingTranslation");
+        assert_eq!(WarningKind::StaleTranslation("x".to_string()).get_name(), "StaleTranslation");
+        assert_eq!(WarningKind::UnsafeTranslation("x".to_string()).get_name(), "UnsafeTranslation");
+        assert_eq!(WarningKind::UnsupportedExport("x".to_string()).get_name(), "UnsupportedExport");
+        assert_eq!(WarningKind::AmbiguousReplacement("x".to_string()).get_name(), "AmbiguousReplacement");
+        assert_eq!(WarningKind::UnescapedHtml("x".to_string()).get_name(), "UnescapedHtml");
+        assert_eq!(WarningKind::TrimmedLinkMatch("x".to_string(), "y".to_string()).get_name(), "TrimmedLinkMatch");
+        assert_eq!(WarningKind::SuspiciousTag("x".to_string()).get_name(), "SuspiciousTag");
+        assert_eq!(WarningKind::MissingAsset("x".to_string()).get_name(), "MissingAsset");
+        assert_eq!(WarningKind::UnknownSpecialTag("x".to_string(), "y".to_string()).get_name(), "UnknownSpecialTag");
+        assert_eq!(WarningKind::UnknownTemplate("x".to_string()).get_name(), "UnknownTemplate");
+        assert_eq!(WarningKind::MissingTemplateArgument("x".to_string(), "y".to_string()).get_name(), "MissingTemplateArgument");
+        assert_eq!(WarningKind::ExcludedLink("x".to_string(), "y".to_string()).get_name(), "ExcludedLink");
+        assert_eq!(WarningKind::GeneratedStoryTitle("x".to_string()).get_name(), "GeneratedStoryTitle");
+        assert_eq!(WarningKind::GeneratedStoryData("x".to_string()).get_name(), "GeneratedStoryData");
+        assert_eq!(WarningKind::DeadDynamicTarget("x".to_string()).get_name(), "DeadDynamicTarget");
+        assert_eq!(WarningKind::UnexportedLink("x".to_string()).get_name(), "UnexportedLink");
+        assert_eq!(WarningKind::MissingAltText("x".to_string()).get_name(), "MissingAltText");
+        assert_eq!(WarningKind::VagueLinkText("x".to_string()).get_name(), "VagueLinkText");
+        assert_eq!(WarningKind::ColorOnlyReference("x".to_string()).get_name(), "ColorOnlyReference");
+        assert_eq!(WarningKind::InvalidMetadata("x".to_string(), "y".to_string()).get_name(), "InvalidMetadata");
+        assert_eq!(WarningKind::SimilarPassageNames("x".to_string(), "y".to_string()).get_name(), "SimilarPassageNames");
+        assert_eq!(WarningKind::ExcludedFile(PathBuf::from("x")).get_name(), "ExcludedFile");
+        assert_eq!(WarningKind::UnmatchedPath(PathBuf::from("x")).get_name(), "UnmatchedPath");
+        assert_eq!(WarningKind::UnknownStoryDataKeys(vec!["x".to_string()]).get_name(), "UnknownStoryDataKeys");
+    }
+
+    #[test]
+    fn explanation_codes() {
+        let kinds = vec![
+            WarningKind::EscapedOpenSquare("x".to_string()),
+            WarningKind::EscapedCloseSquare("x".to_string()),
+            WarningKind::EscapedOpenCurly("x".to_string()),
+            WarningKind::EscapedCloseCurly("x".to_string()),
+            WarningKind::JsonError("x".to_string()),
+            WarningKind::DuplicateStoryData,
+            WarningKind::DuplicateStoryTitle,
+            WarningKind::MissingStoryData,
+            WarningKind::MissingStoryTitle,
+            WarningKind::UnclosedLink("x".to_string()),
+            WarningKind::WhitespaceInLink("x".to_string()),
+            WarningKind::DeadLink("x".to_string()),
+            WarningKind::MissingStartPassage,
+            WarningKind::DeadStartPassage("x".to_string()),
+            WarningKind::DuplicatePassage("x".to_string()),
+            WarningKind::DuplicatePassageAcrossFiles("x".to_string()),
+            WarningKind::MergeConflict("x".to_string()),
+            WarningKind::LegacyTagSeparator(','),
+            WarningKind::LegacyEncoding,
+            WarningKind::ProseIssue("x".to_string()),
+            WarningKind::MissingTranslation("x".to_string()),
+            WarningKind::StaleTranslation("x".to_string()),
+            WarningKind::UnsafeTranslation("x".to_string()),
+            WarningKind::UnsupportedExport("x".to_string()),
+            WarningKind::AmbiguousReplacement("x".to_string()),
+            WarningKind::UnescapedHtml("x".to_string()),
+            WarningKind::TrimmedLinkMatch("x".to_string(), "y".to_string()),
+            WarningKind::SuspiciousTag("x".to_string()),
+            WarningKind::MissingAsset("x".to_string()),
+            WarningKind::UnknownSpecialTag("x".to_string(), "y".to_string()),
+            WarningKind::UnknownTemplate("x".to_string()),
+            WarningKind::MissingTemplateArgument("x".to_string(), "y".to_string()),
+            WarningKind::ExcludedLink("x".to_string(), "y".to_string()),
+            WarningKind::GeneratedStoryTitle("x".to_string()),
+            WarningKind::GeneratedStoryData("x".to_string()),
+            WarningKind::DeadDynamicTarget("x".to_string()),
+            WarningKind::UnexportedLink("x".to_string()),
+            WarningKind::MissingAltText("x".to_string()),
+            WarningKind::VagueLinkText("x".to_string()),
+            WarningKind::ColorOnlyReference("x".to_string()),
+            WarningKind::InvalidMetadata("x".to_string(), "y".to_string()),
+            WarningKind::SimilarPassageNames("x".to_string(), "y".to_string()),
+            WarningKind::ExcludedFile(PathBuf::from("x")),
+            WarningKind::UnmatchedPath(PathBuf::from("x")),
+            WarningKind::UnknownStoryDataKeys(vec!["x".to_string()]),
+        ];
+        for kind in kinds {
+            assert_eq!(kind.explanation().code, kind.get_name());
+        }
+    }
+    #[test]
+    fn payload_display() {
+        assert_eq!(
+            WarningKind::EscapedOpenCurly("A \\{b".to_string()).to_string(),
+            "Escaped { character in passage header: A \\{b"
+        );
+        assert_eq!(
+            WarningKind::UnclosedLink("north".to_string()).to_string(),
+            "Unclosed passage link to north"
+        );
     }
 }
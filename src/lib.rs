@@ -36,6 +36,34 @@
 //! provides the same interface, but provides [`Passage`] objects in places
 //! where usually unnecessary information is stripped out.
 //!
+//! With the `parallel` feature, the passages of each file are parsed on as
+//! many threads as there are available cores. The resulting story and the
+//! order of its warnings are the same as without the feature.
+//!
+//! With the `tracing` feature, parsing is instrumented with [`tracing`] spans
+//! for each file and passage, and debug events for the decisions made along
+//! the way, such as files being skipped or passages being dropped as
+//! duplicates. The `log` feature also emits these as [`log`] records for
+//! applications that don't use a `tracing` subscriber. Without either
+//! feature, nothing is logged.
+//!
+//! With the `binary` feature, a parsed [`Story`] can be saved as a compact
+//! binary blob with `Story::to_bytes` and loaded back with `Story::from_bytes`
+//! without parsing any twee code, e.g. to embed a pre-parsed story in a game.
+//!
+//! [`tracing`]: https://docs.rs/tracing
+//! [`log`]: https://docs.rs/log
+//!
+//! # WebAssembly
+//! tweep has no platform-specific code and builds for `wasm32-unknown-unknown`
+//! with no additional features. On that target the string-based entry points,
+//! such as [`Story::from_string`], work as usual, while the `Path`-based entry
+//! points return a [`BadInputPath`] error because the target has no
+//! filesystem. With the `serialize-issues` feature, errors and warnings can be
+//! serialized to JSON for display by a browser-based editor. The
+//! `wasm-bindgen` feature exports the parser to JavaScript directly; see the
+//! [`wasm`] module.
+//!
 //! # Examples
 //! ```
 //! use tweep::Story;
@@ -100,10 +128,17 @@
 //! [`Story`]: struct.Story.html
 //! [`StoryPassages`]: struct.StoryPassages.html
 //! [`Passage`]: struct.Passage.html
+//! [`Story::from_string`]: struct.Story.html#method.from_string
+//! [`BadInputPath`]: enum.ErrorKind.html#variant.BadInputPath
+//! [`wasm`]: wasm/index.html
 
+#![forbid(unsafe_code)]
 #![warn(missing_docs)]
 #![warn(missing_doc_code_examples)]
 
+#[macro_use]
+mod trace;
+
 /// The output context type for [`Error`]s and [`Warning`]s. If the feature
 /// `full-context` is enabled, this will be a [`FullContext`] which will include
 /// the ability to retrieve the associated source Twee v3 code. Otherwise, it
@@ -135,21 +170,35 @@ pub use context::Position;
 pub use context::PositionKind;
 pub use context::FullContext;
 pub use context::PartialContext;
+pub use context::SharedStr;
+
+pub mod cst;
+
+pub mod i18n;
 
 mod issues;
 pub use issues::Error;
 pub use issues::ErrorList;
 pub use issues::ErrorKind;
+pub use issues::Explanation;
+pub use issues::IssueKind;
+pub use issues::Severity;
 pub use issues::Warning;
 pub use issues::WarningKind;
+pub use issues::WarningPolicy;
 
 mod output;
 pub use output::Output;
 
 mod passages;
+pub use passages::MetadataKey;
+pub use passages::MetadataRegistry;
+pub use passages::MetadataType;
+pub use passages::NormalizedName;
 pub use passages::Passage;
 pub use passages::PassageContent;
 pub use passages::PassageHeader;
+pub use passages::PassageKind;
 pub use passages::ScriptContent;
 pub use passages::StoryData;
 pub use passages::StoryTitle;
@@ -158,10 +207,44 @@ pub use passages::TwineContent;
 pub use passages::TwineLink;
 pub use passages::TwinePassage;
 
+pub mod runtime;
+
 mod stories;
+pub use stories::AssetReference;
+pub use stories::BuildProfile;
+pub use stories::CancellationToken;
+pub use stories::CheckOptions;
 #[cfg(feature = "full-context")]
 pub use stories::CodeMap;
 #[cfg(feature = "full-context")]
 pub use stories::ContextErrorList;
+pub use stories::ExportFilter;
+pub use stories::ExportOptions;
+pub use stories::FileReport;
+pub use stories::Fix;
+pub use stories::FixSession;
+pub use stories::LayoutStyle;
+pub use stories::ParseObserver;
+pub use stories::ParseOptions;
+pub use stories::PassageDegree;
+pub use stories::PassageDiff;
+pub use stories::PassageOrder;
+pub use stories::ProseChecker;
+pub use stories::Reachability;
+pub use stories::ReplaceScope;
+pub use stories::SearchMatch;
+pub use stories::SearchScope;
 pub use stories::Story;
+pub use stories::StoryConfig;
+pub use stories::StoryManifest;
 pub use stories::StoryPassages;
+pub use stories::StorySkeleton;
+pub use stories::TagUsage;
+pub use stories::TextEdit;
+pub use stories::ValidationReport;
+pub use stories::Workspace;
+
+#[cfg(feature = "wasm-bindgen")]
+pub mod wasm;
+
+mod windows_1252;
@@ -1,4 +1,9 @@
+use crate::Error;
+use crate::ErrorKind;
+use crate::ErrorList;
+use crate::Severity;
 use crate::Warning;
+use crate::WarningPolicy;
 
 /// Represents the output of an operation along with a [`Vec`] of any
 /// [`Warning`]s generated by the operation.
@@ -88,7 +93,7 @@ impl<T> Output<T> {
     /// let out:Output<u8> = Output::new(5);
     /// assert!(!out.has_warnings());
     /// let out:Output<u8> = Output::new(5)
-    ///     .with_warnings(vec![ Warning::new(WarningKind::UnclosedLink, Some(context)) ]);
+    ///     .with_warnings(vec![ Warning::new(WarningKind::UnclosedLink("x".to_string()), Some(context)) ]);
     /// assert!(out.has_warnings());
     /// ```
     pub fn has_warnings(&self) -> bool {
@@ -102,13 +107,95 @@ impl<T> Output<T> {
     /// use tweep::{FullContext, Output, Warning, WarningKind};
     /// # let context = FullContext::from(None, String::new());
     /// let out:Output<u8> = Output::new(5)
-    ///     .with_warnings(vec![ Warning::new(WarningKind::UnclosedLink, Some(context.clone())) ]);
-    /// assert_eq!(out.get_warnings(), &vec![ Warning::new(WarningKind::UnclosedLink, Some(context)) ]);
+    ///     .with_warnings(vec![ Warning::new(WarningKind::UnclosedLink("x".to_string()), Some(context.clone())) ]);
+    /// assert_eq!(out.get_warnings(), &vec![ Warning::new(WarningKind::UnclosedLink("x".to_string()), Some(context)) ]);
     /// ```
     pub fn get_warnings(&self) -> &Vec<Warning> {
         &self.warnings
     }
 
+    /// Returns the associated [`Warning`]s ordered by file, then by line and
+    /// column. Warnings without a context come last, and warnings at the same
+    /// position keep the order in which they were produced
+    ///
+    /// # Examples
+    /// ```
+    /// use tweep::{FullContext, Output, Position, Warning, WarningKind};
+    /// let context = FullContext::from(None, ":: Start\n[[Nowhere]]".to_string());
+    /// let late = Warning::new(WarningKind::DeadLink("Nowhere".to_string()),
+    ///     Some(context.subcontext(Position::rel(2, 1)..)));
+    /// let early = Warning::new(WarningKind::MissingStoryTitle, Some(context));
+    /// let story = Warning::new::<FullContext>(WarningKind::MissingStoryData, None);
+    /// let out = Output::new(5).with_warnings(vec![story.clone(), late.clone(), early.clone()]);
+    /// assert_eq!(out.sorted_warnings(), vec![&early, &late, &story]);
+    /// ```
+    ///
+    /// [`Warning`]: struct.Warning.html
+    pub fn sorted_warnings(&self) -> Vec<&Warning> {
+        let mut warnings: Vec<&Warning> = self.warnings.iter().collect();
+        warnings.sort_by_key(|w| {
+            w.context.as_ref().map_or((true, None, 0, 0), |c| {
+                let position = c.get_start_position();
+                (false, c.get_file_path(), position.line, position.column)
+            })
+        });
+        warnings
+    }
+
+    /// Renders the associated [`Warning`]s, one per line, in the order given by
+    /// [`sorted_warnings`]. Each line starts with the [`Severity`] of the
+    /// warning
+    ///
+    /// # Examples
+    /// ```
+    /// use tweep::{Output, Warning, WarningKind};
+    /// let warning = Warning::new::<tweep::FullContext>(WarningKind::MissingStoryTitle, None);
+    /// let out = Output::new(5).with_warnings(vec![warning]);
+    /// assert_eq!(out.render_warnings(), "warning: No StoryTitle passage found at None\n");
+    /// ```
+    ///
+    /// [`Warning`]: struct.Warning.html
+    /// [`Severity`]: enum.Severity.html
+    /// [`sorted_warnings`]: #method.sorted_warnings
+    pub fn render_warnings(&self) -> String {
+        self.render_warnings_with(Warning::severity)
+    }
+
+    /// Renders the associated [`Warning`]s like [`render_warnings`], but with
+    /// the [`Severity`] shown for each warning given by `severity`. This lets
+    /// an application present warnings as more or less serious than their
+    /// kinds are, such as showing dead links as errors, without changing how
+    /// anything is parsed or checked
+    ///
+    /// # Examples
+    /// ```
+    /// use tweep::{Output, Severity, Warning, WarningKind};
+    /// let dead = Warning::new::<tweep::FullContext>(WarningKind::DeadLink("Cellar".to_string()), None);
+    /// let escaped = Warning::new::<tweep::FullContext>(WarningKind::EscapedOpenSquare("x".to_string()), None);
+    /// let out = Output::new(5).with_warnings(vec![dead, escaped]);
+    /// let rendered = out.render_warnings_with(|warning| match warning.kind {
+    ///     WarningKind::DeadLink(_) => Severity::Error,
+    ///     WarningKind::EscapedOpenSquare(_) => Severity::Hint,
+    ///     _ => warning.severity(),
+    /// });
+    /// let lines: Vec<&str> = rendered.lines().collect();
+    /// assert!(lines[0].starts_with("error: "));
+    /// assert!(lines[1].starts_with("hint: "));
+    /// ```
+    ///
+    /// [`Warning`]: struct.Warning.html
+    /// [`Severity`]: enum.Severity.html
+    /// [`render_warnings`]: #method.render_warnings
+    pub fn render_warnings_with<F>(&self, severity: F) -> String
+    where
+        F: Fn(&Warning) -> Severity,
+    {
+        self.sorted_warnings()
+            .into_iter()
+            .map(|warning| format!("{}: {}\n", severity(warning), warning))
+            .collect()
+    }
+
     /// Consumes the `Output` and returns the `output` and `warnings` as a tuple
     ///
     /// # Examples
@@ -226,6 +313,158 @@ impl<T,E> Output<Result<T,E>> {
             self.into_err()
         }
     }
+
+    /// Folds the `Ok` values of a sequence of `Output`s into `init` using `f`,
+    /// which may produce additional [`Warning`]s. The [`Warning`]s of each
+    /// `Output` are collected, followed by any produced by `f` for its value.
+    ///
+    /// Stops at the first `Err`, returning it along with the [`Warning`]s
+    /// collected up to and including that `Output`. Any remaining `Output`s
+    /// are not consumed.
+    ///
+    /// # Examples
+    /// ```
+    /// use tweep::Output;
+    /// let outputs: Vec<Output<Result<u32, String>>> = vec![
+    ///     Output::new(Ok(1)),
+    ///     Output::new(Ok(2)),
+    /// ];
+    /// let out = Output::merge_all(outputs, 0, |sum, x| { *sum += x; Vec::new() });
+    /// assert_eq!(*out.get_output(), Ok(3));
+    ///
+    /// let outputs: Vec<Output<Result<u32, String>>> = vec![
+    ///     Output::new(Ok(1)),
+    ///     Output::new(Err("oops".to_string())),
+    /// ];
+    /// let out = Output::merge_all(outputs, 0, |sum, x| { *sum += x; Vec::new() });
+    /// assert_eq!(*out.get_output(), Err("oops".to_string()));
+    /// ```
+    ///
+    /// [`Warning`]: struct.Warning.html
+    pub fn merge_all<I, A, F>(outputs: I, init: A, mut f: F) -> Output<Result<A, E>>
+    where
+        I: IntoIterator<Item = Self>,
+        F: FnMut(&mut A, T) -> Vec<Warning>,
+    {
+        let mut acc = init;
+        let mut warnings = Vec::new();
+        for out in outputs {
+            let (res, mut sub_warnings) = out.take();
+            warnings.append(&mut sub_warnings);
+            match res {
+                Ok(t) => warnings.append(&mut f(&mut acc, t)),
+                Err(e) => return Output::new(Err(e)).with_warnings(warnings),
+            }
+        }
+        Output::new(Ok(acc)).with_warnings(warnings)
+    }
+}
+
+impl<T> Output<Result<T, ErrorList>> {
+    /// Combines a sequence of `Output`s into a single `Output` containing a
+    /// collection of all the `Ok` values and the concatenation of all the
+    /// [`Warning`]s. If any of the `Output`s contains an `Err`, the result is
+    /// instead an `Err` containing every [`Error`] from every [`ErrorList`], in
+    /// order.
+    ///
+    /// # Examples
+    /// ```
+    /// use tweep::{Error, ErrorKind, ErrorList, FullContext, Output};
+    /// # let context = FullContext::from(None, "::".to_string());
+    /// let outputs: Vec<Output<Result<u32, ErrorList>>> = vec![
+    ///     Output::new(Ok(1)),
+    ///     Output::new(Ok(2)),
+    /// ];
+    /// let out: Output<Result<Vec<u32>, ErrorList>> = Output::collect(outputs);
+    /// assert_eq!(*out.get_output(), Ok(vec![1, 2]));
+    ///
+    /// let error = Error::new(ErrorKind::EmptyName, Some(context));
+    /// let outputs: Vec<Output<Result<u32, ErrorList>>> = vec![
+    ///     Output::new(Ok(1)),
+    ///     Output::new(Err(error.clone().into())),
+    ///     Output::new(Err(error.clone().into())),
+    /// ];
+    /// let out: Output<Result<Vec<u32>, ErrorList>> = Output::collect(outputs);
+    /// assert_eq!(out.take().0.err().unwrap().errors, vec![error.clone(), error]);
+    /// ```
+    ///
+    /// [`Warning`]: struct.Warning.html
+    /// [`Error`]: struct.Error.html
+    /// [`ErrorList`]: struct.ErrorList.html
+    pub fn collect<I, C>(outputs: I) -> Output<Result<C, ErrorList>>
+    where
+        I: IntoIterator<Item = Self>,
+        C: std::iter::FromIterator<T>,
+    {
+        let mut warnings = Vec::new();
+        let mut errors = ErrorList::new();
+        let values: C = outputs
+            .into_iter()
+            .filter_map(|out| {
+                let (res, mut sub_warnings) = out.take();
+                warnings.append(&mut sub_warnings);
+                match res {
+                    Ok(t) => Some(t),
+                    Err(mut e) => {
+                        errors.errors.append(&mut e.errors);
+                        None
+                    }
+                }
+            })
+            .collect();
+        let res = if errors.is_empty() { Ok(values) } else { Err(errors) };
+        Output::new(res).with_warnings(warnings)
+    }
+
+    /// Consumes the `Output` and converts it into a plain `Result`, treating
+    /// any [`Warning`]s denied by `policy` as [`Error`]s of kind
+    /// [`DeniedWarning`], which are appended to any existing [`ErrorList`].
+    /// [`Warning`]s allowed by the policy are discarded.
+    ///
+    /// # Examples
+    /// ```
+    /// use tweep::{ErrorKind, ErrorList, FullContext, Output, Warning, WarningKind, WarningPolicy};
+    /// # let context = FullContext::from(None, String::new());
+    /// let warning = Warning::new(WarningKind::DeadLink("Nowhere".to_string()), Some(context));
+    /// let out: Output<Result<u32, ErrorList>> = Output::new(Ok(23))
+    ///     .with_warnings(vec![warning.clone()]);
+    /// assert_eq!(out.into_result_with(&WarningPolicy::Allow), Ok(23));
+    ///
+    /// let out: Output<Result<u32, ErrorList>> = Output::new(Ok(23))
+    ///     .with_warnings(vec![warning]);
+    /// let policy = WarningPolicy::DenyCodes(vec!["DeadLink".to_string()]);
+    /// let errors = out.into_result_with(&policy).err().unwrap();
+    /// assert_eq!(errors.errors.len(), 1);
+    /// assert_eq!(
+    ///     errors.errors[0].kind,
+    ///     ErrorKind::DeniedWarning(WarningKind::DeadLink("Nowhere".to_string()))
+    /// );
+    /// ```
+    ///
+    /// [`Warning`]: struct.Warning.html
+    /// [`Error`]: struct.Error.html
+    /// [`DeniedWarning`]: enum.ErrorKind.html#variant.DeniedWarning
+    /// [`ErrorList`]: struct.ErrorList.html
+    pub fn into_result_with(self, policy: &WarningPolicy) -> Result<T, ErrorList> {
+        let (res, warnings) = self.take();
+        let mut denied = warnings
+            .into_iter()
+            .filter(|w| policy.denies(w))
+            .map(|w| Error {
+                kind: ErrorKind::DeniedWarning(w.kind),
+                context: w.context,
+                fix: None,
+            })
+            .peekable();
+        match res {
+            Ok(_) if denied.peek().is_some() => Err(ErrorList { errors: denied.collect() }),
+            Ok(t) => Ok(t),
+            Err(mut errors) => {
+                errors.errors.extend(denied);
+                Err(errors)
+            }
+        }
+    }
 }
 
 #[cfg(test)]
@@ -289,6 +528,124 @@ mod tests {
         assert_eq!(y.get_output(), &Err(23));
     }
 
+    #[test]
+    fn merge_all_warnings() {
+        use crate::WarningKind;
+        use crate::FullContext;
+        let context = FullContext::from(None, "".to_string());
+        let warning = |kind| Warning::new(kind, Some(context.clone()));
+        let outputs: Vec<Output<Result<u8, u8>>> = vec![
+            Output::new(Ok(1)).with_warnings(vec![warning(WarningKind::UnclosedLink("x".to_string()))]),
+            Output::new(Ok(2)),
+            Output::new(Err(3)).with_warnings(vec![warning(WarningKind::MissingStoryData)]),
+            Output::new(Ok(4)),
+        ];
+        let out = Output::merge_all(outputs, Vec::new(), |v, x| {
+            v.push(x);
+            vec![warning(WarningKind::DuplicatePassage(x.to_string()))]
+        });
+        let (res, warnings) = out.take();
+        assert_eq!(res, Err(3));
+        assert_eq!(warnings, vec![
+            warning(WarningKind::UnclosedLink("x".to_string())),
+            warning(WarningKind::DuplicatePassage("1".to_string())),
+            warning(WarningKind::DuplicatePassage("2".to_string())),
+            warning(WarningKind::MissingStoryData),
+        ]);
+    }
+
+    #[test]
+    fn collect() {
+        use crate::{Error, ErrorKind, WarningKind};
+        use crate::FullContext;
+        let context = FullContext::from(None, "".to_string());
+        let outputs: Vec<Output<Result<u8, ErrorList>>> = vec![
+            Output::new(Ok(1)).with_warnings(vec![Warning::new(WarningKind::UnclosedLink("x".to_string()), Some(context.clone()))]),
+            Output::new(Ok(2)),
+        ];
+        let out: Output<Result<Vec<u8>, ErrorList>> = Output::collect(outputs);
+        assert_eq!(out.get_warnings().len(), 1);
+        assert_eq!(out.take().0, Ok(vec![1, 2]));
+
+        let outputs: Vec<Output<Result<u8, ErrorList>>> = vec![
+            Output::new(Err(Error::new(ErrorKind::EmptyName, Some(context.clone())).into())),
+            Output::new(Ok(2)),
+            Output::new(Err(Error::new(ErrorKind::MissingSigil("Blah".to_string()), Some(context.clone())).into())),
+        ];
+        let out: Output<Result<Vec<u8>, ErrorList>> = Output::collect(outputs);
+        let kinds: Vec<ErrorKind> = out.take().0.err().unwrap().errors.into_iter().map(|e| e.kind).collect();
+        assert_eq!(kinds, vec![ErrorKind::EmptyName, ErrorKind::MissingSigil("Blah".to_string())]);
+    }
+
+    #[test]
+    fn into_result_with() {
+        use crate::{ErrorKind, WarningKind};
+        use crate::FullContext;
+        let context = FullContext::from(None, "".to_string());
+        let make = |res: Result<u8, ErrorList>| -> Output<Result<u8, ErrorList>> {
+            Output::new(res).with_warnings(vec![
+                Warning::new(WarningKind::UnclosedLink("x".to_string()), Some(context.clone())),
+                Warning::new(WarningKind::MissingStoryData, Some(context.clone())),
+            ])
+        };
+
+        assert_eq!(make(Ok(5)).into_result_with(&WarningPolicy::Allow), Ok(5));
+
+        let errs = make(Ok(5)).into_result_with(&WarningPolicy::Deny).err().unwrap();
+        assert_eq!(errs.errors.len(), 2);
+
+        let policy = WarningPolicy::AllowCodes(vec!["UnclosedLink".to_string()]);
+        let errs = make(Ok(5)).into_result_with(&policy).err().unwrap();
+        assert_eq!(errs.errors, vec![
+            Error::new(ErrorKind::DeniedWarning(WarningKind::MissingStoryData), Some(context.clone())),
+        ]);
+
+        let error = Error::new(ErrorKind::EmptyName, Some(context.clone()));
+        let policy = WarningPolicy::DenyCodes(vec!["UnclosedLink".to_string()]);
+        let errs = make(Err(error.clone().into())).into_result_with(&policy).err().unwrap();
+        assert_eq!(errs.errors, vec![
+            error,
+            Error::new(ErrorKind::DeniedWarning(WarningKind::UnclosedLink("x".to_string())), Some(context.clone())),
+        ]);
+    }
+
+    #[test]
+    fn sorted_warnings() {
+        use crate::WarningKind;
+        use crate::FullContext;
+        use crate::Position;
+        let a = FullContext::from(Some("a.twee".to_string()), ":: One\n[[x]]\n[[y]]".to_string());
+        let b = FullContext::from(Some("b.twee".to_string()), ":: Two\n[[z]]".to_string());
+        let dead = |context: &FullContext, line, name: &str| Warning::new(
+            WarningKind::DeadLink(name.to_string()),
+            Some(context.subcontext(Position::rel(line, 1)..)),
+        );
+        let warnings = vec![
+            dead(&b, 2, "z"),
+            dead(&a, 3, "y"),
+            dead(&a, 2, "x"),
+            dead(&a, 2, "w"),
+        ];
+        let out = Output::new(()).with_warnings(warnings.clone());
+        assert_eq!(out.sorted_warnings(), vec![&warnings[2], &warnings[3], &warnings[1], &warnings[0]]);
+    }
+
+    #[test]
+    fn render_with_severity() {
+        use crate::WarningKind;
+        use crate::FullContext;
+        use crate::Position;
+        let context = FullContext::from(None, ":: Start\n\\[".to_string());
+        let escaped = Warning::new(WarningKind::EscapedOpenSquare("x".to_string()), Some(context.subcontext(Position::rel(2, 1)..)));
+        let generated = Warning::new::<FullContext>(WarningKind::GeneratedStoryData("X".to_string()), None);
+        let out = Output::new(()).with_warnings(vec![generated.clone(), escaped.clone()]);
+        assert_eq!(out.render_warnings(), format!("warning: {}\nnote: {}\n", escaped, generated));
+
+        let promoted = out.render_warnings_with(|w| std::cmp::max(w.severity(), Severity::Error));
+        assert_eq!(promoted, format!("error: {}\nerror: {}\n", escaped, generated));
+        assert_eq!(Output::new(()).render_warnings(), "");
+    }
+
     #[test]
     #[should_panic]
     fn into_ok_panic() {
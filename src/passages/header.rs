@@ -1,12 +1,16 @@
 use crate::issues::*;
 use crate::FullContext;
+use crate::NormalizedName;
 use crate::Output;
+use crate::ParseOptions;
 use crate::Position;
+use crate::TextEdit;
+use crate::windows_1252;
+use super::json_error_context;
+use super::json_error_message;
 
 use std::ops::Range;
 
-use serde_json::json;
-
 /// A passage header, along with associated [`Position`], tags, and metadata
 ///
 /// # Parse Errors
@@ -19,6 +23,8 @@ use serde_json::json;
 /// * [`UnescapedOpenSquare`] - Unescaped `[` character in passage name
 /// * [`UnescapedCloseSquare`] - Unescaped `]` character in passage name
 /// * [`EmptyName`] - No passage name present in header line
+/// * [`UnclosedMetadataBlock`] - Metadata block present but unclosed, only
+///   when parsing with [`strict_headers`]
 ///
 /// # Parse Warnings
 /// * [`JsonError`] - Error encountered when parsing metadata
@@ -26,6 +32,10 @@ use serde_json::json;
 /// * [`EscapedCloseCurly`] - `\}` present in passage name
 /// * [`EscapedOpenSquare`] - `\[` present in passage name
 /// * [`EscapedCloseSquare`] - `\]` present in passage name
+/// * [`SuspiciousTag`] - A tag containing a character such as `,` or `/` that
+///   suggests it was meant to be several tags
+/// * [`UnknownSpecialTag`] - A tag such as `Script` or `styles` that looks
+///   like it was meant to be a special tag, but isn't recognized
 ///
 /// # Examples
 /// ```
@@ -49,11 +59,15 @@ use serde_json::json;
 /// [`UnescapedOpenSquare`]: enum.ErrorKind.html#variant.UnescapedOpenSquare
 /// [`UnescapedCloseSquare`]: enum.ErrorKind.html#variant.UnescapedCloseSquare
 /// [`EmptyName`]: enum.ErrorKind.html#variant.EmptyName
+/// [`UnclosedMetadataBlock`]: enum.ErrorKind.html#variant.UnclosedMetadataBlock
+/// [`strict_headers`]: struct.ParseOptions.html#structfield.strict_headers
 /// [`JsonError`]: enum.WarningKind.html#variant.JsonError
 /// [`EscapedOpenCurly`]: enum.WarningKind.html#variant.EscapedOpenCurly
 /// [`EscapedCloseCurly`]: enum.WarningKind.html#variant.EscapedCloseCurly
 /// [`EscapedOpenSquare`]: enum.WarningKind.html#variant.EscapedOpenSquare
 /// [`EscapedCloseSquare`]: enum.WarningKind.html#variant.EscapedCloseSquare
+/// [`SuspiciousTag`]: enum.WarningKind.html#variant.SuspiciousTag
+/// [`UnknownSpecialTag`]: enum.WarningKind.html#variant.UnknownSpecialTag
 #[derive(Debug)]
 pub struct PassageHeader {
     /// The name of the header. This can be a Twine passage name or a special name
@@ -62,8 +76,23 @@ pub struct PassageHeader {
     /// The list of comma separated tags
     pub tags: Vec<String>,
 
-    /// A json object containing metadata for the passage
+    /// A json object containing metadata for the passage. Keys, including
+    /// those of nested objects, keep the order they were written in, followed
+    /// by any defaults that weren't written
     pub metadata: serde_json::Map<String, serde_json::Value>,
+
+    /// The exact text of the header line
+    original_text: String,
+
+    /// The byte range of the name within the source
+    name_range: Range<usize>,
+
+    /// The byte range of the tag block, including brackets, within the source
+    tag_block_range: Option<Range<usize>>,
+
+    /// The byte range of the metadata block, including braces, within the
+    /// source
+    metadata_range: Option<Range<usize>>,
 }
 
 impl PassageHeader {
@@ -81,8 +110,125 @@ impl PassageHeader {
         self.tags.contains(&tag)
     }
 
+    /// Serializes the `metadata` field as a JSON object, with its keys in
+    /// order
+    ///
+    /// # Examples
+    /// ```
+    /// use tweep::{FullContext, PassageHeader};
+    /// let input = r#":: A passage {"size": "1,1", "position": "5,5"}"#;
+    /// let context = FullContext::from(None, input.to_string());
+    /// let header = PassageHeader::parse(context).take().0.ok().unwrap();
+    /// assert_eq!(header.metadata_json(), r#"{"size":"1,1","position":"5,5"}"#);
+    /// ```
+    pub fn metadata_json(&self) -> String {
+        serde_json::Value::Object(self.metadata.clone()).to_string()
+    }
+
+    /// Returns the exact text of the header line as written in the source,
+    /// without the line break, so that tools which only modify passage bodies
+    /// can re-emit headers byte-for-byte. It isn't updated when the header is
+    /// changed, such as by [`StoryPassages::rename_tag`]
+    ///
+    /// # Examples
+    /// ```
+    /// use tweep::{FullContext, PassageHeader};
+    /// let input = r#"::  An \{escaped\} passage [b a] {"size":"5,5"}"#;
+    /// let context = FullContext::from(None, input.to_string());
+    /// let header = PassageHeader::parse(context).take().0.ok().unwrap();
+    /// assert_eq!(header.name, "An {escaped} passage");
+    /// assert_eq!(header.original_text(), input);
+    /// ```
+    ///
+    /// [`StoryPassages::rename_tag`]: struct.StoryPassages.html#method.rename_tag
+    pub fn original_text(&self) -> &str {
+        &self.original_text
+    }
+
+    /// Returns `true` if the given top-level metadata key was written in the
+    /// header, rather than filled in with a default
+    pub(crate) fn has_authored_metadata(&self, key: &str) -> bool {
+        let range = match HeaderLayout::scan(&self.original_text).metadata {
+            Some(Ok(range)) => range,
+            Some(Err(pos)) => pos..self.original_text.len(),
+            None => return false,
+        };
+        serde_json::from_str::<serde_json::Map<String, serde_json::Value>>(&self.original_text[range])
+            .is_ok_and(|metadata| metadata.contains_key(key))
+    }
+
+    /// Creates a `PassageHeader` from its parts, with metadata given as a JSON
+    /// object
+    #[cfg(any(test, feature = "binary"))]
+    pub(crate) fn from_parts(
+        name: String,
+        tags: Vec<String>,
+        metadata_json: &str,
+        original_text: String,
+    ) -> Result<Self, serde_json::Error> {
+        Ok(PassageHeader {
+            name,
+            tags,
+            metadata: serde_json::from_str(metadata_json)?,
+            original_text,
+            name_range: 0..0,
+            tag_block_range: None,
+            metadata_range: None,
+        })
+    }
+
+    /// Returns the byte range of the passage name as written, escapes
+    /// included, within the source of the header
+    ///
+    /// # Examples
+    /// ```
+    /// use tweep::{FullContext, PassageHeader};
+    /// let input = r#":: A \[grand\] hall [dark cold] {"size": "5,5"}"#;
+    /// let context = FullContext::from(None, input.to_string());
+    /// let header = PassageHeader::parse(context).take().0.ok().unwrap();
+    /// assert_eq!(&input[header.name_range()], r"A \[grand\] hall");
+    /// assert_eq!(&input[header.tag_block_range().unwrap()], "[dark cold]");
+    /// assert_eq!(&input[header.metadata_range().unwrap()], r#"{"size": "5,5"}"#);
+    /// ```
+    pub fn name_range(&self) -> Range<usize> {
+        self.name_range.clone()
+    }
+
+    /// Returns the byte range of the tag block, including its brackets,
+    /// within the source of the header, if there is one. Like
+    /// [`original_text`], it describes the tags as written
+    ///
+    /// [`original_text`]: #method.original_text
+    pub fn tag_block_range(&self) -> Option<Range<usize>> {
+        self.tag_block_range.clone()
+    }
+
+    /// Returns the byte range of the metadata block, including its braces,
+    /// within the source of the header, if there is one
+    pub fn metadata_range(&self) -> Option<Range<usize>> {
+        self.metadata_range.clone()
+    }
+
     /// Parses a `PassageHeader` out of the given context
     pub fn parse(context: FullContext) -> Output<Result<Self, ErrorList>> {
+        PassageHeader::parse_with_options(context, &ParseOptions::default())
+    }
+
+    /// Parses a `PassageHeader` out of the given context using the given
+    /// [`ParseOptions`]
+    ///
+    /// # Examples
+    /// ```
+    /// use tweep::{ErrorKind, FullContext, ParseOptions, PassageHeader};
+    /// let context = FullContext::from(None, r#":: Name { "size": "5,5""#.to_string());
+    /// let options = ParseOptions::new().with_strict_headers(true);
+    /// let (res, _) = PassageHeader::parse_with_options(context, &options).take();
+    /// let errors = res.err().unwrap().errors;
+    /// assert_eq!(errors[0].kind, ErrorKind::UnclosedMetadataBlock);
+    /// ```
+    ///
+    /// [`ParseOptions`]: struct.ParseOptions.html
+    pub fn parse_with_options(context: FullContext, options: &ParseOptions) -> Output<Result<Self, ErrorList>> {
         let mut warnings = Vec::new();
         let mut errors = ErrorList::default();
         let input = context.get_contents();
@@ -97,7 +243,8 @@ impl PassageHeader {
                 if trimmed.starts_with("::") {
                     Error::new(ErrorKind::LeadingWhitespace, Some(context.clone()))
                 } else {
-                    Error::new(ErrorKind::MissingSigil, Some(context.clone()))
+                    let found = trimmed.split_whitespace().next().unwrap_or_default();
+                    Error::new(ErrorKind::MissingSigil(found.to_string()), Some(context.clone()))
                 }
             );
         }
@@ -105,47 +252,94 @@ impl PassageHeader {
         // Check for metadata
         let mut name_end_pos = input.len();
 
-        // Default metadata
-        let metadata = json!({ "position": "10,10", "size":"100,100" });
-        let mut metadata = if let serde_json::Value::Object(map) = metadata {
-            map
-        } else {
-            panic!("Unreachable: Failed to extract map from JSON object");
+        let mut metadata = serde_json::Map::new();
+
+        let layout = HeaderLayout::scan(input);
+        let offset = context.get_byte_range().start;
+        let shift = |range: &Range<usize>| range.start + offset..range.end + offset;
+        let metadata_range = match &layout.metadata {
+            Some(Ok(range)) => Some(shift(range)),
+            Some(Err(pos)) if !options.strict_headers => Some(shift(&(*pos..input.len()))),
+            _ => None,
+        };
+        let tag_block_range = match &layout.tags {
+            Some(Ok(range)) => Some(shift(range)),
+            _ => None,
+        };
+        let range = match layout.metadata {
+            Some(Err(pos)) if options.strict_headers => {
+                name_end_pos = pos;
+                let error = Error::new(ErrorKind::UnclosedMetadataBlock, Some(context.subcontext(Position::rel(1, pos+1)..)));
+                errors.push(error);
+                None
+            }
+            Some(Err(pos)) => Some(pos..input.len()),
+            Some(Ok(range)) => Some(range),
+            None => None,
         };
 
-        if let Some(range) = guess_metadata_range(input) {
+        if let Some(range) = range {
             let pos = range.start;
             name_end_pos = pos;
 
-            if find_last_unescaped(&input[range.end..], "[").is_some() {
+            if layout.tags_after_metadata {
                 let error = Error::new(ErrorKind::MetadataBeforeTags, Some(context.subcontext(Position::rel(1, pos+1)..)));
                 errors.push(error);
             }
 
-            let meta_context = context.subcontext(Position::rel(1, range.start)..=Position::rel(1, range.end));
+            let meta_context = context.subcontext(Position::rel(1, range.start + 1)..=Position::rel(1, range.end));
             let res = parse_metadata(meta_context);
             if res.is_ok() {
-                for (k, v) in res.ok().unwrap().iter() {
-                    metadata.insert(k.to_string(), v.clone());
-                }
+                metadata = res.ok().unwrap();
             } else {
-                warnings.push(res.err().unwrap());
+                warnings.push(*res.err().unwrap());
             }
         }
 
+        // Default metadata, after any that was written
+        for (key, value) in [("position", "10,10"), ("size", "100,100")] {
+            metadata.entry(key).or_insert_with(|| value.into());
+        }
+
         // Check for tags
         let mut tags: Vec<String> = Vec::new();
-        if let Some(pos) = find_last_unescaped(&input[..name_end_pos], "[") {
-            let end_pos = find_last_unescaped(&input[pos + 1..name_end_pos], "]");
-
-            if let Some(p) = end_pos {
-                tags = input[pos + 1..pos + 1 + p]
-                    .trim()
-                    .split_whitespace()
-                    .map(|s| s.to_string())
-                    .collect();
+        if let Some(block) = layout.tags {
+            let pos = match &block {
+                Ok(range) => range.start,
+                Err(pos) => *pos,
+            };
+
+            if let Ok(range) = block {
+                let tag_str = &input[range.start + 1..range.end - 1];
+                // Tabs are whitespace, so only commas are a legacy separator
+                let legacy = tag_str.chars().find(|&c| c == ',').filter(|_| options.lenient);
+                if let Some(separator) = legacy {
+                    let tag_context = context.subcontext(Position::rel(1, range.start + 1)..=Position::rel(1, range.end));
+                    warnings.push(Warning::new(WarningKind::LegacyTagSeparator(separator), Some(tag_context)));
+                }
+                let mut special_lookalikes = Vec::new();
+                for span in tag_spans(tag_str, legacy.is_some()) {
+                    let tag = &tag_str[span.clone()];
+                    let start = range.start + 2 + span.start;
+                    let tag_context = || context.subcontext(Position::rel(1, start)..=Position::rel(1, start + tag.len() - 1));
+                    if tag.contains(SUSPICIOUS_TAG_CHARS) {
+                        warnings.push(Warning::new(WarningKind::SuspiciousTag(tag.to_string()), Some(tag_context())));
+                    }
+                    if let Some(special) = special_lookalike(tag) {
+                        let kind = WarningKind::UnknownSpecialTag(tag.to_string(), special.to_string());
+                        special_lookalikes.push(Warning::new(kind, Some(tag_context())));
+                    }
+                    tags.push(tag.to_string());
+                }
+
+                // A lookalike doesn't matter if the real special tag is there too
+                if !tags.iter().any(|tag| SPECIAL_TAGS.contains(&tag.as_str())) {
+                    warnings.append(&mut special_lookalikes);
+                }
             } else {
-                let error = Error::new(ErrorKind::UnclosedTagBlock, Some(context.subcontext(Position::rel(1, pos+1)..)));
+                let block = input[pos + 1..name_end_pos.max(pos + 1)].trim_end();
+                let kind = ErrorKind::UnclosedTagBlock(block.to_string());
+                let error = Error::new(kind, Some(context.subcontext(Position::rel(1, pos+1)..)));
                 errors.push(error);
             }
 
@@ -154,7 +348,8 @@ impl PassageHeader {
 
         // Check for unescaped special characters in the name portion. This also
         // produces a list of warning locations for escaped chars in the name
-        for (c, e, w) in vec![
+        let raw_name = input.get(2..name_end_pos).unwrap_or("").trim();
+        let checks: [EscapeCheck; 4] = [
             (
                 "{",
                 ErrorKind::UnescapedOpenCurly,
@@ -175,29 +370,43 @@ impl PassageHeader {
                 ErrorKind::UnescapedCloseSquare,
                 WarningKind::EscapedCloseSquare,
             ),
-        ] {
-            // If there are unescaped special chars, return the error now. Pass
+        ];
+        for (c, e, w) in checks {
+            // If there are unescaped special chars, add an error for each. Pass
             // in 0 as the starting index because that way we don't have to
             // massage the character position of the error or warnings
-            let indices = check_name(context.subcontext(..=Position::rel(1, name_end_pos)), c, e);
+            let indices = check_name(context.subcontext(..=Position::rel(1, name_end_pos)), c, e(raw_name.to_string()));
             if indices.is_err() {
-                errors.push(indices.err().unwrap());
+                indices.err().unwrap().into_iter().for_each(|error| errors.push(error));
             } else {
                 let indices = indices.ok().unwrap();
 
                 // For any warning locations returned, add them to the warning list
                 for idx in indices {
-                    let warning = Warning::new(w.clone(), Some(context.subcontext(Position::rel(1, idx + 1)..=Position::rel(1, idx+2))));
+                    let warning = Warning::new(w(raw_name.to_string()), Some(context.subcontext(Position::rel(1, idx + 1)..=Position::rel(1, idx+2))));
                     warnings.push(warning);
                 }
             }
         }
 
-        let name = if name_end_pos > 2 {
-            input[2..name_end_pos].trim().replace("\\", "")
+        let name_range = if name_end_pos > 2 {
+            let raw = &input[2..name_end_pos];
+            let start = 2 + raw.len() - raw.trim_start().len();
+            let end = name_end_pos - (raw.len() - raw.trim_end().len());
+            shift(&(start..end.max(start)))
+        } else {
+            offset..offset
+        };
+        let mut name = if name_end_pos > 2 {
+            NormalizedName::new(&input[2..name_end_pos]).into_string()
         } else {
             String::default()
         };
+        if options.lenient && windows_1252::has_misdecoded_chars(&name) {
+            let name_context = context.subcontext(Position::rel(1, 3)..=Position::rel(1, name_end_pos));
+            warnings.push(Warning::new(WarningKind::LegacyEncoding, Some(name_context)));
+            name = windows_1252::fix_misdecoded_chars(&name);
+        }
         if name.is_empty() {
             let error = Error::new(ErrorKind::EmptyName, Some(context.subcontext(Position::rel(1,3)..)));
             errors.push(error);
@@ -208,6 +417,10 @@ impl PassageHeader {
                 name,
                 tags,
                 metadata,
+                original_text: input.to_string(),
+                name_range,
+                tag_block_range,
+                metadata_range,
             }))
             .with_warnings(warnings)
         } else {
@@ -216,88 +429,176 @@ impl PassageHeader {
     }
 }
 
+/// Characters that suggest a tag was meant to be a list of several tags
+const SUSPICIOUS_TAG_CHARS: &[char] = &[',', ';', '|', '/', '\\'];
+
+/// Tags that mark a passage as a script or stylesheet
+const SPECIAL_TAGS: &[&str] = &["script", "stylesheet"];
+
+/// If `tag` isn't a special tag but looks like it was meant to be one, because
+/// it differs only in case, plurality, or non-alphanumeric characters, or is
+/// `style` or `styles`, returns the special tag it resembles
+fn special_lookalike(tag: &str) -> Option<&'static str> {
+    if SPECIAL_TAGS.contains(&tag) {
+        return None;
+    }
+    let normalized: String = tag.chars().filter(|c| c.is_alphanumeric()).collect::<String>().to_lowercase();
+    match normalized.as_str() {
+        "script" | "scripts" => Some("script"),
+        "stylesheet" | "stylesheets" | "style" | "styles" => Some("stylesheet"),
+        _ => None,
+    }
+}
+
+/// Returns the byte ranges of the tags within the contents of a tag block.
+/// Tags are separated by whitespace, and also by commas if `split_commas` is
+/// set
+fn tag_spans(tag_str: &str, split_commas: bool) -> Vec<Range<usize>> {
+    let is_separator = |c: char| c.is_whitespace() || (split_commas && c == ',');
+    let mut spans = Vec::new();
+    let mut start = None;
+    for (i, c) in tag_str.char_indices() {
+        match (is_separator(c), start) {
+            (true, Some(s)) => {
+                spans.push(s..i);
+                start = None;
+            }
+            (false, None) => start = Some(i),
+            _ => (),
+        }
+    }
+    if let Some(s) = start {
+        spans.push(s..tag_str.len());
+    }
+    spans
+}
+
 /// Given metadata in `meta_str`, parses out the metadata object, or returns a
 /// warning if the metadata can't be parsed
-fn parse_metadata(context: FullContext) -> Result<serde_json::Map<String, serde_json::Value>, Warning> {
+fn parse_metadata(context: FullContext) -> Result<serde_json::Map<String, serde_json::Value>, Box<Warning>> {
     let meta_str = context.get_contents();
     let res = serde_json::from_str(meta_str);
     if res.is_ok() {
-        use serde_json::Value;
-        let tmp_meta: Value = res.ok().unwrap();
-        if let Value::Object(map) = tmp_meta {
-            Ok(map)
-        } else {
-            // shouldn't be possible?
-            panic!("found a metadata object but it isn't an object?");
-        }
+        Ok(res.ok().unwrap())
     } else {
         let err = res.err().unwrap();
-        let col = err.column();
-        // Get the error part of error string generated by serde
-        let err_string = format!("{}", err).split(" at ").next().unwrap().to_string();
-        let warning = Warning::new(WarningKind::JsonError(err_string), Some(context.subcontext(Position::rel(1, col)..)));
-        Err(warning)
+        let start = *json_error_context(&context, &err).get_start_position();
+        let warning = Warning::new(WarningKind::JsonError(json_error_message(&err)), Some(context.subcontext(start..)));
+        Err(Box::new(warning))
     }
 }
 
-/// Finds the last unescaped string `s` in the input string `input`
-fn find_last_unescaped(input: &str, s: &str) -> Option<usize> {
-    // Check for last 's'
-    input.rfind(s).and_then(|pos| {
-        let escaped_str = format!("\\{}", s);
-        // Find last escaped 's' or use input length
-        let escaped_pos = input.rfind(&escaped_str).unwrap_or_else(|| input.len());
-
-        // If the position of the escaped and unescaped locations don't match
-        // then we found an unescaped 's'
-        if pos != (escaped_pos + 1) {
-            Some(pos)
-        } else {
-            None
-        }
-    })
+/// The blocks of a passage header, found by scanning it from left to right.
+/// Escaped characters are skipped, and the metadata block is scanned as JSON,
+/// so that brackets and braces within JSON strings don't end it
+#[derive(Debug, Default, PartialEq)]
+struct HeaderLayout {
+    /// The range of the tag block, including its brackets, or the position of
+    /// the `[` if the block is unclosed. When there are several bracketed
+    /// blocks before the metadata, the last is the tag block, and the others
+    /// are part of the name
+    tags: Option<Result<Range<usize>, usize>>,
+
+    /// The range of the metadata block, including its braces, or the position
+    /// of the `{` if the block is unclosed
+    metadata: Option<Result<Range<usize>, usize>>,
+
+    /// Whether a `[` follows the metadata block
+    tags_after_metadata: bool,
 }
 
-/// Finds all unescaped occurrences of the string `s` in input string `input`
-fn find_all_unescaped(input: &str, s: &str) -> Vec<usize> {
-    let esc_s = format!("\\{}", s);
-    let escaped: Vec<usize> = input.match_indices(&esc_s).map(|(i, _)| i + 1).collect();
-    let unescaped: Vec<usize> = input
-        .match_indices(s)
-        .filter(|(i, _)| !escaped.contains(i))
-        .map(|(i, _)| i)
-        .collect();
+impl HeaderLayout {
+    /// Scans the header `input` in a single pass
+    fn scan(input: &str) -> Self {
+        let mut layout = HeaderLayout::default();
+        let mut open_tag = None;
+        let mut i = 0;
+        while let Some(c) = input[i..].chars().next() {
+            let mut next = i + c.len_utf8();
+            match c {
+                '\\' => next += input[next..].chars().next().map_or(0, char::len_utf8),
+                '[' if layout.metadata.is_some() => layout.tags_after_metadata = true,
+                '[' => open_tag = Some(i),
+                ']' => {
+                    if let Some(start) = open_tag.take() {
+                        layout.tags = Some(Ok(start..next));
+                    }
+                }
+                '{' if layout.metadata.is_none() && opens_metadata(&input[next..]) => {
+                    if let Some(start) = open_tag.take() {
+                        layout.tags = Some(Err(start));
+                    }
+                    match metadata_len(&input[i..]) {
+                        Some(len) => {
+                            layout.metadata = Some(Ok(i..i + len));
+                            next = i + len;
+                        }
+                        None => {
+                            layout.metadata = Some(Err(i));
+                            return layout;
+                        }
+                    }
+                }
+                _ => (),
+            }
+            i = next;
+        }
+        if let Some(start) = open_tag {
+            layout.tags = Some(Err(start));
+        }
+        layout
+    }
+}
 
-    unescaped
+/// Returns `true` if a `{` followed by `rest` starts a JSON object: that is,
+/// the next character other than whitespace is a `"` or `}`, or there is none
+fn opens_metadata(rest: &str) -> bool {
+    matches!(rest.trim_start().chars().next(), None | Some('"') | Some('}'))
 }
 
-/// Given a header string, tries to guess what the best range is representing
-/// the metadata within the header, if present. Returns `None` if no metadata is
-/// found. If it's found, it returns the range
-///
-/// Code-chan... ganbarre
-fn guess_metadata_range(input: &str) -> Option<Range<usize>> {
-    let opens = find_all_unescaped(input, "{");
-    let closes = find_all_unescaped(input, "}");
-
-    if opens.is_empty() {
-        None
-    } else if closes.is_empty() {
-        Some(opens[opens.len() - 1]..input.len())
-    } else if opens.len() > closes.len() {
-        let diff = opens.len() - closes.len();
-        Some(opens[diff]..(closes[closes.len() - 1] + 1))
-    } else {
-        Some(opens[0]..(closes[closes.len() - 1] + 1))
+/// Given input starting with `{`, returns the length of the JSON object it
+/// opens, skipping over any braces within JSON strings, or `None` if the
+/// object is not closed
+fn metadata_len(input: &str) -> Option<usize> {
+    let mut depth = 0;
+    let mut in_string = false;
+    let mut escaped = false;
+    for (i, c) in input.char_indices() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => (),
+            }
+            continue;
+        }
+        match c {
+            '"' => in_string = true,
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i + 1);
+                }
+            }
+            _ => (),
+        }
     }
+    None
 }
 
+/// A character that must be escaped in passage names, with constructors for
+/// the error produced when it isn't and the warning produced when it is
+type EscapeCheck = (&'static str, fn(String) -> ErrorKind, fn(String) -> WarningKind);
+
 /// Checks the name of a passage (`input`) for validity. If the name contains
-/// any of the unescaped special character (`str`), return the error `error`. If
-/// the name contains any instances of that character but escaped, return a list
-/// of locations in the name where the escaped character is found so that
-/// warnings can be generated
-fn check_name(context: FullContext, unescaped_str: &str, error: ErrorKind) -> Result<Vec<usize>, Error> {
+/// any of the unescaped special character (`str`), return the error `error` for
+/// each, with a fix that inserts a backslash before it. If the name contains
+/// any instances of that character but escaped, return a list of locations in
+/// the name where the escaped character is found so that warnings can be
+/// generated
+fn check_name(context: FullContext, unescaped_str: &str, error: ErrorKind) -> Result<Vec<usize>, Vec<Error>> {
     let escaped_str = format!("\\{}", unescaped_str);
     let input = context.get_contents();
 
@@ -311,13 +612,26 @@ fn check_name(context: FullContext, unescaped_str: &str, error: ErrorKind) -> Re
     if unescaped.is_empty() {
         Ok(escaped)
     } else {
-        let err_range = Position::rel(1, unescaped[0] + 1)..=Position::rel(1, unescaped[0]+1);
-        let error = Error::new(error, Some(context.subcontext(err_range)));
-        Err(error)
+        // Each error can be fixed by inserting a backslash before the character
+        let passage = input.get(2..).unwrap_or("").trim().to_string();
+        let errors = unescaped
+            .into_iter()
+            .map(|i| {
+                let fix = TextEdit {
+                    passage: passage.clone(),
+                    context: context.slice(i..i),
+                    replacement: "\\".to_string(),
+                };
+                Error::new(error.clone(), Some(context.slice(i..i + 1))).with_fix(fix)
+            })
+            .collect();
+        Err(errors)
     }
 }
 
 #[cfg(test)]
+// Contexts only need converting without full-context
+#[allow(clippy::useless_conversion)]
 mod tests {
     use super::*;
 
@@ -327,11 +641,8 @@ mod tests {
         let expected = context.clone();
         let out = PassageHeader::parse(context);
         let (res, _) = out.take();
-        assert_eq!(res.is_err(), true);
-        assert_eq!(res.err().unwrap().errors[0], {
-            let error = Error::new(ErrorKind::MissingSigil, Some(expected));
-            error
-        });
+        assert!(res.is_err());
+        assert_eq!(res.err().unwrap().errors[0], Error::new(ErrorKind::MissingSigil("An".to_string()), Some(expected)));
     }
 
     #[test]
@@ -340,11 +651,8 @@ mod tests {
         let expected = context.clone();
         let out = PassageHeader::parse(context);
         let (res, _) = out.take();
-        assert_eq!(res.is_err(), true);
-        assert_eq!(res.err().unwrap().errors[0], {
-            let error = Error::new(ErrorKind::LeadingWhitespace, Some(expected));
-            error
-        });
+        assert!(res.is_err());
+        assert_eq!(res.err().unwrap().errors[0], Error::new(ErrorKind::LeadingWhitespace, Some(expected)));
     }
 
     #[test]
@@ -353,21 +661,15 @@ mod tests {
         let expected = context.subcontext(Position::rel(1, 3)..);
         let out = PassageHeader::parse(context);
         let (res, _) = out.take();
-        assert_eq!(res.is_err(), true);
-        assert_eq!(res.err().unwrap().errors[0], {
-            let error = Error::new(ErrorKind::EmptyName, Some(expected));
-            error
-        });
+        assert!(res.is_err());
+        assert_eq!(res.err().unwrap().errors[0], Error::new(ErrorKind::EmptyName, Some(expected)));
 
         let context = FullContext::from(None, ":: \t".to_string());
         let expected = context.subcontext(Position::rel(1, 3)..);
         let out = PassageHeader::parse(context);
         let (res, _) = out.take();
-        assert_eq!(res.is_err(), true);
-        assert_eq!(res.err().unwrap().errors[0], {
-            let error = Error::new(ErrorKind::EmptyName, Some(expected));
-            error
-        });
+        assert!(res.is_err());
+        assert_eq!(res.err().unwrap().errors[0], Error::new(ErrorKind::EmptyName, Some(expected)));
     }
 
     #[test]
@@ -379,21 +681,20 @@ mod tests {
         let expected = context.subcontext(Position::rel(1, 22)..);
         let out = PassageHeader::parse(context);
         let (res, _) = out.take();
-        assert_eq!(res.is_err(), true);
-        assert_eq!(res.err().unwrap().errors[0], {
-            let error = Error::new(ErrorKind::MetadataBeforeTags, Some(expected));
-            error
-        });
+        assert!(res.is_err());
+        assert_eq!(res.err().unwrap().errors[0], Error::new(ErrorKind::MetadataBeforeTags, Some(expected)));
     }
 
     #[test]
     fn unescaped_chars() {
-        for (c, e) in vec![
+        type Check = (&'static str, fn(String) -> ErrorKind);
+        let checks: [Check; 4] = [
             ("{", ErrorKind::UnescapedOpenCurly),
             ("}", ErrorKind::UnescapedCloseCurly),
             ("[", ErrorKind::UnescapedOpenSquare),
             ("]", ErrorKind::UnescapedCloseSquare),
-        ] {
+        ];
+        for (c, e) in checks {
             let context = FullContext::from(
                 None,
                 format!(":: {}An overgrown path [tag] {{ \"size\": \"5,5\" }}", c),
@@ -402,12 +703,11 @@ mod tests {
             
             let out = PassageHeader::parse(context);
             let (res, _) = out.take();
-            assert_eq!(res.is_err(), true);
+            assert!(res.is_err());
             let errors = res.err().unwrap().errors;
             assert!(errors.iter().any(|a| {
                 let sub = sub.subcontext(Position::rel(1, 4)..=Position::rel(1, 4));
-                let expected = Error::new(e.clone(), Some(sub));
-                *a == expected
+                a.kind == e(format!("{}An overgrown path", c)) && a.context == Some(sub.into())
             }));
 
             let input = format!(
@@ -418,11 +718,10 @@ mod tests {
             let sub = context.clone();
             let out = PassageHeader::parse(context);
             let (res, _) = out.take();
-            assert_eq!(res.is_err(), true);
+            assert!(res.is_err());
             assert!(res.err().unwrap().errors.iter().any(|a| {
                 let sub = sub.subcontext(Position::rel(1,4)..=Position::rel(1,4));
-                let expected = Error::new(e.clone(), Some(sub));
-                *a == expected
+                a.kind == e(format!("{}\\{}An overgrown path", c, c)) && a.context == Some(sub.into())
             }));
             let input = format!(
                 ":: \\{}{}An overgrown path [tag] {{ \"size\": \"5,5\" }}",
@@ -432,11 +731,10 @@ mod tests {
             let sub = context.clone();
             let out = PassageHeader::parse(context);
             let (res, _) = out.take();
-            assert_eq!(res.is_err(), true);            
+            assert!(res.is_err());            
             assert!(res.err().unwrap().errors.iter().any(|a| {
                 let sub = sub.subcontext(Position::rel(1,6)..=Position::rel(1,6));
-                let expected = Error::new(e.clone(), Some(sub));
-                *a == expected
+                a.kind == e(format!("\\{}{}An overgrown path", c, c)) && a.context == Some(sub.into())
             }));
         }
     }
@@ -447,11 +745,8 @@ mod tests {
         let expected = context.subcontext(Position::rel(1, 22)..);
         let out = PassageHeader::parse(context);
         let (res, _) = out.take();
-        assert_eq!(res.is_err(), true);
-        assert_eq!(res.err().unwrap().errors[0], {
-            let error = Error::new(ErrorKind::UnclosedTagBlock, Some(expected));
-            error
-        });
+        assert!(res.is_err());
+        assert_eq!(res.err().unwrap().errors[0], Error::new(ErrorKind::UnclosedTagBlock(" tag1 tag2".to_string()), Some(expected)));
     }
 
     #[test]
@@ -460,14 +755,155 @@ mod tests {
             FullContext::from(None, ":: An overgrown path { \"foo\": \"bar\"".to_string());
         let out = PassageHeader::parse(context);
         let (res, warnings) = out.take();
-        assert_eq!(res.is_err(), false);
-        assert!(
-            if let WarningKind::JsonError(_) = warnings[0].kind {
-                true
-            } else {
-                false
-            }
-        )
+        assert!(res.is_ok());
+        assert!(matches!(warnings[0].kind, WarningKind::JsonError(_)))
+    }
+
+    #[test]
+    fn strict_unclosed_metadata() {
+        let options = ParseOptions::new().with_strict_headers(true);
+        let context = FullContext::from(None, ":: An overgrown path [tag] { \"foo\": \"}\"".to_string());
+        let expected = context.subcontext(Position::rel(1, 28)..);
+        let (res, warnings) = PassageHeader::parse_with_options(context, &options).take();
+        assert!(warnings.is_empty());
+        assert_eq!(res.err().unwrap().errors, vec![Error::new(ErrorKind::UnclosedMetadataBlock, Some(expected))]);
+
+        let context = FullContext::from(None, ":: Title {\"a\": {\"b\": \"{\"}}".to_string());
+        let (res, warnings) = PassageHeader::parse_with_options(context, &options).take();
+        assert!(warnings.is_empty());
+        assert_eq!(res.ok().unwrap().metadata["a"]["b"], "{");
+    }
+
+    #[test]
+    fn brackets_and_braces_in_metadata() {
+        let context = FullContext::from(None, ":: X { \"a\": \"[1]\" }".to_string());
+        let (res, warnings) = PassageHeader::parse(context).take();
+        let ph = res.ok().unwrap();
+        assert!(warnings.is_empty());
+        assert_eq!(ph.name, "X");
+        assert!(ph.tags.is_empty());
+        assert_eq!(ph.metadata["a"], "[1]");
+
+        let context = FullContext::from(None, ":: X [t] {\"a\": \"{\", \"b\": \"]\"}".to_string());
+        let (res, warnings) = PassageHeader::parse(context).take();
+        let ph = res.ok().unwrap();
+        assert!(warnings.is_empty());
+        assert_eq!(ph.tags, vec!["t"]);
+        assert_eq!(ph.metadata["a"], "{");
+        assert_eq!(ph.metadata["b"], "]");
+
+        let context = FullContext::from(None, ":: X\\[1\\] {\"a\": 1} [t]".to_string());
+        let layout = HeaderLayout::scan(context.get_contents());
+        assert_eq!(layout, HeaderLayout {
+            tags: None,
+            metadata: Some(Ok(10..18)),
+            tags_after_metadata: true,
+        });
+    }
+
+    #[test]
+    fn component_ranges() {
+        let input = ":: Skipped\n::  A passage  [tag] { \"a\": 1 }\nText".to_string();
+        let context = FullContext::from(None, input.clone());
+        let sub = context.subcontext(Position::rel(2, 1)..=Position::rel(2, 35));
+        let ph = PassageHeader::parse(sub).take().0.ok().unwrap();
+        assert_eq!(&input[ph.name_range()], "A passage");
+        assert_eq!(&input[ph.tag_block_range().unwrap()], "[tag]");
+        assert_eq!(&input[ph.metadata_range().unwrap()], "{ \"a\": 1 }");
+
+        let context = FullContext::from(None, ":: Plain ".to_string());
+        let ph = PassageHeader::parse(context).take().0.ok().unwrap();
+        assert_eq!(ph.name_range(), 3..8);
+        assert_eq!(ph.tag_block_range(), None);
+        assert_eq!(ph.metadata_range(), None);
+
+        let context = FullContext::from(None, ":: Open { \"a\": 1".to_string());
+        let ph = PassageHeader::parse(context).take().0.ok().unwrap();
+        assert_eq!(ph.metadata_range(), Some(8..16));
+    }
+
+    #[test]
+    fn json_error_position() {
+        let context = FullContext::from(None, ":: Title {\"a\": 1,}".to_string());
+        let (_, warnings) = PassageHeader::parse(context.clone()).take();
+        assert_eq!(warnings.len(), 1);
+        let expected = Warning::new(warnings[0].kind.clone(), Some(context.subcontext(Position::rel(1, 18)..)));
+        assert_eq!(warnings[0], expected);
+    }
+
+    #[test]
+    fn suspicious_tags() {
+        let context = FullContext::from(None, ":: A [ok my,tag foo/bar]".to_string());
+        let (res, warnings) = PassageHeader::parse(context.clone()).take();
+        assert_eq!(res.ok().unwrap().tags, vec!["ok", "my,tag", "foo/bar"]);
+        assert_eq!(
+            warnings,
+            vec![
+                Warning::new(
+                    WarningKind::SuspiciousTag("my,tag".to_string()),
+                    Some(context.subcontext(Position::rel(1, 10)..=Position::rel(1, 15)))
+                ),
+                Warning::new(
+                    WarningKind::SuspiciousTag("foo/bar".to_string()),
+                    Some(context.subcontext(Position::rel(1, 17)..=Position::rel(1, 23)))
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn unknown_special_tags() {
+        let context = FullContext::from(None, ":: A [Script styles stylesheet\u{200b} js]".to_string());
+        let (res, warnings) = PassageHeader::parse(context.clone()).take();
+        assert_eq!(res.ok().unwrap().tags.len(), 4);
+        let kinds: Vec<WarningKind> = warnings.into_iter().map(|w| w.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                WarningKind::UnknownSpecialTag("Script".to_string(), "script".to_string()),
+                WarningKind::UnknownSpecialTag("styles".to_string(), "stylesheet".to_string()),
+                WarningKind::UnknownSpecialTag("stylesheet\u{200b}".to_string(), "stylesheet".to_string()),
+            ]
+        );
+
+        let context = FullContext::from(None, ":: A [Script script]".to_string());
+        let (_, warnings) = PassageHeader::parse(context).take();
+        assert!(warnings.is_empty());
+
+        let context = FullContext::from(None, ":: A [styles]".to_string());
+        let (_, warnings) = PassageHeader::parse(context.clone()).take();
+        assert_eq!(
+            warnings,
+            vec![Warning::new(
+                WarningKind::UnknownSpecialTag("styles".to_string(), "stylesheet".to_string()),
+                Some(context.subcontext(Position::rel(1, 7)..=Position::rel(1, 12)))
+            )]
+        );
+    }
+
+    #[test]
+    fn lenient() {
+        let options = ParseOptions::new().with_lenient(true);
+        let context = FullContext::from(None, ":: A [ tag1,tag2\ttag3, tag4 ]".to_string());
+        let expected = context.subcontext(Position::rel(1, 6)..=Position::rel(1, 29));
+        let (res, warnings) = PassageHeader::parse_with_options(context.clone(), &options).take();
+        assert_eq!(res.ok().unwrap().tags, vec!["tag1", "tag2", "tag3", "tag4"]);
+        assert_eq!(warnings, vec![Warning::new(WarningKind::LegacyTagSeparator(','), Some(expected))]);
+
+        let (res, warnings) = PassageHeader::parse(context).take();
+        assert_eq!(warnings.len(), 2);
+        assert_eq!(warnings[0].kind, WarningKind::SuspiciousTag("tag1,tag2".to_string()));
+        assert_eq!(res.ok().unwrap().tags, vec!["tag1,tag2", "tag3,", "tag4"]);
+
+        let context = FullContext::from(None, ":: A [tag1\ttag2]".to_string());
+        let (res, warnings) = PassageHeader::parse_with_options(context, &options).take();
+        assert_eq!(res.ok().unwrap().tags, vec!["tag1", "tag2"]);
+        assert!(warnings.is_empty());
+
+        let context = FullContext::from(None, ":: It\u{92}s over".to_string());
+        let (res, warnings) = PassageHeader::parse_with_options(context, &options).take();
+        assert_eq!(res.ok().unwrap().name, "It’s over");
+        assert_eq!(warnings[0].kind, WarningKind::LegacyEncoding);
     }
 
     #[test]
@@ -477,32 +913,32 @@ mod tests {
             ":: An overgrown path [tag1 tag2 tag3   tag4   ]".to_string(),
         );
         let out = PassageHeader::parse(context);
-        assert_eq!(out.has_warnings(), false);
+        assert!(!out.has_warnings());
         let (res, _) = out.take();
-        assert_eq!(res.is_ok(), true);
+        assert!(res.is_ok());
         let ph = res.ok().unwrap();
         assert_eq!(ph.tags.len(), 4);
         assert_eq!(ph.tags, vec!["tag1", "tag2", "tag3", "tag4"]);
-        assert_eq!(ph.has_tag("tag1"), true);
-        assert_eq!(ph.has_tag("tag5"), false);
+        assert!(ph.has_tag("tag1"));
+        assert!(!ph.has_tag("tag5"));
 
         let context = FullContext::from(None, ":: An overgrown path []".to_string());
         let out = PassageHeader::parse(context);
-        assert_eq!(out.has_warnings(), false);
+        assert!(!out.has_warnings());
         let (res, _) = out.take();
-        assert_eq!(res.is_ok(), true);
+        assert!(res.is_ok());
         let ph = res.ok().unwrap();
         assert_eq!(ph.tags.len(), 0);
-        assert_eq!(ph.has_tag("tag1"), false);
+        assert!(!ph.has_tag("tag1"));
 
         let context = FullContext::from(
             None,
             ":: An overgrown path [              \t          ]".to_string(),
         );
         let out = PassageHeader::parse(context);
-        assert_eq!(out.has_warnings(), false);
+        assert!(!out.has_warnings());
         let (res, _) = out.take();
-        assert_eq!(res.is_ok(), true);
+        assert!(res.is_ok());
         let ph = res.ok().unwrap();
         assert_eq!(ph.tags.len(), 0);
     }
@@ -511,9 +947,9 @@ mod tests {
     fn metadata() {
         let context = FullContext::from(None, ":: Title {\"foo\":\"bar\"}".to_string());
         let out = PassageHeader::parse(context);
-        assert_eq!(out.has_warnings(), false);
+        assert!(!out.has_warnings());
         let (res, _) = out.take();
-        assert_eq!(res.is_ok(), true);
+        assert!(res.is_ok());
         let ph = res.ok().unwrap();
         let meta = &ph.metadata;
         assert_eq!(meta["size"], "100,100");
@@ -522,9 +958,9 @@ mod tests {
 
         let context = FullContext::from(None, ":: Title {\"size\":\"23,23\"}".to_string());
         let out = PassageHeader::parse(context);
-        assert_eq!(out.has_warnings(), false);
+        assert!(!out.has_warnings());
         let (res, _) = out.take();
-        assert_eq!(res.is_ok(), true);
+        assert!(res.is_ok());
         let ph = res.ok().unwrap();
         let meta = &ph.metadata;
         assert_eq!(meta["size"], "23,23");
@@ -532,9 +968,9 @@ mod tests {
 
         let context = FullContext::from(None, ":: Title { \"position\":\"5,5\" }".to_string());
         let out = PassageHeader::parse(context);
-        assert_eq!(out.has_warnings(), false);
+        assert!(!out.has_warnings());
         let (res, _) = out.take();
-        assert_eq!(res.is_ok(), true);
+        assert!(res.is_ok());
         let ph = res.ok().unwrap();
         let meta = &ph.metadata;
         assert_eq!(meta["size"], "100,100");
@@ -545,15 +981,32 @@ mod tests {
             ":: Title {\"size\":\"23,23\", \"position\":\"5,5\"}".to_string(),
         );
         let out = PassageHeader::parse(context);
-        assert_eq!(out.has_warnings(), false);
+        assert!(!out.has_warnings());
         let (res, _) = out.take();
-        assert_eq!(res.is_ok(), true);
+        assert!(res.is_ok());
         let ph = res.ok().unwrap();
         let meta = &ph.metadata;
         assert_eq!(meta["size"], "23,23");
         assert_eq!(meta["position"], "5,5");
     }
 
+    #[test]
+    fn metadata_order() {
+        let context = FullContext::from(None, ":: Title {\"b\": 1, \"size\": \"2,2\", \"a\": {\"y\": 1, \"x\": 2}, \"b\": 3}".to_string());
+        let ph = PassageHeader::parse(context).take().0.ok().unwrap();
+        let keys: Vec<&str> = ph.metadata.keys().map(|k| k.as_str()).collect();
+        assert_eq!(keys, vec!["b", "size", "a", "position"]);
+        assert_eq!(ph.metadata["b"], 3);
+        assert!(ph.has_authored_metadata("size"));
+        assert!(!ph.has_authored_metadata("position"));
+
+        let json = ph.metadata_json();
+        assert_eq!(json, r#"{"b":3,"size":"2,2","a":{"y":1,"x":2},"position":"10,10"}"#);
+        assert_eq!(serde_json::to_string(&ph.metadata).unwrap(), json);
+        let copy = PassageHeader::from_parts(ph.name.clone(), Vec::new(), &json, String::new()).unwrap();
+        assert_eq!(copy.metadata_json(), json);
+    }
+
     #[test]
     fn multilevel_metadata() {
         let context = FullContext::from(
@@ -561,9 +1014,9 @@ mod tests {
             ":: Title {\"size\": \"23,23\", \"foo\": { \"bar\": 5 } }".to_string(),
         );
         let out = PassageHeader::parse(context);
-        assert_eq!(out.has_warnings(), false);
+        assert!(!out.has_warnings());
         let (res, _) = out.take();
-        assert_eq!(res.is_ok(), true);
+        assert!(res.is_ok());
         let ph = res.ok().unwrap();
         let meta = &ph.metadata;
         assert_eq!(meta["size"], "23,23");
@@ -576,19 +1029,14 @@ mod tests {
         let context = FullContext::from(None, ":: Title {\"size\":\"23, }".to_string());
         let out = PassageHeader::parse(context);
         let (res, warnings) = out.take();
-        assert_eq!(res.is_ok(), true);
+        assert!(res.is_ok());
         let ph = res.ok().unwrap();
         let meta = &ph.metadata;
         assert_eq!(meta["size"], "100,100");
         assert_eq!(meta["position"], "10,10");
 
         assert_eq!(warnings.len(), 1);
-        let expected = if let WarningKind::JsonError(_) = warnings[0].kind {
-            true
-        } else {
-            false
-        };
-        assert_eq!(expected, true);
+        assert!(matches!(warnings[0].kind, WarningKind::JsonError(_)));
     }
 
     #[test]
@@ -596,24 +1044,26 @@ mod tests {
         let context = FullContext::from(None, ":: An over\\[grown\\} pa\\th[ tag ]".to_string());
         let out = PassageHeader::parse(context);
         let (res, warnings) = out.take();
-        assert_eq!(res.is_ok(), true);
+        assert!(res.is_ok());
         let ph = res.ok().unwrap();
-        assert_eq!(ph.name, "An over[grown} path");
+        assert_eq!(ph.name, "An over[grown} pa\\th");
         assert_eq!(ph.tags.len(), 1);
         assert_eq!(warnings.len(), 2);
-        assert_eq!(warnings[1].kind, WarningKind::EscapedOpenSquare);
-        assert_eq!(warnings[0].kind, WarningKind::EscapedCloseCurly);
+        let raw_name = "An over\\[grown\\} pa\\th".to_string();
+        assert_eq!(warnings[1].kind, WarningKind::EscapedOpenSquare(raw_name.clone()));
+        assert_eq!(warnings[0].kind, WarningKind::EscapedCloseCurly(raw_name));
 
         let context = FullContext::from(None, ":: An over\\{grown\\] pa\\th[ tag ]".to_string());
         let out = PassageHeader::parse(context);
         let (res, warnings) = out.take();
-        assert_eq!(res.is_ok(), true);
+        assert!(res.is_ok());
         let ph = res.ok().unwrap();
-        assert_eq!(ph.name, "An over{grown] path");
+        assert_eq!(ph.name, "An over{grown] pa\\th");
         assert_eq!(ph.tags.len(), 1);
         assert_eq!(warnings.len(), 2);
-        assert_eq!(warnings[0].kind, WarningKind::EscapedOpenCurly);
-        assert_eq!(warnings[1].kind, WarningKind::EscapedCloseSquare);
+        let raw_name = "An over\\{grown\\] pa\\th".to_string();
+        assert_eq!(warnings[0].kind, WarningKind::EscapedOpenCurly(raw_name.clone()));
+        assert_eq!(warnings[1].kind, WarningKind::EscapedCloseSquare(raw_name));
     }
 
     #[test]
@@ -623,9 +1073,9 @@ mod tests {
             ":: An overgrown path [ tag ] { \"size\": \"5,5\" }".to_string(),
         );
         let out = PassageHeader::parse(context);
-        assert_eq!(out.has_warnings(), false);
+        assert!(!out.has_warnings());
         let (res, _) = out.take();
-        assert_eq!(res.is_ok(), true);
+        assert!(res.is_ok());
         let ph = res.ok().unwrap();
         assert_eq!(ph.name, "An overgrown path");
         assert_eq!(ph.tags.len(), 1);
@@ -642,9 +1092,9 @@ mod tests {
             ":: An overgrown path { \"size\": \"5,5\", \"foo\":[2,3] }".to_string(),
         );
         let out = PassageHeader::parse(context);
-        assert_eq!(out.has_warnings(), false);
+        assert!(!out.has_warnings());
         let (res, _) = out.take();
-        assert_eq!(res.is_ok(), true);
+        assert!(res.is_ok());
         let ph = res.ok().unwrap();
         assert_eq!(ph.name, "An overgrown path");
         let meta = &ph.metadata;
@@ -657,9 +1107,9 @@ mod tests {
     fn empty_tags() {
         let context = FullContext::from(None, ":: An overgrown path []".to_string());
         let out = PassageHeader::parse(context);
-        assert_eq!(out.has_warnings(), false);
+        assert!(!out.has_warnings());
         let (res, _) = out.take();
-        assert_eq!(res.is_ok(), true);
+        assert!(res.is_ok());
         let ph = res.ok().unwrap();
         assert_eq!(ph.tags.len(), 0);
     }
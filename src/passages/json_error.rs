@@ -0,0 +1,47 @@
+use crate::FullContext;
+
+/// Returns the message of a JSON error without the position serde appends
+pub(crate) fn json_error_message(err: &serde_json::Error) -> String {
+    format!("{}", err).split(" at ").next().unwrap().to_string()
+}
+
+/// Returns the context of the character at which `err` was detected, given
+/// the `context` of the JSON that was parsed. serde counts lines and columns
+/// from the start of the JSON, so they're mapped onto the position of the
+/// JSON in its file. An error at the end of the input is placed on its last
+/// character
+pub(crate) fn json_error_context(context: &FullContext, err: &serde_json::Error) -> FullContext {
+    let json = context.get_contents();
+    if json.is_empty() {
+        return context.clone();
+    }
+    let line_start: usize = json.split_inclusive('\n').take(err.line().saturating_sub(1)).map(str::len).sum();
+    let mut offset = std::cmp::min(line_start + err.column().saturating_sub(1), json.len() - 1);
+    while !json.is_char_boundary(offset) {
+        offset -= 1;
+    }
+    let len = json[offset..].chars().next().map_or(0, char::len_utf8);
+    context.slice(offset..offset + len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Position;
+
+    #[test]
+    fn positions() {
+        let source = ":: StoryData\n{\n  \"ifid\": \"X\",\n  \"zoom\": é\n}\n".to_string();
+        let context = FullContext::from(None, source).subcontext(Position::rel(2, 1)..);
+        let err = serde_json::from_str::<serde_json::Value>(context.get_contents()).unwrap_err();
+        let at = json_error_context(&context, &err);
+        assert_eq!(at.get_contents(), "é");
+        assert_eq!(*at.get_start_position(), Position::abs(4, 11));
+        assert_eq!(json_error_message(&err), "expected value");
+
+        let context = FullContext::from(None, "{\"a\": [1,\n".to_string());
+        let err = serde_json::from_str::<serde_json::Value>(context.get_contents()).unwrap_err();
+        let at = json_error_context(&context, &err);
+        assert_eq!(*at.get_start_position(), Position::abs(1, 10));
+    }
+}
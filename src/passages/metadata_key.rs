@@ -0,0 +1,96 @@
+use crate::MetadataType;
+use serde_json::Value;
+use std::sync::Arc;
+
+/// A check on the value of a metadata field, returning a message on failure
+type MetadataValidator = Arc<dyn Fn(&Value) -> Result<(), String> + Send + Sync>;
+
+/// A custom metadata field that passages may declare in their header, such as
+/// `"audio"` or `"mood"`, registered with a [`MetadataRegistry`]. The value
+/// of the field must be of the expected [`MetadataType`] and pass the
+/// validator, if any, or parsing produces an [`InvalidMetadata`] warning.
+/// Passages that don't declare the field get the default, if any
+///
+/// # Examples
+/// ```
+/// use tweep::{MetadataKey, MetadataType};
+/// use serde_json::json;
+/// let key = MetadataKey::new("volume", MetadataType::Number)
+///     .with_default(json!(1.0))
+///     .with_validator(|value| match value.as_f64() {
+///         Some(v) if (0.0..=1.0).contains(&v) => Ok(()),
+///         _ => Err("must be between 0 and 1".to_string()),
+///     });
+/// assert_eq!(key.validate(&json!(0.5)), Ok(()));
+/// assert_eq!(key.validate(&json!("loud")), Err("expected a number".to_string()));
+/// assert_eq!(key.validate(&json!(2)), Err("must be between 0 and 1".to_string()));
+/// ```
+///
+/// [`MetadataRegistry`]: struct.MetadataRegistry.html
+/// [`MetadataType`]: enum.MetadataType.html
+/// [`InvalidMetadata`]: enum.WarningKind.html#variant.InvalidMetadata
+#[derive(Clone)]
+pub struct MetadataKey {
+    /// The name of the field in the passage metadata
+    pub name: String,
+
+    /// The JSON type the value must have
+    pub kind: MetadataType,
+
+    /// The value used when a passage doesn't declare the field
+    pub default: Option<Value>,
+
+    validator: Option<MetadataValidator>,
+}
+
+impl MetadataKey {
+    /// Creates a new key with the given name and type, no default, and no
+    /// validator
+    pub fn new(name: &str, kind: MetadataType) -> Self {
+        MetadataKey {
+            name: name.to_string(),
+            kind,
+            default: None,
+            validator: None,
+        }
+    }
+
+    /// Builder method to set the `default`
+    pub fn with_default(mut self, default: Value) -> Self {
+        self.default = Some(default);
+        self
+    }
+
+    /// Builder method to set a validator, which is only called with values of
+    /// the expected type
+    pub fn with_validator<F>(mut self, validator: F) -> Self
+    where
+        F: Fn(&Value) -> Result<(), String> + Send + Sync + 'static,
+    {
+        self.validator = Some(Arc::new(validator));
+        self
+    }
+
+    /// Checks `value` against the type and validator of this key, returning a
+    /// message describing the problem, if any
+    pub fn validate(&self, value: &Value) -> Result<(), String> {
+        if !self.kind.matches(value) {
+            return Err(format!("expected {}", self.kind));
+        }
+        match &self.validator {
+            Some(validator) => validator(value),
+            None => Ok(()),
+        }
+    }
+}
+
+impl std::fmt::Debug for MetadataKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MetadataKey")
+            .field("name", &self.name)
+            .field("kind", &self.kind)
+            .field("default", &self.default)
+            .field("validator", &self.validator.is_some())
+            .finish()
+    }
+}
@@ -0,0 +1,162 @@
+use crate::MetadataKey;
+use crate::Passage;
+use crate::Warning;
+use crate::WarningKind;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+/// A set of custom metadata fields that tools built on `tweep` declare for
+/// passages, such as the `"audio"` to play or the `"mood"` of a passage.
+/// Registered fields are validated when a story is parsed with the registry
+/// in [`ParseOptions::metadata`], and read back with [`get`]
+///
+/// # Examples
+/// ```
+/// use tweep::{MetadataKey, MetadataRegistry, MetadataType, ParseOptions, StoryPassages, WarningKind};
+/// use serde_json::json;
+/// let registry = MetadataRegistry::new()
+///     .with_key(MetadataKey::new("audio", MetadataType::String))
+///     .with_key(MetadataKey::new("mood", MetadataType::String).with_default(json!("calm")));
+/// let options = ParseOptions::new().with_metadata(registry.clone());
+/// let input = r#":: Start {"audio": "rain.ogg"}
+/// It's raining.
+///
+/// :: Storm {"audio": 7, "mood": "tense"}
+/// Thunder!
+/// "#.to_string();
+/// let (res, warnings) = StoryPassages::from_string_with_options(input, &options).take();
+/// let story = res.ok().unwrap();
+/// assert!(warnings.iter().any(|w| w.kind == WarningKind::InvalidMetadata(
+///     "audio".to_string(),
+///     "expected a string".to_string(),
+/// )));
+///
+/// let start = &story.passages["Start"];
+/// assert_eq!(registry.get::<String>(start, "audio"), Some("rain.ogg".to_string()));
+/// assert_eq!(registry.get::<String>(start, "mood"), Some("calm".to_string()));
+/// assert_eq!(registry.get::<String>(&story.passages["Storm"], "audio"), None);
+/// ```
+///
+/// [`ParseOptions::metadata`]: struct.ParseOptions.html#structfield.metadata
+/// [`get`]: #method.get
+#[derive(Clone, Debug, Default)]
+pub struct MetadataRegistry {
+    keys: Vec<MetadataKey>,
+}
+
+impl MetadataRegistry {
+    /// Creates a new, empty registry
+    pub fn new() -> Self {
+        MetadataRegistry::default()
+    }
+
+    /// Builder method to register a key
+    pub fn with_key(mut self, key: MetadataKey) -> Self {
+        self.register(key);
+        self
+    }
+
+    /// Registers a key, replacing any key with the same name
+    pub fn register(&mut self, key: MetadataKey) {
+        self.keys.retain(|k| k.name != key.name);
+        self.keys.push(key);
+    }
+
+    /// Returns the registered key with the given name, if any
+    pub fn key(&self, name: &str) -> Option<&MetadataKey> {
+        self.keys.iter().find(|k| k.name == name)
+    }
+
+    /// Returns `true` if no keys are registered
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Returns the value of the registered field `name` for the given
+    /// passage: the value in its header if that is valid, and otherwise the
+    /// default of the key, if any. Returns `None` if no key `name` is
+    /// registered
+    pub fn value<'a>(&'a self, passage: &'a Passage, name: &str) -> Option<&'a Value> {
+        let key = self.key(name)?;
+        passage
+            .header
+            .metadata
+            .get(name)
+            .filter(|value| key.validate(value).is_ok())
+            .or(key.default.as_ref())
+    }
+
+    /// Returns the value of the registered field `name` for the given passage,
+    /// as with [`value`], converted to `T`. Returns `None` if there is no value
+    /// or it can't be converted
+    ///
+    /// [`value`]: #method.value
+    pub fn get<T: DeserializeOwned>(&self, passage: &Passage, name: &str) -> Option<T> {
+        self.value(passage, name).and_then(|value| T::deserialize(value).ok())
+    }
+
+    /// Checks the registered fields declared in the header of the given
+    /// passage, returning an [`InvalidMetadata`] warning for each invalid
+    /// value, in the order the keys were registered
+    ///
+    /// [`InvalidMetadata`]: enum.WarningKind.html#variant.InvalidMetadata
+    pub fn validate(&self, passage: &Passage) -> Vec<Warning> {
+        let contents = passage.context.get_contents();
+        let header_end = contents.find('\n').unwrap_or(contents.len());
+        self.keys
+            .iter()
+            .filter_map(|key| {
+                let value = passage.header.metadata.get(&key.name)?;
+                let message = key.validate(value).err()?;
+                Some(Warning::new(
+                    WarningKind::InvalidMetadata(key.name.clone(), message),
+                    Some(passage.context.slice(0..header_end)),
+                ))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FullContext;
+    use crate::MetadataType;
+    use crate::ParseOptions;
+    use crate::Position;
+    use serde_json::json;
+
+    #[test]
+    fn validation() {
+        let registry = MetadataRegistry::new()
+            .with_key(MetadataKey::new("mood", MetadataType::Number))
+            .with_key(MetadataKey::new("mood", MetadataType::String).with_validator(|value| {
+                match value.as_str() {
+                    Some("calm") | Some("tense") => Ok(()),
+                    _ => Err("unknown mood".to_string()),
+                }
+            }))
+            .with_key(MetadataKey::new("loop", MetadataType::Bool).with_default(json!(false)));
+        assert_eq!(registry.key("mood").unwrap().kind, MetadataType::String);
+
+        let context = FullContext::from(None, ":: Start {\"mood\": \"sad\", \"loop\": 1}\nHello".to_string());
+        let passage = Passage::parse(context.clone(), &ParseOptions::default()).take().0.ok().unwrap();
+        let header = context.subcontext(..=Position::rel(1, 35));
+        assert_eq!(
+            registry.validate(&passage),
+            vec![
+                Warning::new(
+                    WarningKind::InvalidMetadata("mood".to_string(), "unknown mood".to_string()),
+                    Some(header.clone())
+                ),
+                Warning::new(
+                    WarningKind::InvalidMetadata("loop".to_string(), "expected a boolean".to_string()),
+                    Some(header)
+                ),
+            ]
+        );
+        assert_eq!(registry.get::<String>(&passage, "mood"), None);
+        assert_eq!(registry.get::<bool>(&passage, "loop"), Some(false));
+        assert_eq!(registry.get::<i64>(&passage, "unregistered"), None);
+    }
+}
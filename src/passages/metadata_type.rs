@@ -0,0 +1,63 @@
+use serde_json::Value;
+
+/// The JSON type expected of a registered metadata field. See
+/// [`MetadataKey`] for details
+///
+/// [`MetadataKey`]: struct.MetadataKey.html
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum MetadataType {
+    /// A JSON `true` or `false`
+    Bool,
+
+    /// A JSON number
+    Number,
+
+    /// A JSON string
+    String,
+
+    /// A JSON array
+    Array,
+
+    /// A JSON object
+    Object,
+
+    /// Any JSON value
+    Any,
+}
+
+impl MetadataType {
+    /// Returns `true` if `value` is of this type
+    ///
+    /// # Examples
+    /// ```
+    /// use tweep::MetadataType;
+    /// use serde_json::json;
+    /// assert!(MetadataType::Number.matches(&json!(0.5)));
+    /// assert!(!MetadataType::Number.matches(&json!("0.5")));
+    /// assert!(MetadataType::Any.matches(&json!(null)));
+    /// ```
+    pub fn matches(&self, value: &Value) -> bool {
+        match self {
+            MetadataType::Bool => value.is_boolean(),
+            MetadataType::Number => value.is_number(),
+            MetadataType::String => value.is_string(),
+            MetadataType::Array => value.is_array(),
+            MetadataType::Object => value.is_object(),
+            MetadataType::Any => true,
+        }
+    }
+}
+
+impl std::fmt::Display for MetadataType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            MetadataType::Bool => "a boolean",
+            MetadataType::Number => "a number",
+            MetadataType::String => "a string",
+            MetadataType::Array => "an array",
+            MetadataType::Object => "an object",
+            MetadataType::Any => "any value",
+        };
+        write!(f, "{}", name)
+    }
+}
@@ -1,12 +1,31 @@
 mod header;
 pub use header::PassageHeader;
 
+mod json_error;
+pub(crate) use json_error::json_error_context;
+pub(crate) use json_error::json_error_message;
+
+mod metadata_key;
+pub use metadata_key::MetadataKey;
+
+mod metadata_registry;
+pub use metadata_registry::MetadataRegistry;
+
+mod metadata_type;
+pub use metadata_type::MetadataType;
+
+mod normalized_name;
+pub use normalized_name::NormalizedName;
+
 mod passage;
 pub use passage::Passage;
 
 mod passage_content;
 pub use passage_content::PassageContent;
 
+mod passage_kind;
+pub use passage_kind::PassageKind;
+
 mod script_content;
 pub use script_content::ScriptContent;
 
@@ -24,6 +43,8 @@ pub use twine_content::TwineContent;
 
 mod twine_link;
 pub use twine_link::TwineLink;
+pub(crate) use twine_link::display_text;
+pub(crate) use twine_link::link_target;
 
 mod twine_passage;
 pub use twine_passage::TwinePassage;
@@ -0,0 +1,174 @@
+use std::ops::Range;
+
+/// A passage name normalized the way tweep normalizes the names in passage
+/// headers, together with a map from each byte of the normalized name back to
+/// the bytes of the text it was normalized from. Tools that compare or display
+/// normalized names can use the map to point at the name as it was written
+///
+/// Offsets into the original text are zero-indexed bytes. Since a name is a
+/// single line, the one-indexed column of an original offset within the line
+/// the name starts on is the offset plus one, which [`original_column`]
+/// returns
+///
+/// # Examples
+/// ```
+/// use tweep::NormalizedName;
+/// let name = NormalizedName::new("  The \\[Cellar\\] ");
+/// assert_eq!(name.as_str(), "The [Cellar]");
+/// assert_eq!(name.original_range(4..12), 7..16);
+/// assert_eq!(name.normalized_offset(7), Some(4));
+/// assert_eq!(name.normalized_offset(6), None);
+///
+/// // Backslashes that don't escape a bracket or brace are kept
+/// assert_eq!(NormalizedName::new("C:\\Games").as_str(), "C:\\Games");
+///
+/// let lower = name.to_lowercase();
+/// assert_eq!(lower.as_str(), "the [cellar]");
+/// assert_eq!(lower.original_column(5), 9);
+/// ```
+///
+/// [`original_column`]: #method.original_column
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct NormalizedName {
+    /// The normalized name
+    name: String,
+
+    /// For each byte of `name`, the byte range in the original text of the
+    /// character it came from
+    origins: Vec<Range<usize>>,
+
+    /// The offset in the original text of the end of the name
+    end: usize,
+}
+
+impl NormalizedName {
+    /// Normalizes a passage name as written in a header: trims the surrounding
+    /// whitespace and unescapes `\[`, `\]`, `\{`, and `\}`. Any other
+    /// backslash is kept as part of the name
+    pub fn new(raw: &str) -> Self {
+        let start = raw.len() - raw.trim_start().len();
+        let trimmed = raw.trim();
+        let mut name = String::with_capacity(trimmed.len());
+        let mut origins = Vec::with_capacity(trimmed.len());
+        let mut chars = trimmed.char_indices().peekable();
+        while let Some((i, c)) = chars.next() {
+            if c == '\\' && matches!(chars.peek(), Some((_, '[' | ']' | '{' | '}'))) {
+                continue;
+            }
+            let origin = start + i..start + i + c.len_utf8();
+            name.push(c);
+            origins.extend(std::iter::repeat_n(origin, c.len_utf8()));
+        }
+        NormalizedName { name, origins, end: start + trimmed.len() }
+    }
+
+    /// Returns a lowercase copy of this name that maps back to the same
+    /// original text
+    pub fn to_lowercase(&self) -> Self {
+        let mut name = String::with_capacity(self.name.len());
+        let mut origins = Vec::with_capacity(self.origins.len());
+        for (i, c) in self.name.char_indices() {
+            for lower in c.to_lowercase() {
+                name.push(lower);
+                origins.extend(std::iter::repeat_n(self.origins[i].clone(), lower.len_utf8()));
+            }
+        }
+        NormalizedName { name, origins, end: self.end }
+    }
+
+    /// Returns the normalized name
+    pub fn as_str(&self) -> &str {
+        &self.name
+    }
+
+    /// Consumes this `NormalizedName`, returning the normalized name
+    pub fn into_string(self) -> String {
+        self.name
+    }
+
+    /// Returns the byte offset in the original text of the character at the
+    /// given byte offset of the normalized name. An offset at the end of the
+    /// name maps to the end of the name in the original text
+    ///
+    /// # Panics
+    /// Panics if `offset` is past the end of the normalized name
+    pub fn original_offset(&self, offset: usize) -> usize {
+        if offset == self.name.len() {
+            self.end
+        } else {
+            self.origins[offset].start
+        }
+    }
+
+    /// Returns the byte offset in the normalized name of the character at the
+    /// given byte offset of the original text, or `None` if that character
+    /// isn't part of the normalized name, such as the backslash of an escape
+    /// or surrounding whitespace. The end of the name in the original text
+    /// maps to the end of the normalized name
+    pub fn normalized_offset(&self, original: usize) -> Option<usize> {
+        if original == self.end {
+            return Some(self.name.len());
+        }
+        self.origins.iter().position(|origin| origin.start == original)
+    }
+
+    /// Returns the one-indexed column in the original text of the character at
+    /// the given byte offset of the normalized name
+    ///
+    /// # Panics
+    /// Panics if `offset` is past the end of the normalized name
+    pub fn original_column(&self, offset: usize) -> usize {
+        self.original_offset(offset) + 1
+    }
+
+    /// Returns the byte range in the original text covering the given byte
+    /// range of the normalized name. The range covers whole characters of the
+    /// original text, along with any escapes between them
+    ///
+    /// # Panics
+    /// Panics if `range` extends past the end of the normalized name
+    pub fn original_range(&self, range: Range<usize>) -> Range<usize> {
+        let start = self.original_offset(range.start);
+        if range.end <= range.start {
+            return start..start;
+        }
+        start..self.origins[range.end - 1].end
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn offsets() {
+        let name = NormalizedName::new(" \\{Café\\} ");
+        assert_eq!(name.as_str(), "{Café}");
+        assert_eq!(name.original_offset(0), 2);
+        assert_eq!(name.original_offset(4), 6);
+        assert_eq!(name.original_range(4..6), 6..8);
+        assert_eq!(name.original_range(0..7), 2..10);
+        assert_eq!(name.original_range(3..3), 5..5);
+        assert_eq!(name.original_offset(7), 10);
+
+        let lower = NormalizedName::new("İx").to_lowercase();
+        assert_eq!(lower.as_str(), "i\u{307}x");
+        assert_eq!(lower.original_range(0..3), 0..2);
+        assert_eq!(lower.original_offset(3), 2);
+
+        assert_eq!(name.normalized_offset(6), Some(4));
+        assert_eq!(name.normalized_offset(7), None);
+        assert_eq!(name.normalized_offset(8), None);
+        assert_eq!(name.normalized_offset(9), Some(6));
+        assert_eq!(name.normalized_offset(10), Some(7));
+        assert_eq!(name.normalized_offset(11), None);
+
+        let kept = NormalizedName::new("a\\b\\\\c\\");
+        assert_eq!(kept.as_str(), "a\\b\\\\c\\");
+        assert_eq!(kept.original_range(0..kept.as_str().len()), 0..7);
+
+        let empty = NormalizedName::new("   ");
+        assert_eq!(empty.as_str(), "");
+        assert_eq!(empty.original_offset(0), 3);
+    }
+}
@@ -1,8 +1,10 @@
 use crate::ErrorList;
 use crate::FullContext;
 use crate::Output;
+use crate::ParseOptions;
 use crate::PassageContent;
 use crate::PassageHeader;
+use crate::PassageKind;
 use crate::Position;
 use crate::PositionKind;
 use crate::ScriptContent;
@@ -10,6 +12,8 @@ use crate::StoryData;
 use crate::StoryTitle;
 use crate::StylesheetContent;
 use crate::TwineContent;
+use std::ops::Range;
+use std::path::Path;
 
 /// A complete Twee passage, including header and content
 ///
@@ -31,6 +35,12 @@ pub struct Passage {
 
     /// The context
     pub context: FullContext,
+
+    /// The byte range of this passage within its source
+    byte_range: Range<usize>,
+
+    /// The kind of this passage
+    kind: PassageKind,
 }
 
 impl Passage {
@@ -75,10 +85,14 @@ impl Passage {
             Ok(_) => {
                 let header = header_res.ok().unwrap();
                 let content = content_res.ok().unwrap();
+                let byte_range = context.get_byte_range();
+                let kind = PassageKind::of_content(&content);
                 Ok(Passage {
                     header,
                     content,
                     context,
+                    byte_range,
+                    kind,
                 })
             }
         })
@@ -95,10 +109,61 @@ impl Passage {
         &self.header.tags
     }
 
-    pub(crate) fn parse(context: FullContext) -> Output<Result<Self, ErrorList>> {
+    /// Returns the path of the file this passage was parsed from, or `None` if
+    /// it was not parsed from a file
+    ///
+    /// # Examples
+    /// ```
+    /// # use tweep::StoryPassages;
+    /// let out = StoryPassages::from_string(":: Start\nHello".to_string());
+    /// let story = out.take().0.ok().unwrap();
+    /// assert_eq!(story.passages["Start"].source_path(), None);
+    /// ```
+    pub fn source_path(&self) -> Option<&Path> {
+        self.context.get_file_path()
+    }
+
+    /// Returns the byte range of this passage, from the start of its header to
+    /// the end of its last line that isn't blank, within its source. When
+    /// parsed with [`ParseOptions::keep_trailing_blank_lines`], the range
+    /// ends with the last line before the next passage instead
+    ///
+    /// [`ParseOptions::keep_trailing_blank_lines`]: struct.ParseOptions.html#structfield.keep_trailing_blank_lines
+    ///
+    /// # Examples
+    /// ```
+    /// # use tweep::StoryPassages;
+    /// let input = ":: Start\nHello\n\n:: Other\nWorld\n".to_string();
+    /// let out = StoryPassages::from_string(input.clone());
+    /// let story = out.take().0.ok().unwrap();
+    /// assert_eq!(&input[story.passages["Other"].byte_range()], ":: Other\nWorld");
+    /// ```
+    pub fn byte_range(&self) -> Range<usize> {
+        self.byte_range.clone()
+    }
+
+    /// Returns the [`PassageKind`] of this passage. For a passage created
+    /// with `new` rather than parsed, this is the kind matching its content
+    ///
+    /// # Examples
+    /// ```
+    /// # use tweep::{PassageKind, StoryPassages};
+    /// let out = StoryPassages::from_string(":: Start\nHello\n\n:: Style [stylesheet]\nbody {}".to_string());
+    /// let story = out.take().0.ok().unwrap();
+    /// assert_eq!(story.passages["Start"].kind(), &PassageKind::Normal);
+    /// assert_eq!(story.stylesheets[0].kind(), &PassageKind::Stylesheet);
+    /// ```
+    ///
+    /// [`PassageKind`]: enum.PassageKind.html
+    pub fn kind(&self) -> &PassageKind {
+        &self.kind
+    }
+
+    pub(crate) fn parse(context: FullContext, options: &ParseOptions) -> Output<Result<Self, ErrorList>> {
+        let _span = enter_span!("passage", line = context.get_start_position().line);
         let header_context = context.subcontext(..=context.end_of_line(1, PositionKind::Relative));
         // Parse the first line as the header
-        let header = PassageHeader::parse(header_context);
+        let header = PassageHeader::parse_with_options(header_context, options);
 
         // Since we can't know how to parse the passage contents if we don't know
         // the passage type from the header, we can't continue
@@ -110,32 +175,50 @@ impl Passage {
         // get the Ok side and unwrap it, getting a reference to the header
         let header_ref = header.get_output().as_ref().ok().unwrap();
 
-        // Find the position of the last non-empty line
-        let mut new_iter = context.get_contents().split('\n');
-        new_iter.rfind(|&x| !x.is_empty());
-        let len = new_iter.fold(0, |acc, _| acc + 1);
+        // Find the last line of the passage: the last line that isn't blank,
+        // unless trailing blank lines are kept as content. The header line is
+        // never blank
+        let contents = context.get_contents();
+        let line_count = memchr::memchr_iter(b'\n', contents.as_bytes()).count() + 1;
+        let last = if options.keep_trailing_blank_lines {
+            // The empty string after a line break ending the source isn't a
+            // line of its own
+            let at_end = context.get_byte_range().end == context.get_source().len();
+            line_count - usize::from(at_end && line_count > 1 && contents.ends_with('\n'))
+        } else {
+            let blank = contents.rsplit('\n').take_while(|line| line.trim().is_empty()).count();
+            (line_count - blank).max(1)
+        };
 
         // Create the content's context
         let content_context = context
-            .subcontext(Position::rel(2, 1)..=context.end_of_line(len + 1, PositionKind::Relative));
+            .subcontext(Position::rel(2, 1)..=context.end_of_line(last, PositionKind::Relative));
         let trimmed_context = context.subcontext(..=content_context.get_end_position());
 
-        // Parse the content based on the type indicated by the header
-        let content: Output<Result<PassageContent, ErrorList>>;
-        content = if header_ref.name == "StoryTitle" {
-            StoryTitle::parse(content_context).into_result()
-        } else if header_ref.name == "StoryData" {
-            StoryData::parse(content_context).into_result()
-        } else if header_ref.has_tag("script") {
-            ScriptContent::parse(content_context).into_result()
-        } else if header_ref.has_tag("stylesheet") {
-            StylesheetContent::parse(content_context).into_result()
-        } else {
-            TwineContent::parse(content_context).into_result()
+        // Parse the content based on the kind indicated by the header
+        let kind = options.passage_kind(header_ref);
+        debug!(name = %header_ref.name, kind = ?kind, "Parsed passage header");
+        let mut content: Output<Result<PassageContent, ErrorList>> = match kind {
+            PassageKind::StoryTitle => StoryTitle::parse(content_context).into_result(),
+            PassageKind::StoryData => StoryData::parse(content_context).into_result(),
+            PassageKind::Script | PassageKind::Custom(_) => ScriptContent::parse(content_context).into_result(),
+            PassageKind::Stylesheet => StylesheetContent::parse(content_context).into_result(),
+            PassageKind::Normal => TwineContent::parse(content_context).into_result(),
         };
 
+        // Keep the untrimmed body, including any trailing blank lines
+        if let Ok(content) = content.mut_output() {
+            let contents = context.get_contents();
+            let body_start = contents.find('\n').map_or(contents.len(), |i| i + 1);
+            content.set_raw(context.shared_slice(body_start..contents.len()));
+        }
+
         // Assemble and return the output
-        Self::new(header, content, trimmed_context)
+        let mut out = Self::new(header, content, trimmed_context);
+        if let Ok(passage) = out.mut_output() {
+            passage.kind = kind;
+        }
+        out
     }
 }
 
@@ -145,10 +228,10 @@ mod tests {
 
     fn story_title_subtest(input: String, expected_title: &str) {
         let context = FullContext::from(None, input);
-        let out = Passage::parse(context);
-        assert_eq!(out.has_warnings(), false);
+        let out = Passage::parse(context, &ParseOptions::default());
+        assert!(!out.has_warnings());
         let (res, _) = out.take();
-        assert_eq!(res.is_ok(), true);
+        assert!(res.is_ok());
         let passage = res.ok().unwrap();
         let content = passage.content;
         let expected = if let PassageContent::StoryTitle(story_title) = content {
@@ -157,7 +240,7 @@ mod tests {
         } else {
             false
         };
-        assert_eq!(expected, true);
+        assert!(expected);
     }
 
     #[test]
@@ -176,10 +259,10 @@ mod tests {
     fn script_passage() {
         let input = ":: Script Passage [script]\nfoo\nbar".to_string();
         let context = FullContext::from(None, input);
-        let out = Passage::parse(context);
-        assert_eq!(out.has_warnings(), false);
+        let out = Passage::parse(context, &ParseOptions::default());
+        assert!(!out.has_warnings());
         let (res, _) = out.take();
-        assert_eq!(res.is_ok(), true);
+        assert!(res.is_ok());
         let passage = res.ok().unwrap();
         assert_eq!(passage.tags(), &vec!["script".to_string()]);
         let content = passage.content;
@@ -190,17 +273,17 @@ mod tests {
         } else {
             false
         };
-        assert_eq!(expected, true);
+        assert!(expected);
     }
 
     #[test]
     fn stylesheet_passage() {
         let input = ":: Style Passage [stylesheet]\nfoo\nbar".to_string();
         let context = FullContext::from(None, input);
-        let out = Passage::parse(context);
-        assert_eq!(out.has_warnings(), false);
+        let out = Passage::parse(context, &ParseOptions::default());
+        assert!(!out.has_warnings());
         let (res, _) = out.take();
-        assert_eq!(res.is_ok(), true);
+        assert!(res.is_ok());
         let passage = res.ok().unwrap();
         assert_eq!(passage.metadata()["position"], "10,10");
         assert_eq!(passage.metadata()["size"], "100,100");
@@ -212,7 +295,7 @@ mod tests {
         } else {
             false
         };
-        assert_eq!(expected, true);
+        assert!(expected);
     }
 
     #[test]
@@ -226,10 +309,10 @@ That
 "#
         .to_string();
         let context = FullContext::from(None, input_string);
-        let out = Passage::parse(context);
-        assert_eq!(out.has_warnings(), false);
+        let out = Passage::parse(context, &ParseOptions::default());
+        assert!(!out.has_warnings());
         let (res, _) = out.take();
-        assert_eq!(res.is_ok(), true);
+        assert!(res.is_ok());
         let passage = res.ok().unwrap();
         let content = passage.content;
         let expected = if let PassageContent::Normal(normal) = content {
@@ -239,6 +322,82 @@ That
         } else {
             false
         };
-        assert_eq!(expected, true);
+        assert!(expected);
+    }
+
+    #[test]
+    fn raw_content() {
+        let input = ":: A passage\nbar\nbaz  \n\n\n:: Code [script]\nfoo();\n\n".to_string();
+        let story = crate::StoryPassages::from_string(input).take().0.ok().unwrap();
+        match &story.passages["A passage"].content {
+            PassageContent::Normal(content) => {
+                assert_eq!(content.raw(), "bar\nbaz  \n\n");
+                assert_eq!(content.trimmed(), "bar\nbaz");
+                assert_eq!(content.content(), "bar\nbaz  \n");
+            }
+            _ => panic!("Expected Normal passage"),
+        }
+        match &story.scripts[0].content {
+            PassageContent::Script(content) => {
+                assert_eq!(content.raw(), "foo();\n\n");
+                assert_eq!(content.trimmed(), "foo();");
+            }
+            _ => panic!("Expected Script passage"),
+        }
+        let passage = Passage::parse(FullContext::from(None, ":: Empty\n".to_string()), &ParseOptions::default()).take().0.ok().unwrap();
+        match &passage.content {
+            PassageContent::Normal(content) => assert_eq!(content.raw(), ""),
+            _ => panic!("Expected Normal passage"),
+        }
+    }
+
+    #[test]
+    fn classified_kind() {
+        let options = ParseOptions::new().with_special_tag("module").with_classifier(|header| {
+            header.has_tag("css").then_some(PassageKind::Stylesheet)
+        });
+        let parse = |input: &str| {
+            Passage::parse(FullContext::from(None, input.to_string()), &options).take().0.ok().unwrap()
+        };
+
+        let passage = parse(":: Theme [css]\nbody {}");
+        assert_eq!(passage.kind(), &PassageKind::Stylesheet);
+        assert!(matches!(passage.content, PassageContent::Stylesheet(_)));
+
+        let passage = parse(":: Util [module]\nlet x = 1;");
+        assert_eq!(passage.kind(), &PassageKind::Custom("module".to_string()));
+        assert!(matches!(passage.content, PassageContent::Script(_)));
+
+        let passage = parse(":: StoryData\n{\"ifid\": \"ABC\"}");
+        assert_eq!(passage.kind(), &PassageKind::StoryData);
+    }
+
+    #[test]
+    fn byte_range() {
+        let input = ":: Skipped\nfoo\n:: A passage [tag]\nbar\nbaz\n\n\n".to_string();
+        let context = FullContext::from(None, input.clone());
+        let sub = context.subcontext(Position::rel(3, 1)..);
+        let passage = Passage::parse(sub, &ParseOptions::default()).take().0.ok().unwrap();
+        assert_eq!(passage.byte_range(), 15..41);
+        assert_eq!(&input[passage.byte_range()], ":: A passage [tag]\nbar\nbaz");
+        assert!(passage.source_path().is_none());
+    }
+
+    #[test]
+    fn trailing_blank_lines() {
+        let content = |input: &str, options: &ParseOptions| {
+            let passage = Passage::parse(FullContext::from(None, input.to_string()), options).take().0.ok().unwrap();
+            let body = match &passage.content {
+                PassageContent::Normal(twine) => twine.content.to_string(),
+                _ => panic!("Expected normal content"),
+            };
+            (body, passage.byte_range())
+        };
+        let default = ParseOptions::default();
+        let keep = ParseOptions::default().with_keep_trailing_blank_lines(true);
+        assert_eq!(content(":: A\r\nHello\r\n\r\n \t\r\n", &default), ("Hello\r\n".to_string(), 0..12));
+        assert_eq!(content(":: A\nHello\n\n\n", &default), ("Hello\n".to_string(), 0..10));
+        assert_eq!(content(":: A\nHello\n\n\n", &keep), ("Hello\n\n\n".to_string(), 0..12));
+        assert_eq!(content(":: A\nHello", &keep), ("Hello\n".to_string(), 0..10));
     }
 }
@@ -1,3 +1,4 @@
+use crate::context::SharedStr;
 use crate::ScriptContent;
 use crate::StoryData;
 use crate::StoryTitle;
@@ -25,6 +26,19 @@ pub enum PassageContent {
     Stylesheet(StylesheetContent),
 }
 
+impl PassageContent {
+    /// Sets the raw source text of the passage body for content types that
+    /// keep it
+    pub(crate) fn set_raw(&mut self, raw: SharedStr) {
+        match self {
+            PassageContent::Normal(c) => c.set_raw(raw),
+            PassageContent::Script(c) => c.set_raw(raw),
+            PassageContent::Stylesheet(c) => c.set_raw(raw),
+            PassageContent::StoryTitle(_) | PassageContent::StoryData(_) => (),
+        }
+    }
+}
+
 impl std::convert::From<TwineContent> for PassageContent {
     fn from(p: TwineContent) -> PassageContent {
         PassageContent::Normal(p)
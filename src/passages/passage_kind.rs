@@ -0,0 +1,86 @@
+use crate::PassageContent;
+use crate::PassageHeader;
+
+/// The kind of a passage, which decides how its body is parsed and where the
+/// passage is kept in a story. The kind is derived from the header: by name
+/// for `StoryTitle` and `StoryData`, and by tag for the others. Custom kinds
+/// come from [`ParseOptions::special_tags`] or a classifier set with
+/// [`ParseOptions::with_classifier`], and their passages are parsed like
+/// scripts and kept in [`StoryPassages::tagged_passages`]
+///
+/// # Examples
+/// ```
+/// use tweep::{FullContext, PassageHeader, PassageKind};
+/// let header = |input: &str| PassageHeader::parse(FullContext::from(None, input.to_string()))
+///     .take().0.ok().unwrap();
+/// let special_tags = vec!["module".to_string()];
+/// assert_eq!(PassageKind::from_header(&header(":: StoryTitle"), &special_tags), PassageKind::StoryTitle);
+/// assert_eq!(PassageKind::from_header(&header(":: Init [script]"), &special_tags), PassageKind::Script);
+/// assert_eq!(
+///     PassageKind::from_header(&header(":: Util [module]"), &special_tags),
+///     PassageKind::Custom("module".to_string())
+/// );
+/// assert_eq!(PassageKind::from_header(&header(":: Start [dark]"), &special_tags), PassageKind::Normal);
+/// ```
+///
+/// [`ParseOptions::special_tags`]: struct.ParseOptions.html#structfield.special_tags
+/// [`ParseOptions::with_classifier`]: struct.ParseOptions.html#method.with_classifier
+/// [`StoryPassages::tagged_passages`]: struct.StoryPassages.html#structfield.tagged_passages
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum PassageKind {
+    /// A passage of story text
+    Normal,
+
+    /// The `StoryTitle` passage
+    StoryTitle,
+
+    /// The `StoryData` passage
+    StoryData,
+
+    /// A passage tagged `script`
+    Script,
+
+    /// A passage tagged `stylesheet`
+    Stylesheet,
+
+    /// A passage of a kind defined by the user. Contains the name of the kind,
+    /// which is the key of the passage in `tagged_passages`
+    Custom(String),
+}
+
+impl PassageKind {
+    /// Derives the kind of a passage from its header, treating passages
+    /// tagged with any of `special_tags` as [`Custom`] passages of the first
+    /// such tag
+    ///
+    /// [`Custom`]: #variant.Custom
+    pub fn from_header(header: &PassageHeader, special_tags: &[String]) -> Self {
+        if header.name == "StoryTitle" {
+            PassageKind::StoryTitle
+        } else if header.name == "StoryData" {
+            PassageKind::StoryData
+        } else if header.has_tag("script") {
+            PassageKind::Script
+        } else if header.has_tag("stylesheet") {
+            PassageKind::Stylesheet
+        } else if let Some(tag) = special_tags.iter().find(|tag| header.has_tag(tag)) {
+            PassageKind::Custom(tag.clone())
+        } else {
+            PassageKind::Normal
+        }
+    }
+
+    /// Returns the kind matching the storage of the given content, which is
+    /// [`Script`] for any script content
+    ///
+    /// [`Script`]: #variant.Script
+    pub(crate) fn of_content(content: &PassageContent) -> Self {
+        match content {
+            PassageContent::Normal(_) => PassageKind::Normal,
+            PassageContent::StoryTitle(_) => PassageKind::StoryTitle,
+            PassageContent::StoryData(_) => PassageKind::StoryData,
+            PassageContent::Script(_) => PassageKind::Script,
+            PassageContent::Stylesheet(_) => PassageKind::Stylesheet,
+        }
+    }
+}
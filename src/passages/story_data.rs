@@ -1,7 +1,8 @@
 use crate::ErrorList;
 use crate::FullContext;
 use crate::Output;
-use crate::Position;
+use super::json_error_context;
+use super::json_error_message;
 use crate::Warning;
 use crate::WarningKind;
 use std::collections::HashMap;
@@ -20,12 +21,33 @@ use serde::{Deserialize, Serialize};
 /// * tag-colors - Object of tag(string):color(string) pairs. Pairs map to <tw-tag> nodes as <tw-tag name>:<tw-tag color>.
 /// * zoom - Decimal. Maps to <tw-storydata zoom>.
 ///
+/// Any other fields, such as ones added by other tools, are kept in
+/// `unknown`, so that writing the `StoryData` back out with [`to_json`] loses
+/// no information.
+///
 /// # Parse Errors
 /// None
 ///
 /// # Parse Warnings
 /// * [`JsonError`] - Error encountered while parsing the JSON content
-#[derive(Debug, Serialize, Deserialize)]
+/// * [`UnknownStoryDataKeys`] - Fields that aren't part of the specification
+///
+/// # Examples
+/// ```
+/// use tweep::{FullContext, StoryData, WarningKind};
+/// let input = r#"{"ifid": "D674C58C-DEFA-4F70-B7A2-27742230C0FC", "zoom": 0.6, "snap-to-grid": true}"#;
+/// let (res, warnings) = StoryData::parse(FullContext::from(None, input.to_string())).take();
+/// let data = res.ok().unwrap().unwrap();
+/// assert_eq!(data.zoom, Some(0.6));
+/// assert_eq!(data.unknown["snap-to-grid"], true);
+/// assert_eq!(warnings[0].kind, WarningKind::UnknownStoryDataKeys(vec!["snap-to-grid".to_string()]));
+/// assert!(data.to_json().contains("\"snap-to-grid\": true"));
+/// ```
+///
+/// [`to_json`]: #method.to_json
+/// [`JsonError`]: enum.WarningKind.html#variant.JsonError
+/// [`UnknownStoryDataKeys`]: enum.WarningKind.html#variant.UnknownStoryDataKeys
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct StoryData {
     /// Interactive Fiction IDentifier v4 UUID
     pub ifid: String,
@@ -46,6 +68,10 @@ pub struct StoryData {
 
     /// Zoom level for editing in Twine
     pub zoom: Option<f32>,
+
+    /// Fields that aren't part of the specification, mapped to their values
+    #[serde(flatten)]
+    pub unknown: serde_json::Map<String, serde_json::Value>,
 }
 
 impl StoryData {
@@ -55,22 +81,28 @@ impl StoryData {
         let res: serde_json::Result<StoryData> = serde_json::from_str(context.get_contents());
 
         let story_data = if res.is_ok() {
-            Some(res.ok().unwrap())
+            let story_data = res.ok().unwrap();
+            if !story_data.unknown.is_empty() {
+                let keys = story_data.unknown.keys().cloned().collect();
+                warnings.push(Warning::new(WarningKind::UnknownStoryDataKeys(keys), Some(context.clone())));
+            }
+            Some(story_data)
         } else {
             let err = res.err().unwrap();
-            // Get the error part of error string generated by serde
-            let err_string = format!("{}", err).split(" at ").next().unwrap().to_string();
             warnings.push(Warning::new(
-                WarningKind::JsonError(err_string),
-                Some(context.subcontext(
-                    Position::rel(err.line(), err.column())
-                        ..=Position::rel(err.line(), err.column()),
-                )),
+                WarningKind::JsonError(json_error_message(&err)),
+                Some(json_error_context(&context, &err)),
             ));
             None
         };
         Output::new(Ok(story_data)).with_warnings(warnings)
     }
+
+    /// Returns the `StoryData` as pretty-printed JSON, including its `unknown`
+    /// fields
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap()
+    }
 }
 
 #[cfg(test)]
@@ -140,12 +172,34 @@ mod tests {
         let data = res.ok().unwrap();
         assert!(data.is_none());
         assert_eq!(warnings.len(), 1);
-        assert!(
-            if let WarningKind::JsonError(_) = &warnings[0].kind {
-                true
-            } else {
-                false
-            }
+        assert!(matches!(&warnings[0].kind, WarningKind::JsonError(_)));
+    }
+
+    #[test]
+    fn round_trip() {
+        let input = r#"{"ifid": "X", "zoom": 0.6, "tag-colors": {"a": "red"}, "snapToGrid": true, "x-tool": {"v": [1, 2]}}"#;
+        let (res, warnings) = StoryData::parse(FullContext::from(None, input.to_string())).take();
+        let data = res.ok().unwrap().unwrap();
+        assert_eq!(
+            warnings[0].kind,
+            WarningKind::UnknownStoryDataKeys(vec!["snapToGrid".to_string(), "x-tool".to_string()])
         );
+
+        let json = data.to_json();
+        let (res, _) = StoryData::parse(FullContext::from(None, json.clone())).take();
+        assert_eq!(res.ok().unwrap().unwrap(), data);
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["x-tool"]["v"][1], 2);
+        assert_eq!(value["zoom"], 0.6);
+    }
+
+    #[test]
+    fn error_position() {
+        let input = ":: Start\nHi\n\n:: StoryData\n{\n\t\"ifid\": \"X\",\n\t\"zoom\": 1,,\n}\n".to_string();
+        let (_, warnings) = crate::StoryPassages::from_string(input).take();
+        let warning = warnings.iter().find(|w| matches!(w.kind, WarningKind::JsonError(_))).unwrap();
+        let context = warning.context.as_ref().unwrap();
+        assert_eq!(context.get_start_position().line, 7);
+        assert_eq!(context.get_start_position().column, 12);
     }
 }
@@ -1,3 +1,4 @@
+use crate::context::SharedStr;
 use crate::ErrorList;
 use crate::FullContext;
 use crate::Output;
@@ -16,15 +17,60 @@ use crate::Output;
 #[derive(Debug)]
 pub struct StylesheetContent {
     /// The stylesheet content
-    pub content: String,
+    pub content: SharedStr,
+
+    /// The raw source text of the passage body
+    raw: SharedStr,
 }
 
 impl StylesheetContent {
+    /// Creates a `StylesheetContent` from the given content, which is also used as its
+    /// raw source text
+    ///
+    /// # Examples
+    /// ```
+    /// use tweep::StylesheetContent;
+    /// let content = StylesheetContent::new("p { color: red; }");
+    /// assert_eq!(content.content(), "p { color: red; }");
+    /// assert_eq!(content.raw(), "p { color: red; }");
+    /// ```
+    pub fn new<S: Into<SharedStr>>(content: S) -> Self {
+        let raw = content.into();
+        StylesheetContent {
+            content: raw.clone(),
+            raw,
+        }
+    }
+
+    /// Gets the stylesheet content of the passage
+    pub fn content(&self) -> &str {
+        &self.content
+    }
+
+    /// Gets the exact source text of the passage body, including any trailing
+    /// blank lines before the next passage. When parsed directly from a
+    /// context rather than as part of a [`Passage`], this is the full contents
+    /// of the context
+    ///
+    /// [`Passage`]: struct.Passage.html
+    pub fn raw(&self) -> &str {
+        &self.raw
+    }
+
+    /// Gets the source text of the passage body with trailing whitespace,
+    /// including trailing blank lines, removed
+    pub fn trimmed(&self) -> &str {
+        self.raw.trim_end()
+    }
+
+    /// Sets the raw source text of the passage body
+    pub(crate) fn set_raw(&mut self, raw: SharedStr) {
+        self.raw = raw;
+    }
+
     /// Parses a `StylesheetContent` out of the given context
     pub fn parse(context: FullContext) -> Output<Result<Self, ErrorList>> {
-        Output::new(Ok(StylesheetContent {
-            content: context.get_contents().to_string(),
-        }))
+        Output::new(Ok(StylesheetContent::new(context.get_shared_contents())))
     }
 }
 
@@ -1,3 +1,4 @@
+use crate::context::SharedStr;
 use crate::ErrorList;
 use crate::FullContext;
 use crate::Output;
@@ -5,6 +6,9 @@ use crate::Position;
 use crate::TwineLink;
 use crate::Warning;
 use crate::WarningKind;
+use super::twine_link::display_text;
+use super::twine_link::link_target;
+use std::ops::Range;
 
 /// The contents of a Twine passage.
 ///
@@ -44,16 +48,229 @@ use crate::WarningKind;
 #[derive(Debug)]
 pub struct TwineContent {
     /// The content of the passage
-    pub content: String,
+    pub content: SharedStr,
 
     /// The pid (Passage ID) of the passage
     pub pid: usize,
 
     /// A list of parsed links in this content
     links: Vec<TwineLink>,
+
+    /// A list of links in this content that were never closed
+    unclosed_links: Vec<TwineLink>,
+
+    /// The raw source text of the passage body
+    raw: SharedStr,
+
+    /// The absolute position of the start of the content
+    start_position: Position,
 }
 
 impl TwineContent {
+    /// Gets the content of the passage, which is its body followed by a line
+    /// break
+    ///
+    /// # Examples
+    /// ```
+    /// use tweep::TwineContent;
+    /// let content = TwineContent::parse_body("foo\nbar".to_string()).take().0.ok().unwrap();
+    /// assert_eq!(content.content(), "foo\nbar\n");
+    /// ```
+    pub fn content(&self) -> &str {
+        &self.content
+    }
+
+    /// Gets the exact source text of the passage body, including any trailing
+    /// blank lines before the next passage. When parsed directly from a
+    /// context rather than as part of a [`Passage`], this is the full contents
+    /// of the context
+    ///
+    /// [`Passage`]: struct.Passage.html
+    pub fn raw(&self) -> &str {
+        &self.raw
+    }
+
+    /// Gets the source text of the passage body with trailing whitespace,
+    /// including trailing blank lines, removed
+    pub fn trimmed(&self) -> &str {
+        self.raw.trim_end()
+    }
+
+    /// Returns an iterator over the lines of the content, along with the
+    /// absolute [`Position`] of the start of each line
+    ///
+    /// # Examples
+    /// ```
+    /// use tweep::{Position, StoryPassages, PassageContent};
+    /// let input = ":: Start\nHello\nWorld".to_string();
+    /// let story = StoryPassages::from_string(input).take().0.ok().unwrap();
+    /// if let PassageContent::Normal(content) = &story.passages["Start"].content {
+    ///     let lines: Vec<_> = content.lines().collect();
+    ///     assert_eq!(lines, vec![("Hello", Position::abs(2, 1)), ("World", Position::abs(3, 1))]);
+    /// }
+    /// # else { panic!(); }
+    /// ```
+    ///
+    /// [`Position`]: struct.Position.html
+    pub fn lines(&self) -> impl Iterator<Item = (&str, Position)> {
+        let start = self.start_position;
+        let text = self.content.strip_suffix('\n').unwrap_or(&self.content);
+        text.split('\n').enumerate().map(move |(i, line)| {
+            let column = if i == 0 { start.column } else { 1 };
+            (line, Position::abs(start.line + i, column))
+        })
+    }
+
+    /// Gets the prose of the passage body: its [`trimmed`] source text with
+    /// macros (`<<...>>`), HTML tags, and everything but the display text of
+    /// links replaced by spaces. Since the replaced text keeps its length and
+    /// line breaks, byte offsets and positions in the prose are the same as in
+    /// the source, making it suitable for spellcheckers and style checkers
+    ///
+    /// # Examples
+    /// ```
+    /// use tweep::TwineContent;
+    /// let input = "Go <<if $x>>[[north|North Room]]<</if>> or <b>south</b>".to_string();
+    /// let content = TwineContent::parse_body(input).take().0.ok().unwrap();
+    /// let prose = content.prose();
+    /// assert_eq!(prose.len(), content.trimmed().len());
+    /// assert_eq!(prose.split_whitespace().collect::<Vec<_>>(), vec!["Go", "north", "or", "south"]);
+    /// assert_eq!(prose.find("north"), content.trimmed().find("north"));
+    /// ```
+    ///
+    /// [`trimmed`]: #method.trimmed
+    pub fn prose(&self) -> String {
+        let mut prose = self.trimmed().as_bytes().to_vec();
+        for range in self.markup_ranges() {
+            for b in &mut prose[range] {
+                if *b != b'\n' {
+                    *b = b' ';
+                }
+            }
+        }
+        String::from_utf8(prose).expect("Only ASCII delimited ranges are blanked")
+    }
+
+    /// Gets the byte ranges of the [`prose`], relative to the [`trimmed`]
+    /// source, split at markup and line breaks and with surrounding whitespace
+    /// removed. Each range is a segment of text that can be translated on its
+    /// own
+    ///
+    /// [`prose`]: #method.prose
+    /// [`trimmed`]: #method.trimmed
+    pub(crate) fn prose_segments(&self) -> Vec<Range<usize>> {
+        let source = self.trimmed();
+        let mut segments = Vec::new();
+        let mut push = |range: Range<usize>| {
+            let mut start = range.start;
+            for line in source[range].split('\n') {
+                let trimmed = line.trim_start();
+                let seg_start = start + line.len() - trimmed.len();
+                let trimmed = trimmed.trim_end();
+                if !trimmed.is_empty() {
+                    segments.push(seg_start..seg_start + trimmed.len());
+                }
+                start += line.len() + 1;
+            }
+        };
+
+        let mut start = 0;
+        for range in self.markup_ranges() {
+            push(start..range.start);
+            start = range.end;
+        }
+        push(start..source.len());
+        segments
+    }
+
+    /// Gets the byte ranges of the [`trimmed`] source that are markup rather
+    /// than prose, in order
+    ///
+    /// [`trimmed`]: #method.trimmed
+    pub(crate) fn markup_ranges(&self) -> Vec<Range<usize>> {
+        let source = self.trimmed();
+        let bytes = source.as_bytes();
+        let mut ranges = Vec::new();
+        let mut blank = |range: Range<usize>| {
+            if !range.is_empty() {
+                ranges.push(range);
+            }
+        };
+
+        let mut i = 0;
+        while i < bytes.len() {
+            let rest = &source[i..];
+            if rest.starts_with("[[") {
+                let line = rest.split('\n').next().unwrap_or(rest);
+                if let Ok(end) = find_link_end(line, 2) {
+                    let inner = &rest[2..end];
+                    let text = display_text(inner);
+                    let text_start = i + 2 + (text.as_ptr() as usize - inner.as_ptr() as usize);
+                    blank(i..text_start);
+                    blank(text_start + text.len()..i + end + 2);
+                    i += end + 2;
+                    continue;
+                }
+            } else if rest.starts_with("<<") {
+                if let Some(end) = rest.find(">>") {
+                    blank(i..i + end + 2);
+                    i += end + 2;
+                    continue;
+                }
+            } else if rest.starts_with('<')
+                && rest[1..].starts_with(|c: char| c.is_ascii_alphabetic() || c == '/' || c == '!')
+            {
+                if let Some(end) = rest.find('>') {
+                    blank(i..i + end + 1);
+                    i += end + 1;
+                    continue;
+                }
+            }
+            i += rest.chars().next().map_or(1, char::len_utf8);
+        }
+
+        ranges
+    }
+
+    /// Gets the absolute position of the start of the content
+    pub(crate) fn start_position(&self) -> Position {
+        self.start_position
+    }
+
+    /// Creates a `TwineContent` from its already parsed parts
+    #[cfg(feature = "binary")]
+    pub(crate) fn from_parts(
+        content: SharedStr,
+        pid: usize,
+        links: Vec<TwineLink>,
+        raw: SharedStr,
+        start_position: Position,
+    ) -> Self {
+        TwineContent {
+            content,
+            pid,
+            links,
+            unclosed_links: Vec::new(),
+            raw,
+            start_position,
+        }
+    }
+
+    /// Sets the raw source text of the passage body
+    pub(crate) fn set_raw(&mut self, raw: SharedStr) {
+        self.raw = raw;
+    }
+
+    /// Trims the whitespace surrounding the target of every link
+    pub(crate) fn normalize_link_whitespace(&mut self) {
+        for link in &mut self.links {
+            let trimmed = link.target.trim();
+            if trimmed.len() != link.target.len() {
+                link.target = trimmed.to_string();
+            }
+        }
+    }
+
     /// Gets a [`Vec`] of all the links contained within this content
     ///
     /// [`Vec`]: std::Vec
@@ -61,9 +278,92 @@ impl TwineContent {
         &self.links
     }
 
+    /// Gets the links that were opened with `[[` but never closed on the same
+    /// line. The target of each is the partial target text up to the end of
+    /// the line or the next `[[`, and its context runs from the opening
+    /// brackets to the same point
+    ///
+    /// # Examples
+    /// ```
+    /// use tweep::TwineContent;
+    /// let out = TwineContent::parse_body("Go [[north| or [[south]]".to_string());
+    /// let content = out.take().0.ok().unwrap();
+    /// assert_eq!(content.get_unclosed_links()[0].target, " or ");
+    /// assert_eq!(content.get_links()[0].target, "south");
+    /// ```
+    pub fn get_unclosed_links(&self) -> &Vec<TwineLink> {
+        &self.unclosed_links
+    }
+
+    /// Gets the names of the passages transcluded into this passage by an
+    /// include macro, in order. The SugarCube `<<include>>` and `<<display>>`
+    /// macros and the Harlowe `(display:)` macro are recognized, when given a
+    /// quoted passage name or, for SugarCube, a link
+    ///
+    /// # Examples
+    /// ```
+    /// use tweep::TwineContent;
+    /// let input = r#"<<include "Header">> (display: 'Footer') <<include [[Menu]]>> <<include $x>>"#;
+    /// let content = TwineContent::parse_body(input.to_string()).take().0.ok().unwrap();
+    /// assert_eq!(content.transclusions(), vec!["Header", "Footer", "Menu"]);
+    /// ```
+    pub fn transclusions(&self) -> Vec<&str> {
+        let source = self.trimmed();
+        let mut targets = Vec::new();
+        let mut i = 0;
+        while let Some(start) = source[i..].find(['<', '(']).map(|s| i + s) {
+            let rest = &source[start..];
+            i = start + 1;
+            let (argument, close) = if let Some(rest) = rest.strip_prefix("<<include").or_else(|| rest.strip_prefix("<<display")) {
+                (rest, ">>")
+            } else if let Some(rest) = rest.strip_prefix("(display:") {
+                (rest, ")")
+            } else {
+                continue;
+            };
+            if !argument.starts_with(char::is_whitespace) && !argument.starts_with('"') && !argument.starts_with('\'') {
+                continue;
+            }
+            let argument = argument.trim_start();
+            let target = match argument.chars().next() {
+                Some(quote @ '"') | Some(quote @ '\'') => argument[1..].find(quote).map(|end| &argument[1..1 + end]),
+                Some('[') if close == ">>" => argument
+                    .strip_prefix("[[")
+                    .and_then(|link| link.find("]]").map(|end| link_target(&link[..end]))),
+                _ => None,
+            };
+            if let Some(target) = target {
+                targets.push(target);
+            }
+        }
+        targets
+    }
+
+    /// Parses a `TwineContent` out of a standalone passage body, such as an
+    /// editor selection or a passage stored outside of a twee file, without
+    /// needing to construct a full story. The body should not include a
+    /// passage header. [`Position`]s in the resulting links and warnings are
+    /// relative to the start of `input`.
+    ///
+    /// # Examples
+    /// ```
+    /// use tweep::{TwineContent, WarningKind};
+    /// let out = TwineContent::parse_body("Go [[north]] or [[south".to_string());
+    /// let (res, warnings) = out.take();
+    /// let content = res.ok().unwrap();
+    /// assert_eq!(content.get_links()[0].target, "north");
+    /// assert_eq!(warnings[0].kind, WarningKind::UnclosedLink("south".to_string()));
+    /// ```
+    ///
+    /// [`Position`]: struct.Position.html
+    pub fn parse_body(input: String) -> Output<Result<Self, ErrorList>> {
+        TwineContent::parse(FullContext::from(None, input))
+    }
+
     /// Parses a `TwineContent` out of the given context
     pub fn parse(context: FullContext) -> Output<Result<Self, ErrorList>> {
         let mut links = Vec::new();
+        let mut unclosed_links = Vec::new();
         let mut warnings = Vec::new();
         for (row, line) in context.get_contents().split('\n').enumerate() {
             let mut start = 0;
@@ -72,48 +372,46 @@ impl TwineContent {
                     Some(x) => start + x,
                     None => break,
                 };
-                let end = match line[start..].find("]]") {
-                    Some(x) => start + x,
-                    None => {
+
+                let end = match find_link_end(line, start + 2) {
+                    Ok(x) => x,
+                    Err(partial_end) => {
+                        let target = link_target(&line[start + 2..partial_end]).to_string();
                         warnings.push({
                             Warning::new(
-                                WarningKind::UnclosedLink,
+                                WarningKind::UnclosedLink(target.clone()),
                                 Some(context.subcontext(
                                     Position::rel(row + 1, start + 1)
                                         ..=Position::rel(row + 1, line.len()),
                                 )),
                             )
                         });
-                        break;
+                        unclosed_links.push(TwineLink {
+                            target,
+                            context: context.subcontext(
+                                Position::rel(row + 1, start + 1)..=Position::rel(row + 1, partial_end),
+                            ),
+                        });
+
+                        // Resume scanning after the opening brackets
+                        start += 2;
+                        continue;
                     }
                 };
                 let link_context = context.subcontext(
                     Position::rel(row + 1, start + 1)..=Position::rel(row + 1, end + 2),
                 );
                 let link_content = &line[start + 2..end];
-                let linked_passage = if link_content.contains('|') {
-                    // Link format: [[Link Text|Passage Name]]
-                    let mut iter = link_content.split('|');
-                    let _ = iter.next();
-                    iter.next().unwrap()
-                } else if link_content.contains("<-") {
-                    // Link format: [[Passage Name<-Link Text]]
-                    link_content.split("<-").next().unwrap()
-                } else if link_content.contains("->") {
-                    // Link format: [[Link Text->Passage Name]]
-                    let mut iter = link_content.split("->");
-                    let _ = iter.next();
-                    iter.next().unwrap()
-                } else {
-                    // Link format: [[Passage Name]]
-                    link_content
-                };
+                let linked_passage = link_target(link_content);
 
                 if linked_passage.starts_with(char::is_whitespace)
                     || linked_passage.ends_with(char::is_whitespace)
                 {
                     warnings.push({
-                        Warning::new(WarningKind::WhitespaceInLink, Some(link_context.clone()))
+                        Warning::new(
+                            WarningKind::WhitespaceInLink(linked_passage.to_string()),
+                            Some(link_context.clone()),
+                        )
                     });
                 }
 
@@ -122,21 +420,53 @@ impl TwineContent {
                     context: link_context.clone(),
                 });
 
-                start = end;
+                start = end + 2;
             }
         }
 
-        let mut content = context.get_contents().to_string();
-        content.push('\n');
+        let raw = context.get_shared_contents();
         Output::new(Ok(TwineContent {
-            content,
+            content: raw.clone().with_line_break(),
             links,
+            unclosed_links,
             pid: 1,
+            raw,
+            start_position: *context.get_start_position(),
         }))
         .with_warnings(warnings)
     }
 }
 
+/// Given a line and the position just after the `[[` opening a link, returns
+/// the position of the `]]` closing it. Brackets within the link, such as in
+/// display text or the setter of `[[Go][$a[$b[0]] to 1]]`, are matched in
+/// pairs, as long as they are balanced. If another link opens before the link
+/// is closed, or the line ends first, returns the position where the link's
+/// text ends instead
+fn find_link_end(line: &str, from: usize) -> Result<usize, usize> {
+    let bytes = line.as_bytes();
+    let mut depth = 0;
+    let mut i = from;
+    while i < bytes.len() {
+        match (bytes[i], bytes.get(i + 1)) {
+            (b'[', Some(b'[')) if depth == 0 => return Err(i),
+            (b']', Some(b']')) if depth == 0 => return Ok(i),
+            // The `][` starting a setter
+            (b']', Some(b'[')) if depth == 0 => i += 1,
+            (b'[', _) => depth += 1,
+            (b']', _) if depth > 0 => depth -= 1,
+            _ => (),
+        }
+        i += 1;
+    }
+
+    // With unbalanced brackets, the first `]]` closes the link
+    match line[from..].find("]]") {
+        Some(x) if !line[from..from + x].contains("[[") => Ok(from + x),
+        _ => Err(line.len()),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -146,11 +476,37 @@ mod tests {
         let input = "foo\nbar".to_string();
         let out = TwineContent::parse(FullContext::from(None, input));
         let (res, _) = out.take();
-        assert_eq!(res.is_ok(), true);
+        assert!(res.is_ok());
         let content = res.ok().unwrap();
         assert_eq!(content.content, "foo\nbar\n");
     }
 
+    #[test]
+    fn parse_body() {
+        let out = TwineContent::parse_body("foo\n[[ bar]]".to_string());
+        let (res, warnings) = out.take();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].kind, WarningKind::WhitespaceInLink(" bar".to_string()));
+        let content = res.ok().unwrap();
+        assert_eq!(content.content, "foo\n[[ bar]]\n");
+        assert_eq!(content.get_links().len(), 1);
+        assert_eq!(*content.get_links()[0].context.get_start_position(), Position::abs(2, 1));
+    }
+
+    #[test]
+    fn lines() {
+        let context = FullContext::from(None, "xx foo\n\n[[bar]] baz".to_string());
+        let content = TwineContent::parse(context.subcontext(Position::rel(1, 4)..)).take().0.ok().unwrap();
+        let lines: Vec<(&str, Position)> = content.lines().collect();
+        assert_eq!(lines, vec![
+            ("foo", Position::abs(1, 4)),
+            ("", Position::abs(2, 1)),
+            ("[[bar]] baz", Position::abs(3, 1)),
+        ]);
+        let link_start = *content.get_links()[0].context.get_start_position();
+        assert_eq!(link_start, lines[2].1);
+    }
+
     #[test]
     fn links() {
         let input =
@@ -158,12 +514,12 @@ mod tests {
         let context = FullContext::from(None, input);
         let out = TwineContent::parse(context.clone());
         let (res, warnings) = out.take();
-        assert_eq!(warnings.is_empty(), true);
-        assert_eq!(res.is_ok(), true);
+        assert!(warnings.is_empty());
+        assert!(res.is_ok());
         let content = res.ok().unwrap();
-        let expected_targets = vec!["foo", "bar", "baz", "qux"];
-        let expected_lens = vec![7, 17, 18, 19];
-        let expected_links: Vec<TwineLink> = (1 as usize..5)
+        let expected_targets = ["foo", "bar", "baz", "qux"];
+        let expected_lens = [7, 17, 18, 19];
+        let expected_links: Vec<TwineLink> = (1_usize..5)
             .map(|row| {
                 TwineLink::new(
                     expected_targets[row - 1].to_string(),
@@ -176,19 +532,96 @@ mod tests {
         assert_eq!(content.get_links(), &expected_links);
     }
 
+    #[test]
+    fn setter_links() {
+        let input = "[[Next][$met_alice to true]]\n[[Go|Hall][$a = 1; $b += $a]]\n".to_string();
+        let (res, warnings) = TwineContent::parse(FullContext::from(None, input)).take();
+        assert!(warnings.is_empty());
+        let content = res.ok().unwrap();
+        let links = content.get_links();
+        assert_eq!(links[0].target, "Next");
+        assert_eq!(links[0].text(), "Next");
+        assert_eq!(links[0].assigned_variables(), vec!["$met_alice"]);
+        assert_eq!(links[1].target, "Hall");
+        assert_eq!(links[1].text(), "Go");
+        assert_eq!(links[1].assigned_variables(), vec!["$a", "$b"]);
+    }
+
     #[test]
     fn unclosed_link() {
         let context = FullContext::from(None, "blah [[unclosed\nlink]] blah blah\n\n".to_string());
         let out = TwineContent::parse(context.clone());
         let (res, warnings) = out.take();
         let expected = Warning::new(
-            WarningKind::UnclosedLink,
+            WarningKind::UnclosedLink("unclosed".to_string()),
             Some(context.subcontext(Position::rel(1, 6)..=Position::rel(1, 15))),
         );
         assert_eq!(warnings, vec![expected]);
-        assert_eq!(res.is_ok(), true);
+        assert!(res.is_ok());
         let content = res.ok().unwrap();
         assert!(content.links.is_empty());
+        assert_eq!(content.unclosed_links.len(), 1);
+        assert_eq!(content.unclosed_links[0].target, "unclosed");
+    }
+
+    #[test]
+    fn link_matrix() {
+        let cases: Vec<(&str, Vec<&str>, Vec<&str>)> = vec![
+            ("[[A]][[B]]", vec!["A", "B"], vec![]),
+            ("[[A]] [[B]][[C]]", vec!["A", "B", "C"], vec![]),
+            ("[[A|B]][[C->D]][[F<-E]]", vec!["B", "D", "F"], vec![]),
+            ("[[Go][$list[$map[$i]] to 1]]", vec!["Go"], vec![]),
+            ("[[Go|Hall][$seen[\"hall\"] to true]][[Back]]", vec!["Hall", "Back"], vec![]),
+            ("[[Look [closely]|Target]]", vec!["Target"], vec![]),
+            ("[[The [red] door->Door]] and [[Exit]]", vec!["Door", "Exit"], vec![]),
+            ("[[a]b]]", vec!["a]b"], vec![]),
+            ("[[text [x|Target]]", vec!["Target"], vec![]),
+            ("[[A]]]", vec!["A"], vec![]),
+            ("[[A] [[B]]", vec!["B"], vec!["A] "]),
+            ("[[A", vec![], vec!["A"]),
+            ("[[A]", vec![], vec!["A]"]),
+            ("[[]]", vec![""], vec![]),
+        ];
+        for (input, targets, unclosed) in cases {
+            let (res, _) = TwineContent::parse_body(input.to_string()).take();
+            let content = res.ok().unwrap();
+            let found: Vec<&str> = content.links.iter().map(|link| link.target.as_str()).collect();
+            assert_eq!(found, targets, "links in {}", input);
+            let found: Vec<&str> = content.unclosed_links.iter().map(|link| link.target.as_str()).collect();
+            assert_eq!(found, unclosed, "unclosed links in {}", input);
+        }
+
+        let content = TwineContent::parse_body("x [[Go][$a[$b[0]] to 1]] y".to_string()).take().0.ok().unwrap();
+        assert_eq!(content.links[0].context.get_contents(), "[[Go][$a[$b[0]] to 1]]");
+        assert_eq!(content.links[0].setter(), Some("$a[$b[0]] to 1"));
+    }
+
+    #[test]
+    fn unclosed_link_recovery() {
+        let context = FullContext::from(None, "[[Broken->Hal and [[Kitchen]] or [[Cellar".to_string());
+        let (res, warnings) = TwineContent::parse(context.clone()).take();
+        assert_eq!(
+            warnings,
+            vec![
+                Warning::new(
+                    WarningKind::UnclosedLink("Hal and ".to_string()),
+                    Some(context.subcontext(Position::rel(1, 1)..=Position::rel(1, 41))),
+                ),
+                Warning::new(
+                    WarningKind::UnclosedLink("Cellar".to_string()),
+                    Some(context.subcontext(Position::rel(1, 34)..=Position::rel(1, 41))),
+                ),
+            ]
+        );
+        let content = res.ok().unwrap();
+        let targets: Vec<&str> = content.links.iter().map(|link| link.target.as_str()).collect();
+        assert_eq!(targets, vec!["Kitchen"]);
+        let partial: Vec<&str> = content.unclosed_links.iter().map(|link| link.target.as_str()).collect();
+        assert_eq!(partial, vec!["Hal and ", "Cellar"]);
+        assert_eq!(
+            content.unclosed_links[0].context,
+            context.subcontext(Position::rel(1, 1)..=Position::rel(1, 18))
+        );
     }
 
     #[test]
@@ -205,11 +638,12 @@ mod tests {
         let context = FullContext::from(None, input);
         let out = TwineContent::parse(context.clone());
         let (res, warnings) = out.take();
-        let expected_lens = vec![8, 8, 13, 13, 15, 15, 16, 17];
-        let expected_warnings: Vec<Warning> = (1 as usize..9)
+        let expected_lens = [8, 8, 13, 13, 15, 15, 16, 17];
+        let expected_targets = [" foo", "bar ", "baz ", " qux", "quux ", " quuz", " corge", "grault "];
+        let expected_warnings: Vec<Warning> = (1_usize..9)
             .map(|row| {
                 Warning::new(
-                    WarningKind::WhitespaceInLink,
+                    WarningKind::WhitespaceInLink(expected_targets[row - 1].to_string()),
                     Some(context.subcontext(
                         Position::rel(row, 1)..=Position::rel(row, expected_lens[row - 1]),
                     )),
@@ -217,12 +651,12 @@ mod tests {
             })
             .collect();
         assert_eq!(warnings, expected_warnings);
-        assert_eq!(res.is_ok(), true);
+        assert!(res.is_ok());
         let content = res.ok().unwrap();
-        let expected_targets = vec![
+        let expected_targets = [
             " foo", "bar ", "baz ", " qux", "quux ", " quuz", " corge", "grault ",
         ];
-        let expected_links: Vec<TwineLink> = (1 as usize..9)
+        let expected_links: Vec<TwineLink> = (1_usize..9)
             .map(|row| {
                 TwineLink::new(
                     expected_targets[row - 1].to_string(),
@@ -20,4 +20,150 @@ impl TwineLink {
             context,
         }
     }
+
+    /// Returns the text displayed for this link, as written in the source
+    ///
+    /// # Examples
+    /// ```
+    /// use tweep::TwineContent;
+    /// let input = "[[A]] [[B|C]] [[D->E]] [[F<-G]]".to_string();
+    /// let content = TwineContent::parse_body(input).take().0.ok().unwrap();
+    /// let text: Vec<&str> = content.get_links().iter().map(|l| l.text()).collect();
+    /// assert_eq!(text, vec!["A", "B", "D", "G"]);
+    /// ```
+    pub fn text(&self) -> &str {
+        let source = self.context.get_contents();
+        let inner = source
+            .strip_prefix("[[")
+            .and_then(|s| s.strip_suffix("]]"))
+            .unwrap_or(source);
+        display_text(inner)
+    }
+
+    /// Returns `true` if the target of this link is a variable or expression
+    /// that is only evaluated at runtime, rather than a passage name. Targets
+    /// beginning with a story or temporary variable, such as `$destination` or
+    /// `_next`, or wrapped entirely in parentheses, such as
+    /// `(either: "A", "B")`, are dynamic
+    ///
+    /// # Examples
+    /// ```
+    /// use tweep::TwineContent;
+    /// let input = "[[Go|$destination]] [[_next]] [[(either: \"A\", \"B\")]] [[Room (2)]]".to_string();
+    /// let content = TwineContent::parse_body(input).take().0.ok().unwrap();
+    /// let dynamic: Vec<bool> = content.get_links().iter().map(|l| l.is_dynamic()).collect();
+    /// assert_eq!(dynamic, vec![true, true, true, false]);
+    /// ```
+    pub fn is_dynamic(&self) -> bool {
+        let target = self.target.trim();
+        let mut chars = target.chars();
+        match chars.next() {
+            Some('$') | Some('_') => chars.next().is_some_and(|c| c.is_alphabetic() || c == '_' || c == '$'),
+            Some('(') => target.ends_with(')'),
+            _ => false,
+        }
+    }
+
+    /// Returns the setter of this link, run when the link is followed, if it
+    /// is a setter link such as `[[Next][$met_alice to true]]`
+    ///
+    /// # Examples
+    /// ```
+    /// use tweep::TwineContent;
+    /// let input = "[[Say hi|Next][$met_alice to true]] [[Leave]]".to_string();
+    /// let content = TwineContent::parse_body(input).take().0.ok().unwrap();
+    /// let links = content.get_links();
+    /// assert_eq!(links[0].target, "Next");
+    /// assert_eq!(links[0].setter(), Some("$met_alice to true"));
+    /// assert_eq!(links[1].setter(), None);
+    /// ```
+    pub fn setter(&self) -> Option<&str> {
+        let source = self.context.get_contents();
+        let inner = source
+            .strip_prefix("[[")
+            .and_then(|s| s.strip_suffix("]]"))
+            .unwrap_or(source);
+        split_setter(inner).1
+    }
+
+    /// Returns the variables assigned by the setter of this link, in the order
+    /// they are assigned. A variable is assigned when it is followed by `to`,
+    /// `=`, or a compound assignment operator such as `+=`
+    ///
+    /// # Examples
+    /// ```
+    /// use tweep::TwineContent;
+    /// let input = "[[Next][$met_alice to true; _count += 1; $x == $y]]".to_string();
+    /// let content = TwineContent::parse_body(input).take().0.ok().unwrap();
+    /// assert_eq!(content.get_links()[0].assigned_variables(), vec!["$met_alice", "_count"]);
+    /// ```
+    pub fn assigned_variables(&self) -> Vec<&str> {
+        let setter = match self.setter() {
+            Some(setter) => setter,
+            None => return Vec::new(),
+        };
+        let mut variables = Vec::new();
+        let mut rest = setter;
+        while let Some(start) = rest.find(['$', '_']) {
+            let name_len = rest[start + 1..]
+                .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+                .unwrap_or(rest.len() - start - 1);
+            let name = &rest[start..start + 1 + name_len];
+            rest = &rest[start + 1 + name_len..];
+            if name_len == 0 || !name[1..].starts_with(|c: char| c.is_alphabetic() || c == '_') {
+                continue;
+            }
+            let after = rest.trim_start();
+            let assigned = after.starts_with("to ")
+                || (["=", "+=", "-=", "*=", "/=", "%="].iter().any(|op| after.starts_with(op)) && !after.starts_with("=="));
+            if assigned {
+                variables.push(name);
+            }
+        }
+        variables
+    }
+}
+
+/// Given the text between the `[[` and `]]` of a link, returns the link itself
+/// and its setter, if it has one. A SugarCube setter link, such as
+/// `[[Next][$met_alice to true]]`, has its setter after a `][`
+pub(crate) fn split_setter(inner: &str) -> (&str, Option<&str>) {
+    match inner.split_once("][") {
+        Some((link, setter)) => (link, Some(setter)),
+        None => (inner, None),
+    }
+}
+
+/// Given the text between the `[[` and `]]` of a link, returns the name of the
+/// passage it links to
+pub(crate) fn link_target(inner: &str) -> &str {
+    let inner = split_setter(inner).0;
+    if inner.contains('|') {
+        // Link format: [[Link Text|Passage Name]]
+        inner.split('|').nth(1).unwrap()
+    } else if let Some((target, _)) = inner.split_once("<-") {
+        // Link format: [[Passage Name<-Link Text]]
+        target
+    } else if inner.contains("->") {
+        // Link format: [[Link Text->Passage Name]]
+        inner.split("->").nth(1).unwrap()
+    } else {
+        // Link format: [[Passage Name]]
+        inner
+    }
+}
+
+/// Given the text between the `[[` and `]]` of a link, returns the portion of
+/// it that is displayed
+pub(crate) fn display_text(inner: &str) -> &str {
+    let inner = split_setter(inner).0;
+    if let Some((text, _)) = inner.split_once('|') {
+        text
+    } else if let Some((_, text)) = inner.split_once("<-") {
+        text
+    } else if let Some((text, _)) = inner.split_once("->") {
+        text
+    } else {
+        inner
+    }
 }
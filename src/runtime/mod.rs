@@ -0,0 +1,13 @@
+//! A minimal runtime for playing through a parsed [`Story`] directly from
+//! tweep, without a JavaScript story format. Intended for simple text
+//! front-ends, such as terminal interfaces or chat bots.
+//!
+//! [`Story`]: ../struct.Story.html
+
+mod simulator;
+pub use simulator::SimulationReport;
+pub use simulator::Simulator;
+
+mod story_walker;
+pub use story_walker::Choice;
+pub use story_walker::StoryWalker;
@@ -0,0 +1,178 @@
+use crate::runtime::StoryWalker;
+use crate::Story;
+use std::collections::HashMap;
+
+/// The results of a set of random playthroughs performed by a [`Simulator`]
+///
+/// [`Simulator`]: struct.Simulator.html
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct SimulationReport {
+    /// The number of times each passage was visited across all runs
+    pub visits: HashMap<String, usize>,
+
+    /// The number of passages visited in each run, including the start
+    /// passage, in the order the runs were performed
+    pub path_lengths: Vec<usize>,
+
+    /// The number of runs that were stopped because they reached the maximum
+    /// number of steps rather than an ending
+    pub truncated_runs: usize,
+
+    /// The names of the passages that were never visited, sorted
+    pub unvisited: Vec<String>,
+}
+
+/// Plays through a [`Story`] repeatedly from its start passage, choosing a
+/// random link at each step, as an automated smoke test for branching stories
+///
+/// Runs are reproducible: the same seed always produces the same
+/// playthroughs of the same story.
+///
+/// # Examples
+/// ```
+/// use tweep::Story;
+/// use tweep::runtime::Simulator;
+/// let input = r#":: Start
+/// [[Left]] or [[Right]]
+///
+/// :: Left
+/// The end
+///
+/// :: Right
+/// The end
+///
+/// :: Secret
+/// Unreachable
+/// "#.to_string();
+/// let story = Story::from_string(input).take().0.ok().unwrap();
+/// let report = Simulator::new(&story).with_seed(42).run(50);
+/// assert_eq!(report.path_lengths.len(), 50);
+/// assert_eq!(report.visits["Start"], 50);
+/// assert_eq!(report.visits["Left"] + report.visits["Right"], 50);
+/// assert_eq!(report.unvisited, vec!["Secret"]);
+/// ```
+///
+/// [`Story`]: ../struct.Story.html
+pub struct Simulator<'a> {
+    story: &'a Story,
+    seed: u64,
+    max_steps: usize,
+}
+
+impl<'a> Simulator<'a> {
+    /// Creates a new `Simulator` for the given story with a seed of 0 and a
+    /// maximum of 1000 steps per run
+    pub fn new(story: &'a Story) -> Self {
+        Simulator {
+            story,
+            seed: 0,
+            max_steps: 1000,
+        }
+    }
+
+    /// Builder method to set the random seed
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Builder method to set the maximum number of links followed in a single
+    /// run, so that stories with cycles always terminate
+    pub fn with_max_steps(mut self, max_steps: usize) -> Self {
+        self.max_steps = max_steps;
+        self
+    }
+
+    /// Performs `runs` random playthroughs and reports the results. If the
+    /// story has no start passage, no runs are performed
+    pub fn run(&self, runs: usize) -> SimulationReport {
+        let mut report = SimulationReport::default();
+        let mut rng = SplitMix64(self.seed);
+        for _ in 0..runs {
+            let mut walker = match StoryWalker::new(self.story) {
+                Some(walker) => walker,
+                None => break,
+            };
+            let mut length = 1;
+            *report.visits.entry(walker.current_name().to_string()).or_insert(0) += 1;
+            loop {
+                let choices = walker.choices();
+                if choices.is_empty() {
+                    break;
+                }
+                if length > self.max_steps {
+                    report.truncated_runs += 1;
+                    break;
+                }
+                let index = (rng.next() % choices.len() as u64) as usize;
+                let name = walker.follow(index).unwrap();
+                *report.visits.entry(name.to_string()).or_insert(0) += 1;
+                length += 1;
+            }
+            report.path_lengths.push(length);
+        }
+
+        let mut unvisited: Vec<String> = self
+            .story
+            .passages
+            .keys()
+            .filter(|name| !report.visits.contains_key(*name))
+            .cloned()
+            .collect();
+        unvisited.sort();
+        report.unvisited = unvisited;
+        report
+    }
+}
+
+/// A small, fast, seedable pseudorandom number generator
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn story(input: &str) -> Story {
+        Story::from_string(input.to_string()).take().0.ok().unwrap()
+    }
+
+    #[test]
+    fn reproducible() {
+        let s = story(":: Start\n[[A]] [[B]] [[C]]\n\n:: A\n[[Start]] [[B]]\n\n:: B\n[[C]]\n\n:: C\nEnd");
+        let a = Simulator::new(&s).with_seed(7).run(20);
+        let b = Simulator::new(&s).with_seed(7).run(20);
+        assert_eq!(a, b);
+        assert_eq!(a.path_lengths.len(), 20);
+        assert_eq!(a.truncated_runs, 0);
+        assert_eq!(a.visits["C"], 20);
+        assert!(a.unvisited.is_empty());
+    }
+
+    #[test]
+    fn cycles_truncated() {
+        let s = story(":: Start\n[[Loop]]\n\n:: Loop\n[[Start]]");
+        let report = Simulator::new(&s).with_max_steps(5).run(3);
+        assert_eq!(report.truncated_runs, 3);
+        assert_eq!(report.path_lengths, vec![6, 6, 6]);
+        assert_eq!(report.visits["Start"], 9);
+        assert_eq!(report.visits["Loop"], 9);
+    }
+
+    #[test]
+    fn no_start() {
+        let s = story(":: Begin\nHi");
+        let report = Simulator::new(&s).run(5);
+        assert!(report.path_lengths.is_empty());
+        assert_eq!(report.unvisited, vec!["Begin"]);
+    }
+}
@@ -0,0 +1,196 @@
+use crate::Story;
+use crate::TwinePassage;
+
+/// A link out of the current passage of a [`StoryWalker`] that can be followed
+///
+/// [`StoryWalker`]: struct.StoryWalker.html
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Choice<'a> {
+    /// The text displayed for the link
+    pub text: &'a str,
+
+    /// The name of the passage the link leads to
+    pub target: &'a str,
+}
+
+/// Walks through a [`Story`], starting at its start passage, following the
+/// links chosen by the player
+///
+/// Link targets are matched to passage names exactly, ignoring surrounding
+/// whitespace. Links to passages that don't exist are not offered as choices.
+///
+/// # Examples
+/// ```
+/// use tweep::Story;
+/// use tweep::runtime::StoryWalker;
+/// let input = r#":: Start
+/// You are in a cave. [[Go north|North]] or [[Go south->South]]
+///
+/// :: North
+/// A dead end. [[Start]]
+///
+/// :: South
+/// Daylight!
+/// "#.to_string();
+/// let story = Story::from_string(input).take().0.ok().unwrap();
+/// let mut walker = StoryWalker::new(&story).unwrap();
+/// assert_eq!(walker.current_name(), "Start");
+/// let choices = walker.choices();
+/// assert_eq!(choices[0].text, "Go north");
+/// assert_eq!(choices[1].target, "South");
+/// walker.follow(1);
+/// assert_eq!(walker.text(), "Daylight!");
+/// assert!(walker.is_ending());
+/// walker.back();
+/// assert_eq!(walker.current_name(), "Start");
+/// ```
+///
+/// [`Story`]: ../struct.Story.html
+pub struct StoryWalker<'a> {
+    story: &'a Story,
+    current: &'a str,
+    history: Vec<&'a str>,
+}
+
+impl<'a> StoryWalker<'a> {
+    /// Creates a new `StoryWalker` positioned at the start passage of the
+    /// story, or returns `None` if the start passage can't be found
+    pub fn new(story: &'a Story) -> Option<Self> {
+        let start = story.get_start_passage_name()?;
+        StoryWalker::start_at(story, start)
+    }
+
+    /// Creates a new `StoryWalker` positioned at the passage named `name`, or
+    /// returns `None` if no such passage exists
+    pub fn start_at(story: &'a Story, name: &str) -> Option<Self> {
+        let (current, _) = story.passages.get_key_value(name)?;
+        Some(StoryWalker {
+            story,
+            current,
+            history: Vec::new(),
+        })
+    }
+
+    /// Returns the name of the current passage
+    pub fn current_name(&self) -> &'a str {
+        self.current
+    }
+
+    /// Returns the current passage
+    pub fn current(&self) -> &'a TwinePassage {
+        &self.story.passages[self.current]
+    }
+
+    /// Returns the text of the current passage, without trailing whitespace
+    pub fn text(&self) -> &'a str {
+        self.current().content.trimmed()
+    }
+
+    /// Returns the links out of the current passage that lead to existing
+    /// passages, in the order they appear
+    pub fn choices(&self) -> Vec<Choice<'a>> {
+        self.current()
+            .content
+            .get_links()
+            .iter()
+            .filter_map(|link| {
+                let (target, _) = self.story.passages.get_key_value(link.target.trim())?;
+                Some(Choice {
+                    text: link.text(),
+                    target,
+                })
+            })
+            .collect()
+    }
+
+    /// Returns `true` if the current passage has no choices
+    pub fn is_ending(&self) -> bool {
+        self.choices().is_empty()
+    }
+
+    /// Follows the choice at `index` in the list returned by [`choices`],
+    /// returning the name of the new current passage, or `None` if there is no
+    /// such choice
+    ///
+    /// [`choices`]: #method.choices
+    pub fn follow(&mut self, index: usize) -> Option<&'a str> {
+        let target = self.choices().get(index)?.target;
+        self.history.push(self.current);
+        self.current = target;
+        Some(target)
+    }
+
+    /// Moves directly to the passage named `name`, regardless of links.
+    /// Returns `false` and stays put if no such passage exists
+    pub fn go_to(&mut self, name: &str) -> bool {
+        match self.story.passages.get_key_value(name) {
+            Some((name, _)) => {
+                self.history.push(self.current);
+                self.current = name;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns to the previously visited passage. Returns `false` if there is
+    /// no previous passage
+    pub fn back(&mut self) -> bool {
+        match self.history.pop() {
+            Some(name) => {
+                self.current = name;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns the names of the previously visited passages, oldest first
+    pub fn history(&self) -> &[&'a str] {
+        &self.history
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn story(input: &str) -> Story {
+        Story::from_string(input.to_string()).take().0.ok().unwrap()
+    }
+
+    #[test]
+    fn walk() {
+        let story = story(":: Start\n[[A]] [[Nowhere]] [[ B ]]\n\n:: A\n[[Start]]\n\n:: B\nEnd");
+        let mut walker = StoryWalker::new(&story).unwrap();
+        assert_eq!(
+            walker.choices(),
+            vec![
+                Choice { text: "A", target: "A" },
+                Choice { text: " B ", target: "B" },
+            ]
+        );
+        assert_eq!(walker.follow(2), None);
+        assert_eq!(walker.follow(0), Some("A"));
+        assert_eq!(walker.follow(0), Some("Start"));
+        assert_eq!(walker.follow(1), Some("B"));
+        assert!(walker.is_ending());
+        assert_eq!(walker.history(), &["Start", "A", "Start"]);
+        assert!(!walker.go_to("Nowhere"));
+        assert!(walker.go_to("A"));
+        assert_eq!(walker.current_name(), "A");
+        while walker.back() {}
+        assert_eq!(walker.current_name(), "Start");
+        assert!(walker.history().is_empty());
+    }
+
+    #[test]
+    fn start() {
+        let data = ":: StoryData\n{\"ifid\": \"X\", \"start\": \"Begin\"}\n\n";
+        let s = story(&format!("{}:: Begin\nHi\n\n:: Other\nBye", data));
+        assert_eq!(StoryWalker::new(&s).unwrap().current_name(), "Begin");
+        assert_eq!(StoryWalker::start_at(&s, "Other").unwrap().text(), "Bye");
+        assert!(StoryWalker::start_at(&s, "Nowhere").is_none());
+        assert!(StoryWalker::new(&story(":: Begin\nHi")).is_none());
+    }
+}
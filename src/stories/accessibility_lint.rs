@@ -0,0 +1,194 @@
+use crate::PassageContent;
+use crate::StoryPassages;
+use crate::Warning;
+use crate::WarningKind;
+use std::ops::Range;
+
+/// Link display text that doesn't describe where the link leads
+const VAGUE_LINK_TEXT: [&str; 7] = ["here", "click", "click here", "this", "link", "more", "read more"];
+
+/// Color words that, followed by one of `COLORED_NOUNS`, identify something by
+/// its color alone
+const COLORS: [&str; 11] = [
+    "red", "green", "blue", "yellow", "orange", "purple", "pink", "gray", "grey", "black", "white",
+];
+
+/// Words naming the parts of a story that are identified by color
+const COLORED_NOUNS: [&str; 12] = [
+    "link", "links", "button", "buttons", "text", "option", "options", "choice", "choices", "word", "words",
+    "one",
+];
+
+impl StoryPassages {
+    /// Checks the markup of every normal passage for common accessibility
+    /// problems, returning the warnings in order of passage name
+    ///
+    /// # Warnings
+    /// * [`MissingAltText`] - An `<img>` tag without an `alt` attribute, or
+    ///   image markup such as `[img[door.png]]` without a title
+    /// * [`VagueLinkText`] - A link whose display text is only a word such as
+    ///   "here" or "click"
+    /// * [`ColorOnlyReference`] - Prose such as "the red link" that identifies
+    ///   something by its color alone
+    ///
+    /// # Examples
+    /// ```
+    /// use tweep::{StoryPassages, WarningKind};
+    /// let input = ":: Start\n<img src=\"door.png\"> Click [[here->Hall]] or pick the red option.\n".to_string();
+    /// let story = StoryPassages::from_string(input).take().0.ok().unwrap();
+    /// let kinds: Vec<WarningKind> = story.check_accessibility().into_iter().map(|w| w.kind).collect();
+    /// assert_eq!(
+    ///     kinds,
+    ///     vec![
+    ///         WarningKind::MissingAltText("<img src=\"door.png\">".to_string()),
+    ///         WarningKind::VagueLinkText("here".to_string()),
+    ///         WarningKind::ColorOnlyReference("red option".to_string()),
+    ///     ]
+    /// );
+    /// ```
+    ///
+    /// [`MissingAltText`]: enum.WarningKind.html#variant.MissingAltText
+    /// [`VagueLinkText`]: enum.WarningKind.html#variant.VagueLinkText
+    /// [`ColorOnlyReference`]: enum.WarningKind.html#variant.ColorOnlyReference
+    pub fn check_accessibility(&self) -> Vec<Warning> {
+        let mut names: Vec<&String> = self.passages.keys().collect();
+        names.sort();
+
+        let mut warnings = Vec::new();
+        for name in names {
+            let passage = &self.passages[name];
+            let twine = match &passage.content {
+                PassageContent::Normal(twine) => twine,
+                _ => continue,
+            };
+
+            // The body begins on the line after the header
+            let contents = passage.context.get_contents();
+            let body_start = match contents.find('\n') {
+                Some(pos) => pos + 1,
+                None => continue,
+            };
+            let body = &contents[body_start..];
+
+            let mut found: Vec<(Range<usize>, WarningKind)> = Vec::new();
+            for range in images_without_alt(body) {
+                found.push((range.clone(), WarningKind::MissingAltText(body[range].to_string())));
+            }
+            for link in twine.get_links() {
+                let text = link.text().trim();
+                if VAGUE_LINK_TEXT.contains(&text.to_lowercase().as_str()) {
+                    let start = link.context.get_byte_range().start - passage.context.get_byte_range().start - body_start;
+                    let range = start..start + link.context.get_contents().len();
+                    found.push((range, WarningKind::VagueLinkText(text.to_string())));
+                }
+            }
+            let prose = twine.prose();
+            for range in color_references(&prose) {
+                found.push((range.clone(), WarningKind::ColorOnlyReference(prose[range].to_string())));
+            }
+
+            found.sort_by_key(|(range, _)| range.start);
+            for (range, kind) in found {
+                let context = passage.context.slice(body_start + range.start..body_start + range.end);
+                warnings.push(Warning::new(kind, Some(context)));
+            }
+        }
+        warnings
+    }
+}
+
+/// Finds the byte ranges of `<img>` tags without an `alt` attribute and of
+/// image markup without a title
+fn images_without_alt(body: &str) -> Vec<Range<usize>> {
+    let mut ranges = Vec::new();
+    let lower = body.to_ascii_lowercase();
+    let mut search = 0;
+    while let Some(start) = lower[search..].find("<img").map(|i| search + i) {
+        let end = match lower[start..].find('>') {
+            Some(i) => start + i + 1,
+            None => break,
+        };
+        let tag = &lower[start..end];
+        let has_alt = tag
+            .match_indices("alt")
+            .any(|(i, _)| tag[..i].ends_with(char::is_whitespace) && tag[i + 3..].trim_start().starts_with('='));
+        if !has_alt {
+            ranges.push(start..end);
+        }
+        search = end;
+    }
+
+    let mut search = 0;
+    while let Some(start) = body[search..].find("[img[").map(|i| search + i) {
+        let end = match body[start..].find("]]") {
+            Some(i) => start + i + 2,
+            None => break,
+        };
+        if !body[start + 5..end].contains('|') {
+            ranges.push(start..end);
+        }
+        search = end;
+    }
+    ranges
+}
+
+/// Finds the byte ranges of phrases in prose, such as "red link", that
+/// identify something by color alone
+fn color_references(prose: &str) -> Vec<Range<usize>> {
+    let words: Vec<(usize, &str)> = prose
+        .split(|c: char| !c.is_alphanumeric())
+        .scan(0, |offset, word| {
+            let start = *offset;
+            *offset += word.len() + 1;
+            Some((start, word))
+        })
+        .filter(|(_, word)| !word.is_empty())
+        .collect();
+    words
+        .windows(2)
+        .filter(|pair| {
+            let (color_start, color) = pair[0];
+            let between = &prose[color_start + color.len()..pair[1].0];
+            COLORS.contains(&color.to_lowercase().as_str())
+                && !between.is_empty()
+                && between.chars().all(char::is_whitespace)
+                && COLORED_NOUNS.contains(&pair[1].1.to_lowercase().as_str())
+        })
+        .map(|pair| pair[0].0..pair[1].0 + pair[1].1.len())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Position;
+
+    #[test]
+    fn images() {
+        let body = "<img src=\"a.png\" alt=\"A\"> <IMG SRC=b.png> <img alt = \"\" src=c> <img src=salt.png> \
+                    [img[d.png]] [img[Title|e.png]]";
+        let found: Vec<&str> = images_without_alt(body).into_iter().map(|r| &body[r]).collect();
+        assert_eq!(found, vec!["<IMG SRC=b.png>", "<img src=salt.png>", "[img[d.png]]"]);
+    }
+
+    #[test]
+    fn colors() {
+        let prose = "Pick the Green  one, not the red-link or the blue door. Blue\ntext";
+        let found: Vec<&str> = color_references(prose).into_iter().map(|r| &prose[r]).collect();
+        assert_eq!(found, vec!["Green  one", "Blue\ntext"]);
+    }
+
+    #[test]
+    fn check_accessibility() {
+        let input = ":: A\nSee [[Click Here|B]] and [[the map|B]]\n\n:: B\nx\n<img src=\"m.png\">\n".to_string();
+        let story = StoryPassages::from_string(input).take().0.ok().unwrap();
+        let warnings = story.check_accessibility();
+        assert_eq!(warnings.len(), 2);
+        assert_eq!(warnings[0].kind, WarningKind::VagueLinkText("Click Here".to_string()));
+        let context = warnings[0].context.as_ref().unwrap();
+        assert_eq!(*context.get_start_position(), Position::abs(2, 5));
+        assert_eq!(warnings[1].kind, WarningKind::MissingAltText("<img src=\"m.png\">".to_string()));
+        let context = warnings[1].context.as_ref().unwrap();
+        assert_eq!(*context.get_start_position(), Position::abs(6, 1));
+    }
+}
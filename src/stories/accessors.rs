@@ -0,0 +1,101 @@
+use crate::PassageContent;
+use crate::Story;
+use crate::StoryData;
+use crate::StoryPassages;
+
+impl StoryPassages {
+    /// Returns the title from the `StoryTitle` passage, if any
+    ///
+    /// # Examples
+    /// ```
+    /// use tweep::StoryPassages;
+    /// let input = r#":: StoryTitle
+    /// A Tale
+    ///
+    /// :: StoryData
+    /// { "ifid": "D674C58C-DEFA-4F70-B7A2-27742230C0FC", "format": "SugarCube", "format-version": "2.36.1" }
+    /// "#.to_string();
+    /// let story = StoryPassages::from_string(input).take().0.ok().unwrap();
+    /// assert_eq!(story.title_string(), Some("A Tale"));
+    /// assert_eq!(story.ifid(), Some("D674C58C-DEFA-4F70-B7A2-27742230C0FC"));
+    /// assert_eq!(story.format_name(), Some("SugarCube"));
+    /// assert_eq!(story.format_version(), Some("2.36.1"));
+    /// ```
+    pub fn title_string(&self) -> Option<&str> {
+        match &self.title.as_ref()?.content {
+            PassageContent::StoryTitle(title) => Some(&title.title),
+            _ => None,
+        }
+    }
+
+    /// Returns the parsed contents of the `StoryData` passage, if it exists
+    /// and could be parsed
+    pub fn story_data(&self) -> Option<&StoryData> {
+        match &self.data.as_ref()?.content {
+            PassageContent::StoryData(data) => data.as_ref(),
+            _ => None,
+        }
+    }
+
+    /// Returns the IFID from the `StoryData` passage, if any
+    pub fn ifid(&self) -> Option<&str> {
+        self.story_data().map(|data| data.ifid.as_str())
+    }
+
+    /// Returns the name of the story format from the `StoryData` passage, if
+    /// any
+    pub fn format_name(&self) -> Option<&str> {
+        self.story_data()?.format.as_deref()
+    }
+
+    /// Returns the version of the story format from the `StoryData` passage,
+    /// if any
+    pub fn format_version(&self) -> Option<&str> {
+        self.story_data()?.format_version.as_deref()
+    }
+}
+
+impl Story {
+    /// Returns the IFID from the `StoryData` passage, if any
+    ///
+    /// # Examples
+    /// ```
+    /// use tweep::Story;
+    /// let input = ":: StoryData\n{ \"ifid\": \"ABC\", \"format\": \"Harlowe\" }\n".to_string();
+    /// let story = Story::from_string(input).take().0.ok().unwrap();
+    /// assert_eq!(story.ifid(), Some("ABC"));
+    /// assert_eq!(story.format_name(), Some("Harlowe"));
+    /// assert_eq!(story.format_version(), None);
+    /// ```
+    pub fn ifid(&self) -> Option<&str> {
+        self.data.as_ref().map(|data| data.ifid.as_str())
+    }
+
+    /// Returns the name of the story format from the `StoryData` passage, if
+    /// any
+    pub fn format_name(&self) -> Option<&str> {
+        self.data.as_ref()?.format.as_deref()
+    }
+
+    /// Returns the version of the story format from the `StoryData` passage,
+    /// if any
+    pub fn format_version(&self) -> Option<&str> {
+        self.data.as_ref()?.format_version.as_deref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::StoryPassages;
+
+    #[test]
+    fn missing() {
+        let input = ":: StoryData\nnot json\n\n:: Start\nHi".to_string();
+        let story = StoryPassages::from_string(input).take().0.ok().unwrap();
+        assert!(story.data.is_some());
+        assert!(story.story_data().is_none());
+        assert_eq!(story.title_string(), None);
+        assert_eq!(story.ifid(), None);
+        assert_eq!(story.format_name(), None);
+    }
+}
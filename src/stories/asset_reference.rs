@@ -0,0 +1,35 @@
+use crate::FullContext;
+
+/// A reference to an external asset, such as an image or audio file, found
+/// in a passage by [`StoryPassages::assets`]
+///
+/// [`StoryPassages::assets`]: struct.StoryPassages.html#method.assets
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AssetReference {
+    /// The name of the passage containing the reference
+    pub passage: String,
+
+    /// The path or URL of the asset, as written in the passage
+    pub path: String,
+
+    /// The context of the path within the passage
+    pub context: FullContext,
+}
+
+impl AssetReference {
+    /// Returns `true` if the asset is a URL, such as `https://...` or a
+    /// `data:` URI, rather than a path to a local file
+    ///
+    /// # Examples
+    /// ```
+    /// use tweep::StoryPassages;
+    /// let input = ":: Start\n<img src=\"https://example.com/a.png\">[img[b.png]]".to_string();
+    /// let story = StoryPassages::from_string(input).take().0.ok().unwrap();
+    /// let assets = story.assets();
+    /// assert!(assets[0].is_url());
+    /// assert!(!assets[1].is_url());
+    /// ```
+    pub fn is_url(&self) -> bool {
+        self.path.contains("://") || self.path.starts_with("data:")
+    }
+}
@@ -0,0 +1,228 @@
+use crate::AssetReference;
+use crate::PassageContent;
+use crate::StoryPassages;
+use crate::Warning;
+use crate::WarningKind;
+use std::ops::Range;
+use std::path::Path;
+
+/// Macros whose quoted arguments may be paths to media files
+const MEDIA_MACROS: [&str; 4] = ["<<audio", "<<cacheaudio", "<<video", "<<playlist"];
+
+impl StoryPassages {
+    /// Finds the references to external assets in the bodies of all normal
+    /// passages, in order of passage name and then of position
+    ///
+    /// The following references are found:
+    /// * SugarCube image links, such as `[img[cat.png]]` or
+    ///   `[img[A cat|cat.png][Next]]`
+    /// * Quoted file names in the `<<audio>>`, `<<cacheaudio>>`, `<<video>>`,
+    ///   and `<<playlist>>` macros, such as `<<cacheaudio "bgm" "bgm.mp3">>`.
+    ///   Only arguments containing a `.` are treated as file names
+    /// * The `src` attribute of HTML tags, such as `<img src="cat.png">`
+    ///
+    /// Paths that begin with `$` or `_` are story variables rather than
+    /// paths, and are skipped.
+    ///
+    /// # Examples
+    /// ```
+    /// use tweep::StoryPassages;
+    /// let input = r#":: Start
+    /// [img[A cat|images/cat.png]]
+    /// <<cacheaudio "bgm" "audio/bgm.mp3">>
+    /// <video src='intro.webm'></video>
+    /// "#.to_string();
+    /// let story = StoryPassages::from_string(input).take().0.ok().unwrap();
+    /// let paths: Vec<String> = story.assets().into_iter().map(|a| a.path).collect();
+    /// assert_eq!(paths, vec!["images/cat.png", "audio/bgm.mp3", "intro.webm"]);
+    /// ```
+    pub fn assets(&self) -> Vec<AssetReference> {
+        let mut names: Vec<&String> = self.passages.keys().collect();
+        names.sort();
+
+        let mut assets = Vec::new();
+        for name in names {
+            let passage = &self.passages[name];
+            let twine = match &passage.content {
+                PassageContent::Normal(twine) => twine,
+                _ => continue,
+            };
+
+            // The body begins on the line after the header
+            let body_start = match passage.context.get_contents().find('\n') {
+                Some(pos) => pos + 1,
+                None => continue,
+            };
+            let body = twine.trimmed();
+            for range in asset_ranges(body) {
+                assets.push(AssetReference {
+                    passage: name.clone(),
+                    path: body[range.clone()].to_string(),
+                    context: passage.context.slice(body_start + range.start..body_start + range.end),
+                });
+            }
+        }
+        assets
+    }
+
+    /// Checks that every asset found by [`assets`] that isn't a URL exists,
+    /// relative to the given project root, and returns a [`MissingAsset`]
+    /// warning for each that doesn't
+    ///
+    /// [`assets`]: #method.assets
+    /// [`MissingAsset`]: enum.WarningKind.html#variant.MissingAsset
+    pub fn check_assets<P: AsRef<Path>>(&self, root: P) -> Vec<Warning> {
+        let root = root.as_ref();
+        self.assets()
+            .into_iter()
+            .filter(|asset| !asset.is_url() && !root.join(&asset.path).exists())
+            .map(|asset| Warning::new(WarningKind::MissingAsset(asset.path), Some(asset.context)))
+            .collect()
+    }
+}
+
+/// Finds the byte ranges of the asset paths within a passage body
+fn asset_ranges(body: &str) -> Vec<Range<usize>> {
+    let mut ranges = Vec::new();
+    let mut push = |range: Range<usize>| {
+        let path = &body[range.clone()];
+        if !path.is_empty() && !path.starts_with('$') && !path.starts_with('_') {
+            ranges.push(range);
+        }
+    };
+
+    let mut i = 0;
+    while i < body.len() {
+        let rest = &body[i..];
+        let len = if let Some(image) = ["[img[", "[<img[", "[>img["].iter().find(|p| rest.starts_with(**p)) {
+            // SugarCube image link, with an optional title before a `|`
+            let start = image.len();
+            match rest[start..].find(']') {
+                Some(end) => {
+                    let inner = &rest[start..start + end];
+                    let path_start = inner.rfind('|').map_or(0, |p| p + 1);
+                    push(i + start + path_start..i + start + end);
+                    start + end
+                }
+                None => image.len(),
+            }
+        } else if MEDIA_MACROS.iter().any(|m| rest.starts_with(m)) {
+            match rest.find(">>") {
+                Some(end) => {
+                    for range in quoted(&rest[..end]) {
+                        if rest[range.clone()].contains('.') {
+                            push(i + range.start..i + range.end);
+                        }
+                    }
+                    end + 2
+                }
+                None => 2,
+            }
+        } else if rest.starts_with('<') && rest[1..].starts_with(|c: char| c.is_ascii_alphabetic()) {
+            match rest.find('>') {
+                Some(end) => {
+                    if let Some(range) = src_attribute(&rest[..end]) {
+                        push(i + range.start..i + range.end);
+                    }
+                    end + 1
+                }
+                None => 1,
+            }
+        } else {
+            rest.chars().next().unwrap().len_utf8()
+        };
+        i += len;
+    }
+    ranges
+}
+
+/// Finds the byte ranges of the contents of the quoted strings in `input`
+fn quoted(input: &str) -> Vec<Range<usize>> {
+    let mut ranges = Vec::new();
+    let mut open: Option<(char, usize)> = None;
+    for (i, c) in input.char_indices() {
+        match open {
+            Some((quote, start)) if c == quote => {
+                ranges.push(start..i);
+                open = None;
+            }
+            None if c == '"' || c == '\'' => open = Some((c, i + 1)),
+            _ => (),
+        }
+    }
+    ranges
+}
+
+/// Finds the byte range of the value of the `src` attribute of an HTML tag,
+/// given the tag without its closing `>`
+fn src_attribute(tag: &str) -> Option<Range<usize>> {
+    let lower = tag.to_ascii_lowercase();
+    let mut search = 0;
+    while let Some(pos) = lower[search..].find("src") {
+        let pos = search + pos;
+        search = pos + 3;
+        if !lower[..pos].ends_with(char::is_whitespace) {
+            continue;
+        }
+        let after = lower[pos + 3..].trim_start();
+        let after = match after.strip_prefix('=') {
+            Some(after) => after.trim_start(),
+            None => continue,
+        };
+        let start = tag.len() - after.len();
+        return Some(match after.chars().next() {
+            Some(quote) if quote == '"' || quote == '\'' => {
+                let end = after[1..].find(quote).map_or(tag.len(), |e| start + 1 + e);
+                start + 1..end
+            }
+            _ => {
+                let end = after
+                    .find(|c: char| c.is_whitespace() || c == '/')
+                    .map_or(tag.len(), |e| start + e);
+                start..end
+            }
+        });
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Position;
+    use tempfile::tempdir;
+
+    fn paths(body: &str) -> Vec<&str> {
+        asset_ranges(body).into_iter().map(|r| &body[r]).collect()
+    }
+
+    #[test]
+    fn images() {
+        assert_eq!(paths("[img[a.png]] [<img[T|b.png][Next]] [img[$pic]]"), vec!["a.png", "b.png"]);
+        assert_eq!(paths("<IMG alt=x SRC = c.png/> <a data-src=\"no\"> <img src=\"\">"), vec!["c.png"]);
+    }
+
+    #[test]
+    fn macros() {
+        assert_eq!(
+            paths("<<audio \"bgm\" play>> <<cacheaudio 'bgm' 'a.mp3' \"b.ogg\">> <<set $x to \"y.z\">>"),
+            vec!["a.mp3", "b.ogg"]
+        );
+    }
+
+    #[test]
+    fn check_assets() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = tempdir()?;
+        std::fs::create_dir(dir.path().join("img"))?;
+        std::fs::File::create(dir.path().join("img").join("here.png"))?;
+
+        let input = ":: Start\n[img[img/here.png]]\n<img src=\"img/gone.png\"> <img src=\"http://x/y.png\">".to_string();
+        let story = StoryPassages::from_string(input).take().0.ok().unwrap();
+        let warnings = story.check_assets(dir.path());
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].kind, WarningKind::MissingAsset("img/gone.png".to_string()));
+        let context = warnings[0].context.as_ref().unwrap();
+        assert_eq!(*context.get_start_position(), Position::abs(3, 11));
+        Ok(())
+    }
+}
@@ -0,0 +1,50 @@
+use crate::PassageContent;
+use crate::StoryPassages;
+
+/// The tags that mark a passage as author notes
+pub(crate) const AUTHOR_NOTE_TAGS: [&str; 2] = ["notes", "comment"];
+
+impl StoryPassages {
+    /// Moves every normal passage tagged with one of the author note tags into
+    /// `notes`
+    pub(crate) fn separate_author_notes(&mut self) {
+        let passages = std::mem::take(&mut self.passages);
+        for (name, passage) in passages {
+            let is_note = matches!(passage.content, PassageContent::Normal(_))
+                && AUTHOR_NOTE_TAGS.iter().any(|tag| passage.header.has_tag(tag));
+            if is_note {
+                self.notes.insert(name, passage);
+            } else {
+                self.passages.insert(name, passage);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ParseOptions;
+    use crate::StoryPassages;
+    use crate::WarningKind;
+
+    #[test]
+    fn author_notes() {
+        let input = ":: Start\nHello\n\n:: Plot [notes]\n[[Nowhere]]\n\n:: Todo [comment todo]\nFix [[Start]]\n".to_string();
+        let options = ParseOptions::new().with_author_notes(true);
+        let story = StoryPassages::from_string_with_options(input.clone(), &options).take().0.ok().unwrap();
+        let mut notes: Vec<&String> = story.notes.keys().collect();
+        notes.sort();
+        assert_eq!(notes, vec!["Plot", "Todo"]);
+        assert_eq!(story.passages.len(), 1);
+
+        let report = story.validate();
+        assert!(report.dead_links.is_empty());
+        assert!(report.orphans.is_empty());
+        assert!(!story.to_yarn().take().0.contains("Plot"));
+
+        let story = StoryPassages::from_string(input).take().0.ok().unwrap();
+        assert!(story.notes.is_empty());
+        assert!(story.passages.contains_key("Plot"));
+        assert!(story.check().iter().any(|w| w.kind == WarningKind::DeadLink("Nowhere".to_string())));
+    }
+}
@@ -0,0 +1,70 @@
+use crate::Passage;
+
+/// A build profile, which excludes passages carrying any of a set of tags from
+/// the story, set with [`ParseOptions::profile`]
+///
+/// # Examples
+/// ```
+/// use tweep::{BuildProfile, ParseOptions, StoryPassages, WarningKind};
+/// let input = ":: Start\n[[Cheats]]\n\n:: Cheats [debug]\nSkip ahead\n".to_string();
+/// let options = ParseOptions::new().with_profile(BuildProfile::release());
+/// let (res, _) = StoryPassages::from_string_with_options(input, &options).take();
+/// let story = res.ok().unwrap();
+/// assert!(!story.passages.contains_key("Cheats"));
+/// assert!(story.excluded_passages.contains_key("Cheats"));
+/// assert!(story
+///     .check()
+///     .iter()
+///     .any(|w| w.kind == WarningKind::ExcludedLink("Cheats".to_string(), "release".to_string())));
+/// ```
+///
+/// [`ParseOptions::profile`]: struct.ParseOptions.html#structfield.profile
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BuildProfile {
+    /// The name of the profile, used in warnings
+    pub name: String,
+
+    /// Passages carrying any of these tags are excluded
+    pub excluded_tags: Vec<String>,
+}
+
+impl BuildProfile {
+    /// Creates a new profile with the given name that excludes no passages
+    pub fn new(name: &str) -> Self {
+        BuildProfile {
+            name: name.to_string(),
+            excluded_tags: Vec::new(),
+        }
+    }
+
+    /// Creates a profile named `release` that excludes passages tagged `debug`
+    /// or `draft`
+    ///
+    /// # Examples
+    /// ```
+    /// use tweep::BuildProfile;
+    /// let profile = BuildProfile::release();
+    /// assert_eq!(profile.excluded_tags, vec!["debug", "draft"]);
+    /// ```
+    pub fn release() -> Self {
+        BuildProfile::new("release").with_excluded_tag("debug").with_excluded_tag("draft")
+    }
+
+    /// Builder method to add a tag to `excluded_tags`
+    ///
+    /// # Examples
+    /// ```
+    /// use tweep::BuildProfile;
+    /// let profile = BuildProfile::new("demo").with_excluded_tag("chapter3");
+    /// assert_eq!(profile.excluded_tags, vec!["chapter3"]);
+    /// ```
+    pub fn with_excluded_tag(mut self, tag: &str) -> Self {
+        self.excluded_tags.push(tag.to_string());
+        self
+    }
+
+    /// Returns `true` if the profile excludes the given passage
+    pub(crate) fn excludes(&self, passage: &Passage) -> bool {
+        self.excluded_tags.iter().any(|tag| passage.header.has_tag(tag))
+    }
+}
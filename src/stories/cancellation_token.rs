@@ -0,0 +1,105 @@
+use crate::Error;
+use crate::ErrorList;
+use crate::ErrorKind;
+use crate::FullContext;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+
+/// A flag shared between threads that asks a long operation, such as parsing
+/// with [`ParseOptions::cancellation`] or [`StoryPassages::check_cancellable`],
+/// to stop early. Clones of a token share the same flag, so an editor can keep
+/// one clone and cancel a stale parse running on another thread when the user
+/// keeps typing. A cancelled operation returns a [`Cancelled`] error
+///
+/// # Examples
+/// ```
+/// use tweep::{CancellationToken, ErrorKind, ParseOptions, StoryPassages};
+/// let token = CancellationToken::new();
+/// let options = ParseOptions::new().with_cancellation(token.clone());
+/// token.cancel();
+/// let input = ":: Start\nHello".to_string();
+/// let (res, _) = StoryPassages::from_string_with_options(input, &options).take();
+/// let errors = res.err().unwrap();
+/// # #[cfg(feature = "full-context")]
+/// # let errors = errors.error_list;
+/// assert_eq!(errors.errors[0].kind, ErrorKind::Cancelled);
+/// ```
+///
+/// [`ParseOptions::cancellation`]: struct.ParseOptions.html#structfield.cancellation
+/// [`StoryPassages::check_cancellable`]: struct.StoryPassages.html#method.check_cancellable
+/// [`Cancelled`]: enum.ErrorKind.html#variant.Cancelled
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    /// Creates a new token that has not been cancelled
+    pub fn new() -> Self {
+        CancellationToken::default()
+    }
+
+    /// Cancels every operation using this token or one of its clones
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns `true` if this token has been cancelled
+    ///
+    /// # Examples
+    /// ```
+    /// use tweep::CancellationToken;
+    /// let token = CancellationToken::new();
+    /// let clone = token.clone();
+    /// assert!(!token.is_cancelled());
+    /// clone.cancel();
+    /// assert!(token.is_cancelled());
+    /// ```
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    /// Returns a list holding a [`Cancelled`] error if this token has been
+    /// cancelled
+    ///
+    /// [`Cancelled`]: enum.ErrorKind.html#variant.Cancelled
+    pub(crate) fn check(&self) -> Result<(), ErrorList> {
+        if self.is_cancelled() {
+            Err(Error::new::<FullContext>(ErrorKind::Cancelled, None).into())
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::CancellationToken;
+    use crate::ErrorKind;
+    use crate::ParseOptions;
+    use crate::StoryPassages;
+    use std::fs::File;
+    use std::io::Write;
+    use tempfile::tempdir;
+
+    #[test]
+    fn cancelled_path_parse() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = tempdir()?;
+        writeln!(File::create(dir.path().join("one.twee"))?, ":: Start\n[[Other]]")?;
+        writeln!(File::create(dir.path().join("two.tw"))?, ":: Other\nThe end")?;
+
+        let token = CancellationToken::new();
+        let options = ParseOptions::new().with_cancellation(token.clone());
+        let (res, _) = StoryPassages::from_path_with_options(dir.path(), &options).take();
+        assert_eq!(res.ok().unwrap().passages.len(), 2);
+
+        token.cancel();
+        let (res, warnings) = StoryPassages::from_path_with_options(dir.path(), &options).take();
+        let errors = res.err().unwrap();
+        #[cfg(feature = "full-context")]
+        let errors = errors.error_list;
+        assert_eq!(errors.errors.len(), 1);
+        assert_eq!(errors.errors[0].kind, ErrorKind::Cancelled);
+        assert!(warnings.is_empty());
+        Ok(())
+    }
+}
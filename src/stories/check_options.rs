@@ -0,0 +1,79 @@
+use crate::ExportOptions;
+
+/// Options that control how a story is checked by
+/// [`StoryPassages::check_with`], such as for stories that are intentionally
+/// partial, like a single chapter checked on its own, or for the way the story
+/// is exported
+///
+/// # Examples
+/// ```
+/// use tweep::{CheckOptions, StoryPassages, WarningKind};
+/// let input = ":: Chapter 2\n[[Chapter 3]] [[Chapter 1]] [[Chapter 9]]\n".to_string();
+/// let story = StoryPassages::from_string(input).take().0.ok().unwrap();
+/// let options = CheckOptions::new()
+///     .with_partial(true)
+///     .with_external_passages(vec!["Chapter 1", "Chapter 3"]);
+/// let kinds: Vec<WarningKind> = story.check_with(&options).into_iter().map(|w| w.kind).collect();
+/// assert_eq!(kinds, vec![WarningKind::DeadLink("Chapter 9".to_string())]);
+/// ```
+///
+/// [`StoryPassages::check_with`]: struct.StoryPassages.html#method.check_with
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct CheckOptions {
+    /// The story is only part of a whole, so it isn't expected to have its
+    /// own special passages. No [`MissingStoryTitle`], [`MissingStoryData`],
+    /// or [`MissingStartPassage`] warnings are produced
+    ///
+    /// [`MissingStoryTitle`]: enum.WarningKind.html#variant.MissingStoryTitle
+    /// [`MissingStoryData`]: enum.WarningKind.html#variant.MissingStoryData
+    /// [`MissingStartPassage`]: enum.WarningKind.html#variant.MissingStartPassage
+    pub partial: bool,
+
+    /// The names of passages that exist outside of the story, such as in
+    /// other chapters. Links to them don't produce [`DeadLink`] warnings
+    ///
+    /// [`DeadLink`]: enum.WarningKind.html#variant.DeadLink
+    pub external_passages: Vec<String>,
+
+    /// The options the story is exported with. Links from exported passages
+    /// to ones left out by the export's filter produce [`UnexportedLink`]
+    /// warnings
+    ///
+    /// [`UnexportedLink`]: enum.WarningKind.html#variant.UnexportedLink
+    pub export: ExportOptions,
+}
+
+impl CheckOptions {
+    /// Creates a new `CheckOptions` with the defaults, which check a whole
+    /// story
+    pub fn new() -> Self {
+        CheckOptions::default()
+    }
+
+    /// Builder method to set whether the story is partial
+    pub fn with_partial(mut self, partial: bool) -> Self {
+        self.partial = partial;
+        self
+    }
+
+    /// Builder method to set the `external_passages`
+    pub fn with_external_passages<I, S>(mut self, names: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.external_passages = names.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Builder method to set the `export` options
+    pub fn with_export(mut self, export: ExportOptions) -> Self {
+        self.export = export;
+        self
+    }
+
+    /// Returns `true` if `target` names one of the `external_passages`
+    pub(crate) fn is_external(&self, target: &str) -> bool {
+        self.external_passages.iter().any(|name| name == target.trim())
+    }
+}
@@ -2,14 +2,17 @@ use std::collections::HashMap;
 use crate::FullContext;
 use bimap::BiMap;
 use std::ops::Range;
+use std::path::Path;
+use std::path::PathBuf;
 
 /// A code map for stories
 ///
-/// The code map consists of a `BiMap` between file ids (usize) and file names
-/// (String) along with a `HashMap` of file id to contexts
+/// The code map consists of a `BiMap` between file ids (usize) and file paths
+/// (PathBuf) along with a `HashMap` of file id to contexts
 #[derive(Debug, Default)]
 pub struct CodeMap {
-    pub(crate) id_file_map: BiMap<usize, String>,
+    // Boxed to keep parse errors, which carry the code map, small
+    pub(crate) id_file_map: Box<BiMap<usize, PathBuf>>,
     pub(crate) contexts: HashMap<usize, FullContext>,
 }
 
@@ -19,14 +22,22 @@ impl CodeMap {
         self.contexts.get(&id)
     }
 
-    /// Gets the file name for file id `id`
-    pub fn lookup_name(&self, id: usize) -> Option<&str> {
-        self.id_file_map.get_by_left(&id).map(|x| x.as_str())
+    /// Gets the file path for file id `id`
+    pub fn lookup_path(&self, id: usize) -> Option<&Path> {
+        self.id_file_map.get_by_left(&id).map(|x| x.as_path())
     }
 
-    /// Gets the file id for file name `name`
-    pub fn lookup_id(&self, name: String) -> Option<usize> {
-        self.id_file_map.get_by_right(&name).copied()
+    /// Gets the file name for file id `id`, which may involve a lossy
+    /// conversion of the file path
+    #[deprecated(note = "use `lookup_path` instead")]
+    pub fn lookup_name(&self, id: usize) -> Option<String> {
+        self.lookup_path(id).map(|p| p.to_string_lossy().into_owned())
+    }
+
+    /// Gets the file id for file path `path`, which may be given as a `Path`
+    /// or as a string
+    pub fn lookup_id<P: AsRef<Path>>(&self, path: P) -> Option<usize> {
+        self.id_file_map.get_by_right(path.as_ref()).copied()
     }
 
     /// Gets the byte location of line starts for file id `id`
@@ -36,19 +47,36 @@ impl CodeMap {
 
     /// Gets the byte range of the line `line` for file id `id`
     pub fn line_range(&self, id: usize, line: usize) -> Option<Range<usize>> {
-        self.get_context(id).and_then(|ctx| {
+        self.get_context(id).map(|ctx| {
             let (start, end) = ctx.line_bytes(line).into_inner();
-            Some(start..end+1)
+            start..end+1
         })
     }
 
     /// Adds a context to the code map
     pub(crate) fn add(&mut self, context: FullContext) {
-        if let Some(file_name) = context.get_file_name() {
+        if let Some(file_path) = context.get_file_path() {
             let new_id = self.id_file_map.len();
-            self.id_file_map.insert(new_id, file_name.clone());
+            self.id_file_map.insert(new_id, file_path.to_path_buf());
             self.contexts.insert(new_id, context);
         }
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lookups() {
+        let mut code_map = CodeMap::default();
+        code_map.add(FullContext::from(Some("a.twee".to_string()), ":: Start\nHi".to_string()));
+        assert_eq!(code_map.lookup_path(0), Some(Path::new("a.twee")));
+        assert_eq!(code_map.lookup_id(Path::new("a.twee")), Some(0));
+        assert_eq!(code_map.lookup_id(String::from("a.twee")), Some(0));
+        assert_eq!(code_map.lookup_id("b.twee"), None);
+        #[allow(deprecated)]
+        let name = code_map.lookup_name(0);
+        assert_eq!(name, Some("a.twee".to_string()));
+    }
+}
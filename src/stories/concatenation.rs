@@ -0,0 +1,97 @@
+use crate::ExportOptions;
+use crate::Passage;
+use crate::PassageContent;
+use crate::StoryPassages;
+
+impl StoryPassages {
+    /// Concatenates the contents of the script passages, in the order given
+    /// by the `passage_order` of `options`, into a single script. Each passage
+    /// is preceded by a comment naming it and where its body starts, such as
+    /// `/* Passage: Init (story.twee, line 2) */`, so errors in the combined
+    /// script can be traced back to the source
+    ///
+    /// # Examples
+    /// ```
+    /// use tweep::{ExportOptions, StoryPassages};
+    /// let input = ":: B [script]\nb();\n\n:: A [script]\na();\n\n:: Start\nHi\n".to_string();
+    /// let story = StoryPassages::from_string(input).take().0.ok().unwrap();
+    /// assert_eq!(
+    ///     story.combined_script(&ExportOptions::new()),
+    ///     "/* Passage: A (line 5) */\na();\n\n/* Passage: B (line 2) */\nb();\n"
+    /// );
+    /// ```
+    pub fn combined_script(&self, options: &ExportOptions) -> String {
+        self.combine(&self.scripts, options)
+    }
+
+    /// Concatenates the contents of the stylesheet passages, in the order
+    /// given by the `passage_order` of `options`, into a single stylesheet,
+    /// with a comment before each passage as in [`combined_script`]
+    ///
+    /// [`combined_script`]: #method.combined_script
+    pub fn combined_stylesheet(&self, options: &ExportOptions) -> String {
+        self.combine(&self.stylesheets, options)
+    }
+
+    /// Concatenates the given special passages in the order given by the
+    /// `passage_order` of `options`
+    fn combine(&self, passages: &[Passage], options: &ExportOptions) -> String {
+        let chunks: Vec<String> = self
+            .ordered_passages(options.passage_order)
+            .into_iter()
+            .filter(|passage| passages.iter().any(|p| std::ptr::eq(p, *passage)))
+            .map(|passage| {
+                let content = match &passage.content {
+                    PassageContent::Script(script) => script.content(),
+                    PassageContent::Stylesheet(stylesheet) => stylesheet.content(),
+                    _ => "",
+                };
+                format!("{}\n{}\n", source_comment(passage), content.trim_end())
+            })
+            .collect();
+        chunks.join("\n")
+    }
+}
+
+/// Returns a comment, valid in both JavaScript and CSS, naming the passage and
+/// the file and line where its body starts
+fn source_comment(passage: &Passage) -> String {
+    let line = passage.context.get_start_position().line + 1;
+    let location = match passage.context.get_file_path() {
+        Some(path) => format!("{}, line {}", path.display(), line),
+        None => format!("line {}", line),
+    };
+    // Keep the name from closing the comment early
+    let name = passage.header.name.replace("*/", "* /");
+    format!("/* Passage: {} ({}) */", name, location)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ExportOptions;
+    use crate::ParseOptions;
+    use crate::PassageOrder;
+    use crate::StoryPassages;
+
+    #[test]
+    fn combined() {
+        let input = ":: Z [stylesheet]\nbody {}\n\n\n:: A */ [stylesheet]\np {}\n\n:: S [script]\ns();\n".to_string();
+        let story = StoryPassages::from_string(input).take().0.ok().unwrap();
+        let options = ExportOptions::new().with_passage_order(PassageOrder::Source);
+        assert_eq!(
+            story.combined_stylesheet(&options),
+            "/* Passage: Z (line 2) */\nbody {}\n\n/* Passage: A * / (line 6) */\np {}\n"
+        );
+        assert_eq!(story.combined_script(&options), "/* Passage: S (line 9) */\ns();\n");
+
+        let empty = StoryPassages::from_string(":: Start\nHi\n".to_string()).take().0.ok().unwrap();
+        assert_eq!(empty.combined_script(&options), "");
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("story.twee");
+        std::fs::write(&path, ":: Init [script]\ninit();\n").unwrap();
+        let (res, _) = StoryPassages::from_path_with_options(&path, &ParseOptions::new()).take();
+        let script = res.ok().unwrap().combined_script(&options);
+        assert_eq!(script, format!("/* Passage: Init ({}, line 2) */\ninit();\n", path.display()));
+    }
+}
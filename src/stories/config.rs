@@ -0,0 +1,101 @@
+use crate::ParseOptions;
+use crate::Passage;
+use crate::PassageContent;
+use crate::StoryConfig;
+use crate::StoryPassages;
+use crate::Warning;
+use super::story_config::CONFIG_PASSAGE_NAMES;
+
+impl StoryPassages {
+    /// Moves the config passage, if any, out of the map of normal passages,
+    /// merges its configuration with the one in `options`, and applies the
+    /// result to the story. Returns the options to continue with, which
+    /// include the configured special tags and dynamic targets
+    pub(crate) fn apply_config(&mut self, options: &ParseOptions, warnings: &mut Vec<Warning>) -> ParseOptions {
+        let passage = CONFIG_PASSAGE_NAMES
+            .iter()
+            .find_map(|name| self.passages.remove(*name));
+        let in_story = match &passage {
+            Some(passage) => StoryConfig::from_passage(passage, warnings),
+            None => StoryConfig::default(),
+        };
+        if passage.is_some() {
+            self.config_passage = passage;
+        }
+        self.config = options.config.merged(&in_story);
+
+        let mut options = options.clone();
+        for tag in &self.config.special_tags {
+            if !options.special_tags.contains(tag) {
+                options.special_tags.push(tag.clone());
+            }
+        }
+        for target in &self.config.dynamic_targets {
+            if !options.dynamic_targets.contains(target) {
+                options.dynamic_targets.push(target.clone());
+            }
+        }
+
+        // Parse passages carrying newly configured special tags again, so
+        // that they're stored as special passages
+        let special_tags = options.all_special_tags();
+        let reparsed: Vec<String> = self
+            .passages
+            .iter()
+            .filter(|(_, passage)| matches!(passage.content, PassageContent::Normal(_)))
+            .filter(|(_, passage)| special_tags.iter().any(|tag| passage.header.has_tag(tag)))
+            .map(|(name, _)| name.clone())
+            .collect();
+        self.special_tags = special_tags;
+        for name in reparsed {
+            let passage = self.passages.remove(&name).expect("Name was just found");
+            match Passage::parse(passage.context.clone(), &options).take().0 {
+                Ok(mut special) => {
+                    special.header.name = passage.header.name;
+                    self.add_passage(special);
+                }
+                Err(_) => {
+                    self.passages.insert(name, passage);
+                }
+            }
+        }
+        options
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ParseOptions;
+    use crate::StoryConfig;
+    use crate::StoryPassages;
+    use crate::WarningKind;
+
+    #[test]
+    fn in_story_config() {
+        let input = ":: TweepConfig\n{\"allow\": [\"DeadLink\"], \"dynamic-targets\": [\"Attic\"]}\n\n\
+                     :: Start\n[[Nowhere]] [[Go|$where]]\n\n:: Lib [module]\nx\n"
+            .to_string();
+        let external = StoryConfig::from_json(r#"{"special-tags": ["module"], "resolve-transclusions": true}"#).unwrap();
+        let options = ParseOptions::new().with_config(external);
+        let story = StoryPassages::from_string_with_options(input, &options).take().0.ok().unwrap();
+        assert!(!story.passages.contains_key("TweepConfig"));
+        assert_eq!(story.config.resolve_transclusions, Some(true));
+        assert_eq!(story.tagged_passages["module"][0].header.name, "Lib");
+
+        let kinds: Vec<WarningKind> = story.check().into_iter().map(|w| w.kind).collect();
+        assert!(!kinds.iter().any(|k| matches!(k, WarningKind::DeadLink(_))));
+        assert!(kinds.contains(&WarningKind::DeadDynamicTarget("Attic".to_string())));
+    }
+
+    #[test]
+    fn invalid_config() {
+        let input = ":: StoryConfig\n{\"allow\": \"DeadLink\"}\n\n:: Start\nx\n".to_string();
+        let (res, warnings) = StoryPassages::from_string(input).take();
+        assert_eq!(warnings.len(), 1);
+        assert!(matches!(warnings[0].kind, WarningKind::JsonError(_)));
+        assert_eq!(warnings[0].context.as_ref().unwrap().get_start_position().line, 2);
+        let story = res.ok().unwrap();
+        assert!(story.config_passage.is_some());
+        assert_eq!(story.config, StoryConfig::default());
+    }
+}
@@ -1,6 +1,8 @@
 use crate::ErrorList;
 use crate::CodeMap;
 use crate::Error;
+use crate::Output;
+use crate::WarningPolicy;
 
 /// An ErrorList with an attached CodeMap
 #[derive(Debug)]
@@ -24,6 +26,28 @@ impl std::fmt::Display for ContextErrorList {
     }
 }
 
+impl<T> Output<Result<T, ContextErrorList>> {
+    /// Consumes the `Output` and converts it into a plain `Result`, treating
+    /// any [`Warning`]s denied by `policy` as [`Error`]s. See
+    /// [`Output::into_result_with`] for details. If the `Output` contains an
+    /// `Ok` value, the resulting `ContextErrorList` has an empty `CodeMap`
+    ///
+    /// [`Warning`]: struct.Warning.html
+    /// [`Error`]: struct.Error.html
+    /// [`Output::into_result_with`]: struct.Output.html#method.into_result_with
+    pub fn into_result_with(self, policy: &WarningPolicy) -> Result<T, ContextErrorList> {
+        let (res, warnings) = self.take();
+        let (res, code_map) = match res {
+            Ok(t) => (Ok(t), CodeMap::default()),
+            Err(e) => (Err(e.error_list), e.code_map),
+        };
+        Output::new(res)
+            .with_warnings(warnings)
+            .into_result_with(policy)
+            .map_err(|error_list| ContextErrorList { error_list, code_map })
+    }
+}
+
 impl std::convert::From<Error> for ContextErrorList {
     fn from(e: Error) -> ContextErrorList {
         let error_list = e.into();
@@ -0,0 +1,177 @@
+use crate::StoryPassages;
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+
+/// The tag that marks a passage as an ending, along with any configured in
+/// the story's `ending-tags`
+const ENDING_TAG: &str = "ending";
+
+impl StoryPassages {
+    /// Returns `true` if the normal passage named `name` is an ending: a
+    /// passage that leads nowhere, or one tagged `ending` or with one of the
+    /// `ending-tags` of the story's [`config`]. Tagging an ending is only
+    /// needed when it has links, such as one offering to play again
+    ///
+    /// [`config`]: #structfield.config
+    pub fn is_ending(&self, name: &str) -> bool {
+        let passage = match self.passages.get(name) {
+            Some(passage) => passage,
+            None => return false,
+        };
+        let tagged = passage
+            .tags()
+            .iter()
+            .any(|tag| tag == ENDING_TAG || self.config.ending_tags.contains(tag));
+        tagged || self.successors(name).is_empty()
+    }
+
+    /// Returns the names of the ending passages of the story, in sorted order.
+    /// See [`is_ending`] for which passages are endings
+    ///
+    /// # Examples
+    /// ```
+    /// use tweep::StoryPassages;
+    /// let input = r#":: Start
+    /// [[Fight]] or [[Flee]]
+    ///
+    /// :: Fight [defeat]
+    /// You lose. [[Try again->Start]]
+    ///
+    /// :: Flee
+    /// You get away.
+    ///
+    /// :: StoryConfig
+    /// {"ending-tags": ["defeat"]}
+    /// "#.to_string();
+    /// let story = StoryPassages::from_string(input).take().0.ok().unwrap();
+    /// assert_eq!(story.endings(), vec!["Fight", "Flee"]);
+    /// ```
+    ///
+    /// [`is_ending`]: #method.is_ending
+    pub fn endings(&self) -> Vec<&String> {
+        let mut endings: Vec<&String> = self.passages.keys().filter(|name| self.is_ending(name)).collect();
+        endings.sort();
+        endings
+    }
+
+    /// Counts the distinct paths from the start passage to each ending, keyed
+    /// by ending. An ending that can't be reached has no paths. Returns `None`
+    /// if the story has no start passage
+    ///
+    /// A path is a sequence of passages, each leading to the next, so several
+    /// links between the same two passages count once. To keep the counts
+    /// finite, a path never returns to a passage already on it: loops, found
+    /// by a depth-first search from the start passage, are left out. Counts
+    /// too large for a `u64` saturate
+    ///
+    /// # Examples
+    /// ```
+    /// use tweep::StoryPassages;
+    /// let input = r#":: Start
+    /// [[Left]] [[Right]] [[Start]]
+    ///
+    /// :: Left
+    /// [[Treasure]]
+    ///
+    /// :: Right
+    /// [[Treasure]] [[Pit]]
+    ///
+    /// :: Treasure
+    /// You win.
+    ///
+    /// :: Pit
+    /// You fall.
+    ///
+    /// :: Secret
+    /// Nobody finds this.
+    /// "#.to_string();
+    /// let story = StoryPassages::from_string(input).take().0.ok().unwrap();
+    /// let paths = story.ending_paths().unwrap();
+    /// assert_eq!(paths["Treasure"], 2);
+    /// assert_eq!(paths["Pit"], 1);
+    /// assert_eq!(paths["Secret"], 0);
+    /// ```
+    pub fn ending_paths(&self) -> Option<BTreeMap<String, u64>> {
+        let start = self.get_start_passage_name()?;
+        let start = self.passages.get_key_value(start)?.0.as_str();
+
+        // Depth-first search from the start passage, keeping the edges that
+        // don't close a loop and the passages in order of completion
+        let mut edges: HashMap<&str, Vec<&str>> = HashMap::new();
+        let mut on_path: HashMap<&str, bool> = HashMap::new();
+        let mut finished = Vec::new();
+        let mut stack: Vec<(&str, Vec<&str>)> = vec![(start, self.distinct_successors(start))];
+        on_path.insert(start, true);
+        while let Some((name, successors)) = stack.last_mut() {
+            let name = *name;
+            match successors.pop() {
+                Some(target) => match on_path.get(target) {
+                    Some(true) => (),
+                    Some(false) => edges.entry(name).or_default().push(target),
+                    None => {
+                        edges.entry(name).or_default().push(target);
+                        on_path.insert(target, true);
+                        stack.push((target, self.distinct_successors(target)));
+                    }
+                },
+                None => {
+                    on_path.insert(name, false);
+                    finished.push(name);
+                    stack.pop();
+                }
+            }
+        }
+
+        // Without loops, reverse order of completion is a topological order
+        let mut paths: HashMap<&str, u64> = HashMap::new();
+        paths.insert(start, 1);
+        for name in finished.into_iter().rev() {
+            let count = paths.get(name).copied().unwrap_or(0);
+            for target in edges.get(name).into_iter().flatten() {
+                let entry = paths.entry(target).or_insert(0);
+                *entry = entry.saturating_add(count);
+            }
+        }
+
+        Some(
+            self.endings()
+                .into_iter()
+                .map(|ending| (ending.clone(), paths.get(ending.as_str()).copied().unwrap_or(0)))
+                .collect(),
+        )
+    }
+
+    /// Returns the successors of the passage named `name` without repeats, in
+    /// reverse order so they can be popped in order
+    fn distinct_successors(&self, name: &str) -> Vec<&str> {
+        let mut successors: Vec<&str> = Vec::new();
+        for target in self.successors(name) {
+            if !successors.contains(&target) {
+                successors.push(target);
+            }
+        }
+        successors.reverse();
+        successors
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::StoryPassages;
+
+    #[test]
+    fn ending_paths() {
+        let input = ":: Start\n[[A]] [[B]]\n\n:: A\n[[C]] [[A]]\n\n:: B\n[[C]] [[Start]]\n\n:: C\n[[D]] [[End]]\n\n:: D\n[[End]]\n\n:: End [ending]\n[[Start]]\n"
+            .to_string();
+        let story = StoryPassages::from_string(input).take().0.ok().unwrap();
+        assert_eq!(story.endings(), vec!["End"]);
+        let paths = story.ending_paths().unwrap();
+        assert_eq!(paths.len(), 1);
+        assert_eq!(paths["End"], 4);
+
+        let story = StoryPassages::from_string(":: Intro\nHi\n".to_string()).take().0.ok().unwrap();
+        assert!(story.ending_paths().is_none());
+        assert!(story.is_ending("Intro"));
+        assert!(!story.is_ending("Nowhere"));
+    }
+}
@@ -0,0 +1,106 @@
+use crate::Passage;
+
+/// A filter on the tags of the normal passages written by exporters, such as
+/// [`to_yarn_with`] and [`to_graph_json_with`], set with
+/// [`ExportOptions::filter`]. Unlike a [`BuildProfile`], the filter leaves the
+/// parsed story untouched, so the filtered passages remain available in the
+/// source
+///
+/// A passage is exported if it carries one of the `included_tags`, or if
+/// there are none, and carries none of the `excluded_tags`. Special passages,
+/// scripts and stylesheets are always exported.
+///
+/// # Examples
+/// ```
+/// use tweep::{ExportFilter, ExportOptions, StoryPassages};
+/// let input = ":: Start\n[[Ideas]]\n\n:: Ideas [notes]\nMaybe a dragon\n".to_string();
+/// let story = StoryPassages::from_string(input).take().0.ok().unwrap();
+/// let options = ExportOptions::new().with_filter(ExportFilter::new().with_excluded_tag("notes"));
+/// let graph = story.to_graph_json_with(&options);
+/// assert_eq!(graph["nodes"].as_array().unwrap().len(), 1);
+/// assert!(story.passages.contains_key("Ideas"));
+/// ```
+///
+/// [`to_yarn_with`]: struct.StoryPassages.html#method.to_yarn_with
+/// [`to_graph_json_with`]: struct.StoryPassages.html#method.to_graph_json_with
+/// [`ExportOptions::filter`]: struct.ExportOptions.html#structfield.filter
+/// [`BuildProfile`]: struct.BuildProfile.html
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ExportFilter {
+    /// If not empty, only passages carrying at least one of these tags are
+    /// exported
+    pub included_tags: Vec<String>,
+
+    /// Passages carrying any of these tags are not exported
+    pub excluded_tags: Vec<String>,
+}
+
+impl ExportFilter {
+    /// Creates a new filter that exports every passage
+    pub fn new() -> Self {
+        ExportFilter::default()
+    }
+
+    /// Builder method to add a tag to `included_tags`
+    pub fn with_included_tag(mut self, tag: &str) -> Self {
+        self.included_tags.push(tag.to_string());
+        self
+    }
+
+    /// Builder method to add a tag to `excluded_tags`
+    pub fn with_excluded_tag(mut self, tag: &str) -> Self {
+        self.excluded_tags.push(tag.to_string());
+        self
+    }
+
+    /// Returns `true` if the filter exports the given normal passage
+    ///
+    /// # Examples
+    /// ```
+    /// use tweep::{ExportFilter, StoryPassages};
+    /// let input = ":: Start [ch1]\nA\n\n:: Todo [ch1 todo]\nB\n\n:: Later [ch2]\nC\n".to_string();
+    /// let story = StoryPassages::from_string(input).take().0.ok().unwrap();
+    /// let filter = ExportFilter::new().with_included_tag("ch1").with_excluded_tag("todo");
+    /// assert!(filter.allows(&story.passages["Start"]));
+    /// assert!(!filter.allows(&story.passages["Todo"]));
+    /// assert!(!filter.allows(&story.passages["Later"]));
+    /// ```
+    pub fn allows(&self, passage: &Passage) -> bool {
+        let included = self.included_tags.is_empty() || self.included_tags.iter().any(|tag| passage.header.has_tag(tag));
+        included && !self.excluded_tags.iter().any(|tag| passage.header.has_tag(tag))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::CheckOptions;
+    use crate::ExportFilter;
+    use crate::ExportOptions;
+    use crate::StoryPassages;
+    use crate::WarningKind;
+
+    #[test]
+    fn export_filter() {
+        let input = ":: Start\n[[Hall]] [[Ideas]]\n\n:: Hall\nH\n\n:: Ideas [notes]\n[[Start]]\n".to_string();
+        let story = StoryPassages::from_string(input).take().0.ok().unwrap();
+        let options = ExportOptions::new().with_filter(ExportFilter::new().with_excluded_tag("notes"));
+
+        assert!(!story.check().iter().any(|w| matches!(w.kind, WarningKind::UnexportedLink(_))));
+        let unexported: Vec<WarningKind> = story
+            .check_with(&CheckOptions::new().with_export(options.clone()))
+            .into_iter()
+            .map(|w| w.kind)
+            .filter(|k| matches!(k, WarningKind::UnexportedLink(_)))
+            .collect();
+        assert_eq!(unexported, vec![WarningKind::UnexportedLink("Ideas".to_string())]);
+
+        let graph = story.to_graph_json_with(&options);
+        assert_eq!(graph["edges"].as_array().unwrap().len(), 1);
+
+        let (yarn, warnings) = story.to_yarn_with(&options).take();
+        assert!(!yarn.contains("title: Ideas"));
+        assert!(!yarn.contains("jump Ideas"));
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].kind, WarningKind::UnexportedLink("Ideas".to_string()));
+    }
+}
@@ -0,0 +1,59 @@
+use crate::ExportFilter;
+use crate::PassageOrder;
+
+/// Options that control how exporters such as [`StoryPassages::to_yarn_with`]
+/// and [`StoryPassages::to_graph_json_with`] write a story. They are also
+/// given to [`StoryPassages::check_with`] through [`CheckOptions::export`],
+/// so that links to passages the export leaves out can be reported
+///
+/// # Examples
+/// ```
+/// use tweep::{ExportFilter, ExportOptions, PassageOrder, StoryPassages};
+/// let input = ":: Start\n[[Ideas]] [[Hall]]\n\n:: Ideas [notes]\nMaybe a dragon\n\n:: Hall\nH\n".to_string();
+/// let story = StoryPassages::from_string(input).take().0.ok().unwrap();
+/// let options = ExportOptions::new()
+///     .with_passage_order(PassageOrder::Source)
+///     .with_filter(ExportFilter::new().with_excluded_tag("notes"));
+/// let graph = story.to_graph_json_with(&options);
+/// let names: Vec<&str> = graph["nodes"].as_array().unwrap().iter().map(|n| n["name"].as_str().unwrap()).collect();
+/// assert_eq!(names, vec!["Start", "Hall"]);
+/// ```
+///
+/// [`StoryPassages::to_yarn_with`]: struct.StoryPassages.html#method.to_yarn_with
+/// [`StoryPassages::to_graph_json_with`]: struct.StoryPassages.html#method.to_graph_json_with
+/// [`StoryPassages::check_with`]: struct.StoryPassages.html#method.check_with
+/// [`CheckOptions::export`]: struct.CheckOptions.html#structfield.export
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ExportOptions {
+    /// The order in which passages are written. Defaults to
+    /// [`PassageOrder::Alphabetical`]
+    ///
+    /// [`PassageOrder::Alphabetical`]: enum.PassageOrder.html#variant.Alphabetical
+    pub passage_order: PassageOrder,
+
+    /// The filter on the tags of the normal passages written. Exports every
+    /// passage by default. See [`ExportFilter`] for details
+    ///
+    /// [`ExportFilter`]: struct.ExportFilter.html
+    pub filter: ExportFilter,
+}
+
+impl ExportOptions {
+    /// Creates a new `ExportOptions` with the defaults, which write every
+    /// passage in alphabetical order
+    pub fn new() -> Self {
+        ExportOptions::default()
+    }
+
+    /// Builder method to set the `passage_order`
+    pub fn with_passage_order(mut self, passage_order: PassageOrder) -> Self {
+        self.passage_order = passage_order;
+        self
+    }
+
+    /// Builder method to set the `filter`
+    pub fn with_filter(mut self, filter: ExportFilter) -> Self {
+        self.filter = filter;
+        self
+    }
+}
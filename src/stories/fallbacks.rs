@@ -0,0 +1,123 @@
+use crate::FullContext;
+use crate::ParseOptions;
+use crate::Passage;
+use crate::StoryPassages;
+use crate::Warning;
+use crate::WarningKind;
+use serde_json::json;
+use std::collections::hash_map::RandomState;
+use std::hash::BuildHasher;
+use std::hash::Hasher;
+use std::path::Path;
+
+impl StoryPassages {
+    /// Generates a `StoryTitle` and `StoryData` if they're missing and enabled
+    /// by `options`, returning a note for each one generated. `source` is the
+    /// file or directory the story was parsed from, if any, used to name the
+    /// story
+    pub(crate) fn generate_missing_specials(&mut self, options: &ParseOptions, source: Option<&Path>) -> Vec<Warning> {
+        let mut notes = Vec::new();
+        if self.title.is_none() && options.fallback_title {
+            if let Some(title) = source.and_then(project_name) {
+                if let Some(passage) = generated_passage(format!(":: StoryTitle\n{}", title), options) {
+                    self.title = Some(passage);
+                    notes.push(Warning::new::<FullContext>(WarningKind::GeneratedStoryTitle(title), None));
+                }
+            }
+        }
+
+        if self.data.is_none() && options.generate_story_data {
+            let ifid = new_ifid();
+            let mut data = json!({ "ifid": ifid });
+            if let Some(format) = &options.story_format {
+                data["format"] = json!(format);
+            }
+            if let Some(version) = &options.story_format_version {
+                data["format-version"] = json!(version);
+            }
+            let source = format!(":: StoryData\n{}", serde_json::to_string_pretty(&data).unwrap());
+            if let Some(passage) = generated_passage(source, options) {
+                self.data = Some(passage);
+                notes.push(Warning::new::<FullContext>(WarningKind::GeneratedStoryData(ifid), None));
+            }
+        }
+        notes
+    }
+}
+
+/// Returns the name of a project from its path: the name of a directory, or of
+/// a file without its extension
+fn project_name(path: &Path) -> Option<String> {
+    let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    let name = if path.is_dir() { path.file_name() } else { path.file_stem() }?;
+    Some(name.to_string_lossy().into_owned())
+}
+
+/// Parses a generated special passage
+fn generated_passage(source: String, options: &ParseOptions) -> Option<Passage> {
+    Passage::parse(FullContext::from(None, source), options).take().0.ok()
+}
+
+/// Generates a random version 4 UUID, in the uppercase form Twine uses for IFIDs
+pub(crate) fn new_ifid() -> String {
+    let mut bytes = [0u8; 16];
+    for (i, chunk) in bytes.chunks_mut(8).enumerate() {
+        // Each RandomState is seeded differently, from the system's source of
+        // randomness
+        let mut hasher = RandomState::new().build_hasher();
+        hasher.write_usize(i);
+        chunk.copy_from_slice(&hasher.finish().to_le_bytes());
+    }
+    bytes[6] = (bytes[6] & 0x0f) | 0x40;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+    let hex: String = bytes.iter().map(|b| format!("{:02X}", b)).collect();
+    format!("{}-{}-{}-{}-{}", &hex[..8], &hex[8..12], &hex[12..16], &hex[16..20], &hex[20..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PassageContent;
+
+    #[test]
+    fn ifid() {
+        let ifid = new_ifid();
+        assert_eq!(ifid.len(), 36);
+        assert_eq!(&ifid[14..15], "4");
+        assert!("89AB".contains(&ifid[19..20]));
+        assert!(ifid.chars().all(|c| c == '-' || c.is_ascii_digit() || c.is_ascii_uppercase()));
+        assert_ne!(ifid, new_ifid());
+    }
+
+    #[test]
+    fn generated() -> Result<(), Box<dyn std::error::Error>> {
+        use std::io::Write;
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("My Story.twee");
+        writeln!(std::fs::File::create(&path)?, ":: Start\nHello")?;
+
+        let options = ParseOptions::new()
+            .with_fallback_title(true)
+            .with_generated_story_data(Some("SugarCube"), Some("2.36.1"));
+        let (res, warnings) = StoryPassages::from_path_with_options(&path, &options).take();
+        let story = res.ok().unwrap();
+        assert_eq!(story.title_string(), Some("My Story"));
+        let ifid = story.ifid().unwrap().to_string();
+        assert_eq!(story.format_name(), Some("SugarCube"));
+        assert_eq!(story.format_version(), Some("2.36.1"));
+        let kinds: Vec<&WarningKind> = warnings.iter().map(|w| &w.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                &WarningKind::GeneratedStoryTitle("My Story".to_string()),
+                &WarningKind::GeneratedStoryData(ifid),
+            ]
+        );
+        assert!(matches!(story.title.as_ref().unwrap().content, PassageContent::StoryTitle(_)));
+
+        let (_, warnings) = StoryPassages::from_path(&path).take();
+        assert_eq!(warnings.len(), 2);
+        assert_eq!(warnings[0].kind, WarningKind::MissingStoryTitle);
+        Ok(())
+    }
+}
@@ -0,0 +1,80 @@
+use crate::StoryPassages;
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::path::Path;
+use std::path::PathBuf;
+
+impl StoryPassages {
+    /// Registers the source of every distinct file in this story with an
+    /// external file database, such as `codespan_reporting`'s `SimpleFiles`,
+    /// and returns a map of file path to the id assigned by the database
+    ///
+    /// `add` is called once per file, in order of file path, with the path of
+    /// the file (or `None` if the story was parsed from a `String`) and its
+    /// full source text, and must return the id of the registered file. The
+    /// file of any [`Warning`] or [`Error`] context can then be looked up in
+    /// the returned map by its file path.
+    ///
+    /// # Examples
+    /// ```
+    /// use tweep::StoryPassages;
+    /// use std::path::PathBuf;
+    /// let input = ":: Start\nHello".to_string();
+    /// let story = StoryPassages::from_string(input).take().0.ok().unwrap();
+    ///
+    /// // A stand-in for e.g. codespan_reporting::files::SimpleFiles
+    /// let mut files: Vec<(String, String)> = Vec::new();
+    /// let ids = story.register_files(|path, source| {
+    ///     let name = path.map(|p| p.display().to_string()).unwrap_or_default();
+    ///     files.push((name, source.to_string()));
+    ///     files.len() - 1
+    /// });
+    /// assert_eq!(ids[&None::<PathBuf>], 0);
+    /// assert_eq!(files[0].1, ":: Start\nHello");
+    /// ```
+    ///
+    /// [`Warning`]: struct.Warning.html
+    /// [`Error`]: struct.Error.html
+    pub fn register_files<I, F>(&self, mut add: F) -> HashMap<Option<PathBuf>, I>
+    where
+        F: FnMut(Option<&Path>, &str) -> I,
+    {
+        let mut sources = BTreeMap::new();
+        for passage in self.all_passages() {
+            sources
+                .entry(passage.context.get_file_path())
+                .or_insert_with(|| passage.context.get_source());
+        }
+        sources
+            .into_iter()
+            .map(|(path, source)| (path.map(Path::to_path_buf), add(path, source)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::tempdir;
+
+    #[test]
+    fn multiple_files() {
+        let dir = tempdir().unwrap();
+        let a = dir.path().join("a.twee");
+        let b = dir.path().join("b.twee");
+        writeln!(std::fs::File::create(&a).unwrap(), ":: Start\n[[Next]]\n\n:: Other\nFoo").unwrap();
+        writeln!(std::fs::File::create(&b).unwrap(), ":: Next\nBar").unwrap();
+        let story = StoryPassages::from_path(dir.path()).take().0.ok().unwrap();
+
+        let mut files = Vec::new();
+        let ids = story.register_files(|path, source| {
+            files.push((path.unwrap().to_path_buf(), source.to_string()));
+            files.len() - 1
+        });
+        assert_eq!(ids.len(), 2);
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[ids[&Some(a.clone())]], (a, ":: Start\n[[Next]]\n\n:: Other\nFoo\n".to_string()));
+        assert_eq!(files[ids[&Some(b.clone())]], (b, ":: Next\nBar\n".to_string()));
+    }
+}
@@ -0,0 +1,93 @@
+#[cfg(feature = "full-context")]
+use crate::ContextErrorList;
+use crate::Error;
+#[cfg(not(feature = "full-context"))]
+use crate::ErrorList;
+use crate::Output;
+use crate::ParseOptions;
+use crate::StoryPassages;
+use crate::Warning;
+use std::path::Path;
+use std::path::PathBuf;
+
+#[cfg(not(feature = "full-context"))]
+type ParseOutput = Output<Result<StoryPassages, ErrorList>>;
+#[cfg(feature = "full-context")]
+type ParseOutput = Output<Result<StoryPassages, ContextErrorList>>;
+
+/// The outcome of parsing one file of a story, as returned by
+/// [`StoryPassages::from_path_with_reports`], so that problems can be shown
+/// by file and a single file can be linted again with [`parse`] without
+/// parsing the whole project
+///
+/// [`StoryPassages::from_path_with_reports`]: struct.StoryPassages.html#method.from_path_with_reports
+/// [`parse`]: #method.parse
+#[derive(Clone, Debug, Default)]
+pub struct FileReport {
+    /// The path of the file
+    pub path: PathBuf,
+
+    /// The names of the passages the file contributes, in sorted order, or
+    /// none if it failed to parse
+    pub passages: Vec<String>,
+
+    /// The errors that kept the file from being parsed
+    pub errors: Vec<Error>,
+
+    /// The warnings located in the file
+    pub warnings: Vec<Warning>,
+}
+
+impl FileReport {
+    /// Parses the file at `path` on its own and reports the outcome. Only
+    /// problems within the file itself are found, so there are no warnings
+    /// about duplicates or dead links, which depend on the rest of the story
+    ///
+    /// # Examples
+    /// ```
+    /// use tweep::{ErrorKind, FileReport, ParseOptions};
+    /// use std::fs;
+    /// let dir = tempfile::tempdir().unwrap();
+    /// let path = dir.path().join("broken.twee");
+    /// fs::write(&path, ":: Start\nHello\n\n::\nNo name\n").unwrap();
+    /// let report = FileReport::parse(&path, &ParseOptions::new());
+    /// assert!(!report.is_ok());
+    /// assert_eq!(report.errors[0].kind, ErrorKind::EmptyName);
+    ///
+    /// fs::write(&path, ":: Start\nHello\n\n:: Named\nText\n").unwrap();
+    /// let report = FileReport::parse(&path, &ParseOptions::new());
+    /// assert!(report.is_ok());
+    /// assert_eq!(report.passages, vec!["Named", "Start"]);
+    /// ```
+    pub fn parse<P: AsRef<Path>>(path: P, options: &ParseOptions) -> Self {
+        let path = path.as_ref();
+        let out = StoryPassages::from_path_internal(path, options, "", None);
+        FileReport::from_output(path, &out)
+    }
+
+    /// Returns `true` if the file was parsed without errors
+    pub fn is_ok(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// Creates a report from the output of parsing the file at `path`
+    pub(crate) fn from_output(path: &Path, out: &ParseOutput) -> Self {
+        let (passages, errors) = match out.get_output() {
+            Ok(story) => {
+                let mut names: Vec<String> = story.all_passages().map(|p| p.header.name.clone()).collect();
+                names.sort();
+                (names, Vec::new())
+            }
+            #[cfg(not(feature = "full-context"))]
+            Err(e) => (Vec::new(), e.errors.clone()),
+            #[cfg(feature = "full-context")]
+            Err(e) => (Vec::new(), e.error_list.errors.clone()),
+        };
+        FileReport {
+            path: path.to_path_buf(),
+            passages,
+            errors,
+            warnings: out.get_warnings().clone(),
+        }
+    }
+}
@@ -0,0 +1,15 @@
+use crate::IssueKind;
+use crate::TextEdit;
+
+/// An issue offered by a [`FixSession`], along with the edit that fixes it
+///
+/// [`FixSession`]: struct.FixSession.html
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Fix {
+    /// The kind of issue being fixed
+    pub kind: IssueKind,
+
+    /// The edit that fixes the issue. Its context is located in the current
+    /// text of its file, with any fixes accepted so far applied
+    pub edit: TextEdit,
+}
@@ -0,0 +1,325 @@
+use crate::passages::link_target;
+use crate::CheckOptions;
+use crate::Error;
+use crate::Fix;
+use crate::FullContext;
+use crate::IssueKind;
+use crate::StoryPassages;
+use crate::TextEdit;
+use crate::Warning;
+use crate::WarningKind;
+use std::collections::VecDeque;
+use std::ops::Range;
+use std::path::Path;
+
+/// A fix that hasn't been accepted or rejected yet
+#[derive(Clone, Debug)]
+struct PendingFix {
+    kind: IssueKind,
+    passage: String,
+    /// The index of the file in `FixSession::files`
+    file: usize,
+    /// The byte range to replace in the current text of the file
+    range: Range<usize>,
+    replacement: String,
+}
+
+/// Offers the fixable issues in a story one at a time, applying the fixes that
+/// are accepted, as created by [`StoryPassages::fix_session`] or
+/// [`from_errors`]. This is the engine for fixing a story interactively, such
+/// as from an editor
+///
+/// Issues are offered in source order. Accepting a fix edits the text of its
+/// file and moves the issues after it to match; an issue whose text the
+/// accepted edit replaced is dropped. The story itself is not modified: once
+/// the decisions are made, the edited text of each file is available from
+/// [`files`]
+///
+/// [`StoryPassages::fix_session`]: struct.StoryPassages.html#method.fix_session
+/// [`from_errors`]: #method.from_errors
+/// [`files`]: #method.files
+#[derive(Clone, Debug)]
+pub struct FixSession {
+    files: Vec<FullContext>,
+    pending: VecDeque<PendingFix>,
+}
+
+impl FixSession {
+    /// Creates a new `FixSession` offering the given fixes. The edit of each
+    /// fix must be located in the source text it was parsed from. Several
+    /// fixes may edit the same line, such as insertions at different points
+    pub fn new(fixes: Vec<Fix>) -> Self {
+        let mut sources: Vec<FullContext> = Vec::new();
+        let mut files = Vec::new();
+        let mut pending = Vec::new();
+        for fix in fixes {
+            let context = &fix.edit.context;
+            let file = match sources.iter().position(|s| s.shares_source(context)) {
+                Some(file) => file,
+                None => {
+                    sources.push(context.clone());
+                    files.push(FullContext::from_file_path(
+                        context.get_file_path().map(Path::to_path_buf),
+                        context.get_source().to_string(),
+                    ));
+                    files.len() - 1
+                }
+            };
+            let range = context.get_byte_range();
+            pending.push(PendingFix {
+                kind: fix.kind,
+                passage: fix.edit.passage,
+                file,
+                range: range.start..std::cmp::max(range.start, range.end),
+                replacement: fix.edit.replacement,
+            });
+        }
+        pending.sort_by_key(|fix| (fix.file, fix.range.start));
+
+        FixSession {
+            files,
+            pending: pending.into(),
+        }
+    }
+
+    /// Creates a new `FixSession` offering the fixes of the given errors, such
+    /// as those returned when parsing a story fails. Errors without a fix are
+    /// skipped
+    ///
+    /// # Examples
+    /// ```
+    /// use tweep::{ErrorKind, FixSession, IssueKind, StoryPassages};
+    /// let input = ":: Start\nHello\n\n:: Dark [room] {1} [night]\nBoo\n".to_string();
+    /// let errors = StoryPassages::from_string(input).take().0.err().unwrap();
+    /// # #[cfg(feature = "full-context")]
+    /// # let errors = errors.error_list;
+    /// let mut session = FixSession::from_errors(&errors.errors);
+    /// assert_eq!(session.remaining(), 4);
+    /// assert_eq!(session.current().unwrap().kind, IssueKind::Error(ErrorKind::UnescapedOpenSquare("Dark [room] {1}".to_string())));
+    /// while session.current().is_some() {
+    ///     session.accept();
+    /// }
+    ///
+    /// let text = session.files()[0].get_contents().to_string();
+    /// assert_eq!(text, ":: Start\nHello\n\n:: Dark \\[room\\] \\{1\\} [night]\nBoo\n");
+    /// assert!(StoryPassages::from_string(text).take().0.is_ok());
+    /// ```
+    pub fn from_errors(errors: &[Error]) -> Self {
+        FixSession::new(
+            errors
+                .iter()
+                .filter_map(|error| {
+                    error.fix.as_ref().map(|edit| Fix {
+                        kind: IssueKind::Error(error.kind.clone()),
+                        edit: TextEdit::clone(edit),
+                    })
+                })
+                .collect(),
+        )
+    }
+
+    /// Returns the issue currently awaiting a decision along with its fix, or
+    /// `None` if every issue has been decided
+    pub fn current(&self) -> Option<Fix> {
+        self.pending.front().map(|fix| {
+            let file = &self.files[fix.file];
+            Fix {
+                kind: fix.kind.clone(),
+                edit: TextEdit {
+                    passage: fix.passage.clone(),
+                    context: file.slice(fix.range.clone()),
+                    replacement: fix.replacement.clone(),
+                },
+            }
+        })
+    }
+
+    /// Applies the fix for the current issue and moves on to the next one.
+    /// Does nothing if every issue has been decided
+    pub fn accept(&mut self) {
+        let fix = match self.pending.pop_front() {
+            Some(fix) => fix,
+            None => return,
+        };
+        let file = &self.files[fix.file];
+        let mut text = file.get_contents().to_string();
+        text.replace_range(fix.range.clone(), &fix.replacement);
+        self.files[fix.file] = FullContext::from_file_path(file.get_file_path().map(Path::to_path_buf), text);
+
+        // Move the later issues in the same file, dropping any the edit replaced
+        self.pending.retain_mut(|other| {
+            if other.file != fix.file || other.range.end <= fix.range.start {
+                true
+            } else if other.range.start >= fix.range.end {
+                let shift = |offset: usize| offset - fix.range.end + fix.range.start + fix.replacement.len();
+                other.range = shift(other.range.start)..shift(other.range.end);
+                true
+            } else {
+                false
+            }
+        });
+    }
+
+    /// Leaves the current issue as it is and moves on to the next one. Does
+    /// nothing if every issue has been decided
+    pub fn reject(&mut self) {
+        self.pending.pop_front();
+    }
+
+    /// Returns the number of issues still awaiting a decision, including the
+    /// current one. Issues dropped because an accepted edit replaced their
+    /// text aren't counted
+    pub fn remaining(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Returns the current text of each file containing a fixable issue, with
+    /// the fixes accepted so far applied, in the order the files were found
+    pub fn files(&self) -> &[FullContext] {
+        &self.files
+    }
+}
+
+impl StoryPassages {
+    /// Starts a [`FixSession`] for the fixable issues in the story:
+    ///
+    /// * [`TrimmedLinkMatch`] - Fixed by replacing the link target with the
+    ///   name of the passage it matches
+    /// * [`WhitespaceInLink`] - Fixed by trimming the whitespace from the link
+    ///   target
+    ///
+    /// Issues allowed by the story's [`config`] aren't offered
+    ///
+    /// # Examples
+    /// ```
+    /// use tweep::{IssueKind, StoryPassages, WarningKind};
+    /// let input = ":: Start\n[[Go->Hall.]] or [[ Cellar ]] or [[Hall ]]\n\n:: Hall\nA\n\n:: Cellar\nB\n".to_string();
+    /// let story = StoryPassages::from_string(input).take().0.ok().unwrap();
+    /// let mut session = story.fix_session();
+    /// assert_eq!(session.remaining(), 3);
+    ///
+    /// let fix = session.current().unwrap();
+    /// let expected = WarningKind::TrimmedLinkMatch("Hall.".to_string(), "Hall".to_string());
+    /// assert_eq!(fix.kind, IssueKind::Warning(expected));
+    /// assert_eq!(fix.edit.context.get_contents(), "Hall.");
+    /// session.accept();
+    /// session.reject();
+    /// assert_eq!(session.current().unwrap().edit.replacement, "Hall");
+    /// session.accept();
+    /// assert!(session.current().is_none());
+    ///
+    /// let text = session.files()[0].get_contents();
+    /// assert!(text.starts_with(":: Start\n[[Go->Hall]] or [[ Cellar ]] or [[Hall]]\n"));
+    /// ```
+    ///
+    /// [`FixSession`]: struct.FixSession.html
+    /// [`TrimmedLinkMatch`]: enum.WarningKind.html#variant.TrimmedLinkMatch
+    /// [`WhitespaceInLink`]: enum.WarningKind.html#variant.WhitespaceInLink
+    /// [`config`]: #structfield.config
+    pub fn fix_session(&self) -> FixSession {
+        let options = CheckOptions::new();
+        let mut fixes = Vec::new();
+        for (name, link) in self.links() {
+            let source = link.context.get_contents();
+            let inner = &source[2..source.len() - 2];
+            let target = link_target(inner);
+            let (kind, replacement) = match self.link_warning(name, link, &options) {
+                Some(WarningKind::TrimmedLinkMatch(written, resolved)) => {
+                    (WarningKind::TrimmedLinkMatch(written, resolved.clone()), resolved)
+                }
+                _ if target.trim() != target && !target.trim().is_empty() => {
+                    (WarningKind::WhitespaceInLink(target.to_string()), target.trim().to_string())
+                }
+                _ => continue,
+            };
+            if self.config.allows(&Warning::new(kind.clone(), Some(link.context.clone()))) {
+                continue;
+            }
+
+            let start = 2 + (target.as_ptr() as usize - inner.as_ptr() as usize);
+            fixes.push(Fix {
+                kind: IssueKind::Warning(kind),
+                edit: TextEdit {
+                    passage: name.to_string(),
+                    context: link.context.slice(start..start + target.len()),
+                    replacement,
+                },
+            });
+        }
+        FixSession::new(fixes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ErrorKind;
+    use crate::FixSession;
+    use crate::IssueKind;
+    use crate::StoryPassages;
+    use crate::WarningKind;
+
+    #[test]
+    fn fix_session() {
+        let input = ":: B\n[[Hall!]] [[ A ]]\n\n:: A\n[[ Hall ]]\n\n:: Hall\n[[B ]] [[Nowhere]]\n\n:: StoryConfig\n{\"allow\": []}\n"
+            .to_string();
+        let story = StoryPassages::from_string(input).take().0.ok().unwrap();
+        let mut session = story.fix_session();
+        let kinds: Vec<IssueKind> = std::iter::from_fn(|| {
+            let kind = session.current().map(|fix| fix.kind);
+            session.accept();
+            kind
+        })
+        .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                IssueKind::Warning(WarningKind::TrimmedLinkMatch("Hall!".to_string(), "Hall".to_string())),
+                IssueKind::Warning(WarningKind::WhitespaceInLink(" A ".to_string())),
+                IssueKind::Warning(WarningKind::WhitespaceInLink(" Hall ".to_string())),
+                IssueKind::Warning(WarningKind::WhitespaceInLink("B ".to_string())),
+            ]
+        );
+        assert_eq!(
+            session.files()[0].get_contents(),
+            ":: B\n[[Hall]] [[A]]\n\n:: A\n[[Hall]]\n\n:: Hall\n[[B]] [[Nowhere]]\n\n:: StoryConfig\n{\"allow\": []}\n"
+        );
+        session.accept();
+        session.reject();
+        assert_eq!(session.remaining(), 0);
+
+        let input = ":: Start\n[[ Start ]]\n\n:: StoryConfig\n{\"allow\": [\"WhitespaceInLink\"]}\n".to_string();
+        let story = StoryPassages::from_string(input).take().0.ok().unwrap();
+        assert!(story.fix_session().current().is_none());
+        assert!(story.fix_session().files().is_empty());
+    }
+
+    #[test]
+    fn escape_fixes() {
+        let input = ":: A {b} ]c[d] e [t]\nText\n\n:: }\n".to_string();
+        let errors = StoryPassages::from_string(input).take().0.err().unwrap();
+        #[cfg(feature = "full-context")]
+        let errors = errors.error_list;
+        let mut session = FixSession::from_errors(&errors.errors);
+        let kinds: Vec<IssueKind> = std::iter::from_fn(|| {
+            let kind = session.current().map(|fix| fix.kind);
+            session.accept();
+            kind
+        })
+        .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                IssueKind::Error(ErrorKind::UnescapedOpenCurly("A {b} ]c[d] e".to_string())),
+                IssueKind::Error(ErrorKind::UnescapedCloseCurly("A {b} ]c[d] e".to_string())),
+                IssueKind::Error(ErrorKind::UnescapedCloseSquare("A {b} ]c[d] e".to_string())),
+                IssueKind::Error(ErrorKind::UnescapedOpenSquare("A {b} ]c[d] e".to_string())),
+                IssueKind::Error(ErrorKind::UnescapedCloseSquare("A {b} ]c[d] e".to_string())),
+                IssueKind::Error(ErrorKind::UnescapedCloseCurly("}".to_string())),
+            ]
+        );
+        let text = session.files()[0].get_contents().to_string();
+        assert_eq!(text, ":: A \\{b\\} \\]c\\[d\\] e [t]\nText\n\n:: \\}\n");
+        let story = StoryPassages::from_string(text).take().0.ok().unwrap();
+        assert!(story.passages.contains_key("A {b} ]c[d] e"));
+    }
+}
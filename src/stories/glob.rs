@@ -0,0 +1,43 @@
+/// Returns `true` if `text` matches the glob `pattern`, where `*` matches any
+/// run of characters other than `/`, `**` matches any run of characters
+/// including `/`, and `?` matches any single character other than `/`
+pub(crate) fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    matches(&pattern, &text)
+}
+
+fn matches(pattern: &[char], text: &[char]) -> bool {
+    match pattern {
+        [] => text.is_empty(),
+        ['*', '*', rest @ ..] => {
+            // Let `**/` also match no directories at all
+            let rest_without_slash = rest.strip_prefix(&['/']).unwrap_or(rest);
+            (0..=text.len()).any(|i| matches(rest, &text[i..]))
+                || matches(rest_without_slash, text)
+        }
+        ['*', rest @ ..] => (0..=text.len())
+            .take_while(|&i| i == 0 || text[i - 1] != '/')
+            .any(|i| matches(rest, &text[i..])),
+        ['?', rest @ ..] => matches!(text, [c, ..] if *c != '/') && matches(rest, &text[1..]),
+        [p, rest @ ..] => matches!(text, [c, ..] if c == p) && matches(rest, &text[1..]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn globs() {
+        assert!(glob_match("*_old.twee", "intro_old.twee"));
+        assert!(!glob_match("*_old.twee", "intro.twee"));
+        assert!(!glob_match("*.twee", "drafts/intro.twee"));
+        assert!(glob_match("drafts/*.tw", "drafts/a.tw"));
+        assert!(glob_match("**/a.tw", "a.tw"));
+        assert!(glob_match("**/a.tw", "x/y/a.tw"));
+        assert!(glob_match("chapter?", "chapter1"));
+        assert!(!glob_match("chapter?", "chapter10"));
+        assert!(glob_match("drafts", "drafts"));
+    }
+}
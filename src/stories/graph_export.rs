@@ -0,0 +1,108 @@
+use crate::ExportOptions;
+use crate::StoryPassages;
+use serde_json::json;
+
+impl StoryPassages {
+    /// Exports the link graph of the story as a JSON adjacency list, for use
+    /// by web-based visualizers such as D3
+    ///
+    /// The result is an object with two arrays:
+    /// * `nodes` - One object per passage, in order of name, with the
+    ///   passage's `name`, `tags` and `metadata`
+    /// * `edges` - One object per link that resolves to a passage, in the order
+    ///   given by [`links`], with the `source` and `target` passage names and
+    ///   the `file`, `line` and `column` where the link starts. `file` is null
+    ///   if the story wasn't parsed from a file
+    ///
+    /// Dead links are left out, so that every edge refers to a node. To choose
+    /// the order of the nodes, or to leave passages out, use
+    /// [`to_graph_json_with`].
+    ///
+    /// # Examples
+    /// ```
+    /// use tweep::StoryPassages;
+    /// let input = ":: Start [intro]\nGo [[Next]] or [[Nowhere]]\n\n:: Next\nThe end\n".to_string();
+    /// let story = StoryPassages::from_string(input).take().0.ok().unwrap();
+    /// let graph = story.to_graph_json();
+    /// assert_eq!(graph["nodes"][0]["name"], "Next");
+    /// assert_eq!(graph["nodes"][1]["tags"][0], "intro");
+    /// assert_eq!(graph["nodes"][1]["metadata"]["position"], "10,10");
+    /// assert_eq!(graph["edges"].as_array().unwrap().len(), 1);
+    /// assert_eq!(graph["edges"][0]["source"], "Start");
+    /// assert_eq!(graph["edges"][0]["target"], "Next");
+    /// assert_eq!(graph["edges"][0]["line"], 2);
+    /// assert_eq!(graph["edges"][0]["column"], 4);
+    /// ```
+    ///
+    /// [`links`]: #method.links
+    /// [`to_graph_json_with`]: #method.to_graph_json_with
+    pub fn to_graph_json(&self) -> serde_json::Value {
+        self.to_graph_json_with(&ExportOptions::default())
+    }
+
+    /// Exports the link graph of the story like [`to_graph_json`], with the
+    /// given [`ExportOptions`]. Nodes are in the order given by their
+    /// `passage_order`, and passages left out by their `filter` are skipped,
+    /// along with any links to or from them
+    ///
+    /// [`to_graph_json`]: #method.to_graph_json
+    /// [`ExportOptions`]: struct.ExportOptions.html
+    pub fn to_graph_json_with(&self, options: &ExportOptions) -> serde_json::Value {
+        let nodes: Vec<serde_json::Value> = self
+            .exported_names(options)
+            .into_iter()
+            .map(|name| {
+                let passage = &self.passages[name];
+                json!({
+                    "name": name,
+                    "tags": passage.tags(),
+                    "metadata": passage.metadata(),
+                })
+            })
+            .collect();
+
+        let edges: Vec<serde_json::Value> = self
+            .links()
+            .filter(|(name, _)| self.is_exported(name, &options.filter))
+            .filter_map(|(name, link)| {
+                let target = self
+                    .resolve_link(name, &link.target)
+                    .filter(|target| self.is_exported(target, &options.filter))?;
+                let position = link.context.get_start_position();
+                Some(json!({
+                    "source": name,
+                    "target": target,
+                    "file": link.context.get_file_path().map(|path| path.to_string_lossy()),
+                    "line": position.line,
+                    "column": position.column,
+                }))
+            })
+            .collect();
+
+        json!({ "nodes": nodes, "edges": edges })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ExportOptions;
+    use crate::PassageOrder;
+    use crate::StoryPassages;
+
+    #[test]
+    fn graph_json() {
+        let input = ":: Start\n[[B]]\n[[A]]\n\n:: B\n[[Start]]\n\n:: A\nA\n".to_string();
+        let story = StoryPassages::from_string(input).take().0.ok().unwrap();
+        let graph = story.to_graph_json_with(&ExportOptions::new().with_passage_order(PassageOrder::Source));
+        let names: Vec<&str> = graph["nodes"].as_array().unwrap().iter().map(|n| n["name"].as_str().unwrap()).collect();
+        assert_eq!(names, vec!["Start", "B", "A"]);
+        let edges: Vec<(&str, &str, u64)> = graph["edges"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|e| (e["source"].as_str().unwrap(), e["target"].as_str().unwrap(), e["line"].as_u64().unwrap()))
+            .collect();
+        assert_eq!(edges, vec![("B", "Start", 6), ("Start", "B", 2), ("Start", "A", 3)]);
+        assert!(graph["edges"][0]["file"].is_null());
+    }
+}
@@ -0,0 +1,112 @@
+use crate::PassageContent;
+use crate::StoryPassages;
+use crate::Warning;
+use crate::WarningKind;
+use std::ops::Range;
+
+impl StoryPassages {
+    /// Checks the prose of every normal passage for `<` and `&` sequences that
+    /// will be interpreted as HTML by most story formats, returning an
+    /// [`UnescapedHtml`] warning for each, in order of passage name
+    ///
+    /// A `<` that is followed by a letter, `/`, or `!` but never closed
+    /// starts a tag that swallows the text after it, and an `&` followed by a
+    /// name or number and a `;` is rendered as a character entity. Complete
+    /// HTML tags, macros, links, and the escapes `&lt;`, `&gt;`, `&amp;`,
+    /// `&quot;`, and `&apos;` are not reported
+    ///
+    /// # Examples
+    /// ```
+    /// use tweep::{StoryPassages, WarningKind};
+    /// let input = ":: Start\nFish &chips; <b>bold</b> and x <y\n".to_string();
+    /// let story = StoryPassages::from_string(input).take().0.ok().unwrap();
+    /// let warnings = story.check_html();
+    /// assert_eq!(warnings.len(), 2);
+    /// assert_eq!(warnings[0].kind, WarningKind::UnescapedHtml("&chips;".to_string()));
+    /// assert_eq!(warnings[1].kind, WarningKind::UnescapedHtml("<y".to_string()));
+    /// ```
+    ///
+    /// [`UnescapedHtml`]: enum.WarningKind.html#variant.UnescapedHtml
+    pub fn check_html(&self) -> Vec<Warning> {
+        let mut names: Vec<&String> = self.passages.keys().collect();
+        names.sort();
+
+        let mut warnings = Vec::new();
+        for name in names {
+            let passage = &self.passages[name];
+            let twine = match &passage.content {
+                PassageContent::Normal(twine) => twine,
+                _ => continue,
+            };
+
+            // The body begins on the line after the header
+            let body_start = match passage.context.get_contents().find('\n') {
+                Some(pos) => pos + 1,
+                None => continue,
+            };
+            let prose = twine.prose();
+            for range in html_sequences(&prose) {
+                let text = prose[range.clone()].to_string();
+                let context = passage.context.slice(body_start + range.start..body_start + range.end);
+                warnings.push(Warning::new(WarningKind::UnescapedHtml(text), Some(context)));
+            }
+        }
+        warnings
+    }
+}
+
+/// Entities that are written to escape a character, and so are never stray
+const ESCAPES: [&str; 5] = ["&lt;", "&gt;", "&amp;", "&quot;", "&apos;"];
+
+/// Finds the byte ranges of unclosed tags and character entities in prose
+fn html_sequences(prose: &str) -> Vec<Range<usize>> {
+    let mut ranges = Vec::new();
+    for (i, c) in prose.char_indices() {
+        let rest = &prose[i + 1..];
+        if c == '<' && rest.starts_with(|c: char| c.is_ascii_alphabetic() || c == '/' || c == '!') {
+            let len = rest
+                .find(|c: char| c.is_whitespace())
+                .unwrap_or(rest.len());
+            ranges.push(i..i + 1 + len);
+        } else if c == '&' {
+            let name = rest.strip_prefix('#').unwrap_or(rest);
+            let len = name
+                .find(|c: char| !c.is_ascii_alphanumeric())
+                .unwrap_or(name.len());
+            if len > 0 && name[len..].starts_with(';') {
+                let end = i + 1 + (rest.len() - name.len()) + len + 1;
+                if !ESCAPES.contains(&&prose[i..end]) {
+                    ranges.push(i..end);
+                }
+            }
+        }
+    }
+    ranges
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Position;
+
+    #[test]
+    fn sequences() {
+        let prose = "a < b && c; &#169; &amp; x<br <!-- &;";
+        let found: Vec<&str> = html_sequences(prose).into_iter().map(|r| &prose[r]).collect();
+        assert_eq!(found, vec!["&#169;", "<br", "<!--"]);
+    }
+
+    #[test]
+    fn check_html() {
+        let input = ":: A\n<<if $x < 3>>[[a&b;|B]]<</if>>\n\n:: B\n2 &lt; 3 &copy;\n<i>&amp;</i>".to_string();
+        let story = StoryPassages::from_string(input).take().0.ok().unwrap();
+        let warnings = story.check_html();
+        assert_eq!(warnings.len(), 2);
+        assert_eq!(warnings[0].kind, WarningKind::UnescapedHtml("&b;".to_string()));
+        let context = warnings[0].context.as_ref().unwrap();
+        assert_eq!(*context.get_start_position(), Position::abs(2, 17));
+        assert_eq!(warnings[1].kind, WarningKind::UnescapedHtml("&copy;".to_string()));
+        let context = warnings[1].context.as_ref().unwrap();
+        assert_eq!(*context.get_start_position(), Position::abs(5, 10));
+    }
+}
@@ -0,0 +1,172 @@
+use crate::LayoutStyle;
+use crate::StoryPassages;
+use std::collections::HashMap;
+use std::collections::HashSet;
+
+/// The distance between the top left corners of adjacent passages, which
+/// leaves a gap between passages of the default size
+const SPACING: i64 = 150;
+
+/// The position of the top left corner of the layout when no passages have
+/// been positioned yet
+const ORIGIN: (i64, i64) = (100, 100);
+
+impl StoryPassages {
+    /// Assigns `position` metadata to passages that don't have a sensible one,
+    /// so that a story compiled to Twine 2 HTML doesn't open as a pile of
+    /// overlapping passages. Returns the names of the passages that were
+    /// positioned, in sorted order
+    ///
+    /// If no passage has a position written in its header, or every passage
+    /// has the same one, all passages are laid out using the given
+    /// [`LayoutStyle`]. Otherwise, only passages without a position are laid
+    /// out, below the passages that already have one.
+    ///
+    /// # Examples
+    /// ```
+    /// use tweep::{LayoutStyle, StoryPassages};
+    /// let input = ":: Start\n[[A]] [[B]]\n\n:: A\nOne\n\n:: B\nTwo".to_string();
+    /// let mut story = StoryPassages::from_string(input).take().0.ok().unwrap();
+    /// let moved = story.auto_layout(LayoutStyle::Layered);
+    /// assert_eq!(moved, vec!["A", "B", "Start"]);
+    /// assert_eq!(story.passages["Start"].header.metadata["position"], "100,100");
+    /// assert_eq!(story.passages["A"].header.metadata["position"], "100,250");
+    /// assert_eq!(story.passages["B"].header.metadata["position"], "250,250");
+    /// ```
+    ///
+    /// [`LayoutStyle`]: enum.LayoutStyle.html
+    pub fn auto_layout(&mut self, style: LayoutStyle) -> Vec<String> {
+        let authored: HashMap<&str, (i64, i64)> = self
+            .passages
+            .iter()
+            .filter(|(_, p)| p.header.has_authored_metadata("position"))
+            .filter_map(|(name, p)| {
+                let position = p.header.metadata.get("position")?.as_str()?;
+                Some((name.as_str(), parse_position(position)?))
+            })
+            .collect();
+        let distinct: HashSet<&(i64, i64)> = authored.values().collect();
+
+        let (to_place, origin): (HashSet<&str>, (i64, i64)) = if distinct.len() <= 1 {
+            (self.passages.keys().map(|k| k.as_str()).collect(), ORIGIN)
+        } else {
+            let min_x = authored.values().map(|p| p.0).min().unwrap_or(ORIGIN.0);
+            let max_y = authored.values().map(|p| p.1).max().unwrap_or(ORIGIN.1);
+            let missing = self
+                .passages
+                .keys()
+                .map(|k| k.as_str())
+                .filter(|k| !authored.contains_key(k))
+                .collect();
+            (missing, (min_x, max_y + SPACING))
+        };
+
+        let rows = match style {
+            LayoutStyle::Grid => {
+                let mut names: Vec<&str> = to_place.iter().copied().collect();
+                names.sort_unstable();
+                let columns = ((names.len() as f64).sqrt().ceil() as usize).max(1);
+                names.chunks(columns).map(|row| row.to_vec()).collect()
+            }
+            LayoutStyle::Layered => self.layers(&to_place),
+        };
+
+        let mut positions = Vec::new();
+        for (y, row) in rows.iter().enumerate() {
+            for (x, name) in row.iter().enumerate() {
+                let position = format!("{},{}", origin.0 + x as i64 * SPACING, origin.1 + y as i64 * SPACING);
+                positions.push((name.to_string(), position));
+            }
+        }
+
+        let mut moved = Vec::new();
+        for (name, position) in positions {
+            let header = &mut self.passages.get_mut(&name).unwrap().header;
+            header.metadata.insert("position".to_string(), position.into());
+            moved.push(name);
+        }
+        moved.sort();
+        moved
+    }
+
+    /// Groups the given passages into rows by their distance from the start
+    /// passage, in the order they're found, followed by a row of unreachable
+    /// passages in sorted order
+    fn layers<'a>(&'a self, to_place: &HashSet<&'a str>) -> Vec<Vec<&'a str>> {
+        let mut depths: Vec<Vec<&str>> = Vec::new();
+        let mut seen = HashSet::new();
+        let start = self
+            .get_start_passage_name()
+            .and_then(|name| self.passages.get_key_value(name))
+            .map(|(k, _)| k.as_str());
+        let mut frontier: Vec<&str> = start.into_iter().collect();
+        seen.extend(frontier.iter().copied());
+        while !frontier.is_empty() {
+            let mut next = Vec::new();
+            for name in &frontier {
+                for target in self.successors(name) {
+                    if seen.insert(target) {
+                        next.push(target);
+                    }
+                }
+            }
+            depths.push(frontier);
+            frontier = next;
+        }
+
+        let mut unreachable: Vec<&str> = to_place.iter().copied().filter(|n| !seen.contains(n)).collect();
+        unreachable.sort_unstable();
+        depths.push(unreachable);
+
+        depths
+            .into_iter()
+            .map(|row| row.into_iter().filter(|n| to_place.contains(n)).collect::<Vec<_>>())
+            .filter(|row| !row.is_empty())
+            .collect()
+    }
+}
+
+/// Parses a Twine 2 `"x,y"` position, rounding to whole pixels
+fn parse_position(position: &str) -> Option<(i64, i64)> {
+    let (x, y) = position.split_once(',')?;
+    let x: f64 = x.trim().parse().ok()?;
+    let y: f64 = y.trim().parse().ok()?;
+    Some((x.round() as i64, y.round() as i64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn position(story: &StoryPassages, name: &str) -> String {
+        story.passages[name].header.metadata["position"].as_str().unwrap().to_string()
+    }
+
+    #[test]
+    fn grid() {
+        let input = ":: A {\"position\":\"5,5\"}\na\n\n:: B {\"position\":\"5,5\"}\nb\n\n:: C\nc\n\n:: D\nd\n\n:: E\ne\n".to_string();
+        let mut story = StoryPassages::from_string(input).take().0.ok().unwrap();
+        assert_eq!(story.auto_layout(LayoutStyle::Grid).len(), 5);
+        assert_eq!(position(&story, "A"), "100,100");
+        assert_eq!(position(&story, "C"), "400,100");
+        assert_eq!(position(&story, "D"), "100,250");
+        assert_eq!(position(&story, "E"), "250,250");
+    }
+
+    #[test]
+    fn only_missing() {
+        let input = ":: Start {\"position\":\"300,200\"}\n[[B]]\n\n:: A {\"position\":\"50.4,700\"}\na\n\n:: B\nb\n\n:: C\nc\n".to_string();
+        let mut story = StoryPassages::from_string(input).take().0.ok().unwrap();
+        assert_eq!(story.auto_layout(LayoutStyle::Layered), vec!["B", "C"]);
+        assert_eq!(position(&story, "Start"), "300,200");
+        assert_eq!(position(&story, "B"), "50,850");
+        assert_eq!(position(&story, "C"), "50,1000");
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!(parse_position(" 10.6, 20"), Some((11, 20)));
+        assert_eq!(parse_position("10"), None);
+        assert_eq!(parse_position("a,b"), None);
+    }
+}
@@ -0,0 +1,13 @@
+/// The arrangement used by [`StoryPassages::auto_layout`] to position
+/// passages
+///
+/// [`StoryPassages::auto_layout`]: struct.StoryPassages.html#method.auto_layout
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum LayoutStyle {
+    /// A square grid, in order of passage name
+    Grid,
+
+    /// Rows of passages by their distance in links from the start passage,
+    /// with passages that can't be reached from it in a final row
+    Layered,
+}
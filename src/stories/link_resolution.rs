@@ -0,0 +1,104 @@
+use crate::StoryPassages;
+
+impl StoryPassages {
+    /// Resolves the target of a link found in the passage named `from` to the
+    /// name of an existing passage, or returns `None` if the link is dead
+    ///
+    /// Whitespace surrounding the target is ignored, so that a whitespace
+    /// warning and a dead link warning aren't both generated for one link.
+    ///
+    /// If the story was parsed with [`directory_namespaces`] enabled, passage
+    /// names are treated as `/` separated paths and the target is resolved
+    /// relative to the directory of `from`:
+    /// * `/Name` refers to `Name` in the top level directory
+    /// * `./Name` and `../Name` refer to `Name` in the same or parent directory
+    /// * Any other target is looked up in the directory of `from`, then in
+    ///   each enclosing directory in turn
+    ///
+    /// # Examples
+    /// ```
+    /// use tweep::StoryPassages;
+    /// let input = ":: Start\nGo to [[ Next ]]\n\n:: Next\nThe end".to_string();
+    /// let story = StoryPassages::from_string(input).take().0.ok().unwrap();
+    /// assert_eq!(story.resolve_link("Start", " Next "), Some("Next"));
+    /// assert_eq!(story.resolve_link("Start", "Nowhere"), None);
+    /// ```
+    ///
+    /// [`directory_namespaces`]: struct.ParseOptions.html#structfield.directory_namespaces
+    pub fn resolve_link(&self, from: &str, target: &str) -> Option<&str> {
+        let target = target.trim();
+        if !self.namespaced {
+            return self.passages.get_key_value(target).map(|(k, _)| k.as_str());
+        }
+
+        namespace_candidates(from, target)
+            .iter()
+            .find_map(|name| self.passages.get_key_value(name.as_str()))
+            .map(|(k, _)| k.as_str())
+    }
+}
+
+/// Given the name of a passage and the target of a link within it, returns the
+/// list of fully namespaced passage names the link may refer to, in order of
+/// preference
+fn namespace_candidates(from: &str, target: &str) -> Vec<String> {
+    let mut dir: Vec<&str> = from.split('/').collect();
+    dir.pop();
+
+    if let Some(absolute) = target.strip_prefix('/') {
+        return vec![absolute.to_string()];
+    }
+
+    if target.starts_with("./") || target.starts_with("../") {
+        for component in target.split('/') {
+            match component {
+                "." => (),
+                ".." => {
+                    // Can't go above the top level directory
+                    if dir.pop().is_none() {
+                        return Vec::new();
+                    }
+                }
+                _ => dir.push(component),
+            }
+        }
+        return vec![dir.join("/")];
+    }
+
+    (0..=dir.len())
+        .rev()
+        .map(|n| {
+            let mut path = dir[..n].to_vec();
+            path.push(target);
+            path.join("/")
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn candidates() {
+        assert_eq!(namespace_candidates("Start", "Next"), vec!["Next"]);
+        assert_eq!(
+            namespace_candidates("a/b/Start", "Next"),
+            vec!["a/b/Next", "a/Next", "Next"]
+        );
+        assert_eq!(namespace_candidates("a/b/Start", "c/Next"), vec!["a/b/c/Next", "a/c/Next", "c/Next"]);
+        assert_eq!(namespace_candidates("a/b/Start", "/Next"), vec!["Next"]);
+        assert_eq!(namespace_candidates("a/b/Start", "./Next"), vec!["a/b/Next"]);
+        assert_eq!(namespace_candidates("a/b/Start", "../Next"), vec!["a/Next"]);
+        assert_eq!(namespace_candidates("a/b/Start", "../../c/Next"), vec!["c/Next"]);
+        assert!(namespace_candidates("Start", "../Next").is_empty());
+    }
+
+    #[test]
+    fn without_namespaces() {
+        let input = ":: a/Start\n[[Next]] [[a/Next]]\n\n:: a/Next\nFoo".to_string();
+        let story = StoryPassages::from_string(input).take().0.ok().unwrap();
+        assert_eq!(story.resolve_link("a/Start", "Next"), None);
+        assert_eq!(story.resolve_link("a/Start", "a/Next"), Some("a/Next"));
+    }
+}
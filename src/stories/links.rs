@@ -0,0 +1,84 @@
+use crate::PassageContent;
+use crate::StoryPassages;
+use crate::TwineLink;
+
+impl StoryPassages {
+    /// Returns every link in the story along with the name of the passage
+    /// containing it. Passages are visited in order of name, and the links
+    /// within each passage in the order they appear
+    ///
+    /// Only normal passages can contain links, so special passages, scripts
+    /// and stylesheets are skipped.
+    ///
+    /// # Examples
+    /// ```
+    /// use tweep::StoryPassages;
+    /// let input = ":: Start\n[[Next]] [[Back|Start]]\n\n:: Next\n[[Start]]\n".to_string();
+    /// let story = StoryPassages::from_string(input).take().0.ok().unwrap();
+    /// let links: Vec<(&str, &str)> = story
+    ///     .links()
+    ///     .map(|(name, link)| (name, link.target.as_str()))
+    ///     .collect();
+    /// assert_eq!(links, vec![("Next", "Start"), ("Start", "Next"), ("Start", "Start")]);
+    /// ```
+    pub fn links(&self) -> impl Iterator<Item = (&str, &TwineLink)> {
+        let mut names: Vec<&String> = self.passages.keys().collect();
+        names.sort();
+        names.into_iter().flat_map(move |name| {
+            let links: &[TwineLink] = match &self.passages[name].content {
+                PassageContent::Normal(twine) => twine.get_links(),
+                _ => &[],
+            };
+            links.iter().map(move |link| (name.as_str(), link))
+        })
+    }
+
+    /// Returns the names of the passages that the passage named `name` leads
+    /// to, in order: the targets of its links that resolve, followed by the
+    /// passages it transcludes if the story's [`config`] resolves
+    /// transclusions. If it has a dynamic link, every dynamic target in the
+    /// `config` follows
+    ///
+    /// [`config`]: #structfield.config
+    pub(crate) fn successors(&self, name: &str) -> Vec<&str> {
+        let twine = match self.passages.get(name).map(|p| &p.content) {
+            Some(PassageContent::Normal(twine)) => twine,
+            _ => return Vec::new(),
+        };
+        let links = twine.get_links().iter().map(|link| link.target.as_str());
+        let transclusions = twine.transclusions().into_iter().filter(|_| self.config.resolve_transclusions.unwrap_or(false));
+        let mut successors: Vec<&str> =
+            links.chain(transclusions).filter_map(|target| self.resolve_link(name, target)).collect();
+        if twine.get_links().iter().any(|link| link.is_dynamic() && self.resolve_link(name, &link.target).is_none()) {
+            successors.extend(self.config.dynamic_targets.iter().filter_map(|target| self.resolve_link(name, target)));
+        }
+        successors
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ParseOptions;
+    use crate::StoryPassages;
+    use crate::WarningKind;
+
+    #[test]
+    fn links() {
+        let input = ":: Start\n[[A]]\n\n:: StoryTitle\n[[Not a link]]\n\n:: Script [script]\n[[B]]\n\n:: A\nNo links\n".to_string();
+        let story = StoryPassages::from_string(input).take().0.ok().unwrap();
+        let links: Vec<(&str, &str)> = story.links().map(|(name, link)| (name, link.target.as_str())).collect();
+        assert_eq!(links, vec![("Start", "A")]);
+    }
+
+    #[test]
+    fn dynamic_links() {
+        let input = ":: Start\n[[Go|$destination]] [[Room]]\n\n:: Room\nR\n\n:: Cellar\nC\n".to_string();
+        let options = ParseOptions::new().with_dynamic_target("Cellar").with_dynamic_target("Attic");
+        let story = StoryPassages::from_string_with_options(input, &options).take().0.ok().unwrap();
+        let warnings = story.check();
+        let kinds: Vec<&WarningKind> = warnings.iter().map(|w| &w.kind).collect();
+        assert!(!kinds.iter().any(|kind| matches!(kind, WarningKind::DeadLink(_))));
+        assert!(kinds.contains(&&WarningKind::DeadDynamicTarget("Attic".to_string())));
+        assert!(story.reachable_from("Start").unwrap().unreachable.is_empty());
+    }
+}
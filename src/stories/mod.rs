@@ -1,15 +1,152 @@
+mod accessibility_lint;
+
+mod accessors;
+
+mod asset_reference;
+pub use asset_reference::AssetReference;
+
+mod assets;
+
+mod author_notes;
+
+mod build_profile;
+pub use build_profile::BuildProfile;
+
+mod cancellation_token;
+pub use cancellation_token::CancellationToken;
+
+mod check_options;
+pub use check_options::CheckOptions;
+
 #[cfg(feature = "full-context")]
 mod code_map;
 #[cfg(feature = "full-context")]
 pub use code_map::CodeMap;
 
+mod concatenation;
+
+mod config;
+
 #[cfg(feature = "full-context")]
 mod context_error_list;
 #[cfg(feature = "full-context")]
 pub use context_error_list::ContextErrorList;
 
+mod endings;
+
+mod export_filter;
+pub use export_filter::ExportFilter;
+
+mod export_options;
+pub use export_options::ExportOptions;
+
+mod fallbacks;
+
+mod file_database;
+
+mod file_report;
+pub use file_report::FileReport;
+
+mod fix;
+pub use fix::Fix;
+
+mod fix_session;
+pub use fix_session::FixSession;
+
+mod glob;
+
+mod graph_export;
+
+mod layout;
+
+mod layout_style;
+pub use layout_style::LayoutStyle;
+
+mod html_lint;
+
+mod link_resolution;
+
+mod links;
+
+mod name_lint;
+
+mod ordering;
+
+mod parse_observer;
+pub use parse_observer::ParseObserver;
+
+mod parse_options;
+pub use parse_options::ParseOptions;
+
+mod passage_degree;
+pub use passage_degree::PassageDegree;
+
+mod passage_diff;
+pub use passage_diff::PassageDiff;
+
+mod passage_order;
+pub use passage_order::PassageOrder;
+
+mod pids;
+
+mod profile;
+
+mod prose_checker;
+pub use prose_checker::ProseChecker;
+
+mod reachability;
+pub use reachability::Reachability;
+
+mod replace;
+
+mod replace_scope;
+pub use replace_scope::ReplaceScope;
+
+mod search;
+
+mod search_match;
+pub use search_match::SearchMatch;
+
+mod search_scope;
+pub use search_scope::SearchScope;
+
 mod story;
 pub use story::Story;
 
+#[cfg(feature = "binary")]
+mod story_binary;
+
+mod story_config;
+pub use story_config::StoryConfig;
+
+mod story_manifest;
+pub use story_manifest::StoryManifest;
+
+mod story_merge;
+
 mod story_passages;
 pub use story_passages::StoryPassages;
+
+mod story_skeleton;
+pub use story_skeleton::StorySkeleton;
+
+mod tag_rename;
+
+mod tag_usage;
+pub use tag_usage::TagUsage;
+
+mod templates;
+
+mod validation_report;
+pub use validation_report::ValidationReport;
+
+mod text_edit;
+pub use text_edit::TextEdit;
+
+mod workspace;
+pub use workspace::Workspace;
+
+mod yarn_export;
+
+#[cfg(feature = "zip")]
+mod zip_input;
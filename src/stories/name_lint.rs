@@ -0,0 +1,108 @@
+use crate::StoryPassages;
+use crate::Warning;
+use crate::WarningKind;
+
+impl StoryPassages {
+    /// Checks the names of the normal passages for accidental near-duplicates:
+    /// pairs of names that differ only by case or whitespace, or by a single
+    /// inserted, removed, or substituted character. Returns one warning per
+    /// pair, in order of passage name, pointing at the later passage with the
+    /// earlier one as its referent
+    ///
+    /// # Warnings
+    /// * [`SimilarPassageNames`] - Two passage names such as `The Cellar` and
+    ///   `The  Cellar` that are almost, but not quite, the same
+    ///
+    /// # Examples
+    /// ```
+    /// use tweep::{StoryPassages, WarningKind};
+    /// let input = ":: The Cellar\nDark\n\n:: The  Cellar\nDamp\n\n:: The Attic\nDusty\n".to_string();
+    /// let story = StoryPassages::from_string(input).take().0.ok().unwrap();
+    /// let kinds: Vec<WarningKind> = story.check_passage_names().into_iter().map(|w| w.kind).collect();
+    /// assert_eq!(
+    ///     kinds,
+    ///     vec![WarningKind::SimilarPassageNames("The Cellar".to_string(), "The  Cellar".to_string())]
+    /// );
+    /// ```
+    ///
+    /// [`SimilarPassageNames`]: enum.WarningKind.html#variant.SimilarPassageNames
+    pub fn check_passage_names(&self) -> Vec<Warning> {
+        let mut names: Vec<&String> = self.passages.keys().collect();
+        names.sort();
+        let normalized: Vec<String> = names.iter().map(|name| normalize(name)).collect();
+
+        let mut warnings = Vec::new();
+        for (i, first) in names.iter().enumerate() {
+            for (j, second) in names.iter().enumerate().skip(i + 1) {
+                if normalized[i] != normalized[j] && !within_one_edit(first, second) {
+                    continue;
+                }
+                let kind = WarningKind::SimilarPassageNames(second.to_string(), first.to_string());
+                let warning = Warning::new(kind, Some(self.passages[*second].context.clone()))
+                    .with_referent(self.passages[*first].context.clone());
+                warnings.push(warning);
+            }
+        }
+        warnings
+    }
+}
+
+/// Lowercases a name and collapses each run of whitespace to a single space
+fn normalize(name: &str) -> String {
+    name.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase()
+}
+
+/// Returns `true` if the Levenshtein distance between `a` and `b` is at most
+/// one
+fn within_one_edit(a: &str, b: &str) -> bool {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let (short, long) = if a.len() <= b.len() { (&a, &b) } else { (&b, &a) };
+    if long.len() - short.len() > 1 {
+        return false;
+    }
+
+    // Skip the common prefix, then the rest must match after one edit
+    let prefix = short.iter().zip(long.iter()).take_while(|(x, y)| x == y).count();
+    if short.len() == long.len() {
+        short[prefix..].iter().skip(1).eq(long[prefix..].iter().skip(1))
+    } else {
+        short[prefix..] == long[prefix + 1..]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn edit_distance() {
+        assert!(within_one_edit("Cellar", "Cellar"));
+        assert!(within_one_edit("Cellar", "Celar"));
+        assert!(within_one_edit("Celar", "Cellar"));
+        assert!(within_one_edit("Cellar", "Cellor"));
+        assert!(within_one_edit("", "a"));
+        assert!(!within_one_edit("Cellar", "Clelar"));
+        assert!(!within_one_edit("Cellar", "Cel"));
+        assert!(!within_one_edit("Attic", "Cellar"));
+    }
+
+    #[test]
+    fn similar_names() {
+        let input = ":: Start\n[[The Cellar]]\n\n:: The Cellar\nDark\n\n:: the cellar\nDamp\n\n:: Hall\nLong\n\n:: Hal\nShort\n"
+            .to_string();
+        let story = StoryPassages::from_string(input).take().0.ok().unwrap();
+        let warnings = story.check_passage_names();
+        let pairs: Vec<WarningKind> = warnings.iter().map(|w| w.kind.clone()).collect();
+        assert_eq!(
+            pairs,
+            vec![
+                WarningKind::SimilarPassageNames("Hall".to_string(), "Hal".to_string()),
+                WarningKind::SimilarPassageNames("the cellar".to_string(), "The Cellar".to_string()),
+            ]
+        );
+        let expected = Warning::new(pairs[0].clone(), Some(story.passages["Hall"].context.clone()))
+            .with_referent(story.passages["Hal"].context.clone());
+        assert_eq!(warnings[0], expected);
+    }
+}
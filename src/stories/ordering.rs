@@ -0,0 +1,182 @@
+use crate::ExportFilter;
+use crate::ExportOptions;
+use crate::Passage;
+use crate::PassageOrder;
+use crate::Position;
+use crate::StoryPassages;
+use std::collections::HashSet;
+use std::path::Path;
+
+impl StoryPassages {
+    /// Returns every passage in the story, special or not, in the given
+    /// [`PassageOrder`]
+    ///
+    /// # Examples
+    /// ```
+    /// use tweep::{PassageOrder, StoryPassages};
+    /// let input = ":: Start\n[[Zebra]]\n\n:: StoryTitle\nZoo\n\n:: Apple\nA\n\n:: Zebra\nZ".to_string();
+    /// let story = StoryPassages::from_string(input).take().0.ok().unwrap();
+    /// let names = |order: PassageOrder| -> Vec<String> {
+    ///     story.ordered_passages(order).iter().map(|p| p.header.name.clone()).collect()
+    /// };
+    /// assert_eq!(names(PassageOrder::Alphabetical), vec!["Apple", "Start", "StoryTitle", "Zebra"]);
+    /// assert_eq!(names(PassageOrder::Source), vec!["Start", "StoryTitle", "Apple", "Zebra"]);
+    /// assert_eq!(names(PassageOrder::Reachability), vec!["StoryTitle", "Start", "Zebra", "Apple"]);
+    /// assert_eq!(names(PassageOrder::SpecialsFirst), vec!["StoryTitle", "Start", "Apple", "Zebra"]);
+    /// ```
+    ///
+    /// [`PassageOrder`]: enum.PassageOrder.html
+    pub fn ordered_passages(&self, order: PassageOrder) -> Vec<&Passage> {
+        let specials = self.specials_in_source_order();
+        let normal = self.ordered_names(order).into_iter().map(|name| &self.passages[name]);
+        match order {
+            PassageOrder::Alphabetical => {
+                let mut all: Vec<&Passage> = self.all_passages().collect();
+                all.sort_by(|a, b| (&a.header.name, self.source_key(a)).cmp(&(&b.header.name, self.source_key(b))));
+                all
+            }
+            PassageOrder::Source => {
+                let mut all: Vec<&Passage> = self.all_passages().collect();
+                all.sort_by_key(|p| self.source_key(p));
+                all
+            }
+            PassageOrder::Reachability | PassageOrder::SpecialsFirst => specials.into_iter().chain(normal).collect(),
+        }
+    }
+
+    /// Returns the names of the non-special passages in the order they appeared
+    /// in the input. Passages from different files are ordered by the order in
+    /// which the files were parsed and merged: the order they were given in,
+    /// with the files of a directory in order of path
+    ///
+    /// # Examples
+    /// ```
+    /// use tweep::StoryPassages;
+    /// let input = ":: Zebra\n[[Apple]]\n\n:: Apple\nA\n\n:: Mango\nM".to_string();
+    /// let story = StoryPassages::from_string(input).take().0.ok().unwrap();
+    /// assert_eq!(story.source_order(), vec!["Zebra", "Apple", "Mango"]);
+    /// ```
+    pub fn source_order(&self) -> Vec<&String> {
+        let mut names: Vec<&String> = self.passages.keys().collect();
+        names.sort_by_key(|name| self.source_key(&self.passages[*name]));
+        names
+    }
+
+    /// Returns the names of the non-special passages in the given order
+    pub(crate) fn ordered_names(&self, order: PassageOrder) -> Vec<&String> {
+        let mut names: Vec<&String> = self.passages.keys().collect();
+        match order {
+            PassageOrder::Alphabetical => names.sort(),
+            PassageOrder::Source | PassageOrder::SpecialsFirst => return self.source_order(),
+            PassageOrder::Reachability => {
+                names.sort();
+                let mut ordered = Vec::new();
+                let mut seen = HashSet::new();
+                if let Some((start, _)) = self
+                    .get_start_passage_name()
+                    .and_then(|name| self.passages.get_key_value(name))
+                {
+                    seen.insert(start);
+                    ordered.push(start);
+                }
+                let mut i = 0;
+                while i < ordered.len() {
+                    let name = ordered[i];
+                    for target in self.successors(name) {
+                        let (target, _) = self.passages.get_key_value(target).expect("Successors are passages");
+                        if seen.insert(target) {
+                            ordered.push(target);
+                        }
+                    }
+                    i += 1;
+                }
+                ordered.extend(names.into_iter().filter(|name| !seen.contains(name)));
+                return ordered;
+            }
+        }
+        names
+    }
+
+    /// Returns the names of the non-special passages allowed by the filter of
+    /// `options`, in the order they give
+    pub(crate) fn exported_names(&self, options: &ExportOptions) -> Vec<&String> {
+        let mut names = self.ordered_names(options.passage_order);
+        names.retain(|name| self.is_exported(name, &options.filter));
+        names
+    }
+
+    /// Returns `true` if the passage named `name` is allowed by `filter`
+    pub(crate) fn is_exported(&self, name: &str, filter: &ExportFilter) -> bool {
+        self.passages.get(name).is_some_and(|passage| filter.allows(passage))
+    }
+
+    /// Returns the special passages: `StoryTitle`, `StoryData`, then scripts,
+    /// stylesheets, and passages with additional special tags, each in source
+    /// order
+    fn specials_in_source_order(&self) -> Vec<&Passage> {
+        let mut scripts: Vec<&Passage> = self.scripts.iter().collect();
+        scripts.sort_by_key(|p| self.source_key(p));
+        let mut stylesheets: Vec<&Passage> = self.stylesheets.iter().collect();
+        stylesheets.sort_by_key(|p| self.source_key(p));
+        let mut tagged: Vec<&Passage> = self.tagged_passages.values().flatten().collect();
+        tagged.sort_by_key(|p| self.source_key(p));
+        self.title
+            .iter()
+            .chain(self.data.iter())
+            .chain(scripts)
+            .chain(stylesheets)
+            .chain(tagged)
+            .collect()
+    }
+
+    /// The key used to sort passages into source order: the position of the
+    /// passage's file in `source_files`, then its path and position
+    fn source_key<'a>(&self, passage: &'a Passage) -> (usize, Option<&'a Path>, Position) {
+        let path = passage.context.get_file_path();
+        let rank = self
+            .source_files
+            .iter()
+            .position(|file| file.as_deref() == path)
+            .unwrap_or(usize::MAX);
+        (rank, path, *passage.context.get_start_position())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn specials_first() {
+        let input = ":: B\nb\n\n:: Second [script]\nx\n\n:: A\na\n\n:: Style [stylesheet]\ny\n\n\
+                     :: First [script]\nz\n\n:: StoryData\n{\"ifid\":\"x\"}\n"
+            .to_string();
+        let story = StoryPassages::from_string(input).take().0.ok().unwrap();
+        let order = PassageOrder::SpecialsFirst;
+        let names: Vec<&str> = story.ordered_passages(order).iter().map(|p| p.header.name.as_str()).collect();
+        assert_eq!(names, vec!["StoryData", "Second", "First", "Style", "B", "A"]);
+        assert_eq!(story.ordered_names(order), vec!["B", "A"]);
+    }
+
+    #[test]
+    fn files_in_given_order() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = tempdir()?;
+        let b = dir.path().join("b.tw");
+        let a = dir.path().join("a.tw");
+        std::fs::write(&b, ":: Zebra\nz\n\n:: Bs [script]\nb();\n\n:: Mango\nm\n")?;
+        std::fs::write(&a, ":: Apple\na\n\n:: As [script]\na();\n")?;
+        let story = StoryPassages::from_paths(vec![&b, &a]).take().0.ok().unwrap();
+        assert_eq!(story.source_order(), vec!["Zebra", "Mango", "Apple"]);
+        let names: Vec<&str> = story
+            .ordered_passages(PassageOrder::Source)
+            .iter()
+            .map(|p| p.header.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["Zebra", "Bs", "Mango", "Apple", "As"]);
+
+        let story = StoryPassages::from_paths(vec![&a, &b]).take().0.ok().unwrap();
+        assert_eq!(story.source_order(), vec!["Apple", "Zebra", "Mango"]);
+        Ok(())
+    }
+}
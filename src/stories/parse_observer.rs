@@ -0,0 +1,49 @@
+use crate::Passage;
+use crate::Warning;
+use std::path::Path;
+
+/// A set of callbacks that are notified of progress while a story is parsed,
+/// for use by long-running consumers such as GUIs, language servers, and
+/// command line tools displaying progress
+///
+/// Every method has an empty default implementation, so implementors only
+/// need to override the events they are interested in. Since the observer is
+/// shared through [`ParseOptions`], methods take `&self`; use interior
+/// mutability to record state.
+///
+/// # Examples
+/// ```
+/// use tweep::{ParseObserver, ParseOptions, Passage, StoryPassages};
+/// use std::sync::Arc;
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+///
+/// #[derive(Default)]
+/// struct Counter(AtomicUsize);
+///
+/// impl ParseObserver for Counter {
+///     fn on_passage_parsed(&self, _passage: &Passage) {
+///         self.0.fetch_add(1, Ordering::SeqCst);
+///     }
+/// }
+///
+/// let counter = Arc::new(Counter::default());
+/// let options = ParseOptions::new().with_observer(counter.clone());
+/// let input = ":: Start\nHello\n\n:: End\nBye".to_string();
+/// let out = StoryPassages::from_string_with_options(input, &options);
+/// assert!(out.is_ok());
+/// assert_eq!(counter.0.load(Ordering::SeqCst), 2);
+/// ```
+///
+/// [`ParseOptions`]: struct.ParseOptions.html
+pub trait ParseObserver: Send + Sync {
+    /// Called when parsing of the file at `path` begins
+    fn on_file_started(&self, _path: &Path) {}
+
+    /// Called after each passage is successfully parsed
+    fn on_passage_parsed(&self, _passage: &Passage) {}
+
+    /// Called for each [`Warning`] as it is produced
+    ///
+    /// [`Warning`]: struct.Warning.html
+    fn on_warning(&self, _warning: &Warning) {}
+}
@@ -0,0 +1,553 @@
+use crate::BuildProfile;
+use crate::CancellationToken;
+use crate::MetadataRegistry;
+use crate::ParseObserver;
+use crate::Passage;
+use crate::PassageHeader;
+use crate::PassageKind;
+use crate::StoryConfig;
+use crate::Warning;
+use super::glob::glob_match;
+use std::path::Path;
+use std::sync::Arc;
+
+/// A predicate deciding whether to skip a directory entry
+type ExcludeFilter = Arc<dyn Fn(&Path) -> bool + Send + Sync>;
+
+/// A function deciding the kind of a passage from its header
+type PassageClassifier = Arc<dyn Fn(&PassageHeader) -> Option<PassageKind> + Send + Sync>;
+
+/// A function deciding whether a link target with no matching passage exists
+/// anyway, given the name of the linking passage and the target
+pub(crate) type LinkResolver = Arc<dyn Fn(&str, &str) -> bool + Send + Sync>;
+
+/// Options that control how a story is parsed
+///
+/// # Examples
+/// ```
+/// use tweep::ParseOptions;
+/// let options = ParseOptions::new().with_directory_namespaces(true);
+/// assert!(options.directory_namespaces);
+/// ```
+#[derive(Clone, Default)]
+pub struct ParseOptions {
+    /// When parsing a directory, also parse any subdirectories, and prefix the
+    /// name of each non-special passage with the path of the subdirectory it
+    /// was found in, relative to the parsed directory (e.g., a passage `Intro`
+    /// in `chapter1/intro.twee` becomes `chapter1/Intro`). Links are then
+    /// resolved relative to the linking passage's directory. See
+    /// [`StoryPassages::resolve_link`] for details.
+    ///
+    /// [`StoryPassages::resolve_link`]: struct.StoryPassages.html#method.resolve_link
+    pub directory_namespaces: bool,
+
+    /// Glob patterns for the files and subdirectories to skip when parsing a
+    /// directory, such as `*_old.twee` or `drafts/`. A pattern ending in `/`
+    /// only matches directories. A pattern containing any other `/` is
+    /// matched against the path of the entry relative to the parsed
+    /// directory, and any other pattern against its name. `*` matches any run
+    /// of characters other than `/`, `**` also matches `/`, and `?` matches a
+    /// single character. Paths given directly to a parse method are never
+    /// skipped
+    ///
+    /// # Examples
+    /// ```
+    /// use tweep::{ParseOptions, StoryPassages};
+    /// # let dir = tempfile::tempdir().unwrap();
+    /// # std::fs::write(dir.path().join("start.twee"), ":: Start\nHi").unwrap();
+    /// # std::fs::write(dir.path().join("start_old.twee"), ":: Old\nBye").unwrap();
+    /// let options = ParseOptions::new().with_exclude("*_old.twee");
+    /// let (res, _) = StoryPassages::from_path_with_options(dir.path(), &options).take();
+    /// assert!(!res.ok().unwrap().passages.contains_key("Old"));
+    /// ```
+    pub exclude: Vec<String>,
+
+    /// A predicate given the path of each file and subdirectory found when
+    /// parsing a directory, which skips the entry if it returns `true`. Used
+    /// along with `exclude`
+    pub exclude_filter: Option<ExcludeFilter>,
+
+    /// Report the twee files that a misconfigured project leaves out: an
+    /// [`ExcludedFile`] warning for each twee file skipped by `exclude` or
+    /// `exclude_filter`, including those in skipped subdirectories, and an
+    /// [`UnmatchedPath`] warning for each directory given to a parse method
+    /// in which no passages were found
+    ///
+    /// # Examples
+    /// ```
+    /// use tweep::{ParseOptions, StoryPassages, WarningKind};
+    /// # let dir = tempfile::tempdir().unwrap();
+    /// # std::fs::write(dir.path().join("start.twee"), ":: Start\nHi").unwrap();
+    /// # std::fs::write(dir.path().join("start_old.twee"), ":: Old\nBye").unwrap();
+    /// let options = ParseOptions::new().with_exclude("*_old.twee").with_report_orphan_files(true);
+    /// let (_, warnings) = StoryPassages::from_path_with_options(dir.path(), &options).take();
+    /// let path = dir.path().join("start_old.twee");
+    /// assert!(warnings.iter().any(|w| w.kind == WarningKind::ExcludedFile(path.clone())));
+    /// ```
+    ///
+    /// [`ExcludedFile`]: enum.WarningKind.html#variant.ExcludedFile
+    /// [`UnmatchedPath`]: enum.WarningKind.html#variant.UnmatchedPath
+    pub report_orphan_files: bool,
+
+    /// Treat whitespace around the `|`, `->`, and `<-` separators of a link as
+    /// insignificant, the way most story formats do at runtime. Link targets
+    /// are stored with surrounding whitespace removed, and no
+    /// [`WhitespaceInLink`] warnings are generated
+    ///
+    /// [`WhitespaceInLink`]: enum.WarningKind.html#variant.WhitespaceInLink
+    pub normalize_link_whitespace: bool,
+
+    /// Report a passage header with an unclosed metadata block, such as
+    /// `:: Name {"position": "5,5"`, as an [`UnclosedMetadataBlock`] error at
+    /// the position of the opening `{`, rather than a [`JsonError`] warning
+    ///
+    /// [`UnclosedMetadataBlock`]: enum.ErrorKind.html#variant.UnclosedMetadataBlock
+    /// [`JsonError`]: enum.WarningKind.html#variant.JsonError
+    pub strict_headers: bool,
+
+    /// Keep the blank lines at the end of a passage, before the next header,
+    /// as part of the passage's content. By default they are trivia between
+    /// passages: the content, and the context and byte range of the passage,
+    /// end with the last line containing anything other than whitespace, so a
+    /// passage reads, counts, and compiles the same however far it is from
+    /// the next. The exact source text, blank lines included, is always
+    /// available from the `raw` method of the content
+    ///
+    /// # Examples
+    /// ```
+    /// use tweep::{ParseOptions, PassageContent, StoryPassages};
+    /// let input = ":: Start\nHello\n  \n\n:: Next\nBye\n".to_string();
+    /// let content = |options: &ParseOptions| {
+    ///     let story = StoryPassages::from_string_with_options(input.clone(), options).take().0.ok().unwrap();
+    ///     match &story.passages["Start"].content {
+    ///         PassageContent::Normal(twine) => twine.content.to_string(),
+    ///         _ => unreachable!(),
+    ///     }
+    /// };
+    /// assert_eq!(content(&ParseOptions::new()), "Hello\n");
+    /// assert_eq!(content(&ParseOptions::new().with_keep_trailing_blank_lines(true)), "Hello\n  \n\n");
+    /// ```
+    pub keep_trailing_blank_lines: bool,
+
+    /// Accept common non-spec patterns found in old Twine 1 projects, each
+    /// with a warning, to ease migration:
+    /// * Tags separated by commas, with a [`LegacyTagSeparator`] warning
+    /// * Windows-1252 punctuation in passage names, and files that aren't
+    ///   valid UTF-8, which are converted with a [`LegacyEncoding`] warning
+    ///
+    /// [`LegacyTagSeparator`]: enum.WarningKind.html#variant.LegacyTagSeparator
+    /// [`LegacyEncoding`]: enum.WarningKind.html#variant.LegacyEncoding
+    pub lenient: bool,
+
+    /// Additional tags that mark a passage as special, like `script` and
+    /// `stylesheet`, such as `module` or `init`. A passage carrying one of
+    /// these tags has its contents stored unparsed, as with a script, and is
+    /// added to the [`tagged_passages`] of the story under the first of these
+    /// tags that it carries, instead of to the map of normal passages. The
+    /// `script` and `stylesheet` tags take precedence over these
+    ///
+    /// [`tagged_passages`]: struct.StoryPassages.html#structfield.tagged_passages
+    pub special_tags: Vec<String>,
+
+    /// Expand template instantiations before the story is checked. A passage
+    /// tagged `template` defines a template named after the passage, and is
+    /// treated as a special tag, so it's kept out of the map of normal
+    /// passages. In the body of any normal passage, an instantiation such as
+    /// `{{greeting name=Ann title="Dr. Who"}}` is then replaced with the body
+    /// of the `greeting` template, with each `{{name}}` and `{{title}}`
+    /// placeholder in it replaced by the matching argument. Templates are not
+    /// expanded within other templates
+    ///
+    /// Problems are reported at the instantiation, with
+    /// [`UnknownTemplate`] and [`MissingTemplateArgument`] warnings. The
+    /// contexts of expanded passages, and of the links in them, refer to the
+    /// expanded text
+    ///
+    /// [`UnknownTemplate`]: enum.WarningKind.html#variant.UnknownTemplate
+    /// [`MissingTemplateArgument`]: enum.WarningKind.html#variant.MissingTemplateArgument
+    pub expand_templates: bool,
+
+    /// The [`BuildProfile`] to build with, if any. Passages it excludes are
+    /// moved to the story's [`excluded_passages`] before the story is checked
+    /// or templates are expanded, and links to them produce
+    /// [`ExcludedLink`] warnings rather than [`DeadLink`] warnings
+    ///
+    /// [`BuildProfile`]: struct.BuildProfile.html
+    /// [`excluded_passages`]: struct.StoryPassages.html#structfield.excluded_passages
+    /// [`ExcludedLink`]: enum.WarningKind.html#variant.ExcludedLink
+    /// [`DeadLink`]: enum.WarningKind.html#variant.DeadLink
+    pub profile: Option<BuildProfile>,
+
+    /// The names of passages that dynamic links, whose targets are variables
+    /// or expressions such as `[[Go|$destination]]`, may lead to at runtime.
+    /// Dynamic links can't be resolved statically, so they never produce
+    /// [`DeadLink`] warnings; instead, each of these names that doesn't match
+    /// a passage produces a [`DeadDynamicTarget`] warning. See
+    /// [`TwineLink::is_dynamic`] for which links are dynamic
+    ///
+    /// [`DeadLink`]: enum.WarningKind.html#variant.DeadLink
+    /// [`DeadDynamicTarget`]: enum.WarningKind.html#variant.DeadDynamicTarget
+    /// [`TwineLink::is_dynamic`]: struct.TwineLink.html#method.is_dynamic
+    pub dynamic_targets: Vec<String>,
+
+    /// Treat normal passages tagged `notes` or `comment` as author notes,
+    /// which document the story rather than being part of it. Author notes
+    /// are still parsed, but are kept in the story's [`notes`] instead of the
+    /// map of normal passages, so they aren't exported, the links in them
+    /// aren't checked, and they are never reported as unreachable
+    ///
+    /// [`notes`]: struct.StoryPassages.html#structfield.notes
+    pub author_notes: bool,
+
+    /// External configuration for the story, merged with the configuration
+    /// in its `StoryConfig` or `TweepConfig` passage, if any. See
+    /// [`StoryConfig`] for details
+    ///
+    /// [`StoryConfig`]: struct.StoryConfig.html
+    pub config: StoryConfig,
+
+    /// If the story has no `StoryTitle` passage, generate one from the name
+    /// of the parsed file, without its extension, or directory. A
+    /// [`GeneratedStoryTitle`] note is produced instead of a
+    /// [`MissingStoryTitle`] warning. Has no effect when parsing a string
+    ///
+    /// [`GeneratedStoryTitle`]: enum.WarningKind.html#variant.GeneratedStoryTitle
+    /// [`MissingStoryTitle`]: enum.WarningKind.html#variant.MissingStoryTitle
+    pub fallback_title: bool,
+
+    /// If the story has no `StoryData` passage, generate one with a new,
+    /// random IFID and the `story_format` and `story_format_version`, if set.
+    /// A [`GeneratedStoryData`] note is produced instead of a
+    /// [`MissingStoryData`] warning
+    ///
+    /// [`GeneratedStoryData`]: enum.WarningKind.html#variant.GeneratedStoryData
+    /// [`MissingStoryData`]: enum.WarningKind.html#variant.MissingStoryData
+    pub generate_story_data: bool,
+
+    /// The story format to use in generated `StoryData`
+    pub story_format: Option<String>,
+
+    /// The story format version to use in generated `StoryData`
+    pub story_format_version: Option<String>,
+
+    /// An observer to be notified of progress while parsing. See
+    /// [`ParseObserver`] for details
+    ///
+    /// [`ParseObserver`]: trait.ParseObserver.html
+    pub observer: Option<Arc<dyn ParseObserver>>,
+
+    /// A token that abandons parsing when cancelled, such as when the input
+    /// has changed since parsing began. The token is checked before parsing
+    /// each file and passage, and before the story is checked, and a
+    /// cancelled parse returns a [`Cancelled`] error. See
+    /// [`CancellationToken`] for details
+    ///
+    /// [`Cancelled`]: enum.ErrorKind.html#variant.Cancelled
+    /// [`CancellationToken`]: struct.CancellationToken.html
+    pub cancellation: Option<CancellationToken>,
+
+    /// Custom metadata fields to validate in passage headers, producing an
+    /// [`InvalidMetadata`] warning for each invalid value. See
+    /// [`MetadataRegistry`] for details
+    ///
+    /// [`InvalidMetadata`]: enum.WarningKind.html#variant.InvalidMetadata
+    /// [`MetadataRegistry`]: struct.MetadataRegistry.html
+    pub metadata: MetadataRegistry,
+
+    /// A function that decides the [`PassageKind`] of each passage from its
+    /// header, returning `None` to leave the passage to the default
+    /// classification. See [`passage_kind`] for details
+    ///
+    /// [`PassageKind`]: enum.PassageKind.html
+    /// [`passage_kind`]: #method.passage_kind
+    pub classifier: Option<PassageClassifier>,
+
+    /// A function consulted when the story is checked for each link target
+    /// that doesn't match a passage, given the name of the linking passage
+    /// and the target. Returning `true` means the target exists anyway, such
+    /// as a passage provided by a companion code module or generated at
+    /// runtime, so no [`DeadLink`] or [`TrimmedLinkMatch`] warning is produced
+    /// for the link
+    ///
+    /// # Examples
+    /// ```
+    /// use tweep::{ParseOptions, StoryPassages};
+    /// let options = ParseOptions::new().with_link_resolver(|_, target| target.starts_with("gen/"));
+    /// let input = ":: Start\n[[gen/Map]] [[Nowhere]]\n".to_string();
+    /// let (res, _) = StoryPassages::from_string_with_options(input, &options).take();
+    /// let dead: Vec<String> = res.ok().unwrap().check_links().into_iter().map(|w| w.to_string()).collect();
+    /// assert_eq!(dead.len(), 1);
+    /// assert!(dead[0].contains("Nowhere"));
+    /// ```
+    ///
+    /// [`DeadLink`]: enum.WarningKind.html#variant.DeadLink
+    /// [`TrimmedLinkMatch`]: enum.WarningKind.html#variant.TrimmedLinkMatch
+    pub link_resolver: Option<LinkResolver>,
+}
+
+impl ParseOptions {
+    /// Creates a new `ParseOptions` with the default settings
+    pub fn new() -> Self {
+        ParseOptions::default()
+    }
+
+    /// Builder method to enable or disable directory namespaces
+    pub fn with_directory_namespaces(mut self, enabled: bool) -> Self {
+        self.directory_namespaces = enabled;
+        self
+    }
+
+    /// Builder method to add a glob pattern to `exclude`
+    pub fn with_exclude(mut self, pattern: &str) -> Self {
+        self.exclude.push(pattern.to_string());
+        self
+    }
+
+    /// Builder method to set the `exclude_filter`
+    pub fn with_exclude_filter<F>(mut self, filter: F) -> Self
+    where
+        F: Fn(&Path) -> bool + Send + Sync + 'static,
+    {
+        self.exclude_filter = Some(Arc::new(filter));
+        self
+    }
+
+    /// Builder method to enable or disable reporting of orphan files
+    pub fn with_report_orphan_files(mut self, enabled: bool) -> Self {
+        self.report_orphan_files = enabled;
+        self
+    }
+
+    /// Builder method to enable or disable link whitespace normalization
+    pub fn with_normalize_link_whitespace(mut self, enabled: bool) -> Self {
+        self.normalize_link_whitespace = enabled;
+        self
+    }
+
+    /// Builder method to enable or disable strict header parsing
+    pub fn with_strict_headers(mut self, enabled: bool) -> Self {
+        self.strict_headers = enabled;
+        self
+    }
+
+    /// Builder method to enable or disable keeping trailing blank lines
+    pub fn with_keep_trailing_blank_lines(mut self, enabled: bool) -> Self {
+        self.keep_trailing_blank_lines = enabled;
+        self
+    }
+
+    /// Builder method to enable or disable lenient parsing
+    pub fn with_lenient(mut self, enabled: bool) -> Self {
+        self.lenient = enabled;
+        self
+    }
+
+    /// Builder method to add a tag to `special_tags`
+    pub fn with_special_tag(mut self, tag: &str) -> Self {
+        self.special_tags.push(tag.to_string());
+        self
+    }
+
+    /// Builder method to enable or disable template expansion
+    pub fn with_expand_templates(mut self, enabled: bool) -> Self {
+        self.expand_templates = enabled;
+        self
+    }
+
+    /// Builder method to set the [`BuildProfile`]
+    ///
+    /// [`BuildProfile`]: struct.BuildProfile.html
+    pub fn with_profile(mut self, profile: BuildProfile) -> Self {
+        self.profile = Some(profile);
+        self
+    }
+
+    /// Builder method to add a name to `dynamic_targets`
+    pub fn with_dynamic_target(mut self, name: &str) -> Self {
+        self.dynamic_targets.push(name.to_string());
+        self
+    }
+
+    /// Builder method to enable or disable author notes
+    pub fn with_author_notes(mut self, enabled: bool) -> Self {
+        self.author_notes = enabled;
+        self
+    }
+
+    /// Builder method to set the external [`StoryConfig`]
+    ///
+    /// [`StoryConfig`]: struct.StoryConfig.html
+    pub fn with_config(mut self, config: StoryConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Builder method to enable or disable generating a missing `StoryTitle`
+    pub fn with_fallback_title(mut self, enabled: bool) -> Self {
+        self.fallback_title = enabled;
+        self
+    }
+
+    /// Builder method to enable generating a missing `StoryData` with the
+    /// given story format and version
+    pub fn with_generated_story_data(mut self, format: Option<&str>, version: Option<&str>) -> Self {
+        self.generate_story_data = true;
+        self.story_format = format.map(str::to_string);
+        self.story_format_version = version.map(str::to_string);
+        self
+    }
+
+    /// Builder method to set the [`ParseObserver`]
+    ///
+    /// [`ParseObserver`]: trait.ParseObserver.html
+    pub fn with_observer(mut self, observer: Arc<dyn ParseObserver>) -> Self {
+        self.observer = Some(observer);
+        self
+    }
+
+    /// Builder method to set the [`CancellationToken`]
+    ///
+    /// [`CancellationToken`]: struct.CancellationToken.html
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancellation = Some(token);
+        self
+    }
+
+    /// Builder method to set the [`MetadataRegistry`]
+    ///
+    /// [`MetadataRegistry`]: struct.MetadataRegistry.html
+    pub fn with_metadata(mut self, registry: MetadataRegistry) -> Self {
+        self.metadata = registry;
+        self
+    }
+
+    /// Builder method to set the `classifier`
+    pub fn with_classifier<F>(mut self, classifier: F) -> Self
+    where
+        F: Fn(&PassageHeader) -> Option<PassageKind> + Send + Sync + 'static,
+    {
+        self.classifier = Some(Arc::new(classifier));
+        self
+    }
+
+    /// Builder method to set the `link_resolver`
+    pub fn with_link_resolver<F>(mut self, resolver: F) -> Self
+    where
+        F: Fn(&str, &str) -> bool + Send + Sync + 'static,
+    {
+        self.link_resolver = Some(Arc::new(resolver));
+        self
+    }
+
+    /// Returns the [`PassageKind`] of the passage with the given header: the
+    /// kind chosen by the `classifier`, if any, and otherwise the kind given
+    /// by [`PassageKind::from_header`] with the special tags in effect
+    ///
+    /// # Examples
+    /// ```
+    /// use tweep::{ParseOptions, PassageKind, StoryPassages};
+    /// let options = ParseOptions::new().with_classifier(|header| {
+    ///     header.name.starts_with("Widget:").then(|| PassageKind::Custom("widget".to_string()))
+    /// });
+    /// let input = ":: Start\nHi\n\n:: Widget: Clock\n<<time>>".to_string();
+    /// let story = StoryPassages::from_string_with_options(input, &options).take().0.ok().unwrap();
+    /// assert_eq!(story.tagged_passages["widget"][0].kind(), &PassageKind::Custom("widget".to_string()));
+    /// assert_eq!(story.passages["Start"].kind(), &PassageKind::Normal);
+    /// ```
+    ///
+    /// [`PassageKind`]: enum.PassageKind.html
+    /// [`PassageKind::from_header`]: enum.PassageKind.html#method.from_header
+    pub fn passage_kind(&self, header: &PassageHeader) -> PassageKind {
+        self.classifier
+            .as_ref()
+            .and_then(|classifier| classifier(header))
+            .unwrap_or_else(|| PassageKind::from_header(header, &self.all_special_tags()))
+    }
+
+    /// Returns `true` if the cancellation token, if any, has been cancelled
+    pub(crate) fn is_cancelled(&self) -> bool {
+        self.cancellation.as_ref().is_some_and(CancellationToken::is_cancelled)
+    }
+
+    /// Returns the additional special tags, including `template` if template
+    /// expansion is enabled
+    pub(crate) fn all_special_tags(&self) -> Vec<String> {
+        let mut tags = self.special_tags.clone();
+        if self.expand_templates && !tags.iter().any(|tag| tag == "template") {
+            tags.push("template".to_string());
+        }
+        tags
+    }
+
+    /// Returns `true` if the directory entry at `path`, which is at `relative`
+    /// within the parsed directory, is skipped by `exclude` or
+    /// `exclude_filter`
+    pub(crate) fn excludes(&self, path: &Path, relative: &str, is_dir: bool) -> bool {
+        let name = relative.rsplit('/').next().unwrap_or(relative);
+        let excluded = self.exclude.iter().any(|pattern| {
+            let (pattern, dir_only) = match pattern.strip_suffix('/') {
+                Some(pattern) => (pattern, true),
+                None => (pattern.as_str(), false),
+            };
+            if dir_only && !is_dir {
+                false
+            } else if pattern.contains('/') {
+                glob_match(pattern, relative)
+            } else {
+                glob_match(pattern, name)
+            }
+        });
+        excluded || self.exclude_filter.as_ref().is_some_and(|filter| filter(path))
+    }
+
+    /// Notifies the observer, if any, that parsing of a file has begun
+    pub(crate) fn notify_file_started(&self, path: &Path) {
+        if let Some(observer) = &self.observer {
+            observer.on_file_started(path);
+        }
+    }
+
+    /// Notifies the observer, if any, that a passage has been parsed
+    pub(crate) fn notify_passage_parsed(&self, passage: &Passage) {
+        if let Some(observer) = &self.observer {
+            observer.on_passage_parsed(passage);
+        }
+    }
+
+    /// Notifies the observer, if any, of each of the given warnings
+    pub(crate) fn notify_warnings(&self, warnings: &[Warning]) {
+        if let Some(observer) = &self.observer {
+            warnings.iter().for_each(|w| observer.on_warning(w));
+        }
+    }
+}
+
+impl std::fmt::Debug for ParseOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ParseOptions")
+            .field("directory_namespaces", &self.directory_namespaces)
+            .field("exclude", &self.exclude)
+            .field("exclude_filter", &self.exclude_filter.as_ref().map(|_| "Fn(&Path) -> bool"))
+            .field("report_orphan_files", &self.report_orphan_files)
+            .field("normalize_link_whitespace", &self.normalize_link_whitespace)
+            .field("strict_headers", &self.strict_headers)
+            .field("keep_trailing_blank_lines", &self.keep_trailing_blank_lines)
+            .field("lenient", &self.lenient)
+            .field("special_tags", &self.special_tags)
+            .field("expand_templates", &self.expand_templates)
+            .field("profile", &self.profile)
+            .field("dynamic_targets", &self.dynamic_targets)
+            .field("author_notes", &self.author_notes)
+            .field("config", &self.config)
+            .field("fallback_title", &self.fallback_title)
+            .field("generate_story_data", &self.generate_story_data)
+            .field("story_format", &self.story_format)
+            .field("story_format_version", &self.story_format_version)
+            .field("observer", &self.observer.as_ref().map(|_| "ParseObserver"))
+            .field("cancellation", &self.cancellation)
+            .field("metadata", &self.metadata)
+            .field("classifier", &self.classifier.as_ref().map(|_| "Fn(&PassageHeader) -> Option<PassageKind>"))
+            .field("link_resolver", &self.link_resolver.as_ref().map(|_| "Fn(&str, &str) -> bool"))
+            .finish()
+    }
+}
@@ -0,0 +1,77 @@
+use crate::StoryPassages;
+use std::collections::BTreeMap;
+
+/// The number of links into and out of a passage, as produced by
+/// [`StoryPassages::degrees`]
+///
+/// [`StoryPassages::degrees`]: struct.StoryPassages.html#method.degrees
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct PassageDegree {
+    /// The number of links in the story that lead to this passage
+    pub incoming: usize,
+
+    /// The number of links in this passage that lead to a passage
+    pub outgoing: usize,
+
+    /// The number of links in this passage that lead to no passage. Dynamic
+    /// links, whose target is only known at runtime, aren't counted
+    pub dead_outgoing: usize,
+}
+
+impl StoryPassages {
+    /// Counts the links into and out of every normal passage, keyed by
+    /// passage name. Each link is counted, so a passage linking to another
+    /// twice adds two to the outgoing count of one and the incoming count of
+    /// the other, and a passage linking to itself counts toward both of its
+    /// own counts
+    ///
+    /// # Examples
+    /// ```
+    /// use tweep::{PassageDegree, StoryPassages};
+    /// let input = ":: Start\n[[Hall]] [[Nowhere]] [[Hall]]\n\n:: Hall\n[[Start]]\n\n:: Attic\nDust\n".to_string();
+    /// let story = StoryPassages::from_string(input).take().0.ok().unwrap();
+    /// let degrees = story.degrees();
+    /// assert_eq!(degrees["Start"], PassageDegree { incoming: 1, outgoing: 2, dead_outgoing: 1 });
+    /// assert_eq!(degrees["Hall"], PassageDegree { incoming: 2, outgoing: 1, dead_outgoing: 0 });
+    /// assert_eq!(degrees["Attic"], PassageDegree::default());
+    /// ```
+    pub fn degrees(&self) -> BTreeMap<String, PassageDegree> {
+        let mut degrees: BTreeMap<String, PassageDegree> =
+            self.passages.keys().map(|name| (name.clone(), PassageDegree::default())).collect();
+        for (name, link) in self.links() {
+            match self.resolve_link(name, &link.target) {
+                Some(target) => {
+                    if let Some(degree) = degrees.get_mut(target) {
+                        degree.incoming += 1;
+                    }
+                    if let Some(degree) = degrees.get_mut(name) {
+                        degree.outgoing += 1;
+                    }
+                }
+                None if link.is_dynamic() => (),
+                None => {
+                    if let Some(degree) = degrees.get_mut(name) {
+                        degree.dead_outgoing += 1;
+                    }
+                }
+            }
+        }
+        degrees
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::PassageDegree;
+    use crate::StoryPassages;
+
+    #[test]
+    fn degrees() {
+        let input = ":: Start\n[[Start]] [[Go|$place]] [[Dir/Room]]\n\n:: Dir/Room\n[[Gone]]\n\n:: Script [script]\n[[Start]]\n".to_string();
+        let story = StoryPassages::from_string(input).take().0.ok().unwrap();
+        let degrees = story.degrees();
+        assert_eq!(degrees.len(), 2);
+        assert_eq!(degrees["Start"], PassageDegree { incoming: 1, outgoing: 2, dead_outgoing: 0 });
+        assert_eq!(degrees["Dir/Room"], PassageDegree { incoming: 1, outgoing: 0, dead_outgoing: 1 });
+    }
+}
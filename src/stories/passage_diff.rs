@@ -0,0 +1,183 @@
+use crate::Passage;
+use crate::PassageKind;
+use crate::StoryPassages;
+use std::collections::BTreeSet;
+use std::collections::HashMap;
+use std::path::Path;
+use std::path::PathBuf;
+
+/// A passage-level difference between two versions of a story, as produced by
+/// [`StoryPassages::diff`]
+///
+/// Passages are matched by name, kind and source file, so that, for example,
+/// two scripts with the same name in different files are compared separately.
+/// Author notes, passages excluded by the build profile and the config passage
+/// are compared as well. Two passages are considered equal if their source
+/// text (header and content, excluding trailing blank lines) is identical.
+///
+/// [`StoryPassages::diff`]: struct.StoryPassages.html#method.diff
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum PassageDiff {
+    /// A passage with the contained name exists only in the newer story
+    Added(String),
+
+    /// A passage with the contained name exists only in the older story
+    Removed(String),
+
+    /// A passage with the contained name exists in both stories, but its
+    /// source text differs
+    Modified(String),
+}
+
+impl PassageDiff {
+    /// Returns the name of the passage this difference refers to
+    pub fn name(&self) -> &str {
+        match self {
+            PassageDiff::Added(name) => name,
+            PassageDiff::Removed(name) => name,
+            PassageDiff::Modified(name) => name,
+        }
+    }
+}
+
+impl StoryPassages {
+    /// Compares this story to a newer version of it, returning a list of the
+    /// passages that were added, removed, or modified, sorted by passage name
+    ///
+    /// # Examples
+    /// ```
+    /// use tweep::{PassageDiff, StoryPassages};
+    /// let old = StoryPassages::from_string(":: Start\nHello\n\n:: Gone\nBye".to_string());
+    /// let new = StoryPassages::from_string(":: Start\nHello!\n\n:: New\nHi".to_string());
+    /// let old = old.take().0.ok().unwrap();
+    /// let new = new.take().0.ok().unwrap();
+    /// assert_eq!(old.diff(&new), vec![
+    ///     PassageDiff::Removed("Gone".to_string()),
+    ///     PassageDiff::Added("New".to_string()),
+    ///     PassageDiff::Modified("Start".to_string()),
+    /// ]);
+    /// ```
+    pub fn diff(&self, other: &StoryPassages) -> Vec<PassageDiff> {
+        let old = self.passage_sources();
+        let new = other.passage_sources();
+        let keys: BTreeSet<&PassageKey> = old.keys().chain(new.keys()).collect();
+        keys.into_iter()
+            .filter_map(|key| {
+                let name = key.name.clone();
+                match (old.get(key), new.get(key)) {
+                    (Some(_), None) => Some(PassageDiff::Removed(name)),
+                    (None, Some(_)) => Some(PassageDiff::Added(name)),
+                    (Some(a), Some(b)) if a != b => Some(PassageDiff::Modified(name)),
+                    _ => None,
+                }
+            })
+            .collect()
+    }
+
+    /// Returns every passage in the story, including author notes, excluded
+    /// passages and the config passage, along with the key it is matched by
+    pub(crate) fn keyed_passages(&self) -> impl Iterator<Item = (PassageKey, &Passage)> {
+        let keyed = |slot| move |passage| (PassageKey::new(passage, slot), passage);
+        self.all_passages()
+            .map(keyed(PassageSlot::Story))
+            .chain(self.notes.values().map(keyed(PassageSlot::Note)))
+            .chain(self.excluded_passages.values().map(keyed(PassageSlot::Excluded)))
+            .chain(self.config_passage.iter().map(keyed(PassageSlot::Config)))
+    }
+
+    /// Returns a map of passage key to the source text of that passage
+    pub(crate) fn passage_sources(&self) -> HashMap<PassageKey, &str> {
+        self.keyed_passages()
+            .map(|(key, p)| (key, p.context.get_contents()))
+            .collect()
+    }
+
+    /// Adds a passage to the place in the story given by `slot`
+    pub(crate) fn add_to_slot(&mut self, slot: PassageSlot, passage: Passage) {
+        match slot {
+            PassageSlot::Story => self.add_passage(passage),
+            PassageSlot::Note => {
+                self.notes.insert(passage.header.name.clone(), passage);
+            }
+            PassageSlot::Excluded => {
+                self.excluded_passages.insert(passage.header.name.clone(), passage);
+            }
+            PassageSlot::Config => self.config_passage = Some(passage),
+        }
+    }
+}
+
+/// Where a passage is kept in a story
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub(crate) enum PassageSlot {
+    /// Among the passages of the story, special or not
+    Story,
+
+    /// Among the author notes
+    Note,
+
+    /// Among the passages excluded by the build profile
+    Excluded,
+
+    /// As the config passage
+    Config,
+}
+
+/// Identifies a passage across versions of a story by its name, where it is
+/// kept, its kind and the file it was parsed from. Keys sort by name first
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub(crate) struct PassageKey {
+    pub(crate) name: String,
+    pub(crate) slot: PassageSlot,
+    kind: PassageKind,
+    path: Option<PathBuf>,
+}
+
+impl PassageKey {
+    pub(crate) fn new(passage: &Passage, slot: PassageSlot) -> Self {
+        PassageKey {
+            name: passage.header.name.clone(),
+            slot,
+            kind: PassageKind::of_content(&passage.content),
+            path: passage.source_path().map(Path::to_path_buf),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(input: &str) -> StoryPassages {
+        StoryPassages::from_string(input.to_string()).take().0.ok().unwrap()
+    }
+
+    #[test]
+    fn identical() {
+        let input = ":: StoryTitle\nTitle\n\n:: Start [tag]\nHello\n\n:: Style [stylesheet]\nbody {}";
+        assert!(parse(input).diff(&parse(input)).is_empty());
+    }
+
+    #[test]
+    fn trailing_lines_ignored() {
+        let old = parse(":: Start\nHello\n\n:: Other\nFoo");
+        let new = parse(":: Start\nHello\n\n\n\n:: Other\nFoo\n");
+        assert!(old.diff(&new).is_empty());
+    }
+
+    #[test]
+    fn specials_and_headers() {
+        let old = parse(":: StoryTitle\nOld\n\n:: Start\nHello\n\n:: Code [script]\nfoo();");
+        let new = parse(":: StoryTitle\nNew\n\n:: Start [tag]\nHello");
+        let diff = new.diff(&old);
+        assert_eq!(
+            diff,
+            vec![
+                PassageDiff::Added("Code".to_string()),
+                PassageDiff::Modified("Start".to_string()),
+                PassageDiff::Modified("StoryTitle".to_string()),
+            ]
+        );
+        assert_eq!(diff[0].name(), "Code");
+    }
+}
@@ -0,0 +1,26 @@
+/// The order in which exporters such as [`StoryPassages::to_yarn_with`] write
+/// passages, set with [`ExportOptions::passage_order`]. Every order is
+/// deterministic, so output is reproducible from run to run
+///
+/// [`StoryPassages::to_yarn_with`]: struct.StoryPassages.html#method.to_yarn_with
+/// [`ExportOptions::passage_order`]: struct.ExportOptions.html#structfield.passage_order
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum PassageOrder {
+    /// In order of passage name
+    #[default]
+    Alphabetical,
+
+    /// In the order passages appear in the source, by the order in which their
+    /// files were parsed and merged and then by position within the file
+    Source,
+
+    /// The special passages, in source order, followed by the passages
+    /// reachable from the start passage in breadth-first order, then any
+    /// unreachable passages in order of name
+    Reachability,
+
+    /// `StoryTitle`, `StoryData`, scripts, stylesheets, and passages with
+    /// additional special tags, followed by the remaining passages, each in
+    /// source order
+    SpecialsFirst,
+}
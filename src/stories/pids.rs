@@ -0,0 +1,97 @@
+use crate::PassageContent;
+use crate::StoryPassages;
+use std::collections::BTreeMap;
+use std::collections::HashSet;
+
+impl StoryPassages {
+    /// Returns the pid of each normal passage, keyed by passage name. Saving
+    /// this mapping alongside a compiled story and passing it to
+    /// [`assign_pids`] on the next build keeps each passage's pid the same
+    ///
+    /// [`assign_pids`]: #method.assign_pids
+    pub fn pid_map(&self) -> BTreeMap<String, usize> {
+        self.passages
+            .iter()
+            .filter_map(|(name, passage)| match &passage.content {
+                PassageContent::Normal(twine) => Some((name.clone(), twine.pid)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Assigns the pids of the normal passages, keeping the pid each passage
+    /// had in `previous`, such as the [`pid_map`] of an earlier build, so that
+    /// story formats which save the pid of the current passage can still load
+    /// old saves. Passages that aren't in `previous`, or whose previous pid is
+    /// 0 or was already taken by another passage, get new pids counting up
+    /// from the highest pid in `previous`, in source order. Returns the new
+    /// [`pid_map`]
+    ///
+    /// # Examples
+    /// ```
+    /// use tweep::StoryPassages;
+    /// use std::collections::BTreeMap;
+    /// let input = ":: Start\n[[Cellar]]\n\n:: Attic\nDust\n\n:: Cellar\nDark\n".to_string();
+    /// let mut story = StoryPassages::from_string(input).take().0.ok().unwrap();
+    /// let mut previous = BTreeMap::new();
+    /// previous.insert("Start".to_string(), 1);
+    /// previous.insert("Cellar".to_string(), 2);
+    /// previous.insert("Garden".to_string(), 3);
+    /// let pids = story.assign_pids(&previous);
+    /// assert_eq!(pids["Start"], 1);
+    /// assert_eq!(pids["Cellar"], 2);
+    /// assert_eq!(pids["Attic"], 4);
+    /// ```
+    ///
+    /// [`pid_map`]: #method.pid_map
+    pub fn assign_pids(&mut self, previous: &BTreeMap<String, usize>) -> BTreeMap<String, usize> {
+        let mut next = previous.values().max().copied().unwrap_or(0) + 1;
+        let mut taken = HashSet::new();
+        let mut assigned: Vec<(String, usize)> = Vec::new();
+        let mut unassigned = Vec::new();
+        for name in self.source_order() {
+            match previous.get(name) {
+                Some(&pid) if pid != 0 && taken.insert(pid) => assigned.push((name.clone(), pid)),
+                _ => unassigned.push(name.clone()),
+            }
+        }
+        for name in unassigned {
+            assigned.push((name, next));
+            next += 1;
+        }
+
+        for (name, pid) in assigned {
+            if let Some(PassageContent::Normal(twine)) = self.passages.get_mut(&name).map(|p| &mut p.content) {
+                twine.pid = pid;
+            }
+        }
+        self.pid_map()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::StoryPassages;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn stable_pids() {
+        let input = ":: Start\nA\n\n:: B\nB\n\n:: C\nC\n".to_string();
+        let mut story = StoryPassages::from_string(input).take().0.ok().unwrap();
+        let first = story.assign_pids(&BTreeMap::new());
+        let expected: BTreeMap<String, usize> =
+            vec![("Start".to_string(), 1), ("B".to_string(), 2), ("C".to_string(), 3)].into_iter().collect();
+        assert_eq!(first, expected);
+
+        let input = ":: New\nN\n\n:: C\nC\n\n:: Start\nA\n\n:: D\nD\n".to_string();
+        let mut story = StoryPassages::from_string(input).take().0.ok().unwrap();
+        let mut previous = first.clone();
+        previous.insert("D".to_string(), 3);
+        let second = story.assign_pids(&previous);
+        assert_eq!(second["Start"], 1);
+        assert_eq!(second["C"], 3);
+        assert_eq!(second["New"], 4);
+        assert_eq!(second["D"], 5);
+        assert_eq!(story.pid_map(), second);
+    }
+}
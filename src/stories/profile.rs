@@ -0,0 +1,80 @@
+use crate::BuildProfile;
+use crate::StoryPassages;
+
+impl StoryPassages {
+    /// Moves every passage excluded by `profile`, special or not, into
+    /// `excluded_passages`
+    pub(crate) fn apply_profile(&mut self, profile: &BuildProfile) {
+        let passages = std::mem::take(&mut self.passages);
+        for (name, passage) in passages {
+            if profile.excludes(&passage) {
+                self.excluded_passages.insert(name, passage);
+            } else {
+                self.passages.insert(name, passage);
+            }
+        }
+
+        let mut excluded = Vec::new();
+        for list in self
+            .tagged_passages
+            .values_mut()
+            .chain(std::iter::once(&mut self.scripts))
+            .chain(std::iter::once(&mut self.stylesheets))
+        {
+            let (removed, kept) = std::mem::take(list).into_iter().partition(|p| profile.excludes(p));
+            *list = kept;
+            excluded.extend::<Vec<_>>(removed);
+        }
+        if self.title.as_ref().is_some_and(|p| profile.excludes(p)) {
+            excluded.extend(self.title.take());
+        }
+        if self.data.as_ref().is_some_and(|p| profile.excludes(p)) {
+            excluded.extend(self.data.take());
+        }
+        for passage in excluded {
+            self.excluded_passages.insert(passage.header.name.clone(), passage);
+        }
+        self.profile = Some(profile.name.clone());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::BuildProfile;
+    use crate::ParseOptions;
+    use crate::StoryPassages;
+    use crate::WarningKind;
+
+    #[test]
+    fn profile() {
+        let input = ":: Start\n[[Debug menu]] [[Draft]] [[Nowhere]]\n\n:: Debug menu [debug]\nx\n\n\
+                     :: Draft [draft]\ny\n\n:: Cheat [script debug]\ncheat();\n\n:: Real [script]\nreal();\n"
+            .to_string();
+        let options = ParseOptions::new().with_profile(BuildProfile::release().with_excluded_tag("unused"));
+        let story = StoryPassages::from_string_with_options(input.clone(), &options).take().0.ok().unwrap();
+        let mut excluded: Vec<&String> = story.excluded_passages.keys().collect();
+        excluded.sort();
+        assert_eq!(excluded, vec!["Cheat", "Debug menu", "Draft"]);
+        assert_eq!(story.passages.len(), 1);
+        assert_eq!(story.scripts.len(), 1);
+
+        let warnings = story.check();
+        let links: Vec<&WarningKind> = warnings
+            .iter()
+            .map(|w| &w.kind)
+            .filter(|k| matches!(k, WarningKind::DeadLink(_) | WarningKind::ExcludedLink(_, _)))
+            .collect();
+        assert_eq!(
+            links,
+            vec![
+                &WarningKind::ExcludedLink("Debug menu".to_string(), "release".to_string()),
+                &WarningKind::ExcludedLink("Draft".to_string(), "release".to_string()),
+                &WarningKind::DeadLink("Nowhere".to_string()),
+            ]
+        );
+
+        let story = StoryPassages::from_string(input).take().0.ok().unwrap();
+        assert!(story.excluded_passages.is_empty());
+        assert_eq!(story.passages.len(), 3);
+    }
+}
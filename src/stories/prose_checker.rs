@@ -0,0 +1,114 @@
+use crate::PassageContent;
+use crate::StoryPassages;
+use crate::Warning;
+use crate::WarningKind;
+use std::ops::Range;
+
+/// A hook for plugging a spellchecker or style checker into `tweep`
+///
+/// The checker is given the prose of each passage, as produced by
+/// [`TwineContent::prose`], and returns a list of findings, each of which is a
+/// byte range within the prose and a message. Since the prose preserves the
+/// positions of the source, each finding is surfaced as a [`ProseIssue`]
+/// warning pointing at the offending text.
+///
+/// # Examples
+/// ```
+/// use tweep::{ProseChecker, StoryPassages, WarningKind};
+/// use std::ops::Range;
+///
+/// struct Teh;
+///
+/// impl ProseChecker for Teh {
+///     fn check(&self, _passage: &str, prose: &str) -> Vec<(Range<usize>, String)> {
+///         prose.match_indices("teh")
+///             .map(|(i, s)| (i..i + s.len(), "Did you mean `the`?".to_string()))
+///             .collect()
+///     }
+/// }
+///
+/// let input = ":: Start\nOpen [[teh door|Door]]\n\n:: Door\nThe end".to_string();
+/// let story = StoryPassages::from_string(input).take().0.ok().unwrap();
+/// let warnings = story.check_prose(&Teh);
+/// assert_eq!(warnings.len(), 1);
+/// assert_eq!(warnings[0].kind, WarningKind::ProseIssue("Did you mean `the`?".to_string()));
+/// ```
+///
+/// [`TwineContent::prose`]: struct.TwineContent.html#method.prose
+/// [`ProseIssue`]: enum.WarningKind.html#variant.ProseIssue
+pub trait ProseChecker {
+    /// Checks the prose of the passage named `passage`, returning the byte
+    /// range within `prose` and a message for each finding
+    fn check(&self, passage: &str, prose: &str) -> Vec<(Range<usize>, String)>;
+}
+
+impl StoryPassages {
+    /// Runs the given [`ProseChecker`] over the prose of every normal passage
+    /// and returns its findings as [`ProseIssue`] warnings, in order of
+    /// passage name
+    ///
+    /// [`ProseChecker`]: trait.ProseChecker.html
+    /// [`ProseIssue`]: enum.WarningKind.html#variant.ProseIssue
+    pub fn check_prose(&self, checker: &dyn ProseChecker) -> Vec<Warning> {
+        let mut names: Vec<&String> = self.passages.keys().collect();
+        names.sort();
+
+        let mut warnings = Vec::new();
+        for name in names {
+            let passage = &self.passages[name];
+            let twine = match &passage.content {
+                PassageContent::Normal(twine) => twine,
+                _ => continue,
+            };
+
+            // The body begins on the line after the header
+            let body_start = match passage.context.get_contents().find('\n') {
+                Some(pos) => pos + 1,
+                None => continue,
+            };
+            let prose = twine.prose();
+            for (range, message) in checker.check(name, &prose) {
+                let context = passage.context.slice(body_start + range.start..body_start + range.end);
+                warnings.push(Warning::new(WarningKind::ProseIssue(message), Some(context)));
+            }
+        }
+        warnings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Position;
+
+    struct Words;
+
+    impl ProseChecker for Words {
+        fn check(&self, _passage: &str, prose: &str) -> Vec<(Range<usize>, String)> {
+            let mut findings = Vec::new();
+            let mut offset = 0;
+            for word in prose.split(' ') {
+                if word.contains("zz") {
+                    findings.push((offset..offset + word.len(), word.to_string()));
+                }
+                offset += word.len() + 1;
+            }
+            findings
+        }
+    }
+
+    #[test]
+    fn check_prose() {
+        let input = ":: A\nNo typos [[bzzt|B]]\n\n:: B\n<<set $fizz to 1>>\nA buzz here".to_string();
+        let story = StoryPassages::from_string(input).take().0.ok().unwrap();
+        let warnings = story.check_prose(&Words);
+        assert_eq!(warnings.len(), 2);
+        assert_eq!(warnings[0].kind, WarningKind::ProseIssue("bzzt".to_string()));
+        let context = warnings[0].context.as_ref().unwrap();
+        assert_eq!(*context.get_start_position(), Position::abs(2, 12));
+        #[cfg(feature = "full-context")]
+        assert_eq!(*context.get_end_position(), Position::abs(2, 15));
+        let context = warnings[1].context.as_ref().unwrap();
+        assert_eq!(*context.get_start_position(), Position::abs(6, 3));
+    }
+}
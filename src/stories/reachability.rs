@@ -0,0 +1,105 @@
+use crate::StoryPassages;
+use std::collections::BTreeSet;
+
+/// The passages of a story that can and cannot be reached by following links
+/// from a given passage, as produced by [`StoryPassages::reachable_from`]
+///
+/// [`StoryPassages::reachable_from`]: struct.StoryPassages.html#method.reachable_from
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Reachability {
+    /// The names of the passages reachable from the starting passage,
+    /// including the starting passage itself
+    pub reachable: BTreeSet<String>,
+
+    /// The names of the passages that can't be reached from the starting
+    /// passage
+    pub unreachable: BTreeSet<String>,
+}
+
+impl StoryPassages {
+    /// Finds every passage that can be reached by following links from the
+    /// passage named `start`, and every passage that can't. Returns `None` if
+    /// there is no passage named `start`
+    ///
+    /// Dead links are ignored.
+    ///
+    /// # Examples
+    /// ```
+    /// use tweep::StoryPassages;
+    /// let input = r#":: Start
+    /// [[Chapter 2 Start]]
+    ///
+    /// :: Chapter 2 Start
+    /// [[Chapter 2 End]]
+    ///
+    /// :: Chapter 2 End
+    /// The end
+    /// "#.to_string();
+    /// let story = StoryPassages::from_string(input).take().0.ok().unwrap();
+    /// let chapter = story.reachable_from("Chapter 2 Start").unwrap();
+    /// assert!(chapter.reachable.contains("Chapter 2 End"));
+    /// assert!(chapter.unreachable.contains("Start"));
+    /// assert!(story.reachable_from("Chapter 3 Start").is_none());
+    /// ```
+    pub fn reachable_from(&self, start: &str) -> Option<Reachability> {
+        let start = self.passages.get_key_value(start)?.0.as_str();
+        let mut reachable = BTreeSet::new();
+        reachable.insert(start.to_string());
+        let mut stack = vec![start];
+        while let Some(name) = stack.pop() {
+            for target in self.successors(name) {
+                if reachable.insert(target.to_string()) {
+                    stack.push(target);
+                }
+            }
+        }
+
+        let unreachable = self
+            .passages
+            .keys()
+            .filter(|name| !reachable.contains(*name))
+            .cloned()
+            .collect();
+        Some(Reachability {
+            reachable,
+            unreachable,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::StoryPassages;
+
+    #[test]
+    fn reachable_from() {
+        let input = ":: A\n[[B]] [[Nowhere]]\n\n:: B\n[[A]] [[C]]\n\n:: C\nEnd\n\n:: D\n[[A]]".to_string();
+        let story = StoryPassages::from_string(input).take().0.ok().unwrap();
+
+        let from_a = story.reachable_from("A").unwrap();
+        assert_eq!(from_a.reachable.iter().collect::<Vec<_>>(), vec!["A", "B", "C"]);
+        assert_eq!(from_a.unreachable.iter().collect::<Vec<_>>(), vec!["D"]);
+
+        let from_c = story.reachable_from("C").unwrap();
+        assert_eq!(from_c.reachable.iter().collect::<Vec<_>>(), vec!["C"]);
+        assert_eq!(from_c.unreachable.len(), 3);
+
+        let from_d = story.reachable_from("D").unwrap();
+        assert_eq!(from_d.reachable.len(), 4);
+        assert!(from_d.unreachable.is_empty());
+    }
+
+    #[test]
+    fn transclusions() {
+        let input = ":: Start\n<<include \"Header\">>\n[[Next]]\n\n:: Header\n(display: \"Menu\")\n\n:: Menu\nM\n\n:: Next\nN\n"
+            .to_string();
+        let mut story = StoryPassages::from_string(input).take().0.ok().unwrap();
+        let unreachable = |story: &StoryPassages| -> Vec<String> {
+            story.reachable_from("Start").unwrap().unreachable.into_iter().collect()
+        };
+        assert_eq!(unreachable(&story), vec!["Header", "Menu"]);
+
+        story.config.resolve_transclusions = Some(true);
+        assert!(unreachable(&story).is_empty());
+    }
+}
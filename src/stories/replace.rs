@@ -0,0 +1,177 @@
+use crate::passages::display_text;
+use crate::passages::link_target;
+use crate::Output;
+use crate::PassageContent;
+use crate::Position;
+use crate::ReplaceScope;
+use crate::StoryPassages;
+use crate::TextEdit;
+use crate::Warning;
+use crate::WarningKind;
+use std::ops::Range;
+
+/// The part of a passage body a match was found in
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Part {
+    Prose,
+    LinkText,
+    LinkTarget,
+    /// Both the text and target of a simple `[[Target]]` link
+    LinkTextAndTarget,
+    /// Macros, HTML tags, and link syntax
+    Markup,
+    /// More than one of the above
+    Mixed,
+}
+
+impl StoryPassages {
+    /// Finds every occurrence of `find` in the bodies of normal passages and
+    /// returns the edits that replace it with `replacement` within the given
+    /// [`ReplaceScope`], in order of passage name and position. Occurrences in
+    /// macros and HTML tags are never replaced. The story itself is not
+    /// modified
+    ///
+    /// # Warnings
+    /// * [`AmbiguousReplacement`] - An occurrence that can't be replaced
+    ///   without affecting text outside the scope, such as the text of a
+    ///   `[[Target]]` link, which is also its target, or one that spans both
+    ///   prose and a link. No edit is produced for it
+    ///
+    /// # Examples
+    /// ```
+    /// use tweep::{ReplaceScope, StoryPassages, WarningKind};
+    /// let input = ":: Start\nThe cellar. [[Enter the cellar|cellar]] [[cellar]]\n\n:: cellar\nDark".to_string();
+    /// let story = StoryPassages::from_string(input).take().0.ok().unwrap();
+    ///
+    /// let (edits, warnings) = story.replace("cellar", "basement", ReplaceScope::Text).take();
+    /// assert_eq!(edits.len(), 2);
+    /// assert_eq!(warnings[0].kind, WarningKind::AmbiguousReplacement("Start".to_string()));
+    ///
+    /// let (edits, warnings) = story.replace("cellar", "Cellar", ReplaceScope::Targets).take();
+    /// assert_eq!(edits.len(), 1);
+    /// assert_eq!(warnings.len(), 1);
+    /// ```
+    ///
+    /// [`ReplaceScope`]: enum.ReplaceScope.html
+    /// [`AmbiguousReplacement`]: enum.WarningKind.html#variant.AmbiguousReplacement
+    pub fn replace(&self, find: &str, replacement: &str, scope: ReplaceScope) -> Output<Vec<TextEdit>> {
+        let mut edits = Vec::new();
+        let mut warnings = Vec::new();
+        if find.is_empty() {
+            return Output::new(edits);
+        }
+
+        let mut names: Vec<&String> = self.passages.keys().collect();
+        names.sort();
+        for name in names {
+            let passage = &self.passages[name];
+            let twine = match &passage.content {
+                PassageContent::Normal(twine) => twine,
+                _ => continue,
+            };
+            let body = twine.trimmed();
+            let body_start = passage.context.byte_offset(twine.start_position())
+                - passage.context.byte_offset(Position::rel(1, 1));
+
+            // Find the ranges of the text and target of each link in the body
+            let links: Vec<(Range<usize>, Range<usize>, Range<usize>)> = twine
+                .get_links()
+                .iter()
+                .map(|link| {
+                    let source = link.context.get_contents();
+                    let start = link.context.byte_offset(Position::rel(1, 1))
+                        - passage.context.byte_offset(twine.start_position());
+                    let inner = &source[2..source.len() - 2];
+                    let offset_of = |s: &str| start + 2 + (s.as_ptr() as usize - inner.as_ptr() as usize);
+                    let text = display_text(inner);
+                    let target = link_target(inner);
+                    (
+                        start..start + source.len(),
+                        offset_of(text)..offset_of(text) + text.len(),
+                        offset_of(target)..offset_of(target) + target.len(),
+                    )
+                })
+                .collect();
+            let markup = twine.markup_ranges();
+
+            for (i, _) in body.match_indices(find) {
+                let range = i..i + find.len();
+                let part = classify(&range, &links, &markup);
+                let context = passage.context.slice(body_start + range.start..body_start + range.end);
+                match (part, scope) {
+                    (Part::Prose, ReplaceScope::Text)
+                    | (Part::LinkText, ReplaceScope::Text)
+                    | (Part::LinkTarget, ReplaceScope::Targets) => edits.push(TextEdit {
+                        passage: name.clone(),
+                        context,
+                        replacement: replacement.to_string(),
+                    }),
+                    (Part::LinkTextAndTarget, _) | (Part::Mixed, _) => {
+                        warnings.push(Warning::new(WarningKind::AmbiguousReplacement(name.clone()), Some(context)))
+                    }
+                    _ => (),
+                }
+            }
+        }
+        Output::new(edits).with_warnings(warnings)
+    }
+}
+
+/// Determines which part of a passage body the given range lies in
+fn classify(
+    range: &Range<usize>,
+    links: &[(Range<usize>, Range<usize>, Range<usize>)],
+    markup: &[Range<usize>],
+) -> Part {
+    let contains = |outer: &Range<usize>| outer.start <= range.start && range.end <= outer.end;
+    let overlaps = |other: &Range<usize>| other.start < range.end && range.start < other.end;
+
+    let mut overlapping = links.iter().filter(|(whole, _, _)| overlaps(whole));
+    if let Some((whole, text, target)) = overlapping.next() {
+        if overlapping.next().is_some() || !contains(whole) {
+            return Part::Mixed;
+        }
+        return match (contains(text), contains(target)) {
+            (true, true) => Part::LinkTextAndTarget,
+            (true, false) => Part::LinkText,
+            (false, true) => Part::LinkTarget,
+            (false, false) if overlaps(text) || overlaps(target) => Part::Mixed,
+            (false, false) => Part::Markup,
+        };
+    }
+
+    match markup.iter().find(|m| overlaps(m)) {
+        Some(m) if contains(m) => Part::Markup,
+        Some(_) => Part::Mixed,
+        None => Part::Prose,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replace() {
+        let input = ":: A\nGo north. <<if $north>>[[north->North]]<</if>> [[North<-north]] [[North]]\n[[go north|North]]\n\n:: North\nEnd".to_string();
+        let story = StoryPassages::from_string(input).take().0.ok().unwrap();
+
+        let (edits, warnings) = story.replace("north", "south", ReplaceScope::Text).take();
+        let texts: Vec<&str> = edits.iter().map(|e| e.context.get_contents()).collect();
+        assert_eq!(texts, vec!["north"; 4]);
+        assert!(edits.iter().all(|e| e.passage == "A" && e.replacement == "south"));
+        assert!(warnings.is_empty());
+
+        let (edits, warnings) = story.replace("North", "South", ReplaceScope::Targets).take();
+        assert_eq!(edits.len(), 3);
+        assert_eq!(warnings.len(), 1);
+
+        let (edits, warnings) = story.replace("north]]", "x", ReplaceScope::Text).take();
+        assert!(edits.is_empty());
+        assert_eq!(warnings.len(), 1);
+
+        let (edits, warnings) = story.replace("if $north", "x", ReplaceScope::Text).take();
+        assert!(edits.is_empty());
+        assert!(warnings.is_empty());
+    }
+}
@@ -0,0 +1,11 @@
+/// The parts of passage bodies changed by [`StoryPassages::replace`]
+///
+/// [`StoryPassages::replace`]: struct.StoryPassages.html#method.replace
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ReplaceScope {
+    /// Prose and the display text of links, but not link targets
+    Text,
+
+    /// Link targets, but not prose or the display text of links
+    Targets,
+}
@@ -0,0 +1,123 @@
+use crate::PassageContent;
+use crate::SearchMatch;
+use crate::SearchScope;
+use crate::StoryPassages;
+use std::ops::Range;
+
+impl StoryPassages {
+    /// Searches the story for a literal string, returning every match in
+    /// order of passage name and position. Matches within a passage don't
+    /// overlap
+    ///
+    /// # Examples
+    /// ```
+    /// use tweep::{Position, SearchScope, StoryPassages};
+    /// let input = ":: Start\nA door. <<if $door>>[[Open the door|Door]]<</if>>\n\n:: Door\nDone".to_string();
+    /// let story = StoryPassages::from_string(input).take().0.ok().unwrap();
+    /// let matches = story.search("door", SearchScope::Prose);
+    /// assert_eq!(matches.len(), 2);
+    /// assert_eq!(matches[0].passage, "Start");
+    /// assert_eq!(*matches[1].context.get_start_position(), Position::abs(2, 32));
+    /// assert_eq!(story.search("door", SearchScope::All).len(), 3);
+    /// ```
+    pub fn search(&self, pattern: &str, scope: SearchScope) -> Vec<SearchMatch> {
+        if pattern.is_empty() {
+            return Vec::new();
+        }
+        self.search_with(scope, |text| {
+            text.match_indices(pattern)
+                .map(|(i, s)| i..i + s.len())
+                .collect()
+        })
+    }
+
+    /// Searches the story using a custom matcher, such as a regular
+    /// expression, returning every match in order of passage name and
+    /// position. The matcher is called with the text of each passage within
+    /// the scope, and must return the byte ranges of its matches within it
+    ///
+    /// # Examples
+    /// ```
+    /// use tweep::{SearchScope, StoryPassages};
+    /// let input = ":: Start\nRoll 1d6 or 2d20".to_string();
+    /// let story = StoryPassages::from_string(input).take().0.ok().unwrap();
+    ///
+    /// // With the regex crate: |text| re.find_iter(text).map(|m| m.range()).collect()
+    /// let matches = story.search_with(SearchScope::Prose, |text| {
+    ///     text.match_indices('d')
+    ///         .filter(|(i, _)| text[..*i].ends_with(char::is_numeric))
+    ///         .map(|(i, _)| {
+    ///             let start = text[..i].trim_end_matches(char::is_numeric).len();
+    ///             let end = i + 1 + text[i + 1..].len() - text[i + 1..].trim_start_matches(char::is_numeric).len();
+    ///             start..end
+    ///         })
+    ///         .collect()
+    /// });
+    /// let found: Vec<&str> = matches.iter().map(|m| m.text()).collect();
+    /// assert_eq!(found, vec!["1d6", "2d20"]);
+    /// ```
+    pub fn search_with<F>(&self, scope: SearchScope, mut find: F) -> Vec<SearchMatch>
+    where
+        F: FnMut(&str) -> Vec<Range<usize>>,
+    {
+        let mut passages: Vec<_> = match scope {
+            SearchScope::Prose => self.passages.values().collect(),
+            SearchScope::All => self.all_passages().collect(),
+        };
+        passages.sort_by(|a, b| a.header.name.cmp(&b.header.name));
+
+        let mut matches = Vec::new();
+        for passage in passages {
+            let source = passage.context.get_contents();
+            let (text, offset) = match (scope, &passage.content) {
+                (SearchScope::All, _) => (source.to_string(), 0),
+                (SearchScope::Prose, PassageContent::Normal(twine)) => match source.find('\n') {
+                    Some(pos) => (twine.prose(), pos + 1),
+                    None => continue,
+                },
+                (SearchScope::Prose, _) => continue,
+            };
+            for range in find(&text) {
+                if range.is_empty() {
+                    continue;
+                }
+                matches.push(SearchMatch {
+                    passage: passage.header.name.clone(),
+                    context: passage.context.slice(offset + range.start..offset + range.end),
+                });
+            }
+        }
+        matches
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Position;
+    use crate::SearchScope;
+    use crate::StoryPassages;
+
+    #[test]
+    fn search() {
+        let input = ":: StoryTitle\nKey story\n\n:: B {\"key\": 1}\nNo <span class=\"key\">key</span>\n\n:: A [key]\nThe key\nAnother key\n\n:: Script [script]\nvar key;".to_string();
+        let story = StoryPassages::from_string(input).take().0.ok().unwrap();
+
+        let prose = story.search("key", SearchScope::Prose);
+        let found: Vec<(&str, Position)> = prose
+            .iter()
+            .map(|m| (m.passage.as_str(), *m.context.get_start_position()))
+            .collect();
+        assert_eq!(found, vec![
+            ("A", Position::abs(8, 5)),
+            ("A", Position::abs(9, 9)),
+            ("B", Position::abs(5, 22)),
+        ]);
+        assert!(prose.iter().all(|m| m.text() == "key"));
+
+        let all = story.search("key", SearchScope::All);
+        let passages: Vec<&str> = all.iter().map(|m| m.passage.as_str()).collect();
+        assert_eq!(passages, vec!["A", "A", "A", "B", "B", "B", "Script"]);
+
+        assert!(story.search("", SearchScope::All).is_empty());
+    }
+}
@@ -0,0 +1,20 @@
+use crate::FullContext;
+
+/// A match found by [`StoryPassages::search`]
+///
+/// [`StoryPassages::search`]: struct.StoryPassages.html#method.search
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SearchMatch {
+    /// The name of the passage containing the match
+    pub passage: String,
+
+    /// The context of the match, including its file and span
+    pub context: FullContext,
+}
+
+impl SearchMatch {
+    /// Returns the matched source text
+    pub fn text(&self) -> &str {
+        self.context.get_contents()
+    }
+}
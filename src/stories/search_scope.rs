@@ -0,0 +1,16 @@
+/// The parts of a story searched by [`StoryPassages::search`]
+///
+/// [`StoryPassages::search`]: struct.StoryPassages.html#method.search
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SearchScope {
+    /// Only the prose of normal passages, as produced by
+    /// [`TwineContent::prose`], so that macros, HTML tags, and link targets
+    /// never match
+    ///
+    /// [`TwineContent::prose`]: struct.TwineContent.html#method.prose
+    Prose,
+
+    /// The full source of every passage, including headers, metadata,
+    /// scripts, stylesheets, and special passages
+    All,
+}
@@ -5,6 +5,7 @@ use crate::ContextErrorList;
 #[cfg(not(feature = "full-context"))]
 use crate::ErrorList;
 use crate::Output;
+use crate::ParseOptions;
 use crate::PassageContent;
 use crate::StoryData;
 use crate::StoryPassages;
@@ -24,6 +25,7 @@ use std::path::Path;
 ///
 /// # Parse Errors
 /// * [`BadInputPath`] - The given `Path` cannot be used to parse a story
+///
 /// See [`Passage`] for other errors that can occur during parsing
 ///
 /// # Parse Warnings
@@ -36,6 +38,7 @@ use std::path::Path;
 ///   passage set in `StoryData`
 /// * [`DeadStartPassage`] - Alternate start passage set in `StoryData`, but
 ///   no such passage found in parsing
+///
 /// See [`Passage`] for other warnings that can occur during parsing
 ///
 ///
@@ -118,12 +121,25 @@ pub struct Story {
     /// Map from passage name to `TwinePassage` for any non-special passages
     pub passages: HashMap<String, TwinePassage>,
 
+    /// The names of the non-special passages in the order they appeared in
+    /// the input. See [`StoryPassages::source_order`] for details
+    ///
+    /// [`StoryPassages::source_order`]: struct.StoryPassages.html#method.source_order
+    pub source_order: Vec<String>,
+
     /// A list of the contents of any passages tagged with `script`
     pub scripts: Vec<String>,
 
     /// A list of the contents of any passages tagged with `stylesheet`
     pub stylesheets: Vec<String>,
 
+    /// Map from tag to a list of the contents of any passages with that tag,
+    /// for each of the additional special tags registered with
+    /// [`ParseOptions::special_tags`]
+    ///
+    /// [`ParseOptions::special_tags`]: struct.ParseOptions.html#structfield.special_tags
+    pub tagged_passages: HashMap<String, Vec<String>>,
+
     /// StoryMap for this story
     #[cfg(feature = "full-context")]
     pub code_map: CodeMap,
@@ -143,6 +159,14 @@ impl Story {
         StoryPassages::from_string(input).into_result()
     }
 
+    /// Parses an input `String` using the given [`ParseOptions`]. See
+    /// `from_string` for additional information.
+    ///
+    /// [`ParseOptions`]: struct.ParseOptions.html
+    pub fn from_string_with_options(input: String, options: &ParseOptions) -> ParseOutput {
+        StoryPassages::from_string_with_options(input, options).into_result()
+    }
+
     /// Parses a `Story` from the given [`Path`]. If the given path is a file,
     /// parses that file and returns the `Story`. If it is a directory, it looks
     /// for any files with `.tw` or `.twee` extensions and parses them. Returns
@@ -155,14 +179,41 @@ impl Story {
         StoryPassages::from_path(input).into_result()
     }
 
+    /// Parses a `Story` from the given [`Path`] using the given
+    /// [`ParseOptions`]. See `from_path` for additional information.
+    ///
+    /// [`Path`]: std::path::Path
+    /// [`ParseOptions`]: struct.ParseOptions.html
+    pub fn from_path_with_options<P: AsRef<Path>>(input: P, options: &ParseOptions) -> ParseOutput {
+        StoryPassages::from_path_with_options(input, options).into_result()
+    }
+
     /// Parses a `Story` from the given [`Path`]s. See `from_path` for
     /// additional information on how directories are handled.
     ///
     /// [`Path`]: std::path::Path
-    pub fn from_paths<P: AsRef<Path>>(input: &[P]) -> ParseOutput {
+    pub fn from_paths<I>(input: I) -> ParseOutput
+    where
+        I: IntoIterator,
+        I::Item: AsRef<Path>,
+    {
         StoryPassages::from_paths(input).into_result()
     }
 
+    /// Parses a `Story` from the given [`Path`]s using the given
+    /// [`ParseOptions`]. See `from_path` for additional information on how
+    /// directories are handled.
+    ///
+    /// [`Path`]: std::path::Path
+    /// [`ParseOptions`]: struct.ParseOptions.html
+    pub fn from_paths_with_options<I>(input: I, options: &ParseOptions) -> ParseOutput
+    where
+        I: IntoIterator,
+        I::Item: AsRef<Path>,
+    {
+        StoryPassages::from_paths_with_options(input, options).into_result()
+    }
+
     /// If a start passage is configured in the StoryData, return the name of
     /// that passage. If no start passage is configured, check for the presence
     /// of a passage called "Start". If that passage exists, return that name,
@@ -183,6 +234,7 @@ impl Story {
 
 impl std::convert::From<StoryPassages> for Story {
     fn from(mut s: StoryPassages) -> Story {
+        let source_order = s.source_order().into_iter().cloned().collect();
         let title = match s.title {
             Some(c) => match c.content {
                 PassageContent::StoryTitle(t) => Some(t.title),
@@ -203,7 +255,7 @@ impl std::convert::From<StoryPassages> for Story {
             .scripts
             .into_iter()
             .map(|p| match p.content {
-                PassageContent::Script(script) => script.content,
+                PassageContent::Script(script) => script.content().to_string(),
                 _ => panic!("Expected script to be Script"),
             })
             .collect();
@@ -212,11 +264,26 @@ impl std::convert::From<StoryPassages> for Story {
             .stylesheets
             .into_iter()
             .map(|p| match p.content {
-                PassageContent::Stylesheet(stylesheet) => stylesheet.content,
+                PassageContent::Stylesheet(stylesheet) => stylesheet.content().to_string(),
                 _ => panic!("Expected stylesheet to be Stylesheet"),
             })
             .collect();
 
+        let tagged_passages = s
+            .tagged_passages
+            .drain()
+            .map(|(tag, passages)| {
+                let contents = passages
+                    .into_iter()
+                    .map(|p| match p.content {
+                        PassageContent::Script(script) => script.content().to_string(),
+                        _ => panic!("Expected tagged passage to be Script"),
+                    })
+                    .collect();
+                (tag, contents)
+            })
+            .collect();
+
         let passages: HashMap<String, TwinePassage> =
             s.passages.drain().map(|(k, v)| (k, v.into())).collect();
 
@@ -227,8 +294,10 @@ impl std::convert::From<StoryPassages> for Story {
             title,
             data,
             passages,
+            source_order,
             scripts,
             stylesheets,
+            tagged_passages,
             #[cfg(feature = "full-context")]
             code_map,
         }
@@ -266,17 +335,14 @@ Test Story
         use crate::FullContext;
         use crate::Position;
         let out = Story::from_string(input.clone());
-        assert_eq!(out.has_warnings(), true);
+        assert!(out.has_warnings());
         let (res, warnings) = out.take();
-        assert_eq!(res.is_ok(), true);
+        assert!(res.is_ok());
         let context = FullContext::from(None, input);
-        assert_eq!(warnings[0], {
-            let warning = Warning::new(
-                WarningKind::EscapedOpenSquare,
-                Some(context.subcontext(Position::rel(7, 5)..=Position::rel(7, 6))),
-            );
-            warning
-        });
+        assert_eq!(warnings[0], Warning::new(
+            WarningKind::EscapedOpenSquare(r"A\[nother passage".to_string()),
+            Some(context.subcontext(Position::rel(7, 5)..=Position::rel(7, 6))),
+        ));
     }
 
     #[test]
@@ -307,11 +373,11 @@ Test Story
         writeln!(file, "{}", input)?;
 
         let out = Story::from_path(file_path);
-        assert_eq!(out.has_warnings(), true);
+        assert!(out.has_warnings());
         let (res, warnings) = out.take();
-        assert_eq!(res.is_ok(), true);
+        assert!(res.is_ok());
         let story = res.ok().unwrap();
-        assert_eq!(story.title.is_some(), true);
+        assert!(story.title.is_some());
         let title = story.title.unwrap();
         assert_eq!(title, "Test Story");
         assert_eq!(
@@ -343,12 +409,12 @@ Test Story
 "#
         .to_string();
         let out = Story::from_string(input);
-        assert_eq!(out.has_warnings(), false);
+        assert!(!out.has_warnings());
         let (res, _) = out.take();
-        assert_eq!(res.is_ok(), true);
+        assert!(res.is_ok());
         let story = res.ok().unwrap();
         assert_eq!(story.get_start_passage_name(), None);
-        assert_eq!(story.title.is_some(), true);
+        assert!(story.title.is_some());
         let title = story.title.unwrap();
         assert_eq!(title, "Test Story");
     }
@@ -393,33 +459,27 @@ blah blah
         write!(file_two, "{}", input_two.clone())?;
 
         let out = Story::from_path(dir.path());
-        assert_eq!(out.has_warnings(), true);
+        assert!(out.has_warnings());
         let (res, warnings) = out.take();
         assert_eq!(warnings.len(), 2);
-        assert_eq!(res.is_ok(), true);
+        assert!(res.is_ok());
         let story = res.ok().unwrap();
         assert_eq!(story.title, Some("Test Story".to_string()));
         assert_eq!(story.get_start_passage_name(), Some("Start"));
 
         use crate::FullContext;
         use crate::Position;
-        let context = FullContext::from(Some("test.twee".to_string()), input_one);
-        assert!(warnings.contains(&{
-            let warning = Warning::new(
-                WarningKind::EscapedOpenCurly,
-                Some(context.subcontext(Position::rel(10, 6)..=Position::rel(10, 7))),
-            );
-            warning
-        }));
-
-        let context = FullContext::from(Some("test2.tw".to_string()), input_two);
-        assert!(warnings.contains(&{
-            let warning = Warning::new(
-                WarningKind::EscapedCloseSquare,
-                Some(context.subcontext(Position::rel(9, 16)..=Position::rel(9, 17))),
-            );
-            warning
-        }));
+        let context = FullContext::from_file_path(Some(file_path_one), input_one);
+        assert!(warnings.contains(&Warning::new(
+            WarningKind::EscapedOpenCurly(r"Wa\{rning title one".to_string()),
+            Some(context.subcontext(Position::rel(10, 6)..=Position::rel(10, 7))),
+        )));
+
+        let context = FullContext::from_file_path(Some(file_path_two), input_two);
+        assert!(warnings.contains(&Warning::new(
+            WarningKind::EscapedCloseSquare(r"Warning titl\]e two".to_string()),
+            Some(context.subcontext(Position::rel(9, 16)..=Position::rel(9, 17))),
+        )));
 
         Ok(())
     }
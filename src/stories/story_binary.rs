@@ -0,0 +1,533 @@
+use crate::context::SharedStr;
+use crate::Error;
+use crate::ErrorList;
+use crate::ErrorKind;
+use crate::FullContext;
+use crate::PassageHeader;
+use crate::Position;
+use crate::Story;
+use crate::StoryData;
+use crate::TwineContent;
+use crate::TwineLink;
+use crate::TwinePassage;
+use serde::Deserialize;
+use serde::Serialize;
+use std::path::Path;
+use std::path::PathBuf;
+
+/// Identifies a binary story blob
+const MAGIC: &[u8] = b"TWEEP";
+
+/// The version of the binary story format
+const VERSION: u8 = 1;
+
+/// A story as stored in a binary blob
+#[derive(Deserialize, Serialize)]
+struct BinaryStory {
+    sources: Vec<BinarySource>,
+    title: Option<String>,
+    data: Option<String>,
+    passages: Vec<BinaryPassage>,
+    scripts: Vec<String>,
+    stylesheets: Vec<String>,
+    tagged_passages: Vec<(String, Vec<String>)>,
+}
+
+/// The source text of a file referenced by the story
+#[derive(Deserialize, Serialize)]
+struct BinarySource {
+    path: Option<BinaryPath>,
+    text: String,
+}
+
+/// A file path in the native encoding of the platform it was written on, so
+/// that paths which aren't valid UTF-8 survive a round trip
+#[derive(Deserialize, Serialize)]
+enum BinaryPath {
+    Bytes(Vec<u8>),
+    Wide(Vec<u16>),
+}
+
+/// A passage as stored in a binary blob
+#[derive(Deserialize, Serialize)]
+struct BinaryPassage {
+    name: String,
+    tags: Vec<String>,
+    metadata: String,
+    header: String,
+    content: BinaryText,
+    raw: BinaryText,
+    pid: usize,
+    start: (usize, usize),
+    links: Vec<BinaryLink>,
+}
+
+/// Passage text as stored in a binary blob: either a byte range of one of the
+/// sources, followed by a line break if `line_break` is set, or the text
+/// itself if it doesn't appear in the sources
+#[derive(Deserialize, Serialize)]
+enum BinaryText {
+    Source {
+        source: usize,
+        start: usize,
+        end: usize,
+        line_break: bool,
+    },
+    Inline(String),
+}
+
+/// A link as stored in a binary blob, with its context given as an index into
+/// the sources and the positions of its span
+#[derive(Deserialize, Serialize)]
+struct BinaryLink {
+    target: String,
+    source: usize,
+    start: (usize, usize),
+    end: (usize, usize),
+}
+
+impl Story {
+    /// Serializes this story into a compact binary blob which can be loaded
+    /// back with [`from_bytes`] without parsing any twee code, e.g. to embed a
+    /// pre-parsed story in a game
+    ///
+    /// The source text of each file the story was parsed from is included in
+    /// the blob once, and passage bodies are stored as ranges of it, so the
+    /// blob is about the size of the twee code.
+    ///
+    /// Enabled with the "binary" feature
+    ///
+    /// # Examples
+    /// ```
+    /// use tweep::Story;
+    /// let input = ":: StoryTitle\nBinary\n\n:: Start\nGo [[on]]\n\n:: on\nDone".to_string();
+    /// let story = Story::from_string(input).take().0.ok().unwrap();
+    /// let bytes = story.to_bytes();
+    /// let loaded = Story::from_bytes(&bytes).ok().unwrap();
+    /// assert_eq!(loaded.title, Some("Binary".to_string()));
+    /// assert_eq!(loaded.passages["Start"].content.get_links()[0].target, "on");
+    /// ```
+    ///
+    /// [`from_bytes`]: #method.from_bytes
+    pub fn to_bytes(&self) -> Vec<u8> {
+        // Collect the distinct sources referenced by the story
+        let mut sources: Vec<&FullContext> = Vec::new();
+        #[cfg(feature = "full-context")]
+        {
+            let mut ids: Vec<&usize> = self.code_map.contexts.keys().collect();
+            ids.sort();
+            sources.extend(ids.into_iter().map(|id| &self.code_map.contexts[id]));
+        }
+        // Write passages in source order, followed by any passages missing from
+        // the source order in sorted order
+        let mut names: Vec<&String> = self.source_order.iter().filter(|n| self.passages.contains_key(*n)).collect();
+        let mut rest: Vec<&String> = self.passages.keys().filter(|n| !self.source_order.contains(n)).collect();
+        rest.sort();
+        names.append(&mut rest);
+        for name in &names {
+            for link in self.passages[*name].content.get_links() {
+                if !sources.iter().any(|s| s.shares_source(&link.context)) {
+                    sources.push(&link.context);
+                }
+            }
+        }
+        let mut binary_sources: Vec<BinarySource> = sources.iter().map(|source| BinarySource {
+            path: source.get_file_path().map(BinaryPath::from_path),
+            text: source.get_source().to_string(),
+        }).collect();
+
+        let mut passages = Vec::new();
+        for name in names {
+            let passage = &self.passages[name];
+            let content = &passage.content;
+            let links = content.get_links().iter().map(|link| BinaryLink {
+                target: link.target.clone(),
+                source: sources.iter().position(|s| s.shares_source(&link.context)).unwrap(),
+                start: pair(*link.context.get_start_position()),
+                end: pair(*link.context.get_end_position()),
+            });
+
+            // Passages whose source isn't otherwise stored, such as those
+            // without links when contexts aren't kept, get their body stored
+            // as a source of its own
+            let raw = match locate_body(&sources, content) {
+                Some(raw) => raw,
+                None => {
+                    binary_sources.push(BinarySource { path: None, text: content.raw().to_string() });
+                    (binary_sources.len() - 1, 0)
+                }
+            };
+            passages.push(BinaryPassage {
+                name: passage.header.name.clone(),
+                tags: passage.header.tags.clone(),
+                metadata: passage.header.metadata_json(),
+                header: passage.header.original_text().to_string(),
+                content: BinaryText::new(&binary_sources, raw, content.content()),
+                raw: BinaryText::new(&binary_sources, raw, content.raw()),
+                pid: content.pid,
+                start: pair(content.start_position()),
+                links: links.collect(),
+            });
+        }
+
+        let mut tagged_passages: Vec<(String, Vec<String>)> = self.tagged_passages.clone().into_iter().collect();
+        tagged_passages.sort();
+        let story = BinaryStory {
+            sources: binary_sources,
+            title: self.title.clone(),
+            data: self.data.as_ref().map(|d| serde_json::to_string(d).unwrap()),
+            passages,
+            scripts: self.scripts.clone(),
+            stylesheets: self.stylesheets.clone(),
+            tagged_passages,
+        };
+
+        let mut bytes = MAGIC.to_vec();
+        bytes.push(VERSION);
+        // Serializing into a growable buffer only fails for sequences of
+        // unknown length, which these types don't contain
+        postcard::to_io(&story, &mut bytes).expect("Binary story serialization failed");
+        bytes
+    }
+
+    /// Loads a story from a binary blob created by [`to_bytes`]
+    ///
+    /// Enabled with the "binary" feature
+    ///
+    /// # Errors
+    /// Returns a list holding a [`BadBinaryData`] error if the blob is not a
+    /// valid binary story, was created by an incompatible version of tweep, or
+    /// contains a link or passage text whose position lies outside of its
+    /// source text
+    ///
+    /// [`to_bytes`]: #method.to_bytes
+    /// [`BadBinaryData`]: enum.ErrorKind.html#variant.BadBinaryData
+    pub fn from_bytes(bytes: &[u8]) -> Result<Story, ErrorList> {
+        Story::decode(bytes).map_err(|e| Error::new::<FullContext>(ErrorKind::BadBinaryData(e), None).into())
+    }
+
+    fn decode(bytes: &[u8]) -> Result<Story, String> {
+        let rest = bytes.strip_prefix(MAGIC).ok_or("Missing header")?;
+        let (&version, rest) = rest.split_first().ok_or("Missing header")?;
+        if version != VERSION {
+            return Err(format!("Unsupported version {}", version));
+        }
+        let (binary, rest): (BinaryStory, _) = postcard::take_from_bytes(rest).map_err(|e| e.to_string())?;
+        if !rest.is_empty() {
+            return Err("Trailing data".to_string());
+        }
+
+        let sources: Vec<FullContext> = binary.sources.into_iter()
+            .map(|source| FullContext::from_file_path(source.path.map(BinaryPath::into_path_buf), source.text))
+            .collect();
+
+        let mut story = Story {
+            title: binary.title,
+            scripts: binary.scripts,
+            stylesheets: binary.stylesheets,
+            tagged_passages: binary.tagged_passages.into_iter().collect(),
+            ..Story::default()
+        };
+        if let Some(data) = binary.data {
+            let data: StoryData = serde_json::from_str(&data).map_err(|e| e.to_string())?;
+            story.data = Some(data);
+        }
+
+        for passage in binary.passages {
+            let mut links = Vec::new();
+            for link in passage.links {
+                let source = sources.get(link.source).ok_or("Bad source index")?;
+                let start = Position::abs(link.start.0, link.start.1);
+                let end = Position::abs(link.end.0, link.end.1);
+                if !in_source(source, start, false) || !in_source(source, end, true) {
+                    return Err(format!("Bad link position in {}", passage.name));
+                }
+                links.push(TwineLink::new(link.target, source.inner_subcontext(start, end)));
+            }
+            let (line, column) = passage.start;
+            if line == 0 || column == 0 {
+                return Err(format!("Bad start position for {}", passage.name));
+            }
+            let name = &passage.name;
+            let bad_text = || format!("Bad text range in {}", name);
+            let content = passage.content.into_shared(&sources).ok_or_else(bad_text)?;
+            let raw = passage.raw.into_shared(&sources).ok_or_else(bad_text)?;
+            let header = PassageHeader::from_parts(passage.name.clone(), passage.tags, &passage.metadata, passage.header)
+                .map_err(|e| e.to_string())?;
+            let content = TwineContent::from_parts(content, passage.pid, links, raw, Position::abs(line, column));
+            story.source_order.push(passage.name.clone());
+            story.passages.insert(passage.name, TwinePassage { header, content });
+        }
+
+        #[cfg(feature = "full-context")]
+        for source in sources {
+            story.code_map.add(source);
+        }
+
+        Ok(story)
+    }
+}
+
+impl BinaryPath {
+    #[cfg(unix)]
+    fn from_path(path: &Path) -> Self {
+        use std::os::unix::ffi::OsStrExt;
+        BinaryPath::Bytes(path.as_os_str().as_bytes().to_vec())
+    }
+
+    #[cfg(windows)]
+    fn from_path(path: &Path) -> Self {
+        use std::os::windows::ffi::OsStrExt;
+        BinaryPath::Wide(path.as_os_str().encode_wide().collect())
+    }
+
+    #[cfg(not(any(unix, windows)))]
+    fn from_path(path: &Path) -> Self {
+        BinaryPath::Bytes(path.to_string_lossy().into_owned().into_bytes())
+    }
+
+    /// Converts the path back to a `PathBuf`. Paths written on a platform with
+    /// a different native encoding are converted lossily
+    fn into_path_buf(self) -> PathBuf {
+        match self {
+            #[cfg(unix)]
+            BinaryPath::Bytes(bytes) => {
+                use std::os::unix::ffi::OsStringExt;
+                PathBuf::from(std::ffi::OsString::from_vec(bytes))
+            }
+            #[cfg(not(unix))]
+            BinaryPath::Bytes(bytes) => PathBuf::from(String::from_utf8_lossy(&bytes).into_owned()),
+            #[cfg(windows)]
+            BinaryPath::Wide(wide) => {
+                use std::os::windows::ffi::OsStringExt;
+                PathBuf::from(std::ffi::OsString::from_wide(&wide))
+            }
+            #[cfg(not(windows))]
+            BinaryPath::Wide(wide) => PathBuf::from(String::from_utf16_lossy(&wide)),
+        }
+    }
+}
+
+impl BinaryText {
+    /// Stores `text` as a range of the source at `start`, given as a source
+    /// index and byte offset, if the source contains it there, possibly
+    /// without a final line break. Otherwise, stores it inline
+    fn new(sources: &[BinarySource], start: (usize, usize), text: &str) -> Self {
+        let (source, start) = start;
+        let source_text = &sources[source].text[start..];
+        let (body, line_break) = match text.strip_suffix('\n') {
+            Some(body) if !source_text.starts_with(text) => (body, true),
+            _ => (text, false),
+        };
+        if source_text.starts_with(body) {
+            BinaryText::Source { source, start, end: start + body.len(), line_break }
+        } else {
+            BinaryText::Inline(text.to_string())
+        }
+    }
+
+    /// Converts the text into a `SharedStr` referring to the loaded source
+    /// where possible, or returns `None` if its range isn't part of its source
+    fn into_shared(self, sources: &[FullContext]) -> Option<SharedStr> {
+        match self {
+            BinaryText::Source { source, start, end, line_break } => {
+                let source = sources.get(source)?;
+                source.get_source().get(start..end)?;
+                let text = source.shared_slice(start..end);
+                Some(if line_break { text.with_line_break() } else { text })
+            }
+            BinaryText::Inline(text) => Some(SharedStr::from(text)),
+        }
+    }
+}
+
+/// Finds the source containing the raw body of `content` at the position it
+/// was parsed from, and returns its index and the byte offset of the body
+fn locate_body(sources: &[&FullContext], content: &TwineContent) -> Option<(usize, usize)> {
+    let position = content.start_position();
+    sources.iter().enumerate().find_map(|(index, source)| {
+        let line_start = *source.get_line_starts().get(position.line.checked_sub(1)?)?;
+        let offset = line_start + position.column.checked_sub(1)?;
+        let found = source.get_source().get(offset..)?.starts_with(content.raw());
+        if found {
+            Some((index, offset))
+        } else {
+            None
+        }
+    })
+}
+
+/// Converts a position into a (line, column) pair
+fn pair(position: Position) -> (usize, usize) {
+    (position.line, position.column)
+}
+
+/// Returns `true` if the given absolute position can start (or, if
+/// `inclusive`, end) a span within the source text of `source`
+fn in_source(source: &FullContext, position: Position, inclusive: bool) -> bool {
+    let text = source.get_source();
+    let line_starts = source.get_line_starts();
+    if position.line == 0 || position.line > line_starts.len() || (!inclusive && position.column == 0) {
+        return false;
+    }
+    let line_end = line_starts.get(position.line).map_or(text.len(), |&next| next - 1);
+    let index = line_starts[position.line - 1].checked_add(position.column)
+        .map(|index| if inclusive { index } else { index - 1 });
+    matches!(index, Some(index) if index <= line_end + 1 && index <= text.len() && text.is_char_boundary(index))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let input = r#":: StoryTitle
+Round Trip
+
+:: StoryData
+{ "ifid": "ABC", "start": "Begin" }
+
+:: Begin [a b] {"position":"10,20"}
+Go [[there|There]]
+or [[back->Begin]]
+
+:: There
+The end
+
+:: Code [script]
+foo();
+
+:: Style [stylesheet]
+body {}
+
+:: Setup [init]
+x = 1
+"#
+        .to_string();
+        let options = crate::ParseOptions::new().with_special_tag("init");
+        let story = Story::from_string_with_options(input, &options).take().0.ok().unwrap();
+        let loaded = Story::from_bytes(&story.to_bytes()).ok().unwrap();
+        assert_eq!(loaded.title, story.title);
+        assert_eq!(loaded.data.as_ref().unwrap().ifid, "ABC");
+        assert_eq!(loaded.get_start_passage_name(), Some("Begin"));
+        assert_eq!(loaded.scripts, story.scripts);
+        assert_eq!(loaded.stylesheets, story.stylesheets);
+        assert_eq!(loaded.tagged_passages["init"], vec!["x = 1"]);
+        assert_eq!(loaded.passages.len(), 2);
+        assert_eq!(story.source_order, vec!["Begin", "There"]);
+        assert_eq!(loaded.source_order, story.source_order);
+        for (name, passage) in &story.passages {
+            let other = &loaded.passages[name];
+            assert_eq!(other.header.name, passage.header.name);
+            assert_eq!(other.header.tags, passage.header.tags);
+            assert_eq!(other.header.metadata, passage.header.metadata);
+            assert_eq!(other.header.original_text(), passage.header.original_text());
+            assert_eq!(other.content.content, passage.content.content);
+            assert_eq!(other.content.raw(), passage.content.raw());
+            assert_eq!(other.content.pid, passage.content.pid);
+            assert_eq!(other.content.get_links(), passage.content.get_links());
+            assert_eq!(
+                other.content.lines().collect::<Vec<_>>(),
+                passage.content.lines().collect::<Vec<_>>()
+            );
+        }
+        assert_eq!(story.to_bytes(), loaded.to_bytes());
+    }
+
+    #[test]
+    fn compact() {
+        let text = "The corridor stretches on into the dark. Somewhere ahead, water drips \
+            onto stone, and the air smells of rust and old smoke.";
+        let input: String = (0..200)
+            .map(|i| format!(":: Passage {}\n{}\n{}\n[[Onwards|Passage {}]]\n\n", i, text, text, i + 1))
+            .collect();
+        let story = Story::from_string(input.clone()).take().0.ok().unwrap();
+        let bytes = story.to_bytes();
+        assert!(bytes.len() < input.len() * 3 / 2, "{} bytes for {} bytes of input", bytes.len(), input.len());
+
+        // Without links, bodies are stored without the rest of the source
+        let input: String = (0..200).map(|i| format!(":: Passage {}\nSome text\n{}\n\n", i, text)).collect();
+        let story = Story::from_string(input.clone()).take().0.ok().unwrap();
+        let bytes = story.to_bytes();
+        assert!(bytes.len() < input.len() * 3 / 2, "{} bytes for {} bytes of input", bytes.len(), input.len());
+        let loaded = Story::from_bytes(&bytes).ok().unwrap();
+        let (passage, other) = (&story.passages["Passage 7"], &loaded.passages["Passage 7"]);
+        assert_eq!(other.content.content, passage.content.content);
+        assert_eq!(other.content.raw(), passage.content.raw());
+    }
+
+    #[test]
+    fn changed_content() {
+        let mut story = Story::from_string(":: Start\n[[Start]]".to_string()).take().0.ok().unwrap();
+        story.passages.get_mut("Start").unwrap().content.content = "Edited".to_string().into();
+        let loaded = Story::from_bytes(&story.to_bytes()).ok().unwrap();
+        assert_eq!(loaded.passages["Start"].content.content, "Edited");
+        assert_eq!(loaded.passages["Start"].content.raw(), "[[Start]]");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn non_utf8_path() {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+        let path = PathBuf::from(OsStr::from_bytes(b"story\xff.tw"));
+        let context = FullContext::from_file_path(Some(path.clone()), ":: Start\n[[Start]]".to_string());
+        let options = crate::ParseOptions::default();
+        let passages = crate::StoryPassages::from_context(context, &options).take().0.ok().unwrap();
+        let story = Story::from(passages);
+        let loaded = Story::from_bytes(&story.to_bytes()).ok().unwrap();
+        let link = &loaded.passages["Start"].content.get_links()[0];
+        assert_eq!(link.context.get_file_path(), Some(path.as_path()));
+    }
+
+    /// Returns the message of the `BadBinaryData` error from loading `bytes`
+    fn error(bytes: &[u8]) -> String {
+        match Story::from_bytes(bytes).err().map(|mut e| e.errors.remove(0).kind) {
+            Some(ErrorKind::BadBinaryData(e)) => e,
+            _ => panic!("Expected BadBinaryData"),
+        }
+    }
+
+    #[test]
+    fn bad_data() {
+        let story = Story::from_string(":: Start\n[[Start]]".to_string()).take().0.ok().unwrap();
+        let bytes = story.to_bytes();
+        assert_eq!(error(b"NOT A STORY"), "Missing header");
+        assert!(error(&bytes[..bytes.len() - 1]).contains("end of"));
+        let mut extra = bytes.clone();
+        extra.push(0);
+        assert_eq!(error(&extra), "Trailing data");
+        let mut version = bytes;
+        version[MAGIC.len()] = 99;
+        assert_eq!(error(&version), "Unsupported version 99");
+    }
+
+    #[test]
+    fn bad_positions() {
+        let story = Story::from_string(":: Start\n[[Start]]".to_string()).take().0.ok().unwrap();
+        let bytes = story.to_bytes();
+        let (mut binary, _): (BinaryStory, _) = postcard::take_from_bytes(&bytes[MAGIC.len() + 1..]).unwrap();
+        let encode = |binary: &BinaryStory| {
+            let mut bytes = MAGIC.to_vec();
+            bytes.push(VERSION);
+            bytes.extend(postcard::to_stdvec(binary).unwrap());
+            bytes
+        };
+        assert!(Story::from_bytes(&encode(&binary)).is_ok());
+
+        for (start, end) in &[((0, 1), (2, 9)), ((2, 1), (9, 9)), ((2, 1), (2, 99)), ((2, 0), (2, 9)), ((99, 1), (2, 9))] {
+            binary.passages[0].links[0].start = *start;
+            binary.passages[0].links[0].end = *end;
+            assert_eq!(error(&encode(&binary)), "Bad link position in Start", "{:?}", (start, end));
+        }
+        binary.passages[0].links[0].start = (2, 1);
+        binary.passages[0].links[0].end = (2, 9);
+
+        binary.passages[0].raw = BinaryText::Source { source: 0, start: 9, end: 99, line_break: false };
+        assert_eq!(error(&encode(&binary)), "Bad text range in Start");
+
+        binary.passages[0].links[0].source = 5;
+        assert!(Story::from_bytes(&encode(&binary)).is_err());
+    }
+}
@@ -0,0 +1,209 @@
+use crate::passages::json_error_context;
+use crate::passages::json_error_message;
+use crate::Passage;
+use crate::Warning;
+use crate::WarningKind;
+use crate::WarningPolicy;
+use serde::Deserialize;
+
+/// The names of the special passage that holds a story's configuration
+pub(crate) const CONFIG_PASSAGE_NAMES: [&str; 2] = ["StoryConfig", "TweepConfig"];
+
+/// Configuration for how tweep treats a story, given as JSON either in a
+/// `StoryConfig` or `TweepConfig` passage of the story itself, or externally
+/// with [`ParseOptions::config`]. The configuration in the story is merged
+/// with the external configuration: lists are combined, and settings in the
+/// story take precedence
+///
+/// # JSON fields
+/// * special-tags - Array of strings. Additional special tags, as with
+///   [`ParseOptions::special_tags`]
+/// * dynamic-targets - Array of strings. Passages dynamic links may lead to,
+///   as with [`ParseOptions::dynamic_targets`]
+/// * resolve-transclusions - Boolean. Treat passages transcluded by include
+///   macros as linked from the passage including them when following links.
+///   See [`resolve_transclusions`]
+/// * allow - Array of strings. Codes of warnings that are not reported
+/// * deny - Array of strings. Codes of warnings that [`warning_policy`]
+///   treats as errors
+/// * ending-tags - Array of strings. Tags that mark a passage as an ending,
+///   in addition to `ending`. See [`StoryPassages::endings`]
+/// * deny-duplicates - Boolean. Parsing fails with a [`DeniedWarning`] error
+///   for each duplicate passage, including `StoryTitle` and `StoryData`,
+///   instead of keeping the first one found with a warning
+///
+/// Unknown fields produce a [`JsonError`] warning.
+///
+/// # Examples
+/// ```
+/// use tweep::{ParseOptions, StoryPassages, WarningKind};
+/// let input = r#":: StoryConfig
+/// {"allow": ["MissingStoryTitle"], "deny": ["DeadLink"], "special-tags": ["module"]}
+///
+/// :: Start
+/// Hello
+///
+/// :: Setup [module]
+/// init();
+/// "#.to_string();
+/// let story = StoryPassages::from_string(input).take().0.ok().unwrap();
+/// assert!(story.config_passage.is_some());
+/// assert_eq!(story.tagged_passages["module"].len(), 1);
+/// assert!(!story.check().iter().any(|w| w.kind == WarningKind::MissingStoryTitle));
+/// ```
+///
+/// [`ParseOptions::config`]: struct.ParseOptions.html#structfield.config
+/// [`ParseOptions::special_tags`]: struct.ParseOptions.html#structfield.special_tags
+/// [`ParseOptions::dynamic_targets`]: struct.ParseOptions.html#structfield.dynamic_targets
+/// [`resolve_transclusions`]: #structfield.resolve_transclusions
+/// [`warning_policy`]: #method.warning_policy
+/// [`JsonError`]: enum.WarningKind.html#variant.JsonError
+/// [`DeniedWarning`]: enum.ErrorKind.html#variant.DeniedWarning
+/// [`StoryPassages::endings`]: struct.StoryPassages.html#method.endings
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct StoryConfig {
+    /// Additional special tags
+    pub special_tags: Vec<String>,
+
+    /// The names of passages dynamic links may lead to
+    pub dynamic_targets: Vec<String>,
+
+    /// Whether to treat passages transcluded by include macros, such as
+    /// `<<include "Other">>` or `(display: "Other")`, as linked from the
+    /// passage including them when following links, such as in
+    /// [`reachable_from`] and [`PassageOrder::Reachability`]. Off if not set.
+    /// See [`TwineContent::transclusions`] for the macros recognized
+    ///
+    /// [`reachable_from`]: struct.StoryPassages.html#method.reachable_from
+    /// [`PassageOrder::Reachability`]: enum.PassageOrder.html#variant.Reachability
+    /// [`TwineContent::transclusions`]: struct.TwineContent.html#method.transclusions
+    pub resolve_transclusions: Option<bool>,
+
+    /// Codes of warnings that are not reported
+    pub allow: Vec<String>,
+
+    /// Codes of warnings that are treated as errors
+    pub deny: Vec<String>,
+
+    /// Tags that mark a passage as an ending, in addition to `ending`
+    pub ending_tags: Vec<String>,
+
+    /// Whether duplicate passages are errors rather than warnings, if set
+    pub deny_duplicates: Option<bool>,
+}
+
+impl StoryConfig {
+    /// Parses a `StoryConfig` from JSON, such as the contents of an external
+    /// configuration file
+    ///
+    /// # Examples
+    /// ```
+    /// use tweep::StoryConfig;
+    /// let config = StoryConfig::from_json(r#"{"resolve-transclusions": true}"#).unwrap();
+    /// assert_eq!(config.resolve_transclusions, Some(true));
+    /// assert!(StoryConfig::from_json(r#"{"colour": "red"}"#).is_err());
+    /// ```
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+
+    /// Parses the configuration in the body of a config passage. If it isn't
+    /// valid, a [`JsonError`] warning is added to `warnings` and the default
+    /// configuration returned
+    ///
+    /// [`JsonError`]: enum.WarningKind.html#variant.JsonError
+    pub(crate) fn from_passage(passage: &Passage, warnings: &mut Vec<Warning>) -> Self {
+        let contents = passage.context.get_contents();
+        let body_start = contents.find('\n').map_or(contents.len(), |i| i + 1);
+        let body = &contents[body_start..];
+        if body.trim().is_empty() {
+            return StoryConfig::default();
+        }
+        StoryConfig::from_json(body).unwrap_or_else(|err| {
+            let body_context = passage.context.slice(body_start..contents.len());
+            let context = json_error_context(&body_context, &err);
+            warnings.push(Warning::new(WarningKind::JsonError(json_error_message(&err)), Some(context)));
+            StoryConfig::default()
+        })
+    }
+
+    /// Returns this configuration merged with `other`, combining their lists
+    /// and preferring the settings of `other`
+    ///
+    /// # Examples
+    /// ```
+    /// use tweep::StoryConfig;
+    /// let external = StoryConfig::from_json(r#"{"allow": ["DeadLink"], "resolve-transclusions": true}"#).unwrap();
+    /// let story = StoryConfig::from_json(r#"{"allow": ["UnclosedLink"], "resolve-transclusions": false}"#).unwrap();
+    /// let merged = external.merged(&story);
+    /// assert_eq!(merged.allow, vec!["DeadLink", "UnclosedLink"]);
+    /// assert_eq!(merged.resolve_transclusions, Some(false));
+    /// ```
+    pub fn merged(&self, other: &StoryConfig) -> StoryConfig {
+        let union = |ours: &[String], theirs: &[String]| -> Vec<String> {
+            let mut list = ours.to_vec();
+            list.extend(theirs.iter().filter(|item| !ours.contains(item)).cloned());
+            list
+        };
+        StoryConfig {
+            special_tags: union(&self.special_tags, &other.special_tags),
+            dynamic_targets: union(&self.dynamic_targets, &other.dynamic_targets),
+            resolve_transclusions: other.resolve_transclusions.or(self.resolve_transclusions),
+            allow: union(&self.allow, &other.allow),
+            deny: union(&self.deny, &other.deny),
+            ending_tags: union(&self.ending_tags, &other.ending_tags),
+            deny_duplicates: other.deny_duplicates.or(self.deny_duplicates),
+        }
+    }
+
+    /// Returns `true` if the given warning is not reported under this
+    /// configuration
+    pub fn allows(&self, warning: &Warning) -> bool {
+        let code = warning.explanation().code;
+        self.allow.iter().any(|allowed| allowed == code)
+    }
+
+    /// Returns `true` if the given warning is an error under this
+    /// configuration when parsing
+    ///
+    /// # Examples
+    /// ```
+    /// use tweep::{StoryConfig, Warning, WarningKind};
+    /// let config = StoryConfig::from_json(r#"{"deny-duplicates": true}"#).unwrap();
+    /// let warning = Warning::new::<tweep::FullContext>(WarningKind::DuplicateStoryTitle, None);
+    /// assert!(config.denies_when_parsing(&warning));
+    /// assert!(!StoryConfig::default().denies_when_parsing(&warning));
+    /// ```
+    pub fn denies_when_parsing(&self, warning: &Warning) -> bool {
+        self.deny_duplicates == Some(true)
+            && matches!(
+                warning.kind,
+                WarningKind::DuplicatePassage(_)
+                    | WarningKind::DuplicatePassageAcrossFiles(_)
+                    | WarningKind::DuplicateStoryTitle
+                    | WarningKind::DuplicateStoryData
+            )
+    }
+
+    /// Returns a [`WarningPolicy`] that treats the warnings in `deny` as
+    /// errors, for use with [`Output::into_result_with`]
+    ///
+    /// # Examples
+    /// ```
+    /// use tweep::{StoryConfig, WarningPolicy};
+    /// let config = StoryConfig::from_json(r#"{"deny": ["DeadLink"]}"#).unwrap();
+    /// assert_eq!(config.warning_policy(), WarningPolicy::DenyCodes(vec!["DeadLink".to_string()]));
+    /// assert_eq!(StoryConfig::default().warning_policy(), WarningPolicy::Allow);
+    /// ```
+    ///
+    /// [`WarningPolicy`]: enum.WarningPolicy.html
+    /// [`Output::into_result_with`]: struct.Output.html#method.into_result_with
+    pub fn warning_policy(&self) -> WarningPolicy {
+        if self.deny.is_empty() {
+            WarningPolicy::Allow
+        } else {
+            WarningPolicy::DenyCodes(self.deny.clone())
+        }
+    }
+}
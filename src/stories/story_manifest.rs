@@ -0,0 +1,126 @@
+use crate::PassageContent;
+use crate::StoryPassages;
+use serde::{Deserialize, Serialize};
+
+/// A small summary of a story, for catalogs and archives that index many
+/// stories without keeping their full parses around
+///
+/// The manifest is serialized to JSON with kebab-case field names, such as
+/// `passage-count`
+///
+/// # Examples
+/// ```
+/// use tweep::{StoryManifest, StoryPassages};
+/// let input = r#":: StoryTitle
+/// The Cellar
+///
+/// :: StoryData
+/// {"ifid": "ABC", "format": "Harlowe", "format-version": "3.3.8"}
+///
+/// :: Start [intro]
+/// You stand at the top of the stairs. [[Go down|Cellar]]
+///
+/// :: Cellar [dark intro]
+/// It's dark.
+/// "#.to_string();
+/// let story = StoryPassages::from_string(input).take().0.ok().unwrap();
+/// let manifest = StoryManifest::extract(&story);
+/// assert_eq!(manifest.title.as_deref(), Some("The Cellar"));
+/// assert_eq!(manifest.format.as_deref(), Some("Harlowe"));
+/// assert_eq!(manifest.passage_count, 2);
+/// assert_eq!(manifest.tags, vec!["dark", "intro"]);
+/// assert_eq!(manifest.word_count, 12);
+/// assert!(manifest.to_json().contains("\"passage-count\": 2"));
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct StoryManifest {
+    /// The title of the story, if it has a `StoryTitle` passage
+    pub title: Option<String>,
+
+    /// The IFID of the story, if it has a `StoryData` passage
+    pub ifid: Option<String>,
+
+    /// The story format, if set in `StoryData`
+    pub format: Option<String>,
+
+    /// The version of the story format, if set in `StoryData`
+    pub format_version: Option<String>,
+
+    /// The number of normal passages
+    pub passage_count: usize,
+
+    /// The tags used by the normal passages, sorted and without duplicates
+    pub tags: Vec<String>,
+
+    /// The number of words in the prose of the normal passages, as produced
+    /// by [`TwineContent::prose`]
+    ///
+    /// [`TwineContent::prose`]: struct.TwineContent.html#method.prose
+    pub word_count: usize,
+}
+
+impl StoryManifest {
+    /// Extracts the manifest of the given story
+    pub fn extract(story: &StoryPassages) -> Self {
+        let mut tags: Vec<String> = story.passages.values().flat_map(|p| p.tags().iter().cloned()).collect();
+        tags.sort();
+        tags.dedup();
+
+        let word_count = story
+            .passages
+            .values()
+            .map(|passage| match &passage.content {
+                PassageContent::Normal(twine) => twine.prose().split_whitespace().count(),
+                _ => 0,
+            })
+            .sum();
+
+        StoryManifest {
+            title: story.title_string().map(str::to_string),
+            ifid: story.ifid().map(str::to_string),
+            format: story.format_name().map(str::to_string),
+            format_version: story.format_version().map(str::to_string),
+            passage_count: story.passages.len(),
+            tags,
+            word_count,
+        }
+    }
+
+    /// Writes the manifest as a JSON object
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("A manifest is always serializable")
+    }
+
+    /// Reads a manifest written by [`to_json`]
+    ///
+    /// [`to_json`]: #method.to_json
+    pub fn from_json(input: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let input = ":: Start [a]\nOne two <<set $x to 1>>three\n\n:: Next [b a]\n".to_string();
+        let story = StoryPassages::from_string(input).take().0.ok().unwrap();
+        let manifest = StoryManifest::extract(&story);
+        assert_eq!(
+            manifest,
+            StoryManifest {
+                title: None,
+                ifid: None,
+                format: None,
+                format_version: None,
+                passage_count: 2,
+                tags: vec!["a".to_string(), "b".to_string()],
+                word_count: 3,
+            }
+        );
+        assert_eq!(StoryManifest::from_json(&manifest.to_json()).unwrap(), manifest);
+    }
+}
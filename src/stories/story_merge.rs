@@ -0,0 +1,242 @@
+use crate::Output;
+use crate::StoryPassages;
+use crate::Warning;
+use crate::WarningKind;
+use super::passage_diff::PassageKey;
+use std::collections::BTreeSet;
+
+impl StoryPassages {
+    /// Performs a three-way merge of two stories that were both derived from
+    /// the common ancestor `base`, at passage granularity
+    ///
+    /// Passages are matched by name, kind and source file, as in [`diff`],
+    /// and author notes, excluded passages and the config passage are merged
+    /// along with the others. For each passage found in any of the three
+    /// stories:
+    /// * If `ours` and `theirs` agree, that version is kept
+    /// * If only one side changed the passage relative to `base`, the changed
+    ///   version is kept. This includes adding or removing the passage
+    /// * If both sides changed the passage differently, a [`MergeConflict`]
+    ///   warning is produced and the version from `ours` is kept, unless
+    ///   `ours` removed it, in which case the version from `theirs` is kept
+    ///
+    /// Conflict warnings use the passage from `ours` as their context and the
+    /// passage from `theirs` as their referent, when present.
+    ///
+    /// # Examples
+    /// ```
+    /// use tweep::StoryPassages;
+    /// # fn parse(s: &str) -> StoryPassages {
+    /// #     StoryPassages::from_string(s.to_string()).take().0.ok().unwrap()
+    /// # }
+    /// let base = parse(":: Start\nHello\n\n:: End\nEnd");
+    /// let ours = parse(":: Start\nHello there\n\n:: End\nEnd");
+    /// let theirs = parse(":: Start\nHello\n\n:: End\nFin");
+    /// let out = StoryPassages::merge3(&base, ours, theirs);
+    /// assert!(!out.has_warnings());
+    /// let merged = out.take().0;
+    /// assert_eq!(merged.passages["Start"].context.get_contents(), ":: Start\nHello there");
+    /// assert_eq!(merged.passages["End"].context.get_contents(), ":: End\nFin");
+    /// ```
+    ///
+    /// [`diff`]: #method.diff
+    /// [`MergeConflict`]: enum.WarningKind.html#variant.MergeConflict
+    pub fn merge3(
+        base: &StoryPassages,
+        ours: StoryPassages,
+        theirs: StoryPassages,
+    ) -> Output<StoryPassages> {
+        let mut story = StoryPassages {
+            link_resolver: ours.link_resolver.clone(),
+            config: ours.config.clone(),
+            namespaced: ours.namespaced || theirs.namespaced,
+            special_tags: ours
+                .special_tags
+                .iter()
+                .chain(theirs.special_tags.iter().filter(|tag| !ours.special_tags.contains(tag)))
+                .cloned()
+                .collect(),
+            source_files: ours
+                .source_files
+                .iter()
+                .chain(theirs.source_files.iter().filter(|file| !ours.source_files.contains(file)))
+                .cloned()
+                .collect(),
+            ..StoryPassages::default()
+        };
+        let mut warnings = Vec::new();
+
+        #[cfg(feature = "full-context")]
+        let (ours, theirs) = {
+            let (mut ours, mut theirs) = (ours, theirs);
+            theirs.renumber_file_ids(ours.code_map.contexts.len());
+            story.code_map = std::mem::take(&mut ours.code_map);
+            story.code_map.contexts.extend(theirs.code_map.contexts.drain());
+            for (id, file_name) in theirs.code_map.id_file_map.iter() {
+                story.code_map.id_file_map.insert(*id, file_name.clone());
+            }
+            (ours, theirs)
+        };
+
+        let base = base.passage_sources();
+        let mut ours = ours.into_keyed_passages();
+        let mut theirs = theirs.into_keyed_passages();
+        let keys: BTreeSet<PassageKey> = base
+            .keys()
+            .cloned()
+            .chain(ours.keys().cloned())
+            .chain(theirs.keys().cloned())
+            .collect();
+
+        for key in keys {
+            let ours = ours.remove(&key);
+            let theirs = theirs.remove(&key);
+            let (agree, ours_unchanged, theirs_unchanged) = {
+                let base = base.get(&key).copied();
+                let ours = ours.as_ref().map(|p| p.context.get_contents());
+                let theirs = theirs.as_ref().map(|p| p.context.get_contents());
+                (ours == theirs, ours == base, theirs == base)
+            };
+
+            let chosen = if agree || theirs_unchanged {
+                ours
+            } else if ours_unchanged {
+                theirs
+            } else {
+                let context = ours.as_ref().or(theirs.as_ref()).unwrap();
+                let mut warning = Warning::new(
+                    WarningKind::MergeConflict(key.name.clone()),
+                    Some(context.context.clone()),
+                );
+                if let (Some(_), Some(theirs)) = (&ours, &theirs) {
+                    warning.set_referent(theirs.context.clone());
+                }
+                warnings.push(warning);
+                ours.or(theirs)
+            };
+
+            if let Some(passage) = chosen {
+                story.add_to_slot(key.slot, passage);
+            }
+        }
+
+        story.renumber_pids(1);
+        Output::new(story).with_warnings(warnings)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FullContext;
+    use crate::ParseOptions;
+    use crate::PassageContent;
+    use crate::PassageDiff;
+
+    fn parse(input: &str) -> StoryPassages {
+        StoryPassages::from_string(input.to_string()).take().0.ok().unwrap()
+    }
+
+    fn title(story: &StoryPassages) -> &str {
+        match &story.title.as_ref().unwrap().content {
+            PassageContent::StoryTitle(t) => &t.title,
+            _ => panic!("Expected StoryTitle"),
+        }
+    }
+
+    #[test]
+    fn one_sided_changes() {
+        let base = parse(":: StoryTitle\nBase\n\n:: Start\nHello\n\n:: Doomed\nBye");
+        let ours = parse(":: StoryTitle\nOurs\n\n:: Start\nHello\n\n:: Doomed\nBye\n\n:: Added\nNew");
+        let theirs = parse(":: StoryTitle\nBase\n\n:: Start\nHello [[Added]]");
+        let out = StoryPassages::merge3(&base, ours, theirs);
+        assert!(!out.has_warnings());
+        let story = out.take().0;
+        assert_eq!(title(&story), "Ours");
+        assert!(!story.passages.contains_key("Doomed"));
+        assert!(story.passages.contains_key("Added"));
+        assert_eq!(
+            story.passages["Start"].context.get_contents(),
+            ":: Start\nHello [[Added]]"
+        );
+        assert!(story.check().iter().all(|w| w.kind != WarningKind::DeadLink("Added".to_string())));
+    }
+
+    #[test]
+    fn same_change_both_sides() {
+        let base = parse(":: Start\nHello");
+        let ours = parse(":: Start\nGoodbye\n\n:: Code [script]\nfoo();");
+        let theirs = parse(":: Start\nGoodbye\n\n:: Code [script]\nfoo();");
+        let out = StoryPassages::merge3(&base, ours, theirs);
+        assert!(!out.has_warnings());
+        let story = out.take().0;
+        assert_eq!(story.passages.len(), 1);
+        assert_eq!(story.scripts.len(), 1);
+    }
+
+    #[test]
+    fn conflicts() {
+        let base = parse(":: Start\nHello\n\n:: Other\nOther");
+        let ours = parse(":: Start\nHello from us");
+        let theirs = parse(":: Start\nHello from them\n\n:: Other\nChanged");
+        let out = StoryPassages::merge3(&base, ours, theirs);
+        let (story, warnings) = out.take();
+        assert_eq!(warnings.len(), 2);
+        assert_eq!(warnings[0].kind, WarningKind::MergeConflict("Other".to_string()));
+        assert!(!warnings[0].has_referent());
+        assert_eq!(warnings[1].kind, WarningKind::MergeConflict("Start".to_string()));
+        assert!(warnings[1].has_referent());
+        assert_eq!(
+            story.passages["Start"].context.get_contents(),
+            ":: Start\nHello from us"
+        );
+        assert_eq!(
+            story.passages["Other"].context.get_contents(),
+            ":: Other\nChanged"
+        );
+    }
+
+    fn parse_files(a: &str, b: &str) -> StoryPassages {
+        let parse_file = |name: &str, input: &str| {
+            let context = FullContext::from(Some(name.to_string()), input.to_string());
+            StoryPassages::from_context(context, &ParseOptions::default()).take().0.ok().unwrap()
+        };
+        let mut story = parse_file("a.twee", a);
+        story.merge_from(parse_file("b.twee", b));
+        story
+    }
+
+    #[test]
+    fn same_named_scripts() {
+        let base = parse_files(":: Script [script]\na();", ":: Script [script]\nb();");
+        let ours = parse_files(":: Script [script]\na(1);", ":: Script [script]\nb();");
+        let theirs = parse_files(":: Script [script]\na();", ":: Script [script]\nb(2);");
+        assert_eq!(
+            ours.diff(&theirs),
+            vec![PassageDiff::Modified("Script".to_string()), PassageDiff::Modified("Script".to_string())]
+        );
+        let out = StoryPassages::merge3(&base, ours, theirs);
+        assert!(!out.has_warnings());
+        let story = out.take().0;
+        let scripts: Vec<&str> = story.scripts.iter().map(|p| p.context.get_contents()).collect();
+        assert_eq!(scripts, vec![":: Script [script]\na(1);", ":: Script [script]\nb(2);"]);
+        assert!(story.passages.is_empty());
+    }
+
+    #[test]
+    fn set_aside_passages() {
+        let options = ParseOptions::new().with_author_notes(true);
+        let parse = |input: &str| {
+            StoryPassages::from_string_with_options(input.to_string(), &options).take().0.ok().unwrap()
+        };
+        let base = parse(":: Start\nHello\n\n:: Todo [notes]\nOld note");
+        let ours = parse(":: Start\nHello\n\n:: Todo [notes]\nOld note\n\n:: StoryConfig\n{}");
+        let theirs = parse(":: Start\nHello\n\n:: Todo [notes]\nNew note");
+        let out = StoryPassages::merge3(&base, ours, theirs);
+        assert!(!out.has_warnings());
+        let story = out.take().0;
+        assert_eq!(story.passages["Start"].context.get_contents(), ":: Start\nHello");
+        assert_eq!(story.notes["Todo"].context.get_contents(), ":: Todo [notes]\nNew note");
+        assert!(story.config_passage.is_some());
+    }
+}
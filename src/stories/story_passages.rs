@@ -1,18 +1,30 @@
 #[cfg(feature = "full-context")]
 use crate::CodeMap;
+use crate::CancellationToken;
+use crate::CheckOptions;
 use crate::Context;
 #[cfg(feature = "full-context")]
 use crate::ContextErrorList;
 use crate::Error;
+use crate::ErrorKind;
 use crate::ErrorList;
+use crate::FileReport;
 use crate::FullContext;
 use crate::Output;
+use crate::ParseOptions;
 use crate::Passage;
 use crate::PassageContent;
+use crate::PassageKind;
 use crate::Position;
 use crate::PositionKind;
+use crate::StoryConfig;
+use crate::TwineLink;
 use crate::Warning;
 use crate::WarningKind;
+use crate::windows_1252;
+use super::parse_options::LinkResolver;
+use super::passage_diff::PassageKey;
+use super::passage_diff::PassageSlot;
 #[cfg(feature = "full-context")]
 use bimap::BiMap;
 use std::collections::HashMap;
@@ -20,11 +32,14 @@ use std::default::Default;
 use std::fs::File;
 use std::io::Read;
 use std::path::Path;
+use std::path::PathBuf;
 
 #[cfg(not(feature = "full-context"))]
-type ParseOutput = Output<Result<StoryPassages, ErrorList>>;
+type ParseError = ErrorList;
 #[cfg(feature = "full-context")]
-type ParseOutput = Output<Result<StoryPassages, ContextErrorList>>;
+type ParseError = ContextErrorList;
+
+type ParseOutput = Output<Result<StoryPassages, ParseError>>;
 
 /// A parsed Twee story, that stores the full [`Passage`] object of each field
 ///
@@ -49,48 +64,260 @@ pub struct StoryPassages {
     /// List of passages tagged with `stylesheet`
     pub stylesheets: Vec<Passage>,
 
+    /// Map from tag to the list of passages with that tag, for each of the
+    /// additional special tags registered with [`ParseOptions::special_tags`]
+    ///
+    /// [`ParseOptions::special_tags`]: struct.ParseOptions.html#structfield.special_tags
+    pub tagged_passages: HashMap<String, Vec<Passage>>,
+
     /// StoryMap for this story
     #[cfg(feature = "full-context")]
     pub code_map: CodeMap,
+
+    /// The function consulted for link targets that don't match a passage, if
+    /// any. See [`ParseOptions::link_resolver`] for details
+    ///
+    /// [`ParseOptions::link_resolver`]: struct.ParseOptions.html#structfield.link_resolver
+    pub(crate) link_resolver: Option<LinkResolver>,
+
+    /// Map from passage name to `Passage` for any passages, special or not,
+    /// excluded by the [`BuildProfile`] the story was parsed with
+    ///
+    /// [`BuildProfile`]: struct.BuildProfile.html
+    pub excluded_passages: HashMap<String, Passage>,
+
+    /// Map from passage name to `Passage` for any author notes, if enabled
+    /// with [`ParseOptions::author_notes`]
+    ///
+    /// [`ParseOptions::author_notes`]: struct.ParseOptions.html#structfield.author_notes
+    pub notes: HashMap<String, Passage>,
+
+    /// The `StoryConfig` or `TweepConfig` passage, if any
+    pub config_passage: Option<Passage>,
+
+    /// The configuration of the story: the external configuration from
+    /// [`ParseOptions::config`] merged with the story's config passage. Its
+    /// dynamic targets also include those of [`ParseOptions::dynamic_targets`].
+    /// See [`StoryConfig`] for details
+    ///
+    /// [`ParseOptions::config`]: struct.ParseOptions.html#structfield.config
+    /// [`ParseOptions::dynamic_targets`]: struct.ParseOptions.html#structfield.dynamic_targets
+    /// [`StoryConfig`]: struct.StoryConfig.html
+    pub config: StoryConfig,
+
+    /// The name of the build profile the story was parsed with, if any
+    pub(crate) profile: Option<String>,
+
+    /// Whether passage names are namespaced by directory
+    pub(crate) namespaced: bool,
+
+    /// The additional special tags the story was parsed with
+    pub(crate) special_tags: Vec<String>,
+
+    /// The path of each source the story was parsed from, `None` for a
+    /// string, in the order they were parsed and merged
+    pub(crate) source_files: Vec<Option<PathBuf>>,
 }
 
 impl StoryPassages {
     /// Renumber pids, starting at the given number and counting up
-    fn renumber_pids(&mut self, start: usize) {
-        let mut pid = start;
-        for passage in self.passages.values_mut() {
+    pub(crate) fn renumber_pids(&mut self, start: usize) {
+        for (pid, passage) in (start..).zip(self.passages.values_mut()) {
             if let PassageContent::Normal(twine) = &mut passage.content {
                 twine.pid = pid;
             }
-
-            pid += 1;
         }
     }
 
     #[cfg(feature = "full-context")]
-    fn renumber_file_ids(&mut self, start: usize) {
+    pub(crate) fn renumber_file_ids(&mut self, start: usize) {
         let mut new_id_file_map = BiMap::new();
         let mut new_contexts = HashMap::new();
         for (id, context) in self.code_map.contexts.drain() {
             let new_id = id + start;
-            new_id_file_map.insert(new_id, context.get_file_name().clone().unwrap());
+            new_id_file_map.insert(new_id, context.get_file_path().unwrap().to_path_buf());
             new_contexts.insert(new_id, context);
         }
-        self.code_map.id_file_map = new_id_file_map;
+        *self.code_map.id_file_map = new_id_file_map;
         self.code_map.contexts = new_contexts;
     }
 
+    /// Returns an iterator over every passage in the story, special or not
+    pub(crate) fn all_passages(&self) -> impl Iterator<Item = &Passage> {
+        self.title
+            .iter()
+            .chain(self.data.iter())
+            .chain(self.passages.values())
+            .chain(self.scripts.iter())
+            .chain(self.stylesheets.iter())
+            .chain(self.tagged_passages.values().flatten())
+    }
+
+    /// Returns a mutable iterator over every passage in the story, special or
+    /// not
+    pub(crate) fn all_passages_mut(&mut self) -> impl Iterator<Item = &mut Passage> {
+        self.title
+            .iter_mut()
+            .chain(self.data.iter_mut())
+            .chain(self.passages.values_mut())
+            .chain(self.scripts.iter_mut())
+            .chain(self.stylesheets.iter_mut())
+            .chain(self.tagged_passages.values_mut().flatten())
+    }
+
+    /// Consumes the story and returns every passage, including author notes,
+    /// excluded passages and the config passage, by the key it is matched by
+    pub(crate) fn into_keyed_passages(self) -> HashMap<PassageKey, Passage> {
+        let StoryPassages {
+            title,
+            data,
+            passages,
+            scripts,
+            stylesheets,
+            tagged_passages,
+            excluded_passages,
+            notes,
+            config_passage,
+            ..
+        } = self;
+        let keyed = |slot| move |passage| (PassageKey::new(&passage, slot), passage);
+        title
+            .into_iter()
+            .chain(data)
+            .chain(passages.into_values())
+            .chain(scripts)
+            .chain(stylesheets)
+            .chain(tagged_passages.into_values().flatten())
+            .map(keyed(PassageSlot::Story))
+            .chain(notes.into_values().map(keyed(PassageSlot::Note)))
+            .chain(excluded_passages.into_values().map(keyed(PassageSlot::Excluded)))
+            .chain(config_passage.into_iter().map(keyed(PassageSlot::Config)))
+            .collect()
+    }
+
+    /// Prepares a parsed story for checking according to `options`, by
+    /// applying its configuration and the build profile, separating author
+    /// notes, expanding templates, generating any missing special passages,
+    /// and validating registered metadata. The resulting warnings are added to
+    /// `warnings`, and the observer notified of them, after which any warnings
+    /// the configuration allows are removed. `source` is the file or directory
+    /// the story was parsed from, if any
+    pub(crate) fn preprocess(&mut self, options: &ParseOptions, source: Option<&Path>, warnings: &mut Vec<Warning>) {
+        let mut new_warnings = Vec::new();
+        let options = &self.apply_config(options, &mut new_warnings);
+        if let Some(profile) = &options.profile {
+            self.apply_profile(profile);
+        }
+        if options.author_notes {
+            self.separate_author_notes();
+        }
+        self.config.dynamic_targets = options.dynamic_targets.clone();
+        self.link_resolver = options.link_resolver.clone();
+        if options.expand_templates {
+            new_warnings.append(&mut self.expand_templates(options));
+        }
+        new_warnings.append(&mut self.generate_missing_specials(options, source));
+        if !options.metadata.is_empty() {
+            let mut passages: Vec<&Passage> = self.all_passages().collect();
+            passages.sort_by(|a, b| a.header.name.cmp(&b.header.name));
+            for passage in passages {
+                new_warnings.append(&mut options.metadata.validate(passage));
+            }
+        }
+        options.notify_warnings(&new_warnings);
+        warnings.append(&mut new_warnings);
+        warnings.retain(|warning| !self.config.allows(warning));
+    }
+
+    /// Adds a passage to the appropriate field based on its content, replacing
+    /// any existing `StoryTitle`, `StoryData`, or passage with the same name
+    pub(crate) fn add_passage(&mut self, passage: Passage) {
+        match &passage.content {
+            PassageContent::Normal(_) => {
+                self.passages.insert(passage.header.name.clone(), passage);
+            }
+            PassageContent::StoryTitle(_) => self.title = Some(passage),
+            PassageContent::StoryData(_) => self.data = Some(passage),
+            PassageContent::Script(_) => match special_tag(&passage, &self.special_tags) {
+                Some(tag) => self.tagged_passages.entry(tag).or_default().push(passage),
+                None => self.scripts.push(passage),
+            },
+            PassageContent::Stylesheet(_) => self.stylesheets.push(passage),
+        }
+    }
+
     /// Parses an input `String` and returns the result or a list of errors,
     /// along with a list of any [`Warning`]s
     ///
     /// [`Warning`]: struct.Warning.html
     pub fn from_string(input: String) -> ParseOutput {
+        StoryPassages::from_string_with_options(input, &ParseOptions::default())
+    }
+
+    /// Parses an input `String` using the given [`ParseOptions`]. See
+    /// `from_string` for additional information.
+    ///
+    /// [`ParseOptions`]: struct.ParseOptions.html
+    pub fn from_string_with_options(input: String, options: &ParseOptions) -> ParseOutput {
         let context = FullContext::from(None, input);
-        StoryPassages::from_context(context)
+        let (res, mut warnings) = StoryPassages::from_context(context, options).take();
+        let res = res.and_then(|story| story.unless_cancelled(options)).and_then(|mut story| {
+            story.preprocess(options, None, &mut warnings);
+            story.deny_duplicates(&mut warnings)
+        });
+        Output::new(res).with_warnings(warnings)
+    }
+
+    /// Moves any duplicate passage warnings out of `warnings` and into a list
+    /// of [`DeniedWarning`] errors, keeping the code map, if the story's
+    /// configuration sets `deny-duplicates`
+    ///
+    /// [`DeniedWarning`]: enum.ErrorKind.html#variant.DeniedWarning
+    pub(crate) fn deny_duplicates(self, warnings: &mut Vec<Warning>) -> Result<StoryPassages, ParseError> {
+        if !warnings.iter().any(|w| self.config.denies_when_parsing(w)) {
+            return Ok(self);
+        }
+        let (denied, kept): (Vec<Warning>, Vec<Warning>) =
+            warnings.drain(..).partition(|w| self.config.denies_when_parsing(w));
+        *warnings = kept;
+        let error_list = ErrorList {
+            errors: denied
+                .into_iter()
+                .map(|w| Error {
+                    kind: ErrorKind::DeniedWarning(w.kind),
+                    context: w.context,
+                    fix: None,
+                })
+                .collect(),
+        };
+        #[cfg(feature = "full-context")]
+        let error_list = ContextErrorList {
+            error_list,
+            code_map: self.code_map,
+        };
+        Err(error_list)
+    }
+
+    /// Converts the story into a [`Cancelled`] error, keeping its code map,
+    /// if parsing with `options` has been cancelled
+    ///
+    /// [`Cancelled`]: enum.ErrorKind.html#variant.Cancelled
+    pub(crate) fn unless_cancelled(self, options: &ParseOptions) -> Result<StoryPassages, ParseError> {
+        match options.cancellation.as_ref().map(CancellationToken::check) {
+            Some(Err(error)) => {
+                #[cfg(feature = "full-context")]
+                let error = ContextErrorList {
+                    error_list: error,
+                    code_map: self.code_map,
+                };
+                Err(error)
+            }
+            _ => Ok(self),
+        }
     }
 
-    pub(crate) fn from_context(context: FullContext) -> ParseOutput {
-        let mut out = StoryPassages::parse(context);
+    pub(crate) fn from_context(context: FullContext, options: &ParseOptions) -> ParseOutput {
+        let mut out = StoryPassages::parse(context, options);
         if out.is_ok() {
             out.mut_output().as_mut().ok().unwrap().renumber_pids(1);
         }
@@ -106,26 +333,131 @@ impl StoryPassages {
     /// [`Path`]: std::path::Path
     /// [`Warning`]: struct.Warning.html
     pub fn from_path<P: AsRef<Path>>(input: P) -> ParseOutput {
-        let out = StoryPassages::from_path_internal(input);
-        let (mut res, mut warnings) = out.take();
+        StoryPassages::from_path_with_options(input, &ParseOptions::default())
+    }
+
+    /// Parses a `StoryPassages` from the given [`Path`] using the given
+    /// [`ParseOptions`]. See `from_path` for additional information.
+    ///
+    /// [`Path`]: std::path::Path
+    /// [`ParseOptions`]: struct.ParseOptions.html
+    pub fn from_path_with_options<P: AsRef<Path>>(input: P, options: &ParseOptions) -> ParseOutput {
+        StoryPassages::from_path_reporting(input.as_ref(), options, None)
+    }
+
+    /// Parses a `StoryPassages` from the given [`Path`] using the given
+    /// [`ParseOptions`], as with `from_path_with_options`, and also returns a
+    /// [`FileReport`] for each twee file found, in the order the files were
+    /// parsed. Unlike the merged output, the reports cover every file, even
+    /// after one of them fails to parse. Warnings found after merging, such
+    /// as duplicates and dead links, are added to the report of the file they
+    /// are located in
+    ///
+    /// # Examples
+    /// ```
+    /// use tweep::{ParseOptions, StoryPassages, WarningKind};
+    /// use std::fs;
+    /// let dir = tempfile::tempdir().unwrap();
+    /// fs::write(dir.path().join("a.twee"), ":: Start\n[[Nowhere]]\n").unwrap();
+    /// fs::write(dir.path().join("b.twee"), ":: Other\nText\n").unwrap();
+    /// let (out, reports) = StoryPassages::from_path_with_reports(dir.path(), &ParseOptions::new());
+    /// assert!(out.is_ok());
+    /// assert_eq!(reports.len(), 2);
+    /// assert_eq!(reports[0].passages, vec!["Start"]);
+    /// assert!(reports[0].warnings.iter().any(|w| w.kind == WarningKind::DeadLink("Nowhere".to_string())));
+    /// assert_eq!(reports[1].passages, vec!["Other"]);
+    /// ```
+    ///
+    /// [`Path`]: std::path::Path
+    /// [`ParseOptions`]: struct.ParseOptions.html
+    /// [`FileReport`]: struct.FileReport.html
+    pub fn from_path_with_reports<P: AsRef<Path>>(input: P, options: &ParseOptions) -> (ParseOutput, Vec<FileReport>) {
+        let mut reports = Vec::new();
+        let out = StoryPassages::from_path_reporting(input.as_ref(), options, Some(&mut reports));
+        for warning in out.get_warnings() {
+            let path = warning.context.as_ref().and_then(|context| context.get_file_path());
+            if let Some(report) = reports.iter_mut().find(|report| Some(report.path.as_path()) == path) {
+                if !report.warnings.contains(warning) {
+                    report.warnings.push(warning.clone());
+                }
+            }
+        }
+        (out, reports)
+    }
+
+    /// Does the work of `from_path_with_options`, adding a [`FileReport`] for
+    /// each file parsed to `reports`, if given
+    ///
+    /// [`FileReport`]: struct.FileReport.html
+    fn from_path_reporting(input: &Path, options: &ParseOptions, reports: Option<&mut Vec<FileReport>>) -> ParseOutput {
+        let out = StoryPassages::from_path_internal(input, options, "", reports);
+        let (res, mut warnings) = out.take();
+        let mut res = res.and_then(|story| story.unless_cancelled(options));
         if res.is_ok() {
-            let story = res.ok().unwrap();
+            let mut story = res.ok().unwrap();
+            story.namespaced = options.directory_namespaces;
+            story.special_tags = options.all_special_tags();
+            story.preprocess(options, Some(input), &mut warnings);
             let mut story_warnings = story.check();
+            options.notify_warnings(&story_warnings);
             warnings.append(&mut story_warnings);
-            res = Ok(story);
+            res = story.deny_duplicates(&mut warnings);
         }
         Output::new(res).with_warnings(warnings)
     }
 
-    /// Parses a `StoryPassages` from the given [`Path`]s. See `from_path` for
+    /// Parses a `StoryPassages` from the given [`Path`]s, which may be any mix
+    /// of files and directories, merging them into a single story in the given
+    /// order. Duplicate passages, including `StoryTitle` and `StoryData`, are
+    /// reported with the same warnings as when merging the files of a
+    /// directory, and the first one found is kept. See `from_path` for
     /// additional information on how directories are handled.
     ///
+    /// # Examples
+    /// ```
+    /// use tweep::{StoryPassages, WarningKind};
+    /// # let dir = tempfile::tempdir().unwrap();
+    /// # std::fs::create_dir(dir.path().join("chapters")).unwrap();
+    /// # std::fs::write(dir.path().join("intro.twee"), ":: StoryTitle\nOne\n\n:: Start\n[[End]]").unwrap();
+    /// # std::fs::write(dir.path().join("chapters/end.twee"), ":: StoryTitle\nTwo\n\n:: End\nFin").unwrap();
+    /// let inputs = vec![dir.path().join("intro.twee"), dir.path().join("chapters")];
+    /// let (res, warnings) = StoryPassages::from_paths(inputs).take();
+    /// let story = res.ok().unwrap();
+    /// assert!(story.passages.contains_key("End"));
+    /// assert!(warnings.iter().any(|w| w.kind == WarningKind::DuplicateStoryTitle));
+    /// ```
+    ///
     /// [`Path`]: std::path::Path
-    pub fn from_paths<P: AsRef<Path>>(input: &[P]) -> ParseOutput {
-        let mut story = StoryPassages::default();
+    pub fn from_paths<I>(input: I) -> ParseOutput
+    where
+        I: IntoIterator,
+        I::Item: AsRef<Path>,
+    {
+        StoryPassages::from_paths_with_options(input, &ParseOptions::default())
+    }
+
+    /// Parses a `StoryPassages` from the given [`Path`]s using the given
+    /// [`ParseOptions`]. See `from_path` for additional information on how
+    /// directories are handled.
+    ///
+    /// [`Path`]: std::path::Path
+    /// [`ParseOptions`]: struct.ParseOptions.html
+    pub fn from_paths_with_options<I>(input: I, options: &ParseOptions) -> ParseOutput
+    where
+        I: IntoIterator,
+        I::Item: AsRef<Path>,
+    {
+        let mut first = None;
+        let mut story = StoryPassages {
+            namespaced: options.directory_namespaces,
+            special_tags: options.all_special_tags(),
+            ..StoryPassages::default()
+        };
         let mut warnings = Vec::new();
         for path in input {
-            let out = StoryPassages::from_path_internal(path);
+            let path = path.as_ref();
+            first.get_or_insert_with(|| path.to_path_buf());
+            let out = StoryPassages::from_path_internal(path, options, "", None);
             let (res, mut sub_warnings) = out.take();
             warnings.append(&mut sub_warnings);
             #[allow(unused_mut)]
@@ -142,125 +474,232 @@ impl StoryPassages {
             }
             let sub_story = res.ok().unwrap();
             let mut merge_warnings = story.merge_from(sub_story);
+            options.notify_warnings(&merge_warnings);
             warnings.append(&mut merge_warnings);
         }
 
+        let mut story = match story.unless_cancelled(options) {
+            Ok(story) => story,
+            Err(e) => return Output::new(Err(e)).with_warnings(warnings),
+        };
+        story.preprocess(options, first.as_deref(), &mut warnings);
         let mut story_warnings = story.check();
+        options.notify_warnings(&story_warnings);
         warnings.append(&mut story_warnings);
 
-        Output::new(Ok(story)).with_warnings(warnings)
+        let res = story.deny_duplicates(&mut warnings);
+        Output::new(res).with_warnings(warnings)
     }
 
-    /// Does the heavy lifting for `from_path`. If given a file, reads its
-    /// contents into a `String` and uses `from_context` to parse it. If given a
-    /// directory, finds the twee files, recurses with each file, then assembles
-    /// the outputs into a single output
-    fn from_path_internal<P: AsRef<Path>>(input: P) -> ParseOutput {
+    /// Does the heavy lifting for `from_path`. If given a file, parses it with
+    /// `from_file`. If given a directory, finds the twee files, recurses with
+    /// each file, then assembles the outputs into a single output. If
+    /// directory namespaces are enabled, also recurses into subdirectories, and
+    /// prefixes passage names with `namespace`. If `reports` is given, adds a
+    /// report for each file to it, and keeps parsing files after an error
+    pub(crate) fn from_path_internal<P: AsRef<Path>>(
+        input: P,
+        options: &ParseOptions,
+        namespace: &str,
+        mut reports: Option<&mut Vec<FileReport>>,
+    ) -> ParseOutput {
         // Get the path
         let path: &Path = input.as_ref();
 
-        // Convert path to string
-        let path_string: String = path.to_string_lossy().to_owned().to_string();
-
         if path.is_file() {
-            // If path is a file, get the file name part
-            let file_name: String = path
-                .file_name()
-                .unwrap()
-                .to_string_lossy()
-                .to_owned()
-                .to_string();
-
-            // Open the file
-            let file = File::open(path);
-
-            if file.is_err() {
-                // Check for errors, return Error if we can't open file
-                let err_string = format!("{}", file.err().unwrap());
-                return Output::new(Err(Error::new(
-                    crate::ErrorKind::BadInputPath(path_string, err_string),
-                    Some(FullContext::from(None, file_name)),
-                )
-                .into()));
+            let out = StoryPassages::from_file(path, options, namespace);
+            if let Some(reports) = reports {
+                reports.push(FileReport::from_output(path, &out));
             }
-
-            // Get the file
-            let mut file = file.ok().unwrap();
-
-            // Slurp the file contents
-            let mut contents = String::new();
-            let res = file.read_to_string(&mut contents);
-
-            if res.is_err() {
-                // Return an error if we can't read the file
-                let err_string = format!("{}", res.err().unwrap());
-                return Output::new(Err(Error::new(
-                    crate::ErrorKind::BadInputPath(path_string, err_string),
-                    Some(FullContext::from(None, file_name)),
-                )
-                .into()));
-            }
-
-            // Create the object from the contents, add file name to Positions
-            let context = FullContext::from(Some(file_name), contents);
-            StoryPassages::from_context(context)
+            out
         } else if path.is_dir() {
             let dir = std::fs::read_dir(path);
             if dir.is_err() {
                 let err_string = format!("{}", dir.err().unwrap());
                 return Output::new(Err(Error::new::<Context>(
-                    crate::ErrorKind::BadInputPath(path_string, err_string),
+                    crate::ErrorKind::BadInputPath(path.to_path_buf(), err_string),
                     None,
                 )
                 .into()));
             }
-            let dir = dir.ok().unwrap();
-            let mut story = StoryPassages::default();
-            let mut warnings = Vec::new();
-            for entry in dir {
-                if entry.is_err() {
-                    continue;
+            debug!(path = %path.display(), "Reading directory");
+            // Parse entries in a fixed order, so that passages are always
+            // merged in the same order
+            let mut entries: Vec<_> = dir.ok().unwrap().filter_map(|entry| entry.ok()).map(|entry| entry.path()).collect();
+            entries.sort();
+            let reporting = reports.is_some();
+            let mut failed = false;
+            let mut orphans = Vec::new();
+            let outputs: Vec<ParseOutput> = entries.into_iter().filter_map(|file_path| {
+                // Without reports, there's no need to parse past an error
+                if failed && !reporting {
+                    return None;
                 }
-                let file_path = entry.ok().unwrap().path();
-                let extension = file_path.extension();
-                if extension.is_none() {
-                    continue;
-                }
-                let extension = extension.unwrap().to_string_lossy();
-                if !((extension == "tw" || extension == "twee") && file_path.is_file()) {
-                    continue;
-                }
-                let out = StoryPassages::from_path_internal(file_path);
-                let (res, mut sub_warnings) = out.take();
-                if res.is_err() {
-                    return Output::new(res).with_warnings(warnings);
+                let file_name = file_path.file_name()?.to_string_lossy();
+                let relative = if namespace.is_empty() {
+                    file_name.to_string()
+                } else {
+                    format!("{}/{}", namespace, file_name)
+                };
+                if options.excludes(&file_path, &relative, file_path.is_dir()) {
+                    debug!(path = %file_path.display(), "Excluded from parsing");
+                    if options.report_orphan_files {
+                        orphans.extend(twee_files(&file_path).into_iter().map(|path| {
+                            let kind = WarningKind::ExcludedFile(path);
+                            Warning::new::<Context>(kind, None)
+                        }));
+                    }
+                    return None;
                 }
-                let sub_story = res.ok().unwrap();
-                let mut merge_warnings = story.merge_from(sub_story);
-                warnings.append(&mut sub_warnings);
-                warnings.append(&mut merge_warnings);
+                let out = if options.directory_namespaces && file_path.is_dir() {
+                    // Namespace passages by the subdirectory's path
+                    StoryPassages::from_path_internal(&file_path, options, &relative, reports.as_deref_mut())
+                } else {
+                    let extension = file_path.extension()?.to_string_lossy();
+                    if (extension == "tw" || extension == "twee") && file_path.is_file() {
+                        StoryPassages::from_path_internal(&file_path, options, namespace, reports.as_deref_mut())
+                    } else {
+                        return None;
+                    }
+                };
+                failed |= out.is_err();
+                Some(out)
+            }).collect();
+            let out = Output::merge_all(outputs, StoryPassages::default(), |story, sub_story| {
+                let merge_warnings = story.merge_from(sub_story);
+                options.notify_warnings(&merge_warnings);
+                merge_warnings
+            });
+
+            // Only directories given to a parse method are reported as
+            // unmatched, not the subdirectories found in them
+            let (res, mut warnings) = out.take();
+            let empty = res.as_ref().is_ok_and(|story| story.all_passages().next().is_none());
+            if options.report_orphan_files && namespace.is_empty() && empty {
+                orphans.push(Warning::new::<Context>(WarningKind::UnmatchedPath(path.to_path_buf()), None));
             }
-            Output::new(Ok(story)).with_warnings(warnings)
+            options.notify_warnings(&orphans);
+            warnings.append(&mut orphans);
+            Output::new(res).with_warnings(warnings)
         } else {
             let err_string = "Path is not a file or directory".to_string();
             Output::new(Err(Error::new::<Context>(
-                crate::ErrorKind::BadInputPath(path_string, err_string),
+                crate::ErrorKind::BadInputPath(path.to_path_buf(), err_string),
                 None,
             )
             .into()))
         }
     }
 
+    /// Reads the file at `path` into a `String` and uses `from_context` to
+    /// parse it, prefixing passage names with `namespace`
+    fn from_file(path: &Path, options: &ParseOptions, namespace: &str) -> ParseOutput {
+        let _span = enter_span!("file", path = %path.display());
+        options.notify_file_started(path);
+
+        // Get the file name part
+        let file_name: String = path
+            .file_name()
+            .unwrap()
+            .to_string_lossy()
+            .into_owned();
+
+        // Open the file
+        let file = File::open(path);
+
+        if file.is_err() {
+            // Check for errors, return Error if we can't open file
+            let err_string = format!("{}", file.err().unwrap());
+            return Output::new(Err(Error::new(
+                crate::ErrorKind::BadInputPath(path.to_path_buf(), err_string),
+                Some(FullContext::from(None, file_name)),
+            )
+            .into()));
+        }
+
+        // Get the file
+        let mut file = file.ok().unwrap();
+
+        // Slurp the file contents, falling back to Windows-1252 for files
+        // that aren't valid UTF-8 in lenient mode
+        let mut contents = String::new();
+        let mut legacy_encoding = false;
+        let res = if options.lenient {
+            let mut bytes = Vec::new();
+            file.read_to_end(&mut bytes).map(|_| {
+                contents = String::from_utf8(bytes).unwrap_or_else(|e| {
+                    legacy_encoding = true;
+                    windows_1252::decode(e.as_bytes())
+                });
+            })
+        } else {
+            file.read_to_string(&mut contents).map(|_| ())
+        };
+
+        if res.is_err() {
+            // Return an error if we can't read the file
+            let err_string = format!("{}", res.err().unwrap());
+            return Output::new(Err(Error::new(
+                crate::ErrorKind::BadInputPath(path.to_path_buf(), err_string),
+                Some(FullContext::from(None, file_name)),
+            )
+            .into()));
+        }
+
+        // Create the object from the contents, add file path to Positions
+        let context = FullContext::from_file_path(Some(path.to_path_buf()), contents);
+        let mut encoding_warnings = Vec::new();
+        if legacy_encoding {
+            debug!("File isn't valid UTF-8, decoded as Windows-1252");
+            encoding_warnings.push(Warning::new(WarningKind::LegacyEncoding, Some(context.clone())));
+            options.notify_warnings(&encoding_warnings);
+        }
+        let (res, mut warnings) = StoryPassages::from_context(context, options).take();
+        encoding_warnings.append(&mut warnings);
+        let mut out = Output::new(res).with_warnings(encoding_warnings);
+        if out.is_ok() && !namespace.is_empty() {
+            out.mut_output().as_mut().ok().unwrap().add_namespace(namespace);
+        }
+        out
+    }
+
+    /// Prefixes the name of every non-special passage with `namespace`
+    pub(crate) fn add_namespace(&mut self, namespace: &str) {
+        let passages = std::mem::take(&mut self.passages);
+        self.passages = passages
+            .into_iter()
+            .map(|(name, mut passage)| {
+                let name = format!("{}/{}", namespace, name);
+                passage.header.name = name.clone();
+                (name, passage)
+            })
+            .collect();
+    }
+
     /// Merges the given `StoryPassages` into this one, producing a possible
     /// list of [`Warning`]s in the process.
     ///
     /// # Warnings
     /// Produces a warning if a duplicate `StoryTitle` or `StoryData` is found.
-    /// The duplicate is ignored and the existing one is kept.
+    /// The duplicate is ignored and the existing one is kept. The same goes for
+    /// a duplicate passage, which produces a [`DuplicatePassageAcrossFiles`]
+    /// warning if it came from a different file than the existing one, or a
+    /// [`DuplicatePassage`] warning otherwise. Each warning has the existing
+    /// definition as its referent
+    ///
+    /// [`Warning`]: struct.Warning.html
+    /// [`DuplicatePassageAcrossFiles`]: enum.WarningKind.html#variant.DuplicatePassageAcrossFiles
+    /// [`DuplicatePassage`]: enum.WarningKind.html#variant.DuplicatePassage
     pub fn merge_from(&mut self, mut other: Self) -> Vec<Warning> {
         let mut warnings = Vec::new();
 
         other.renumber_pids(self.passages.len() + 1);
+        self.namespaced |= other.namespaced;
+        for file in other.source_files.drain(..) {
+            if !self.source_files.contains(&file) {
+                self.source_files.push(file);
+            }
+        }
 
         #[cfg(feature = "full-context")]
         {
@@ -274,12 +713,7 @@ impl StoryPassages {
         match (&self.title, &other.title) {
             (None, Some(_)) => self.title = other.title,
             (Some(self_title), Some(other_title)) => {
-                let mut warning = Warning::new(
-                    WarningKind::DuplicateStoryTitle,
-                    Some(other_title.context.clone()),
-                );
-                warning.set_referent(self_title.context.clone());
-                warnings.push(warning)
+                warnings.push(duplicate_warning(WarningKind::DuplicateStoryTitle, other_title, self_title));
             }
             _ => (),
         }
@@ -287,12 +721,7 @@ impl StoryPassages {
         match (&self.data, &other.data) {
             (None, Some(_)) => self.data = other.data,
             (Some(self_data), Some(other_data)) => {
-                let mut warning = Warning::new(
-                    WarningKind::DuplicateStoryData,
-                    Some(other_data.context.clone()),
-                );
-                warning.set_referent(self_data.context.clone());
-                warnings.push(warning);
+                warnings.push(duplicate_warning(WarningKind::DuplicateStoryData, other_data, self_data));
             }
             _ => (),
         }
@@ -305,24 +734,96 @@ impl StoryPassages {
                     entry.or_insert(passage);
                 },
                 Occupied(v) => {
-                    let warning = Warning::new(WarningKind::DuplicatePassage(name), Some(passage.context.clone())).with_referent(v.get().context.clone());
-                    warnings.push(warning);
+                    let kind = if passage.context.get_file_path() == v.get().context.get_file_path() {
+                        WarningKind::DuplicatePassage(name)
+                    } else {
+                        WarningKind::DuplicatePassageAcrossFiles(name)
+                    };
+                    warnings.push(duplicate_warning(kind, &passage, v.get()));
                 }
             }
         }
 
         self.scripts.append(&mut other.scripts);
         self.stylesheets.append(&mut other.stylesheets);
+        for (tag, mut passages) in other.tagged_passages.drain() {
+            self.tagged_passages.entry(tag).or_default().append(&mut passages);
+        }
+        self.excluded_passages.extend(other.excluded_passages.drain());
+        self.notes.extend(other.notes.drain());
+        if self.profile.is_none() {
+            self.profile = other.profile.take();
+        }
+        for tag in other.special_tags {
+            if !self.special_tags.contains(&tag) {
+                self.special_tags.push(tag);
+            }
+        }
 
         warnings
     }
 
-    /// Performs a set of post-parse checks and returns a list of any warnings
+    /// Performs a set of post-parse checks and returns a list of any warnings.
+    /// This is the warnings of [`check_special_passages`] followed by those of
+    /// [`check_links`]
+    ///
+    /// [`check_special_passages`]: #method.check_special_passages
+    /// [`check_links`]: #method.check_links
+    pub fn check(&self) -> Vec<Warning> {
+        self.check_internal(None, &CheckOptions::default()).expect("Checking without a token is never cancelled")
+    }
+
+    /// Performs the same checks as [`check`], adjusted by the given
+    /// [`CheckOptions`] for a story that is only part of a whole, such as a
+    /// single chapter, or for the options the story is exported with
+    ///
+    /// [`check`]: #method.check
+    /// [`CheckOptions`]: struct.CheckOptions.html
+    pub fn check_with(&self, options: &CheckOptions) -> Vec<Warning> {
+        self.check_internal(None, options).expect("Checking without a token is never cancelled")
+    }
+
+    /// Performs the same checks as [`check`], but returns a list holding a
+    /// [`Cancelled`] error as soon as `token` is cancelled, which is checked before each
+    /// link is checked
+    ///
+    /// # Examples
+    /// ```
+    /// use tweep::{CancellationToken, ErrorKind, StoryPassages};
+    /// let story = StoryPassages::from_string(":: Start\n[[Nowhere]]".to_string()).take().0.ok().unwrap();
+    /// let token = CancellationToken::new();
+    /// assert_eq!(story.check_cancellable(&token).unwrap(), story.check());
+    /// token.cancel();
+    /// assert_eq!(story.check_cancellable(&token).err().unwrap().errors[0].kind, ErrorKind::Cancelled);
+    /// ```
+    ///
+    /// [`check`]: #method.check
+    /// [`Cancelled`]: enum.ErrorKind.html#variant.Cancelled
+    pub fn check_cancellable(&self, token: &CancellationToken) -> Result<Vec<Warning>, ErrorList> {
+        self.check_internal(Some(token), &CheckOptions::default())
+    }
+
+    /// Does the work of `check`, `check_with`, and `check_cancellable`
+    fn check_internal(&self, token: Option<&CancellationToken>, options: &CheckOptions) -> Result<Vec<Warning>, ErrorList> {
+        let mut warnings = self.check_special_passages();
+        if options.partial {
+            warnings.retain(|warning| {
+                !matches!(
+                    warning.kind,
+                    WarningKind::MissingStoryTitle | WarningKind::MissingStoryData | WarningKind::MissingStartPassage
+                )
+            });
+        }
+        warnings.extend(self.check_links_internal(token, options)?);
+        Ok(warnings)
+    }
+
+    /// Checks for the special passages a story needs and returns a list of any
+    /// warnings
     ///
     /// # Warnings
     /// * [`MissingStoryTitle`] - No `StoryTitle` passage found
     /// * [`MissingStoryData`] - No `StoryData` passage found
-    /// * [`DeadLink`] - Found a link to a non-existent passage
     /// * [`MissingStartPassage`] - No `Start` passage found and no alternate
     ///   passage set in `StoryData`
     /// * [`DeadStartPassage`] - Alternate start passage set in `StoryData`, but
@@ -330,10 +831,9 @@ impl StoryPassages {
     ///
     /// [`MissingStoryTitle`]: enum.WarningKind.html#variant.MissingStoryTitle
     /// [`MissingStoryData`]: enum.WarningKind.html#variant.MissingStoryData
-    /// [`DeadLink`]: enum.WarningKind.html#variant.DeadLink
     /// [`MissingStartPassage`]: enum.WarningKind.html#variant.MissingStartPassage
     /// [`DeadStartPassage`]: enum.WarningKind.html#variant.DeadStartPassage
-    pub fn check(&self) -> Vec<Warning> {
+    pub fn check_special_passages(&self) -> Vec<Warning> {
         let mut warnings = Vec::new();
         if self.title.is_none() {
             warnings.push(Warning::new::<Context>(
@@ -361,7 +861,7 @@ impl StoryPassages {
                         // If there is parsed StoryData, get the start field
                         .and_then(|data| data.start.as_ref())
                         // If there is a start field
-                        .and_then(|start| {
+                        .map(|start| {
                             // Even if the start field is a dead link, it's not
                             // missing a start passage
                             missing_start = false;
@@ -375,9 +875,6 @@ impl StoryPassages {
                                     Some(passage.context.clone()),
                                 ));
                             }
-
-                            // Return something
-                            Some(())
                         })
                 } else {
                     None
@@ -391,22 +888,106 @@ impl StoryPassages {
             ));
         }
 
-        for passage in self.passages.values() {
-            if let PassageContent::Normal(twine) = &passage.content {
-                for link in twine.get_links() {
-                    // Trim the target so that a whitespace warning and a dead
-                    // link warning aren't both generated
-                    if !self.passages.contains_key(link.target.trim()) {
-                        warnings.push(Warning::new(
-                            WarningKind::DeadLink(link.target.clone()),
-                            Some(link.context.clone()),
-                        ));
-                    }
-                }
+        warnings.retain(|warning| !self.config.allows(warning));
+        warnings
+    }
+
+    /// Checks every link in the story, and every dynamic target in its
+    /// [`config`], against the passages of the story and returns a list of any
+    /// warnings. This doesn't depend on the special passages, so an editor can
+    /// call it alone to recheck links after a passage changes. Targets accepted
+    /// by the [`ParseOptions::link_resolver`] the story was parsed with are not
+    /// dead
+    ///
+    /// # Warnings
+    /// * [`DeadLink`] - Found a link to a non-existent passage
+    /// * [`TrimmedLinkMatch`] - Found a link that only matches a passage once
+    ///   trailing punctuation is removed from its target
+    /// * [`ExcludedLink`] - Found a link to a passage excluded by the build
+    ///   profile
+    /// * [`DeadDynamicTarget`] - A dynamic target doesn't match any passage
+    /// * [`UnexportedLink`] - Found a link from an exported passage to one
+    ///   left out by the filter of [`CheckOptions::export`]. Only produced
+    ///   by [`check_with`]
+    ///
+    /// # Examples
+    /// ```
+    /// use tweep::{StoryPassages, WarningKind};
+    /// let story = StoryPassages::from_string(":: Start\n[[Hall]] [[Cellar]]\n\n:: Hall\n".to_string())
+    ///     .take().0.ok().unwrap();
+    /// let kinds: Vec<WarningKind> = story.check_links().into_iter().map(|w| w.kind).collect();
+    /// assert_eq!(kinds, vec![WarningKind::DeadLink("Cellar".to_string())]);
+    /// ```
+    ///
+    /// [`DeadLink`]: enum.WarningKind.html#variant.DeadLink
+    /// [`TrimmedLinkMatch`]: enum.WarningKind.html#variant.TrimmedLinkMatch
+    /// [`ExcludedLink`]: enum.WarningKind.html#variant.ExcludedLink
+    /// [`DeadDynamicTarget`]: enum.WarningKind.html#variant.DeadDynamicTarget
+    /// [`UnexportedLink`]: enum.WarningKind.html#variant.UnexportedLink
+    /// [`config`]: #structfield.config
+    /// [`CheckOptions::export`]: struct.CheckOptions.html#structfield.export
+    /// [`check_with`]: #method.check_with
+    /// [`ParseOptions::link_resolver`]: struct.ParseOptions.html#structfield.link_resolver
+    pub fn check_links(&self) -> Vec<Warning> {
+        self.check_links_internal(None, &CheckOptions::default()).expect("Checking without a token is never cancelled")
+    }
+
+    /// Returns the kind of warning to produce for a link in the passage named
+    /// `name`, if any
+    pub(crate) fn link_warning(&self, name: &str, link: &TwineLink, options: &CheckOptions) -> Option<WarningKind> {
+        if let Some(target) = self.resolve_link(name, &link.target) {
+            let filter = &options.export.filter;
+            if self.is_exported(name, filter) && !self.is_exported(target, filter) {
+                return Some(WarningKind::UnexportedLink(target.to_string()));
             }
+            return None;
         }
 
-        warnings
+        if link.is_dynamic() {
+            return None;
+        }
+
+        if let (Some(profile), true) = (&self.profile, self.excluded_passages.contains_key(link.target.trim())) {
+            return Some(WarningKind::ExcludedLink(link.target.clone(), profile.clone()));
+        }
+
+        if self.link_resolver.as_ref().is_some_and(|resolver| resolver(name, &link.target))
+            || options.is_external(&link.target)
+        {
+            return None;
+        }
+
+        // Distinguish likely copy-paste artifacts from hard dead links
+        let trimmed = link.target.trim().trim_end_matches(|c: char| c.is_ascii_punctuation());
+        Some(match self.resolve_link(name, trimmed) {
+            Some(resolved) if !trimmed.is_empty() => {
+                WarningKind::TrimmedLinkMatch(link.target.clone(), resolved.to_string())
+            }
+            _ => WarningKind::DeadLink(link.target.clone()),
+        })
+    }
+
+    /// Does the work of `check_links`, returning a `Cancelled` error as soon
+    /// as `token` is cancelled
+    fn check_links_internal(&self, token: Option<&CancellationToken>, options: &CheckOptions) -> Result<Vec<Warning>, ErrorList> {
+        let mut warnings = Vec::new();
+        for (name, link) in self.links() {
+            if let Some(token) = token {
+                token.check()?;
+            }
+            if let Some(kind) = self.link_warning(name, link, options) {
+                warnings.push(Warning::new(kind, Some(link.context.clone())));
+            }
+        }
+
+        for name in &self.config.dynamic_targets {
+            if !self.passages.contains_key(name) {
+                warnings.push(Warning::new::<Context>(WarningKind::DeadDynamicTarget(name.clone()), None));
+            }
+        }
+
+        warnings.retain(|warning| !self.config.allows(warning));
+        Ok(warnings)
     }
 
     /// If a start passage is configured in the StoryData, return the name of
@@ -414,12 +995,7 @@ impl StoryPassages {
     /// of a passage called "Start". If that passage exists, return that name,
     /// otherwise return None
     pub fn get_start_passage_name(&self) -> Option<&str> {
-        self.data
-            .as_ref()
-            .and_then(|d| match &d.content {
-                PassageContent::StoryData(story_data) => story_data.as_ref(),
-                _ => None,
-            })
+        self.story_data()
             .and_then(|d| d.start.as_deref())
             .or_else(|| {
                 if self.passages.contains_key("Start") {
@@ -430,11 +1006,10 @@ impl StoryPassages {
             })
     }
 
-    pub(crate) fn parse(context: FullContext) -> ParseOutput {
-        let contents = context.get_contents();
-
+    pub(crate) fn parse(context: FullContext, options: &ParseOptions) -> ParseOutput {
         #[cfg(feature = "full-context")]
         let mut code_map = CodeMap::default();
+        let source_file = context.get_file_path().map(Path::to_path_buf);
 
         // Story variables
         let mut title: Option<Passage> = None;
@@ -442,6 +1017,7 @@ impl StoryPassages {
         let mut passages:HashMap<String, Passage> = HashMap::new();
         let mut scripts = Vec::new();
         let mut stylesheets = Vec::new();
+        let mut tagged_passages: HashMap<String, Vec<Passage>> = HashMap::new();
 
         // Running list of warnings
         let mut warnings = Vec::new();
@@ -449,79 +1025,98 @@ impl StoryPassages {
         // Running list of errors
         let mut errors = Ok(());
 
-        // Get an iterator to go through each line
-        let mut iter = contents.split('\n').enumerate();
-        // The first line must be a header, skip over it so we don't have an
-        // empty slice
-        iter.next();
+        // Get the header lines. The first line must be a header, so it's
+        // skipped so we don't have an empty slice. They're collected so that
+        // the context is no longer borrowed once the passages are split
+        let headers: Vec<usize> = header_lines(context.get_contents()).filter(|&i| i > 0).collect();
+        let mut headers = headers.into_iter();
 
         // The starting position of the current passage
         let mut start = Position::rel(1, 1);
 
+        // Split the input into the contexts of its passages
+        let mut chunks = Vec::new();
         let end_line = context.get_end_position().line;
         while start.line <= end_line {
             let subcontext_start = start;
             let subcontext_end =
-                if let Some((i, _)) = iter.find(|&(_, line)| line.trim_start().starts_with("::")) {
+                if let Some(i) = headers.next() {
                     context.end_of_line(i, PositionKind::Relative)
                 } else {
                     *context.get_end_position()
                 };
 
-            let next_line = subcontext_end.line + 1;
-            let subcontext = context.subcontext(subcontext_start..=subcontext_end);
-            // Parse the passage
-            let (mut res, mut passage_warnings) = Passage::parse(subcontext).take();
-            warnings.append(&mut passage_warnings);
+            chunks.push(context.subcontext(subcontext_start..=subcontext_end));
 
             // Update the start position
-            start = Position::rel(next_line, 1);
+            start = Position::rel(subcontext_end.line + 1, 1);
+        }
+        debug!(passages = chunks.len(), "Split input into passages");
+
+        let mut outputs = parse_chunks(chunks, options);
+        if let Some(Err(error)) = options.cancellation.as_ref().map(CancellationToken::check) {
+            debug!("Parsing cancelled");
+            outputs.clear();
+            errors = Err(error);
+        }
+
+        for out in outputs {
+            let (mut res, mut passage_warnings) = out.take();
 
             // If there's an error, update the row before returning
             if res.is_err() {
+                options.notify_warnings(&passage_warnings);
+                warnings.append(&mut passage_warnings);
                 errors = ErrorList::merge(&mut errors, &mut res);
                 continue;
             }
 
-            let passage = res.ok().unwrap();
+            let mut passage = res.ok().unwrap();
+            if options.normalize_link_whitespace {
+                if let PassageContent::Normal(twine) = &mut passage.content {
+                    twine.normalize_link_whitespace();
+                }
+                passage_warnings.retain(|w| !matches!(w.kind, WarningKind::WhitespaceInLink(_)));
+            }
+            options.notify_passage_parsed(&passage);
 
             // Handle passage types appropriately
             match &passage.content {
                 PassageContent::Normal(_) => {
                     let name = &passage.header.name;
-                    if passages.contains_key(name) {
-                        warnings.push(Warning::new(WarningKind::DuplicatePassage(name.clone()), Some(passage.context.clone())).with_referent(passages.get(name).unwrap().context.clone()));
+                    if let Some(existing) = passages.get(name) {
+                        debug!(passage = %name, "Dropping duplicate passage");
+                        passage_warnings.push(duplicate_warning(WarningKind::DuplicatePassage(name.clone()), &passage, existing));
                     } else {
                         passages.insert(name.clone(), passage);
                     }
                 }
                 PassageContent::StoryTitle(_) => {
                     if let Some(existing) = &title {
-                        let mut warning = Warning::new(
-                            WarningKind::DuplicateStoryTitle,
-                            Some(passage.context.clone()),
-                        );
-                        warning.set_referent(existing.context.clone());
-                        warnings.push(warning);
+                        passage_warnings.push(duplicate_warning(WarningKind::DuplicateStoryTitle, &passage, existing));
                     } else {
                         title = Some(passage);
                     }
                 }
                 PassageContent::StoryData(_) => {
                     if let Some(existing) = &data {
-                        let mut warning = Warning::new(
-                            WarningKind::DuplicateStoryData,
-                            Some(passage.context.clone()),
-                        );
-                        warning.set_referent(existing.context.clone());
-                        warnings.push(warning);
+                        passage_warnings.push(duplicate_warning(WarningKind::DuplicateStoryData, &passage, existing));
                     } else {
                         data = Some(passage);
                     }
                 }
-                PassageContent::Script(_) => scripts.push(passage),
+                PassageContent::Script(_) => match special_tag(&passage, &options.all_special_tags()) {
+                    Some(tag) => {
+                        debug!(passage = %passage.header.name, tag = %tag, "Filed under special tag");
+                        tagged_passages.entry(tag).or_default().push(passage)
+                    }
+                    None => scripts.push(passage),
+                },
                 PassageContent::Stylesheet(_) => stylesheets.push(passage),
             }
+
+            options.notify_warnings(&passage_warnings);
+            warnings.append(&mut passage_warnings);
         }
 
         #[cfg(feature = "full-context")]
@@ -534,8 +1129,18 @@ impl StoryPassages {
                     passages,
                     scripts,
                     stylesheets,
+                    tagged_passages,
                     #[cfg(feature = "full-context")]
                     code_map,
+                    link_resolver: None,
+                    excluded_passages: HashMap::new(),
+                    notes: HashMap::new(),
+                    config_passage: None,
+                    config: StoryConfig::default(),
+                    profile: None,
+                    namespaced: false,
+                    special_tags: options.all_special_tags(),
+                    source_files: vec![source_file],
                 };
                 Output::new(Ok(story))
             }
@@ -552,6 +1157,107 @@ impl StoryPassages {
     }
 }
 
+/// Parses the passage in each of the given contexts, returning the outputs in
+/// the same order, or fewer outputs if parsing is cancelled. With the
+/// `parallel` feature, the contexts are split into
+/// one group per available core and parsed on scoped threads
+fn parse_chunks(chunks: Vec<FullContext>, options: &ParseOptions) -> Vec<Output<Result<Passage, ErrorList>>> {
+    #[cfg(feature = "parallel")]
+    {
+        let threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+        if threads > 1 && chunks.len() > 1 {
+            let size = chunks.len().div_ceil(threads);
+            return std::thread::scope(|scope| {
+                let handles: Vec<_> = chunks
+                    .chunks(size)
+                    .map(|group| {
+                        scope.spawn(move || {
+                            group
+                                .iter()
+                                .take_while(|_| !options.is_cancelled())
+                                .map(|chunk| Passage::parse(chunk.clone(), options))
+                                .collect::<Vec<_>>()
+                        })
+                    })
+                    .collect();
+                handles
+                    .into_iter()
+                    .flat_map(|handle| handle.join().expect("Passage parsing thread panicked"))
+                    .collect()
+            });
+        }
+    }
+
+    chunks
+        .into_iter()
+        .take_while(|_| !options.is_cancelled())
+        .map(|chunk| Passage::parse(chunk, options))
+        .collect()
+}
+
+/// Returns the paths of the twee files at `path`: the file itself, or the files
+/// anywhere within the directory, in sorted order
+fn twee_files(path: &Path) -> Vec<PathBuf> {
+    if path.is_dir() {
+        let mut entries: Vec<PathBuf> = match std::fs::read_dir(path) {
+            Ok(dir) => dir.filter_map(|entry| entry.ok()).map(|entry| entry.path()).collect(),
+            Err(_) => return Vec::new(),
+        };
+        entries.sort();
+        entries.iter().flat_map(|entry| twee_files(entry)).collect()
+    } else {
+        let twee = path.extension().is_some_and(|extension| extension == "tw" || extension == "twee");
+        if twee && path.is_file() {
+            vec![path.to_path_buf()]
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+/// Returns a warning of the given kind located at the `duplicate` passage,
+/// with the `original` passage it duplicates as its referent
+fn duplicate_warning(kind: WarningKind, duplicate: &Passage, original: &Passage) -> Warning {
+    Warning::new(kind, Some(duplicate.context.clone())).with_referent(original.context.clone())
+}
+
+/// Returns the zero-based index of each line of `contents` that starts a
+/// passage: a line whose first non-whitespace characters are `::`. Scans for
+/// `::` rather than splitting the input into lines, since large stories are
+/// mostly passage text
+fn header_lines(contents: &str) -> impl Iterator<Item = usize> + '_ {
+    let bytes = contents.as_bytes();
+    let mut line = 0;
+    let mut line_start = 0;
+    let mut scanned = 0;
+    memchr::memmem::find_iter(bytes, b"::").filter_map(move |pos| {
+        // Only look back as far as the previous match, so that lines with
+        // many `::` in them don't get scanned more than once
+        if let Some(i) = memchr::memrchr(b'\n', &bytes[scanned..pos]) {
+            line += memchr::memchr_iter(b'\n', &bytes[scanned..=scanned + i]).count();
+            line_start = scanned + i + 1;
+        }
+        scanned = pos;
+        if contents[line_start..pos].trim_start().is_empty() {
+            Some(line)
+        } else {
+            None
+        }
+    })
+}
+
+/// Returns the custom kind or additional special tag under which a passage
+/// with script content belongs, or `None` if it's a real script
+fn special_tag(passage: &Passage, special_tags: &[String]) -> Option<String> {
+    if let PassageKind::Custom(kind) = passage.kind() {
+        return Some(kind.clone());
+    }
+    if passage.header.has_tag("script") {
+        return None;
+    }
+    special_tags.iter().find(|tag| passage.header.has_tag(tag)).cloned()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -581,16 +1287,13 @@ Test Story
         .to_string();
         let context = FullContext::from(None, input.clone());
         let out = StoryPassages::from_string(input);
-        assert_eq!(out.has_warnings(), true);
+        assert!(out.has_warnings());
         let (res, warnings) = out.take();
-        assert_eq!(res.is_ok(), true);
-        assert_eq!(warnings[0], {
-            let warning = Warning::new(
-                WarningKind::EscapedOpenSquare,
-                Some(context.subcontext(Position::rel(7, 5)..=Position::rel(7, 6))),
-            );
-            warning
-        });
+        assert!(res.is_ok());
+        assert_eq!(warnings[0], Warning::new(
+            WarningKind::EscapedOpenSquare(r"A\[nother passage".to_string()),
+            Some(context.subcontext(Position::rel(7, 5)..=Position::rel(7, 6))),
+        ));
     }
 
     #[test]
@@ -619,23 +1322,20 @@ Test Story
         let mut file = File::create(file_path.clone())?;
         write!(file, "{}", input.clone())?;
 
-        let out = StoryPassages::from_path(file_path);
-        assert_eq!(out.has_warnings(), true);
+        let out = StoryPassages::from_path(file_path.clone());
+        assert!(out.has_warnings());
         let (res, warnings) = out.take();
-        assert_eq!(res.is_ok(), true);
+        assert!(res.is_ok());
         let story = res.ok().unwrap();
-        assert_eq!(story.title.is_some(), true);
+        assert!(story.title.is_some());
         let title_content = story.title.unwrap().content;
-        let context = FullContext::from(Some("test.twee".to_string()), input);
+        let context = FullContext::from_file_path(Some(file_path), input);
         if let PassageContent::StoryTitle(title) = title_content {
             assert_eq!(title.title, "Test Story");
-            assert_eq!(warnings[0], {
-                let warning = Warning::new(
-                    WarningKind::EscapedOpenSquare,
-                    Some(context.subcontext(Position::rel(7, 5)..=Position::rel(7, 6))),
-                );
-                warning
-            });
+            assert_eq!(warnings[0], Warning::new(
+                WarningKind::EscapedOpenSquare(r"A\[nother passage".to_string()),
+                Some(context.subcontext(Position::rel(7, 5)..=Position::rel(7, 6))),
+            ));
             assert_eq!(
                 warnings[1],
                 Warning::new::<Context>(WarningKind::MissingStoryData, None)
@@ -686,12 +1386,12 @@ blah blah
         write!(file_two, "{}", input_two.clone())?;
 
         let out = StoryPassages::from_path(dir.path());
-        assert_eq!(out.has_warnings(), true);
+        assert!(out.has_warnings());
         let (res, warnings) = out.take();
         assert_eq!(warnings.len(), 2);
-        assert_eq!(res.is_ok(), true);
+        assert!(res.is_ok());
         let story = res.ok().unwrap();
-        assert_eq!(story.title.is_some(), true);
+        assert!(story.title.is_some());
         let title_content = story.title.unwrap().content;
         if let PassageContent::StoryTitle(title) = title_content {
             assert_eq!(title.title, "Test Story");
@@ -699,27 +1399,41 @@ blah blah
             panic!("Expected StoryTitle");
         }
 
-        let context = FullContext::from(Some("test.twee".to_string()), input_one);
-        assert!(warnings.contains(&{
-            let warning = Warning::new(
-                WarningKind::EscapedOpenCurly,
-                Some(context.subcontext(Position::rel(10, 6)..=Position::rel(10, 7))),
-            );
-            warning
-        }));
-
-        let context = FullContext::from(Some("test2.tw".to_string()), input_two);
-        assert!(warnings.contains(&{
-            let warning = Warning::new(
-                WarningKind::EscapedCloseSquare,
-                Some(context.subcontext(Position::rel(9, 16)..=Position::rel(9, 17))),
-            );
-            warning
-        }));
+        let context = FullContext::from_file_path(Some(file_path_one), input_one);
+        assert!(warnings.contains(&Warning::new(
+            WarningKind::EscapedOpenCurly(r"Wa\{rning title one".to_string()),
+            Some(context.subcontext(Position::rel(10, 6)..=Position::rel(10, 7))),
+        )));
+
+        let context = FullContext::from_file_path(Some(file_path_two), input_two);
+        assert!(warnings.contains(&Warning::new(
+            WarningKind::EscapedCloseSquare(r"Warning titl\]e two".to_string()),
+            Some(context.subcontext(Position::rel(9, 16)..=Position::rel(9, 17))),
+        )));
 
         Ok(())
     }
 
+    #[test]
+    fn lenient_encoding() -> Result<(), Box<dyn std::error::Error>> {
+        use std::io::Write;
+        let dir = tempdir()?;
+        let file_path = dir.path().join("legacy.tw");
+        let mut file = File::create(file_path.clone())?;
+        file.write_all(b":: Start\n\x93Hello\x94 [[Caf\xe9]]\n\n:: Caf\xe9\nEnd\n")?;
+
+        let out = StoryPassages::from_path(&file_path);
+        assert!(out.is_err());
+
+        let options = ParseOptions::new().with_lenient(true);
+        let (res, warnings) = StoryPassages::from_path_with_options(&file_path, &options).take();
+        let story = res.ok().unwrap();
+        assert!(story.passages.contains_key("Café"));
+        assert_eq!(warnings[0].kind, WarningKind::LegacyEncoding);
+        assert!(!warnings.iter().any(|w| matches!(w.kind, WarningKind::DeadLink(_))));
+        Ok(())
+    }
+
     #[test]
     fn multi_path() -> Result<(), Box<dyn std::error::Error>> {
         let input_one = r#":: Start
@@ -758,14 +1472,14 @@ blah blah
         let mut file_two = File::create(file_path_two.clone())?;
         write!(file_two, "{}", input_two.clone())?;
 
-        let paths = vec![file_path_one, file_path_two];
+        let paths = vec![file_path_one.clone(), file_path_two.clone()];
         let out = StoryPassages::from_paths(&paths);
-        assert_eq!(out.has_warnings(), true);
+        assert!(out.has_warnings());
         let (res, warnings) = out.take();
         assert_eq!(warnings.len(), 2);
-        assert_eq!(res.is_ok(), true);
+        assert!(res.is_ok());
         let story = res.ok().unwrap();
-        assert_eq!(story.title.is_some(), true);
+        assert!(story.title.is_some());
         let title_content = story.title.unwrap().content;
         if let PassageContent::StoryTitle(title) = title_content {
             assert_eq!(title.title, "Test Story");
@@ -773,24 +1487,47 @@ blah blah
             panic!("Expected StoryTitle");
         }
 
-        let context = FullContext::from(Some("test.twee".to_string()), input_one);
-        assert!(warnings.contains(&{
-            let warning = Warning::new(
-                WarningKind::EscapedOpenCurly,
-                Some(context.subcontext(Position::rel(10, 6)..=Position::rel(10, 7))),
-            );
-            warning
-        }));
-
-        let context = FullContext::from(Some("test2.tw".to_string()), input_two);
-        assert!(warnings.contains(&{
-            let warning = Warning::new(
-                WarningKind::EscapedCloseSquare,
-                Some(context.subcontext(Position::rel(9, 16)..=Position::rel(9, 17))),
-            );
-            warning
-        }));
+        let context = FullContext::from_file_path(Some(file_path_one), input_one);
+        assert!(warnings.contains(&Warning::new(
+            WarningKind::EscapedOpenCurly(r"Wa\{rning title one".to_string()),
+            Some(context.subcontext(Position::rel(10, 6)..=Position::rel(10, 7))),
+        )));
+
+        let context = FullContext::from_file_path(Some(file_path_two), input_two);
+        assert!(warnings.contains(&Warning::new(
+            WarningKind::EscapedCloseSquare(r"Warning titl\]e two".to_string()),
+            Some(context.subcontext(Position::rel(9, 16)..=Position::rel(9, 17))),
+        )));
+
+        Ok(())
+    }
 
+    #[test]
+    fn file_reports() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = tempdir()?;
+        let one = dir.path().join("one.twee");
+        let two = dir.path().join("two.twee");
+        let three = dir.path().join("three.twee");
+        std::fs::write(&one, ":: Start\n[[Other]]\n")?;
+        std::fs::write(&three, "::\nNo name\n")?;
+        std::fs::write(&two, ":: Start\nAgain\n\n:: Other\nText\n")?;
+
+        let (out, reports) = StoryPassages::from_path_with_reports(dir.path(), &ParseOptions::default());
+        assert!(out.is_err());
+        let paths: Vec<&Path> = reports.iter().map(|report| report.path.as_path()).collect();
+        assert_eq!(paths, vec![one.as_path(), three.as_path(), two.as_path()]);
+        assert!(reports[0].is_ok());
+        assert_eq!(reports[1].errors[0].kind, ErrorKind::EmptyName);
+        assert_eq!(reports[2].passages, vec!["Other", "Start"]);
+
+        std::fs::remove_file(&three)?;
+        let (out, reports) = StoryPassages::from_path_with_reports(dir.path(), &ParseOptions::default());
+        assert!(out.is_ok());
+        assert_eq!(reports.len(), 2);
+        assert!(reports[0].warnings.is_empty());
+        let kinds: Vec<&WarningKind> = reports[1].warnings.iter().map(|w| &w.kind).collect();
+        assert_eq!(kinds, vec![&WarningKind::DuplicatePassageAcrossFiles("Start".to_string())]);
+        assert!(reports[1].warnings[0].referent.is_some());
         Ok(())
     }
 
@@ -835,7 +1572,7 @@ A Test Story
         writeln!(file_two, "{}", input_two)?;
 
         let out = StoryPassages::from_path(dir.path());
-        assert_eq!(out.has_warnings(), true);
+        assert!(out.has_warnings());
         let (res, warnings) = out.take();
         assert_eq!(warnings.len(), 2);
 
@@ -848,7 +1585,25 @@ A Test Story
             .iter()
             .any(|w| WarningKind::DuplicateStoryTitle == w.kind));
 
-        assert_eq!(res.is_ok(), true);
+        // Both passages of each duplicate should be located in their files
+        for warning in &warnings {
+            let title = warning.kind == WarningKind::DuplicateStoryTitle;
+            let line = |path: &std::path::Path| match (title, path == file_path_one) {
+                (true, true) => 7,
+                (true, false) => 9,
+                (false, true) => 10,
+                (false, false) => 4,
+            };
+            let context = warning.context.as_ref().unwrap();
+            let referent = warning.get_referent().unwrap();
+            let path = context.get_file_path().unwrap();
+            let referent_path = referent.get_file_path().unwrap();
+            assert_ne!(path, referent_path);
+            assert_eq!(context.get_start_position().line, line(path));
+            assert_eq!(referent.get_start_position().line, line(referent_path));
+        }
+
+        assert!(res.is_ok());
 
         Ok(())
     }
@@ -876,10 +1631,10 @@ Link to [[A passage]]
 "#
         .to_string();
         let context = FullContext::from(None, input);
-        let out = StoryPassages::from_context(context.clone());
-        assert_eq!(out.has_warnings(), true);
+        let out = StoryPassages::from_context(context.clone(), &ParseOptions::default());
+        assert!(out.has_warnings());
         let (res, warnings) = out.take();
-        assert_eq!(res.is_ok(), true);
+        assert!(res.is_ok());
         let story = res.ok().unwrap();
         assert_eq!(warnings.len(), 1);
         assert_eq!(
@@ -900,8 +1655,7 @@ Link to [[A passage]]
                     } else {
                         None
                     }
-                })
-                .and_then(|data| Some(data.ifid)),
+                }).map(|data| data.ifid),
             Some("ABC".to_string())
         );
     }
@@ -927,10 +1681,10 @@ Discarded Duplicate Title
 "#
         .to_string();
         let context = FullContext::from(None, input);
-        let out = StoryPassages::from_context(context.clone());
-        assert_eq!(out.has_warnings(), true);
+        let out = StoryPassages::from_context(context.clone(), &ParseOptions::default());
+        assert!(out.has_warnings());
         let (res, warnings) = out.take();
-        assert_eq!(res.is_ok(), true);
+        assert!(res.is_ok());
         let story = res.ok().unwrap();
         assert_eq!(warnings.len(), 1);
         assert_eq!(
@@ -941,7 +1695,7 @@ Discarded Duplicate Title
             )
             .with_referent(story.title.as_ref().unwrap().context.clone())
         );
-        assert_eq!(story.title.is_some(), true);
+        assert!(story.title.is_some());
         let title_content = story.title.unwrap().content;
         if let PassageContent::StoryTitle(title) = title_content {
             assert_eq!(title.title, "Test Story");
@@ -950,6 +1704,76 @@ Discarded Duplicate Title
         }
     }
 
+    #[test]
+    fn chunk_results_in_source_order() {
+        let mut input = String::new();
+        for i in 0..64 {
+            input.push_str(&format!(":: P{}\n[[P{}\n\n", i % 32, i));
+        }
+        let (res, warnings) = StoryPassages::from_string(input).take();
+        let story = res.ok().unwrap();
+        assert_eq!(story.passages.len(), 32);
+        assert_eq!(story.passages["P3"].context.get_start_position().line, 10);
+        let kinds: Vec<WarningKind> = warnings.into_iter().map(|w| w.kind).collect();
+        let mut expected: Vec<WarningKind> = (0..32).map(|i| WarningKind::UnclosedLink(format!("P{}", i))).collect();
+        for i in 0..32 {
+            expected.push(WarningKind::UnclosedLink(format!("P{}", i + 32)));
+            expected.push(WarningKind::DuplicatePassage(format!("P{}", i)));
+        }
+        assert_eq!(kinds, expected);
+    }
+
+    #[test]
+    fn merged_duplicate_specials() {
+        let ours_context = FullContext::from(None, ":: StoryTitle\nOurs\n\n:: StoryData\n{\"ifid\": \"ABC\"}".to_string());
+        let theirs_context = FullContext::from(None, ":: StoryData\n{\"ifid\": \"DEF\"}\n\n:: StoryTitle\nTheirs".to_string());
+        let parse = |context: &FullContext| {
+            StoryPassages::from_context(context.clone(), &ParseOptions::default()).take().0.ok().unwrap()
+        };
+        let mut story = parse(&ours_context);
+        let warnings = story.merge_from(parse(&theirs_context));
+        assert_eq!(
+            warnings,
+            vec![
+                Warning::new(
+                    WarningKind::DuplicateStoryTitle,
+                    Some(theirs_context.subcontext(Position::rel(4, 1)..=Position::rel(5, 6)))
+                )
+                .with_referent(ours_context.subcontext(..=Position::rel(2, 4))),
+                Warning::new(
+                    WarningKind::DuplicateStoryData,
+                    Some(theirs_context.subcontext(..=Position::rel(2, 15)))
+                )
+                .with_referent(ours_context.subcontext(Position::rel(4, 1)..=Position::rel(5, 15))),
+            ]
+        );
+    }
+
+    #[test]
+    fn denied_duplicates() {
+        let input = ":: StoryTitle\nOne\n\n:: StoryTitle\nTwo\n\n:: Start\nHi\n\n:: Start\nAgain\n".to_string();
+        let (res, warnings) = StoryPassages::from_string(input.clone()).take();
+        assert!(res.is_ok());
+        assert_eq!(warnings.len(), 2);
+
+        let config = StoryConfig::from_json(r#"{"deny-duplicates": true}"#).unwrap();
+        let options = ParseOptions::new().with_config(config);
+        let (res, warnings) = StoryPassages::from_string_with_options(input.clone(), &options).take();
+        let errors = res.err().unwrap();
+        #[cfg(feature = "full-context")]
+        let errors = errors.error_list;
+        let kinds: Vec<ErrorKind> = errors.errors.into_iter().map(|e| e.kind).collect();
+        assert_eq!(kinds, vec![
+            ErrorKind::DeniedWarning(WarningKind::DuplicateStoryTitle),
+            ErrorKind::DeniedWarning(WarningKind::DuplicatePassage("Start".to_string())),
+        ]);
+        assert!(warnings.is_empty());
+
+        let input = format!(":: StoryConfig\n{{\"deny-duplicates\": true}}\n\n{}", input);
+        let (res, _) = StoryPassages::from_string(input).take();
+        assert!(res.is_err());
+    }
+
     #[test]
     fn a_test() {
         let input = r#":: A passage
@@ -971,11 +1795,11 @@ Test Story
 "#
         .to_string();
         let out = StoryPassages::from_string(input);
-        assert_eq!(out.has_warnings(), false);
+        assert!(!out.has_warnings());
         let (res, _) = out.take();
-        assert_eq!(res.is_ok(), true);
+        assert!(res.is_ok());
         let story = res.ok().unwrap();
-        assert_eq!(story.title.is_some(), true);
+        assert!(story.title.is_some());
         let title_content = story.title.unwrap().content;
         if let PassageContent::StoryTitle(title) = title_content {
             assert_eq!(title.title, "Test Story");
@@ -1002,9 +1826,9 @@ Test Story
 "#
         .to_string();
         let context = FullContext::from(None, input);
-        let out = StoryPassages::from_context(context.clone());
+        let out = StoryPassages::from_context(context.clone(), &ParseOptions::default());
         let (res, mut warnings) = out.take();
-        assert_eq!(res.is_ok(), true);
+        assert!(res.is_ok());
         let story = res.ok().unwrap();
         let mut check_warnings = story.check();
         warnings.append(&mut check_warnings);
@@ -1016,6 +1840,28 @@ Test Story
         assert_eq!(warnings, expected);
     }
 
+    #[test]
+    fn trimmed_link_match() {
+        let input = ":: Start\n[[Go->Hall.]] [[Go->Hall!? ]] [[What?]] [[...]]\n\n:: Hall\nEnd".to_string();
+        let story = StoryPassages::from_string(input).take().0.ok().unwrap();
+        let mut kinds: Vec<WarningKind> = story
+            .check()
+            .into_iter()
+            .map(|w| w.kind)
+            .filter(|k| matches!(k, WarningKind::DeadLink(_) | WarningKind::TrimmedLinkMatch(_, _)))
+            .collect();
+        kinds.sort_by_key(|k| k.to_string());
+        assert_eq!(
+            kinds,
+            vec![
+                WarningKind::DeadLink("...".to_string()),
+                WarningKind::DeadLink("What?".to_string()),
+                WarningKind::TrimmedLinkMatch("Hall!? ".to_string(), "Hall".to_string()),
+                WarningKind::TrimmedLinkMatch("Hall.".to_string(), "Hall".to_string()),
+            ]
+        );
+    }
+
     #[test]
     fn alt_start() {
         let input = r#":: Alt Start
@@ -1036,7 +1882,7 @@ Test Story
         .to_string();
         let out = StoryPassages::from_string(input);
         let (res, mut warnings) = out.take();
-        assert_eq!(res.is_ok(), true);
+        assert!(res.is_ok());
         let story = res.ok().unwrap();
         let mut check_warnings = story.check();
         warnings.append(&mut check_warnings);
@@ -1068,8 +1914,9 @@ body {font-size: 1.5em;}
 }"#
         .to_string();
         let context = FullContext::from(None, input);
-        let out = StoryPassages::parse(context);
-        assert_eq!(out.has_warnings(), false);
+        let out = StoryPassages::parse(context, &ParseOptions::default());
+        let kinds: Vec<&WarningKind> = out.get_warnings().iter().map(|w| &w.kind).collect();
+        assert_eq!(kinds, vec![&WarningKind::UnknownStoryDataKeys(vec!["formatVersion".to_string()])]);
     }
 
     #[test]
@@ -1091,9 +1938,9 @@ Test Story
 "#
         .to_string();
         let context = FullContext::from(None, input);
-        let out = StoryPassages::from_context(context.clone());
+        let out = StoryPassages::from_context(context.clone(), &ParseOptions::default());
         let (res, mut warnings) = out.take();
-        assert_eq!(res.is_ok(), true);
+        assert!(res.is_ok());
         let story = res.ok().unwrap();
         let mut check_warnings = story.check();
         warnings.append(&mut check_warnings);
@@ -1117,7 +1964,7 @@ blah blah
             .to_string();
         let out = StoryPassages::from_string(input);
         let (res, mut warnings) = out.take();
-        assert_eq!(res.is_ok(), true);
+        assert!(res.is_ok());
         let story = res.ok().unwrap();
         let mut check_warnings = story.check();
         warnings.append(&mut check_warnings);
@@ -1147,7 +1994,7 @@ Test Story
         .to_string();
         let out = StoryPassages::from_string(input);
         let (res, mut warnings) = out.take();
-        assert_eq!(res.is_ok(), true);
+        assert!(res.is_ok());
         let story = res.ok().unwrap();
         let mut check_warnings = story.check();
         warnings.append(&mut check_warnings);
@@ -1158,10 +2005,305 @@ Test Story
         assert_eq!(story.get_start_passage_name(), None);
     }
 
+    #[test]
+    fn provenance() -> Result<(), Box<dyn std::error::Error>> {
+        let input = ":: StoryTitle\nTitle\n\n:: Start\nHello\n\n\n:: Style [stylesheet]\nbody {}\n";
+        use std::io::Write;
+        let dir = tempdir()?;
+        let file_path = dir.path().join("test.twee");
+        let mut file = File::create(file_path.clone())?;
+        write!(file, "{}", input)?;
+
+        let out = StoryPassages::from_path(dir.path());
+        let (res, _) = out.take();
+        let story = res.ok().unwrap();
+        for passage in story.all_passages() {
+            assert_eq!(passage.source_path(), Some(file_path.as_path()));
+        }
+        assert_eq!(&input[story.passages["Start"].byte_range()], ":: Start\nHello");
+        assert_eq!(
+            &input[story.stylesheets[0].byte_range()],
+            ":: Style [stylesheet]\nbody {}"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn normalize_link_whitespace() {
+        let input = ":: Start\n[[Go | Next ]] [[ Next <- Back]]\n\n:: Next\nEnd".to_string();
+        let out = StoryPassages::from_string(input.clone());
+        assert_eq!(out.get_warnings().len(), 2);
+
+        let options = ParseOptions::new().with_normalize_link_whitespace(true);
+        let (res, warnings) = StoryPassages::from_string_with_options(input, &options).take();
+        assert!(warnings.is_empty());
+        let story = res.ok().unwrap();
+        let links = match &story.passages["Start"].content {
+            PassageContent::Normal(twine) => twine.get_links(),
+            _ => panic!("Expected normal passage"),
+        };
+        let targets: Vec<&str> = links.iter().map(|l| l.target.as_str()).collect();
+        assert_eq!(targets, vec!["Next", "Next"]);
+    }
+
+    #[test]
+    fn special_tags() {
+        let input = ":: Start\nHi\n\n:: Mod [module]\n[[not a link]]\n\n:: Both [init module]\nb\n\n\
+                     :: Code [module script]\nc\n\n:: Other [init]\no\n"
+            .to_string();
+        let options = ParseOptions::new().with_special_tag("module").with_special_tag("init");
+        let (res, warnings) = StoryPassages::from_string_with_options(input.clone(), &options).take();
+        assert!(warnings.is_empty());
+        let story = res.ok().unwrap();
+        let names = |tag: &str| -> Vec<&str> {
+            story.tagged_passages[tag].iter().map(|p| p.header.name.as_str()).collect()
+        };
+        assert_eq!(names("module"), vec!["Mod", "Both"]);
+        assert_eq!(names("init"), vec!["Other"]);
+        assert_eq!(story.scripts[0].header.name, "Code");
+        assert_eq!(story.passages.keys().collect::<Vec<_>>(), vec!["Start"]);
+
+        let story = StoryPassages::from_string(input).take().0.ok().unwrap();
+        assert!(story.tagged_passages.is_empty());
+        assert_eq!(story.passages.len(), 4);
+    }
+
+    #[test]
+    fn observer() -> Result<(), Box<dyn std::error::Error>> {
+        use crate::ParseObserver;
+        use std::io::Write;
+        use std::sync::{Arc, Mutex};
+
+        #[derive(Default)]
+        struct Recorder(Mutex<Vec<String>>);
+        impl ParseObserver for Recorder {
+            fn on_file_started(&self, path: &Path) {
+                let name = path.file_name().unwrap().to_string_lossy();
+                self.0.lock().unwrap().push(format!("file {}", name));
+            }
+            fn on_passage_parsed(&self, passage: &Passage) {
+                self.0.lock().unwrap().push(format!("passage {}", passage.header.name));
+            }
+            fn on_warning(&self, warning: &Warning) {
+                self.0.lock().unwrap().push(format!("warning {}", warning.kind));
+            }
+        }
+
+        let dir = tempdir()?;
+        write!(File::create(dir.path().join("a.twee"))?, ":: Start\n[[Nowhere]]\n")?;
+        write!(File::create(dir.path().join("b.twee"))?, ":: Start\nAgain\n")?;
+
+        let recorder = Arc::new(Recorder::default());
+        let options = ParseOptions::new().with_observer(recorder.clone());
+        let (res, warnings) = StoryPassages::from_path_with_options(dir.path(), &options).take();
+        assert!(res.is_ok());
+
+        let events = recorder.0.lock().unwrap();
+        assert_eq!(events.iter().filter(|e| e.starts_with("file ")).count(), 2);
+        assert_eq!(events.iter().filter(|e| *e == "passage Start").count(), 2);
+        let observed: Vec<&String> = events.iter().filter(|e| e.starts_with("warning ")).collect();
+        assert_eq!(observed.len(), warnings.len());
+        for warning in &warnings {
+            assert!(observed.contains(&&format!("warning {}", warning.kind)));
+        }
+        assert!(warnings.iter().any(|w| w.kind == WarningKind::DuplicatePassageAcrossFiles("Start".to_string())));
+        Ok(())
+    }
+
+    #[test]
+    fn directory_namespaces() -> Result<(), Box<dyn std::error::Error>> {
+        let root = r#":: StoryTitle
+Test Story
+
+:: StoryData
+{
+"ifid": "ABC",
+"start": "chapter1/Intro"
+}
+
+:: Credits
+The end
+"#;
+        let chapter1 = r#":: Intro
+Go to [[Outro]] or [[chapter2/Intro]] or [[../Credits]]
+
+:: Outro
+Back to [[./Intro]] or on to [[/chapter2/Intro]]
+"#;
+        let chapter2 = r#":: Intro
+Dead link to [[Outro]], live link to [[Credits]]
+"#;
+        use std::io::Write;
+        let dir = tempdir()?;
+        std::fs::create_dir(dir.path().join("chapter1"))?;
+        std::fs::create_dir(dir.path().join("chapter2"))?;
+        write!(File::create(dir.path().join("root.twee"))?, "{}", root)?;
+        write!(File::create(dir.path().join("chapter1").join("one.twee"))?, "{}", chapter1)?;
+        write!(File::create(dir.path().join("chapter2").join("two.tw"))?, "{}", chapter2)?;
+
+        // Without namespaces, subdirectories are ignored
+        let out = StoryPassages::from_path(dir.path());
+        let (res, _) = out.take();
+        assert_eq!(res.ok().unwrap().passages.len(), 1);
+
+        let options = ParseOptions::new().with_directory_namespaces(true);
+        let out = StoryPassages::from_path_with_options(dir.path(), &options);
+        let (res, warnings) = out.take();
+        let story = res.ok().unwrap();
+        let mut names: Vec<&String> = story.passages.keys().collect();
+        names.sort();
+        assert_eq!(names, vec!["Credits", "chapter1/Intro", "chapter1/Outro", "chapter2/Intro"]);
+        assert_eq!(story.passages["chapter1/Intro"].header.name, "chapter1/Intro");
+        assert!(story.title.is_some());
+        assert_eq!(
+            warnings.iter().map(|w| &w.kind).collect::<Vec<_>>(),
+            vec![&WarningKind::DeadLink("Outro".to_string())]
+        );
+        assert_eq!(story.resolve_link("chapter1/Intro", "Outro"), Some("chapter1/Outro"));
+        assert_eq!(story.resolve_link("chapter2/Intro", "Credits"), Some("Credits"));
+        assert_eq!(story.resolve_link("chapter1/Outro", "/chapter2/Intro"), Some("chapter2/Intro"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn excluded_entries() -> Result<(), Box<dyn std::error::Error>> {
+        use std::io::Write;
+        let dir = tempdir()?;
+        std::fs::create_dir(dir.path().join("drafts"))?;
+        std::fs::create_dir(dir.path().join("chapter1"))?;
+        write!(File::create(dir.path().join("start.twee"))?, ":: Start\nHi")?;
+        write!(File::create(dir.path().join("start_old.twee"))?, ":: Old\nBye")?;
+        write!(File::create(dir.path().join("scratch.tw"))?, ":: Scratch\nHmm")?;
+        write!(File::create(dir.path().join("drafts").join("idea.twee"))?, ":: Idea\nMaybe")?;
+        write!(File::create(dir.path().join("chapter1").join("intro.twee"))?, ":: Intro\nOnce")?;
+        write!(File::create(dir.path().join("chapter1").join("cut.twee"))?, ":: Cut\nNever")?;
+
+        let options = ParseOptions::new()
+            .with_directory_namespaces(true)
+            .with_exclude("*_old.twee")
+            .with_exclude("drafts/")
+            .with_exclude("chapter1/cut.*")
+            .with_exclude_filter(|path| path.ends_with("scratch.tw"));
+        let (res, _) = StoryPassages::from_path_with_options(dir.path(), &options).take();
+        let story = res.ok().unwrap();
+        let mut names: Vec<&String> = story.passages.keys().collect();
+        names.sort();
+        assert_eq!(names, vec!["Start", "chapter1/Intro"]);
+
+        // Paths given directly are parsed even if they match
+        let (res, _) = StoryPassages::from_path_with_options(dir.path().join("start_old.twee"), &options).take();
+        assert!(res.ok().unwrap().passages.contains_key("Old"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn orphan_files() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = tempdir()?;
+        let empty = tempdir()?;
+        std::fs::create_dir(dir.path().join("drafts"))?;
+        std::fs::write(dir.path().join("start.twee"), ":: Start\nHi")?;
+        std::fs::write(dir.path().join("notes.txt"), "Not twee")?;
+        std::fs::write(dir.path().join("drafts").join("b.tw"), ":: B\nMaybe")?;
+        std::fs::write(dir.path().join("drafts").join("a.twee"), ":: A\nMaybe")?;
+        std::fs::write(empty.path().join("old.twee"), ":: Old\nBye")?;
+
+        let options = ParseOptions::new().with_exclude("drafts/").with_exclude("old.twee");
+        let (res, warnings) = StoryPassages::from_paths_with_options(vec![dir.path(), empty.path()], &options).take();
+        assert!(res.is_ok());
+        assert!(!warnings.iter().any(|w| matches!(w.kind, WarningKind::ExcludedFile(_) | WarningKind::UnmatchedPath(_))));
+
+        let options = options.with_report_orphan_files(true);
+        let (res, warnings) = StoryPassages::from_paths_with_options(vec![dir.path(), empty.path()], &options).take();
+        assert!(res.is_ok());
+        let orphans: Vec<WarningKind> = warnings
+            .into_iter()
+            .map(|w| w.kind)
+            .filter(|kind| matches!(kind, WarningKind::ExcludedFile(_) | WarningKind::UnmatchedPath(_)))
+            .collect();
+        assert_eq!(
+            orphans,
+            vec![
+                WarningKind::ExcludedFile(dir.path().join("drafts").join("a.twee")),
+                WarningKind::ExcludedFile(dir.path().join("drafts").join("b.tw")),
+                WarningKind::ExcludedFile(empty.path().join("old.twee")),
+                WarningKind::UnmatchedPath(empty.path().to_path_buf()),
+            ]
+        );
+
+        Ok(())
+    }
+
     #[test]
     fn from_string_error() {
         let input = "".to_string();
         let out = StoryPassages::from_string(input);
         assert!(out.is_err());
     }
+
+    #[test]
+    fn check_parts() {
+        let input = ":: StoryData\n{\"ifid\": \"X\", \"start\": \"Begin\"}\n\n:: Hall\n[[Cellar]] [[Hall.]]\n".to_string();
+        let story = StoryPassages::from_string(input).take().0.ok().unwrap();
+        let special: Vec<WarningKind> = story.check_special_passages().into_iter().map(|w| w.kind).collect();
+        assert_eq!(
+            special,
+            vec![WarningKind::MissingStoryTitle, WarningKind::DeadStartPassage("Begin".to_string())]
+        );
+        let links: Vec<WarningKind> = story.check_links().into_iter().map(|w| w.kind).collect();
+        assert_eq!(
+            links,
+            vec![
+                WarningKind::DeadLink("Cellar".to_string()),
+                WarningKind::TrimmedLinkMatch("Hall.".to_string(), "Hall".to_string()),
+            ]
+        );
+        let mut parts = story.check_special_passages();
+        parts.extend(story.check_links());
+        assert_eq!(story.check(), parts);
+    }
+
+    #[test]
+    fn link_resolver() {
+        let options = ParseOptions::new().with_link_resolver(|name, target| name == "Lab" && target == "Generated");
+        let input = ":: Start\n[[Generated]]\n\n:: Lab\n[[Generated]] [[Generated.]]\n".to_string();
+        let (res, _) = StoryPassages::from_string_with_options(input, &options).take();
+        let story = res.ok().unwrap();
+        let kinds: Vec<WarningKind> = story.check_links().into_iter().map(|w| w.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                WarningKind::DeadLink("Generated.".to_string()),
+                WarningKind::DeadLink("Generated".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn partial_check() {
+        let input = ":: StoryData\n{\"ifid\": \"X\", \"start\": \"Begin\"}\n\n:: Hall\n[[ Cellar ]] [[Attic]]\n".to_string();
+        let story = StoryPassages::from_string(input).take().0.ok().unwrap();
+        let options = CheckOptions::new().with_partial(true).with_external_passages(vec!["Cellar"]);
+        let kinds: Vec<WarningKind> = story.check_with(&options).into_iter().map(|w| w.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![WarningKind::DeadStartPassage("Begin".to_string()), WarningKind::DeadLink("Attic".to_string())]
+        );
+        assert_eq!(story.check_with(&CheckOptions::new()), story.check());
+    }
+
+    #[test]
+    fn header_detection() {
+        let input = ":: A\nx::y\n  :: B\r\na::before, b::after ::\n\t::C\n::\n";
+        let headers: Vec<usize> = header_lines(input).collect();
+        assert_eq!(headers, vec![0, 2, 4, 5]);
+        assert_eq!(header_lines("no headers\n").count(), 0);
+
+        let input = ":: A\nText ::\n\n:: B\nMore\n".to_string();
+        let story = StoryPassages::from_string(input.clone()).take().0.ok().unwrap();
+        assert_eq!(story.passages.len(), 2);
+        assert_eq!(&input[story.passages["A"].byte_range()], ":: A\nText ::");
+    }
 }
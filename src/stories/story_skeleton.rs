@@ -0,0 +1,203 @@
+use super::fallbacks::new_ifid;
+use serde_json::json;
+
+/// Builds the twee source of a new story from an outline of its passages and
+/// the links between them, such as one exported from a planning tool. Every
+/// passage becomes a stub containing just its links, and a `StoryTitle` and
+/// `StoryData` are filled in
+///
+/// # Examples
+/// ```
+/// use tweep::{StoryPassages, StorySkeleton};
+/// let twee = StorySkeleton::new("The Cave")
+///     .with_ifid("D674C58C-DEFA-4F70-B7A2-27742230C0FC")
+///     .with_passage("Entrance", vec!["Tunnel", "Outside"])
+///     .with_passage("Tunnel", vec!["Entrance"])
+///     .to_twee();
+/// assert!(twee.starts_with(":: StoryTitle\nThe Cave\n\n:: StoryData\n"));
+/// assert!(twee.ends_with(":: Entrance\n[[Tunnel]]\n[[Outside]]\n\n:: Tunnel\n[[Entrance]]\n\n:: Outside\n"));
+///
+/// let story = StoryPassages::from_string(twee).take().0.ok().unwrap();
+/// assert_eq!(story.get_start_passage_name(), Some("Entrance"));
+/// assert!(story.check().is_empty());
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct StorySkeleton {
+    /// The title of the story
+    pub title: String,
+
+    /// The IFID of the story. A new one is generated if this is `None`
+    pub ifid: Option<String>,
+
+    /// The name of the story format
+    pub format: Option<String>,
+
+    /// The version of the story format
+    pub format_version: Option<String>,
+
+    /// The name of the start passage. If this is `None`, the first passage is
+    /// the start passage
+    pub start: Option<String>,
+
+    /// The name of each passage along with the names of the passages it links
+    /// to, in order
+    pub passages: Vec<(String, Vec<String>)>,
+}
+
+impl StorySkeleton {
+    /// Creates a new `StorySkeleton` for a story with the given title and no
+    /// passages
+    pub fn new<S: Into<String>>(title: S) -> Self {
+        StorySkeleton {
+            title: title.into(),
+            ..StorySkeleton::default()
+        }
+    }
+
+    /// Creates a new `StorySkeleton` from a graph given as a list of links,
+    /// each from one passage to another. Passages are created in the order
+    /// their names first appear
+    ///
+    /// # Examples
+    /// ```
+    /// use tweep::StorySkeleton;
+    /// let skeleton = StorySkeleton::from_links("Plan", vec![("A", "B"), ("B", "C"), ("A", "C")]);
+    /// let names: Vec<&str> = skeleton.passages.iter().map(|(name, _)| name.as_str()).collect();
+    /// assert_eq!(names, vec!["A", "B", "C"]);
+    /// assert_eq!(skeleton.passages[0].1, vec!["B", "C"]);
+    /// ```
+    pub fn from_links<S, I, N>(title: S, links: I) -> Self
+    where
+        S: Into<String>,
+        I: IntoIterator<Item = (N, N)>,
+        N: Into<String>,
+    {
+        let mut skeleton = StorySkeleton::new(title);
+        for (from, to) in links {
+            let to = to.into();
+            let index = skeleton.passage_index(from.into());
+            skeleton.passages[index].1.push(to.clone());
+            skeleton.passage_index(to);
+        }
+        skeleton
+    }
+
+    /// Builder method to set the IFID
+    pub fn with_ifid<S: Into<String>>(mut self, ifid: S) -> Self {
+        self.ifid = Some(ifid.into());
+        self
+    }
+
+    /// Builder method to set the story format and its version
+    pub fn with_format<S: Into<String>, V: Into<String>>(mut self, format: S, version: V) -> Self {
+        self.format = Some(format.into());
+        self.format_version = Some(version.into());
+        self
+    }
+
+    /// Builder method to set the start passage
+    pub fn with_start<S: Into<String>>(mut self, start: S) -> Self {
+        self.start = Some(start.into());
+        self
+    }
+
+    /// Builder method to add a passage with the given links, or add the links
+    /// to the passage if it was already added
+    pub fn with_passage<S, I, N>(mut self, name: S, links: I) -> Self
+    where
+        S: Into<String>,
+        I: IntoIterator<Item = N>,
+        N: Into<String>,
+    {
+        let index = self.passage_index(name.into());
+        self.passages[index].1.extend(links.into_iter().map(Into::into));
+        self
+    }
+
+    /// Returns the twee source of the story. Links to passages that weren't
+    /// added get stub passages of their own, after the others
+    pub fn to_twee(&self) -> String {
+        let mut data = json!({ "ifid": self.ifid.clone().unwrap_or_else(new_ifid) });
+        if let Some(start) = self.start.as_ref().or_else(|| self.passages.first().map(|(name, _)| name)) {
+            data["start"] = json!(start);
+        }
+        if let Some(format) = &self.format {
+            data["format"] = json!(format);
+        }
+        if let Some(version) = &self.format_version {
+            data["format-version"] = json!(version);
+        }
+
+        let mut passages = self.passages.clone();
+        for (_, links) in &self.passages {
+            for link in links {
+                if !passages.iter().any(|(name, _)| name == link) {
+                    passages.push((link.clone(), Vec::new()));
+                }
+            }
+        }
+
+        let mut twee = format!(
+            ":: StoryTitle\n{}\n\n:: StoryData\n{}\n",
+            self.title,
+            serde_json::to_string_pretty(&data).unwrap()
+        );
+        for (name, links) in passages {
+            twee.push_str(&format!("\n:: {}\n", escape_name(&name)));
+            for link in links {
+                twee.push_str(&format!("[[{}]]\n", link));
+            }
+        }
+        twee
+    }
+
+    /// Returns the index of the passage named `name`, adding it if needed
+    fn passage_index(&mut self, name: String) -> usize {
+        match self.passages.iter().position(|(existing, _)| *existing == name) {
+            Some(index) => index,
+            None => {
+                self.passages.push((name, Vec::new()));
+                self.passages.len() - 1
+            }
+        }
+    }
+}
+
+/// Escapes the characters that have a meaning in a passage header
+fn escape_name(name: &str) -> String {
+    let mut escaped = String::with_capacity(name.len());
+    for c in name.chars() {
+        if matches!(c, '[' | ']' | '{' | '}') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::StoryPassages;
+
+    #[test]
+    fn round_trip() {
+        let twee = StorySkeleton::from_links("Plan", vec![("Intro", "Fork [A]"), ("Fork [A]", "End")])
+            .with_start("Fork [A]")
+            .with_format("Harlowe", "3.3.8")
+            .to_twee();
+        let story = StoryPassages::from_string(twee.clone()).take().0.ok().unwrap();
+        assert_eq!(story.get_start_passage_name(), Some("Fork [A]"));
+        assert_eq!(story.format_name(), Some("Harlowe"));
+        assert_eq!(story.format_version(), Some("3.3.8"));
+        assert_eq!(story.ifid().map(str::len), Some(36));
+        assert_eq!(story.passages.len(), 3);
+        assert!(twee.contains(":: Fork \\[A\\]\n[[End]]\n"));
+        assert!(story.check().is_empty());
+
+        let empty = StorySkeleton::new("Nothing").to_twee();
+        assert!(!empty.contains("start"));
+        let (res, _) = StoryPassages::from_string(empty).take();
+        assert_eq!(res.ok().unwrap().passages.len(), 0);
+    }
+}
@@ -0,0 +1,148 @@
+use crate::PassageContent;
+use crate::StoryPassages;
+
+impl StoryPassages {
+    /// Renames the tag `old` to `new` throughout the story: in the tag list of
+    /// every passage, special or not, including author notes, passages
+    /// excluded by the build profile and the config passage, and in the
+    /// `tag-colors` of the `StoryData`. Returns the names of the passages that
+    /// changed, in sorted order, including `StoryData` if its colors changed
+    ///
+    /// A passage that already carries `new` just loses `old`, and the color of
+    /// `old` is only carried over if `new` doesn't have one. Passages aren't
+    /// reclassified, so renaming a tag such as `script` leaves the passages
+    /// where they are. The story is modified in place, not its source, so the
+    /// [`original_text`] and [`tag_block_range`] of each header still describe
+    /// the tags as written. Passages that share a name, such as scripts from
+    /// different files, are only listed once
+    ///
+    /// # Examples
+    /// ```
+    /// use tweep::StoryPassages;
+    /// let input = ":: StoryData\n{\"ifid\": \"D674C58C-DEFA-4F70-B7A2-27742230C0FC\", \"tag-colors\": {\"drak\": \"red\"}}\n\n\
+    ///     :: Start [drak cold]\nBrr\n\n:: Cellar [drak dark]\nDamp\n\n:: Attic\nDust\n".to_string();
+    /// let mut story = StoryPassages::from_string(input).take().0.ok().unwrap();
+    /// let changed = story.rename_tag("drak", "dark");
+    /// assert_eq!(changed, vec!["Cellar", "Start", "StoryData"]);
+    /// assert_eq!(story.passages["Start"].header.tags, vec!["dark", "cold"]);
+    /// assert_eq!(story.passages["Cellar"].header.tags, vec!["dark"]);
+    /// assert_eq!(story.story_data().unwrap().tag_colors.as_ref().unwrap()["dark"], "red");
+    /// assert_eq!(story.passages["Start"].header.original_text(), ":: Start [drak cold]");
+    /// ```
+    ///
+    /// [`original_text`]: struct.PassageHeader.html#method.original_text
+    /// [`tag_block_range`]: struct.PassageHeader.html#method.tag_block_range
+    pub fn rename_tag(&mut self, old: &str, new: &str) -> Vec<String> {
+        let mut changed = Vec::new();
+        if old == new {
+            return changed;
+        }
+
+        for passage in self.all_passages_mut() {
+            if rename(&mut passage.header.tags, old, new) {
+                changed.push(passage.header.name.clone());
+            }
+        }
+        for passage in self
+            .notes
+            .values_mut()
+            .chain(self.excluded_passages.values_mut())
+            .chain(self.config_passage.iter_mut())
+        {
+            if rename(&mut passage.header.tags, old, new) {
+                changed.push(passage.header.name.clone());
+            }
+        }
+
+        if let Some(passage) = self.data.as_mut() {
+            if let PassageContent::StoryData(Some(data)) = &mut passage.content {
+                if let Some(colors) = data.tag_colors.as_mut() {
+                    if let Some(color) = colors.remove(old) {
+                        colors.entry(new.to_string()).or_insert(color);
+                        changed.push(passage.header.name.clone());
+                    }
+                }
+            }
+        }
+        changed.sort();
+        changed.dedup();
+        changed
+    }
+}
+
+/// Renames `old` to `new` in `tags`, dropping `old` if `new` is already
+/// present. Returns `true` if `old` was found
+fn rename(tags: &mut Vec<String>, old: &str, new: &str) -> bool {
+    if !tags.iter().any(|tag| tag == old) {
+        return false;
+    }
+    let mut renamed = tags.iter().any(|tag| tag == new);
+    tags.retain_mut(|tag| {
+        if tag != old {
+            true
+        } else if renamed {
+            false
+        } else {
+            *tag = new.to_string();
+            renamed = true;
+            true
+        }
+    });
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BuildProfile, ParseOptions, StoryPassages};
+
+    #[test]
+    fn rename_tag() {
+        let input = ":: StoryData\n{\"ifid\": \"X\", \"tag-colors\": {\"a\": \"red\", \"b\": \"blue\"}}\n\n\
+            :: Start [a b a]\nGo\n\n:: Init [script a]\nx = 1\n\n:: Plain\nNo tags\n"
+            .to_string();
+        let mut story = StoryPassages::from_string(input).take().0.ok().unwrap();
+        assert_eq!(story.rename_tag("a", "b"), vec!["Init", "Start", "StoryData"]);
+        assert_eq!(story.passages["Start"].header.tags, vec!["b"]);
+        assert_eq!(story.scripts[0].header.tags, vec!["script", "b"]);
+        let colors = story.story_data().unwrap().tag_colors.clone().unwrap();
+        assert_eq!(colors.len(), 1);
+        assert_eq!(colors["b"], "blue");
+
+        assert!(story.rename_tag("a", "c").is_empty());
+        assert!(story.rename_tag("b", "b").is_empty());
+        assert_eq!(story.rename_tag("b", "c"), vec!["Init", "Start", "StoryData"]);
+        assert_eq!(story.tag_report().keys().collect::<Vec<_>>(), vec!["c", "script"]);
+    }
+
+    #[test]
+    fn rename_tag_everywhere() {
+        let input = ":: StoryConfig [a]
+{}
+
+:: Start [a]
+Go
+
+:: Todo [notes a]
+Fix
+
+:: Cheats [debug a]
+Win
+"
+            .to_string();
+        let options = ParseOptions::new().with_author_notes(true).with_profile(BuildProfile::release());
+        let mut story = StoryPassages::from_string_with_options(input, &options).take().0.ok().unwrap();
+        assert_eq!(story.rename_tag("a", "b"), vec!["Cheats", "Start", "StoryConfig", "Todo"]);
+        assert_eq!(story.notes["Todo"].header.tags, vec!["notes", "b"]);
+        assert_eq!(story.excluded_passages["Cheats"].header.tags, vec!["debug", "b"]);
+        assert_eq!(story.config_passage.as_ref().unwrap().header.tags, vec!["b"]);
+    }
+
+    #[test]
+    fn rename_tag_shared_name() {
+        let input = ":: Init [script a]\nx = 1\n\n:: Init [script a]\ny = 2\n".to_string();
+        let mut story = StoryPassages::from_string(input).take().0.ok().unwrap();
+        assert_eq!(story.rename_tag("a", "b"), vec!["Init"]);
+        assert!(story.scripts.iter().all(|p| p.header.tags == vec!["script", "b"]));
+        assert!(story.scripts.iter().all(|p| p.header.original_text() == ":: Init [script a]"));
+    }
+}
@@ -0,0 +1,110 @@
+use crate::FullContext;
+use crate::Passage;
+use crate::StoryPassages;
+use std::collections::BTreeMap;
+
+/// The passages carrying a tag, as produced by [`StoryPassages::tag_report`]
+///
+/// [`StoryPassages::tag_report`]: struct.StoryPassages.html#method.tag_report
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct TagUsage {
+    /// The name of each passage carrying the tag, along with the context of
+    /// the tag in the passage's header, in order of passage name. A passage
+    /// listing the tag more than once appears once for each listing
+    pub uses: Vec<(String, FullContext)>,
+}
+
+impl TagUsage {
+    /// Returns the number of times the tag is used
+    pub fn count(&self) -> usize {
+        self.uses.len()
+    }
+
+    /// Returns the name of the passage of each use of the tag, in sorted order
+    pub fn passages(&self) -> Vec<&str> {
+        self.uses.iter().map(|(name, _)| name.as_str()).collect()
+    }
+}
+
+impl StoryPassages {
+    /// Reports which passages use each tag, keyed by tag. Every passage is
+    /// included, special or not, so the tags that mark scripts and
+    /// stylesheets are reported too. Tags used only once are often misspelled
+    /// versions of another tag
+    ///
+    /// # Examples
+    /// ```
+    /// use tweep::StoryPassages;
+    /// let input = ":: Start [dark cold]\nBrr\n\n:: Cellar [dark]\nDamp\n\n:: Attic [drak]\nDust\n".to_string();
+    /// let story = StoryPassages::from_string(input).take().0.ok().unwrap();
+    /// let report = story.tag_report();
+    /// assert_eq!(report["dark"].count(), 2);
+    /// assert_eq!(report["dark"].passages(), vec!["Cellar", "Start"]);
+    /// let (_, context) = &report["cold"].uses[0];
+    /// assert_eq!(context.get_contents(), "cold");
+    /// assert_eq!((context.get_start_position().line, context.get_start_position().column), (1, 16));
+    /// let single: Vec<&String> = report.iter().filter(|(_, usage)| usage.count() == 1).map(|(tag, _)| tag).collect();
+    /// assert_eq!(single, vec!["cold", "drak"]);
+    /// ```
+    pub fn tag_report(&self) -> BTreeMap<String, TagUsage> {
+        let mut passages: Vec<&Passage> = self.all_passages().collect();
+        passages.sort_by(|a, b| a.header.name.cmp(&b.header.name));
+
+        let mut report: BTreeMap<String, TagUsage> = BTreeMap::new();
+        for passage in passages {
+            let block = match passage.header.tag_block_range() {
+                Some(block) => block,
+                None => continue,
+            };
+            let start = passage.context.get_byte_range().start;
+            let block = block.start - start..block.end - start;
+            let contents = passage.context.get_contents();
+
+            // Find each tag in the block, in order, falling back to the whole
+            // block if it was normalized out of recognition
+            let mut from = block.start + 1;
+            for tag in passage.header.tags.iter() {
+                let context = match find_tag(&contents[from..block.end], tag) {
+                    Some(pos) => {
+                        from += pos + tag.len();
+                        passage.context.slice(from - tag.len()..from)
+                    }
+                    None => passage.context.slice(block.clone()),
+                };
+                report.entry(tag.clone()).or_default().uses.push((passage.header.name.clone(), context));
+            }
+        }
+        report
+    }
+}
+
+/// Finds the offset of `tag` within `text` as a whole word, delimited by
+/// whitespace, commas, or the end of the tag block
+fn find_tag(text: &str, tag: &str) -> Option<usize> {
+    let is_separator = |c: char| c.is_whitespace() || c == ',' || c == ']';
+    text.match_indices(tag).map(|(pos, _)| pos).find(|&pos| {
+        text[..pos].chars().next_back().is_none_or(is_separator)
+            && text[pos + tag.len()..].chars().next().is_none_or(is_separator)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::StoryPassages;
+
+    #[test]
+    fn tag_report() {
+        let input = ":: StoryTitle [meta]\nT\n\n:: Start [a aa a]\nGo\n\n:: Init [script a]\nx = 1\n\n:: Plain\nNo tags\n"
+            .to_string();
+        let story = StoryPassages::from_string(input).take().0.ok().unwrap();
+        let report = story.tag_report();
+        let tags: Vec<&String> = report.keys().collect();
+        assert_eq!(tags, vec!["a", "aa", "meta", "script"]);
+        assert_eq!(report["a"].passages(), vec!["Init", "Start", "Start"]);
+        let columns: Vec<usize> = report["a"].uses.iter().map(|(_, c)| c.get_start_position().column).collect();
+        assert_eq!(columns, vec![17, 11, 16]);
+        assert_eq!(report["aa"].uses[0].1.get_contents(), "aa");
+        assert_eq!(report["aa"].uses[0].1.get_start_position().column, 13);
+        assert_eq!(report["meta"].uses[0].1.get_start_position().line, 1);
+    }
+}
@@ -0,0 +1,292 @@
+use crate::FullContext;
+use crate::ParseOptions;
+use crate::Passage;
+use crate::PassageContent;
+use crate::Position;
+use crate::StoryPassages;
+use crate::Warning;
+use crate::WarningKind;
+use std::collections::HashMap;
+use std::ops::Range;
+use std::path::Path;
+
+impl StoryPassages {
+    /// Expands the template instantiations in every normal passage, replacing
+    /// each expanded passage with one parsed from the expanded text. See
+    /// [`ParseOptions::expand_templates`] for details
+    ///
+    /// [`ParseOptions::expand_templates`]: struct.ParseOptions.html#structfield.expand_templates
+    pub(crate) fn expand_templates(&mut self, options: &ParseOptions) -> Vec<Warning> {
+        let mut warnings = Vec::new();
+        let templates: HashMap<&str, &str> = self
+            .tagged_passages
+            .get("template")
+            .into_iter()
+            .flatten()
+            .filter_map(|passage| match &passage.content {
+                PassageContent::Script(script) => Some((passage.header.name.as_str(), script.content())),
+                _ => None,
+            })
+            .collect();
+
+        let mut names: Vec<&String> = self.passages.keys().collect();
+        names.sort();
+        let mut expanded_passages = Vec::new();
+        for name in names {
+            let passage = &self.passages[name];
+            if !matches!(passage.content, PassageContent::Normal(_)) {
+                continue;
+            }
+            let contents = passage.context.get_contents();
+            let body_start = match contents.find('\n') {
+                Some(i) => i + 1,
+                None => continue,
+            };
+            let expansion = expand(&contents[body_start..], &templates);
+            for (range, kind) in &expansion.problems {
+                let context = passage.context.slice(body_start + range.start..body_start + range.end);
+                warnings.push(Warning::new(kind.clone(), Some(context)));
+            }
+            if expansion.spans.is_empty() {
+                continue;
+            }
+            if let Some((passage, mut parse_warnings)) = reparse(passage, body_start, &expansion, options) {
+                warnings.append(&mut parse_warnings);
+                expanded_passages.push(passage);
+            }
+        }
+
+        for passage in expanded_passages {
+            self.passages.insert(passage.header.name.clone(), passage);
+        }
+        warnings
+    }
+}
+
+/// The result of expanding the templates in a passage body
+struct Expansion {
+    /// The expanded body
+    body: String,
+
+    /// The byte ranges of the expanded instantiations within the new body
+    spans: Vec<Range<usize>>,
+
+    /// Problems found, with the byte range of their instantiation in the
+    /// original body
+    problems: Vec<(Range<usize>, WarningKind)>,
+}
+
+/// Expands every instantiation of one of `templates` in `body`
+fn expand(body: &str, templates: &HashMap<&str, &str>) -> Expansion {
+    let mut expansion = Expansion {
+        body: String::new(),
+        spans: Vec::new(),
+        problems: Vec::new(),
+    };
+    let mut copied = 0;
+    let mut search = 0;
+    while let Some(start) = body[search..].find("{{").map(|i| search + i) {
+        let end = match body[start..].find("}}") {
+            Some(i) => start + i + 2,
+            None => break,
+        };
+        search = end;
+        let (name, args) = match parse_instantiation(&body[start + 2..end - 2]) {
+            Some(instantiation) => instantiation,
+            None => continue,
+        };
+        let template = match templates.get(name) {
+            Some(template) => template,
+            None => {
+                expansion.problems.push((start..end, WarningKind::UnknownTemplate(name.to_string())));
+                continue;
+            }
+        };
+
+        expansion.body.push_str(&body[copied..start]);
+        let span_start = expansion.body.len();
+        let mut rest = *template;
+        while let Some((before, placeholder, after)) = next_placeholder(rest) {
+            expansion.body.push_str(before);
+            match args.get(placeholder) {
+                Some(value) => expansion.body.push_str(value),
+                None => {
+                    let kind = WarningKind::MissingTemplateArgument(name.to_string(), placeholder.to_string());
+                    expansion.problems.push((start..end, kind));
+                }
+            }
+            rest = after;
+        }
+        expansion.body.push_str(rest);
+        expansion.spans.push(span_start..expansion.body.len());
+        copied = end;
+    }
+    expansion.body.push_str(&body[copied..]);
+    expansion
+}
+
+/// Parses the inside of an instantiation, `name key=value key="quoted value"`,
+/// into the template name and its arguments. Returns `None` if it isn't a
+/// well-formed instantiation
+fn parse_instantiation(inner: &str) -> Option<(&str, HashMap<&str, &str>)> {
+    let inner = inner.trim();
+    let name_end = inner.find(char::is_whitespace).unwrap_or(inner.len());
+    let name = &inner[..name_end];
+    if name.is_empty() || name.contains(['{', '}', '=', '"']) {
+        return None;
+    }
+
+    let mut args = HashMap::new();
+    let mut rest = inner[name_end..].trim_start();
+    while !rest.is_empty() {
+        let eq = rest.find('=')?;
+        let key = &rest[..eq];
+        if key.is_empty() || key.contains(char::is_whitespace) {
+            return None;
+        }
+        rest = &rest[eq + 1..];
+        let value = if let Some(quoted) = rest.strip_prefix('"') {
+            let close = quoted.find('"')?;
+            rest = &quoted[close + 1..];
+            &quoted[..close]
+        } else {
+            let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+            let value = &rest[..end];
+            rest = &rest[end..];
+            value
+        };
+        args.insert(key, value);
+        rest = rest.trim_start();
+    }
+    Some((name, args))
+}
+
+/// Finds the next `{{placeholder}}` in a template body, returning the text
+/// before it, the placeholder name, and the text after it
+fn next_placeholder(text: &str) -> Option<(&str, &str, &str)> {
+    let start = text.find("{{")?;
+    let len = text[start + 2..].find("}}")?;
+    let name = &text[start + 2..start + 2 + len];
+    Some((&text[..start], name.trim(), &text[start + 4 + len..]))
+}
+
+/// Parses `passage` again with its body replaced by the expanded body,
+/// returning the new passage and any warnings located within the expanded
+/// instantiations. Returns `None` if the new passage fails to parse
+fn reparse(
+    passage: &Passage,
+    body_start: usize,
+    expansion: &Expansion,
+    options: &ParseOptions,
+) -> Option<(Passage, Vec<Warning>)> {
+    // Replace the body within a copy of the source, so that the passage keeps
+    // its position in the file
+    let source = passage.context.get_source();
+    let range = passage.context.get_byte_range();
+    let body_offset = range.start + body_start;
+    let new_source = format!("{}{}{}", &source[..body_offset], expansion.body, &source[range.end..]);
+    let root = FullContext::from_file_path(passage.context.get_file_path().map(Path::to_path_buf), new_source);
+    let context = root.slice(range.start..body_offset + expansion.body.len());
+
+    let (res, warnings) = Passage::parse(context, options).take();
+    let mut new_passage = res.ok()?;
+    if let (PassageContent::Normal(new), PassageContent::Normal(old)) = (&mut new_passage.content, &passage.content) {
+        new.pid = old.pid;
+        if options.normalize_link_whitespace {
+            new.normalize_link_whitespace();
+        }
+    }
+
+    // Warnings outside of the expanded text were already reported when the
+    // passage was first parsed
+    let line_column = |position: Position| (position.line, position.column);
+    let spans: Vec<((usize, usize), (usize, usize))> = expansion
+        .spans
+        .iter()
+        .map(|span| {
+            let start = root.position_at(body_offset + span.start);
+            let end = root.position_at(body_offset + span.end);
+            (line_column(start), line_column(end))
+        })
+        .collect();
+    let warnings = warnings
+        .into_iter()
+        .filter(|warning| {
+            let position = match &warning.context {
+                Some(context) => line_column(*context.get_start_position()),
+                None => return false,
+            };
+            spans.iter().any(|(start, end)| *start <= position && position < *end)
+        })
+        .filter(|warning| !(options.normalize_link_whitespace && matches!(warning.kind, WarningKind::WhitespaceInLink(_))))
+        .collect();
+    Some((new_passage, warnings))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ParseOptions;
+    use crate::PassageContent;
+    use crate::StoryPassages;
+    use crate::WarningKind;
+
+    fn options() -> ParseOptions {
+        ParseOptions::new().with_expand_templates(true)
+    }
+
+    #[test]
+    fn expansion() {
+        let input = ":: Start\nBefore\n{{door to=Hall label=\"The hall\"}} after\n\n\
+                     :: door [template]\n[[{{label}}->{{to}}]]\n\n:: Hall\nEnd\n"
+            .to_string();
+        let (res, warnings) = StoryPassages::from_string_with_options(input, &options()).take();
+        assert!(warnings.is_empty());
+        let story = res.ok().unwrap();
+        assert_eq!(story.tagged_passages["template"][0].header.name, "door");
+        assert!(!story.passages.contains_key("door"));
+
+        let start = &story.passages["Start"];
+        assert_eq!(start.context.get_contents(), ":: Start\nBefore\n[[The hall->Hall]] after");
+        assert_eq!(start.context.get_start_position().line, 1);
+        let links = match &start.content {
+            PassageContent::Normal(twine) => twine.get_links(),
+            _ => panic!("Expected normal passage"),
+        };
+        assert_eq!(links[0].target, "Hall");
+        assert_eq!(links[0].context.get_start_position().line, 3);
+        assert!(!story.check().iter().any(|w| matches!(w.kind, WarningKind::DeadLink(_))));
+    }
+
+    #[test]
+    fn problems() {
+        let input = ":: Start\nA {{nope x=1}} B {{greet}} C {{ not an instantiation\n\n\
+                     :: greet [template]\nHi {{name}}\n"
+            .to_string();
+        let (res, warnings) = StoryPassages::from_string_with_options(input, &options()).take();
+        let kinds: Vec<&WarningKind> = warnings.iter().map(|w| &w.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                &WarningKind::UnknownTemplate("nope".to_string()),
+                &WarningKind::MissingTemplateArgument("greet".to_string(), "name".to_string()),
+            ]
+        );
+        assert_eq!(warnings[0].context.as_ref().unwrap().get_start_position().column, 3);
+        assert_eq!(warnings[1].context.as_ref().unwrap().get_start_position().column, 18);
+        let story = res.ok().unwrap();
+        assert_eq!(
+            story.passages["Start"].context.get_contents(),
+            ":: Start\nA {{nope x=1}} B Hi  C {{ not an instantiation"
+        );
+    }
+
+    #[test]
+    fn disabled() {
+        let input = ":: Start\n{{greet}}\n\n:: greet [template]\nHi\n".to_string();
+        let (res, warnings) = StoryPassages::from_string(input).take();
+        assert!(warnings.is_empty());
+        let story = res.ok().unwrap();
+        assert!(story.passages.contains_key("greet"));
+        assert_eq!(story.passages["Start"].context.get_contents(), ":: Start\n{{greet}}");
+    }
+}
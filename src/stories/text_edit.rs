@@ -0,0 +1,17 @@
+use crate::FullContext;
+
+/// A replacement of a span of source text, as produced by
+/// [`StoryPassages::replace`]
+///
+/// [`StoryPassages::replace`]: struct.StoryPassages.html#method.replace
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TextEdit {
+    /// The name of the passage containing the span
+    pub passage: String,
+
+    /// The context of the span to be replaced, including its file
+    pub context: FullContext,
+
+    /// The text to replace the span with
+    pub replacement: String,
+}
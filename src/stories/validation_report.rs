@@ -0,0 +1,196 @@
+use crate::Severity;
+use crate::StoryPassages;
+use crate::Warning;
+use crate::WarningKind;
+
+/// The findings of [`StoryPassages::validate`], grouped by the kind of problem
+///
+/// Warnings that don't belong to one of the named groups, such as those
+/// produced while parsing and added with [`add`], are kept in `other`.
+///
+/// [`StoryPassages::validate`]: struct.StoryPassages.html#method.validate
+/// [`add`]: #method.add
+#[derive(Clone, Debug, Default)]
+pub struct ValidationReport {
+    /// Warnings about a missing `StoryTitle`, `StoryData` or start passage
+    pub missing_specials: Vec<Warning>,
+
+    /// Warnings about links to passages that don't exist
+    pub dead_links: Vec<Warning>,
+
+    /// Warnings about passages that were defined more than once
+    pub duplicates: Vec<Warning>,
+
+    /// The names of the passages that can't be reached from the start
+    /// passage, in sorted order
+    pub orphans: Vec<String>,
+
+    /// Every other warning
+    pub other: Vec<Warning>,
+}
+
+impl ValidationReport {
+    /// Creates an empty report
+    pub fn new() -> Self {
+        ValidationReport::default()
+    }
+
+    /// Creates a report from a list of warnings, placing each in its group
+    ///
+    /// # Examples
+    /// ```
+    /// use tweep::{StoryPassages, ValidationReport};
+    /// let input = ":: Start\n[[Nowhere]]\n\n:: Start\nAgain\n".to_string();
+    /// let (res, mut warnings) = StoryPassages::from_string(input).take();
+    /// warnings.append(&mut res.ok().unwrap().check());
+    /// let report = ValidationReport::from_warnings(warnings);
+    /// assert_eq!(report.duplicates.len(), 1);
+    /// assert_eq!(report.dead_links.len(), 1);
+    /// assert_eq!(report.missing_specials.len(), 2);
+    /// ```
+    pub fn from_warnings(warnings: Vec<Warning>) -> Self {
+        let mut report = ValidationReport::new();
+        for warning in warnings {
+            report.add(warning);
+        }
+        report
+    }
+
+    /// Adds a warning to the appropriate group
+    pub fn add(&mut self, warning: Warning) {
+        let group = match warning.kind {
+            WarningKind::MissingStoryTitle
+            | WarningKind::MissingStoryData
+            | WarningKind::MissingStartPassage
+            | WarningKind::DeadStartPassage(_) => &mut self.missing_specials,
+            WarningKind::DeadLink(_)
+            | WarningKind::TrimmedLinkMatch(_, _)
+            | WarningKind::ExcludedLink(_, _)
+            | WarningKind::DeadDynamicTarget(_)
+            | WarningKind::UnexportedLink(_) => &mut self.dead_links,
+            WarningKind::DuplicatePassage(_)
+            | WarningKind::DuplicatePassageAcrossFiles(_)
+            | WarningKind::DuplicateStoryTitle
+            | WarningKind::DuplicateStoryData => {
+                &mut self.duplicates
+            }
+            _ => &mut self.other,
+        };
+        group.push(warning);
+    }
+
+    /// Returns an iterator over every warning in the report, group by group.
+    /// Orphans aren't warnings, so they aren't included
+    pub fn warnings(&self) -> impl Iterator<Item = &Warning> {
+        self.missing_specials
+            .iter()
+            .chain(self.dead_links.iter())
+            .chain(self.duplicates.iter())
+            .chain(self.other.iter())
+    }
+
+    /// Returns the number of findings with the given [`Severity`]. Each orphan
+    /// counts as a [`Severity::Note`]
+    ///
+    /// # Examples
+    /// ```
+    /// use tweep::{Severity, StoryPassages};
+    /// let input = ":: Start\n[[Nowhere]]\n\n:: Lost\nNobody links here\n".to_string();
+    /// let story = StoryPassages::from_string(input).take().0.ok().unwrap();
+    /// let report = story.validate();
+    /// assert_eq!(report.count(Severity::Warning), 3);
+    /// assert_eq!(report.count(Severity::Note), 1);
+    /// ```
+    ///
+    /// [`Severity`]: enum.Severity.html
+    /// [`Severity::Note`]: enum.Severity.html#variant.Note
+    pub fn count(&self, severity: Severity) -> usize {
+        let orphans = if severity == Severity::Note { self.orphans.len() } else { 0 };
+        self.warnings().filter(|warning| warning.severity() == severity).count() + orphans
+    }
+
+    /// Returns `true` if the report has no findings at all
+    pub fn is_clean(&self) -> bool {
+        self.orphans.is_empty() && self.warnings().next().is_none()
+    }
+}
+
+impl StoryPassages {
+    /// Performs the same checks as [`check`] and finds the passages that can't
+    /// be reached from the start passage, returning the findings as a
+    /// [`ValidationReport`]. If there is no start passage, no orphans are
+    /// reported
+    ///
+    /// Warnings produced while parsing aren't included, but can be added to
+    /// the report with [`ValidationReport::add`].
+    ///
+    /// # Examples
+    /// ```
+    /// use tweep::StoryPassages;
+    /// let input = ":: Start\n[[Nowhere]]\n\n:: Lost\nNobody links here\n".to_string();
+    /// let story = StoryPassages::from_string(input).take().0.ok().unwrap();
+    /// let report = story.validate();
+    /// assert_eq!(report.missing_specials.len(), 2);
+    /// assert_eq!(report.dead_links.len(), 1);
+    /// assert_eq!(report.orphans, vec!["Lost"]);
+    /// assert!(!report.is_clean());
+    /// ```
+    ///
+    /// [`check`]: #method.check
+    /// [`ValidationReport`]: struct.ValidationReport.html
+    /// [`ValidationReport::add`]: struct.ValidationReport.html#method.add
+    pub fn validate(&self) -> ValidationReport {
+        let mut report = ValidationReport::from_warnings(self.check());
+        if let Some(reachability) = self
+            .get_start_passage_name()
+            .and_then(|start| self.reachable_from(start))
+        {
+            report.orphans = reachability.unreachable.into_iter().collect();
+        }
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Severity;
+    use crate::StoryPassages;
+    use crate::ValidationReport;
+    use crate::Warning;
+    use crate::WarningKind;
+
+    #[test]
+    fn grouping() {
+        let input = ":: StoryTitle\nTest\n\n:: StoryData\n{\"ifid\": \"ABC\"}\n\n\
+                     :: Start\n[[Middle]] [[End.]]\n\n:: Middle\n[[Start]]\n\n:: End\nDone\n\n:: Lost\nHi\n"
+            .to_string();
+        let story = StoryPassages::from_string(input).take().0.ok().unwrap();
+        let mut report = story.validate();
+        assert!(report.missing_specials.is_empty());
+        assert_eq!(report.dead_links.len(), 1);
+        assert!(matches!(report.dead_links[0].kind, WarningKind::TrimmedLinkMatch(_, _)));
+        assert_eq!(report.orphans, vec!["End", "Lost"]);
+        assert_eq!(report.count(Severity::Warning), 1);
+        assert_eq!(report.count(Severity::Note), 2);
+
+        report.add(Warning::new::<crate::Context>(WarningKind::GeneratedStoryTitle("x".to_string()), None));
+        report.add(Warning::new::<crate::Context>(WarningKind::DuplicateStoryData, None));
+        assert_eq!(report.duplicates.len(), 1);
+        assert_eq!(report.other.len(), 1);
+        assert_eq!(report.count(Severity::Note), 3);
+        assert_eq!(report.warnings().count(), 3);
+    }
+
+    #[test]
+    fn clean() {
+        let report = ValidationReport::new();
+        assert!(report.is_clean());
+        assert_eq!(report.count(Severity::Warning), 0);
+
+        let input = ":: Start\nNo start data\n".to_string();
+        let story = StoryPassages::from_string(input).take().0.ok().unwrap();
+        let report = story.validate();
+        assert!(report.orphans.is_empty());
+        assert!(!report.is_clean());
+    }
+}
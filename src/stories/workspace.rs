@@ -0,0 +1,168 @@
+#[cfg(not(feature = "full-context"))]
+use crate::ErrorList;
+#[cfg(feature = "full-context")]
+use crate::ContextErrorList;
+use crate::Output;
+use crate::ParseOptions;
+use crate::PassageContent;
+use crate::StoryPassages;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+#[cfg(not(feature = "full-context"))]
+type AddOutput = Output<Result<(), ErrorList>>;
+#[cfg(feature = "full-context")]
+type AddOutput = Output<Result<(), ContextErrorList>>;
+
+/// A collection of independent, named [`StoryPassages`], such as several
+/// related games maintained in one repository, with queries across them
+///
+/// # Examples
+/// ```
+/// use tweep::{StoryPassages, Workspace};
+/// let mut workspace = Workspace::new();
+/// let first = ":: Start\nPart one\n\n:: Credits\nBy me".to_string();
+/// let second = ":: Start\nPart two\n\n:: Credits\nBy me".to_string();
+/// workspace.insert("one", StoryPassages::from_string(first).take().0.ok().unwrap());
+/// workspace.insert("two", StoryPassages::from_string(second).take().0.ok().unwrap());
+///
+/// let shared = workspace.shared_passages();
+/// assert_eq!(shared["Credits"], vec!["one", "two"]);
+/// ```
+///
+/// [`StoryPassages`]: struct.StoryPassages.html
+#[derive(Default)]
+pub struct Workspace {
+    stories: BTreeMap<String, StoryPassages>,
+}
+
+impl Workspace {
+    /// Creates an empty `Workspace`
+    pub fn new() -> Self {
+        Workspace::default()
+    }
+
+    /// Adds a story under the given name, returning the story previously
+    /// stored under that name, if any
+    pub fn insert<S: Into<String>>(&mut self, name: S, story: StoryPassages) -> Option<StoryPassages> {
+        self.stories.insert(name.into(), story)
+    }
+
+    /// Parses a story from the given root [`Path`] using the given
+    /// [`ParseOptions`] and adds it under the given name. See
+    /// [`StoryPassages::from_path`] for how directories are handled. The story
+    /// is only added if it parses without errors
+    ///
+    /// [`Path`]: std::path::Path
+    /// [`ParseOptions`]: struct.ParseOptions.html
+    /// [`StoryPassages::from_path`]: struct.StoryPassages.html#method.from_path
+    pub fn add_path<S: Into<String>, P: AsRef<Path>>(&mut self, name: S, root: P, options: &ParseOptions) -> AddOutput {
+        let (res, warnings) = StoryPassages::from_path_with_options(root, options).take();
+        let res = res.map(|story| {
+            self.insert(name, story);
+        });
+        Output::new(res).with_warnings(warnings)
+    }
+
+    /// Returns the story with the given name, if any
+    pub fn get(&self, name: &str) -> Option<&StoryPassages> {
+        self.stories.get(name)
+    }
+
+    /// Returns a mutable reference to the story with the given name, if any
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut StoryPassages> {
+        self.stories.get_mut(name)
+    }
+
+    /// Removes and returns the story with the given name, if any
+    pub fn remove(&mut self, name: &str) -> Option<StoryPassages> {
+        self.stories.remove(name)
+    }
+
+    /// Returns the names of the stories in the workspace, in order
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.stories.keys().map(|name| name.as_str())
+    }
+
+    /// Returns the stories in the workspace along with their names, in order
+    /// of name
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &StoryPassages)> {
+        self.stories.iter().map(|(name, story)| (name.as_str(), story))
+    }
+
+    /// Finds the passage names that appear in more than one story. Returns a
+    /// map from each such passage name to the names of the stories containing
+    /// it, in order
+    pub fn shared_passages(&self) -> BTreeMap<String, Vec<String>> {
+        let mut shared: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for (story_name, story) in &self.stories {
+            for name in story.passages.keys() {
+                shared.entry(name.clone()).or_default().push(story_name.clone());
+            }
+        }
+        shared.retain(|_, stories| stories.len() > 1);
+        shared
+    }
+
+    /// Finds the stylesheets whose content appears in more than one story,
+    /// ignoring surrounding whitespace. Returns a map from each such
+    /// stylesheet's content to the names of the stories containing it, in
+    /// order
+    pub fn shared_stylesheets(&self) -> BTreeMap<String, Vec<String>> {
+        let mut shared: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for (story_name, story) in &self.stories {
+            for passage in &story.stylesheets {
+                if let PassageContent::Stylesheet(stylesheet) = &passage.content {
+                    let stories = shared.entry(stylesheet.content().trim().to_string()).or_default();
+                    if stories.last() != Some(story_name) {
+                        stories.push(story_name.clone());
+                    }
+                }
+            }
+        }
+        shared.retain(|_, stories| stories.len() > 1);
+        shared
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn story(input: &str) -> StoryPassages {
+        StoryPassages::from_string(input.to_string()).take().0.ok().unwrap()
+    }
+
+    #[test]
+    fn shared_stylesheets() {
+        let mut workspace = Workspace::new();
+        workspace.insert("a", story(":: S [stylesheet]\nbody { color: red; }\n\n:: T [stylesheet]\nbody { color: red; }"));
+        workspace.insert("b", story(":: Other [stylesheet]\n  body { color: red; }  \n\n:: U [stylesheet]\np {}"));
+        workspace.insert("c", story(":: V [stylesheet]\np {}\n\n:: Start\nHi"));
+        let shared = workspace.shared_stylesheets();
+        assert_eq!(shared.len(), 2);
+        assert_eq!(shared["body { color: red; }"], vec!["a", "b"]);
+        assert_eq!(shared["p {}"], vec!["b", "c"]);
+        assert!(workspace.shared_passages().is_empty());
+    }
+
+    #[test]
+    fn add_path() -> Result<(), Box<dyn std::error::Error>> {
+        use std::fs::File;
+        use std::io::Write;
+        let dir = tempdir()?;
+        let mut file = File::create(dir.path().join("story.tw"))?;
+        writeln!(file, ":: Start\nHello")?;
+
+        let mut workspace = Workspace::new();
+        let out = workspace.add_path("game", dir.path(), &ParseOptions::default());
+        assert!(out.is_ok());
+        assert!(workspace.get("game").unwrap().passages.contains_key("Start"));
+
+        let out = workspace.add_path("missing", dir.path().join("nope"), &ParseOptions::default());
+        assert!(out.is_err());
+        assert_eq!(workspace.names().collect::<Vec<_>>(), vec!["game"]);
+        Ok(())
+    }
+}
@@ -0,0 +1,318 @@
+use crate::passages::display_text;
+use crate::passages::link_target;
+use crate::CancellationToken;
+use crate::ErrorList;
+use crate::ExportOptions;
+use crate::FullContext;
+use crate::Output;
+use crate::Passage;
+use crate::PassageContent;
+use crate::Position;
+use crate::StoryPassages;
+use crate::Warning;
+use crate::WarningKind;
+use std::collections::HashMap;
+use std::collections::HashSet;
+
+impl StoryPassages {
+    /// Converts the story into the source of a Yarn Spinner `.yarn` file, for
+    /// migrating narrative content to engines that use Yarn Spinner
+    ///
+    /// Each normal passage becomes a node, with its name converted to a valid
+    /// node title, and its tags and position carried over. Links become
+    /// options that jump to the linked node, placed at the end of the node. A
+    /// line containing only links produces only options; otherwise the line is
+    /// kept with the display text of its links in place. Nodes are written in
+    /// order of passage name. To choose the order, or to leave passages out,
+    /// use [`to_yarn_with`].
+    ///
+    /// # Warnings
+    /// * [`UnsupportedExport`] - A construct that can't be expressed in Yarn,
+    ///   such as a macro, HTML tag, script, or stylesheet. Macros are kept as
+    ///   comments, HTML tags are removed, and scripts and stylesheets are
+    ///   skipped
+    /// * [`DeadLink`] - A link that can't be resolved; no option is produced
+    ///
+    /// # Examples
+    /// ```
+    /// use tweep::StoryPassages;
+    /// let input = r#":: Start [intro]
+    /// You wake up.
+    /// [[Get up->Hallway]]
+    ///
+    /// :: Hallway
+    /// The end.
+    /// "#.to_string();
+    /// let story = StoryPassages::from_string(input).take().0.ok().unwrap();
+    /// let (yarn, warnings) = story.to_yarn().take();
+    /// assert!(warnings.is_empty());
+    /// assert!(yarn.contains("title: Start\ntags: intro\nposition: 10,10\n---\nYou wake up.\n-> Get up\n    <<jump Hallway>>\n===\n"));
+    /// ```
+    ///
+    /// [`UnsupportedExport`]: enum.WarningKind.html#variant.UnsupportedExport
+    /// [`DeadLink`]: enum.WarningKind.html#variant.DeadLink
+    /// [`to_yarn_with`]: #method.to_yarn_with
+    pub fn to_yarn(&self) -> Output<String> {
+        self.to_yarn_with(&ExportOptions::default())
+    }
+
+    /// Converts the story into Yarn Spinner source like [`to_yarn`], with the
+    /// given [`ExportOptions`]. Nodes are written in the order given by their
+    /// `passage_order`, and passages left out by their `filter` are skipped
+    ///
+    /// # Warnings
+    /// The warnings of [`to_yarn`], and:
+    /// * [`UnexportedLink`] - A link to a passage left out by the filter; no
+    ///   option is produced
+    ///
+    /// # Examples
+    /// ```
+    /// use tweep::{ExportFilter, ExportOptions, StoryPassages, WarningKind};
+    /// let input = ":: Start\nHello [[Ideas]]\n\n:: Ideas [todo]\nA dragon?\n".to_string();
+    /// let story = StoryPassages::from_string(input).take().0.ok().unwrap();
+    /// let options = ExportOptions::new().with_filter(ExportFilter::new().with_excluded_tag("todo"));
+    /// let (yarn, warnings) = story.to_yarn_with(&options).take();
+    /// assert!(!yarn.contains("title: Ideas"));
+    /// assert_eq!(warnings[0].kind, WarningKind::UnexportedLink("Ideas".to_string()));
+    /// ```
+    ///
+    /// [`to_yarn`]: #method.to_yarn
+    /// [`ExportOptions`]: struct.ExportOptions.html
+    /// [`UnexportedLink`]: enum.WarningKind.html#variant.UnexportedLink
+    pub fn to_yarn_with(&self, options: &ExportOptions) -> Output<String> {
+        self.to_yarn_internal(options, None).expect("Exporting without a token is never cancelled")
+    }
+
+    /// Converts the story into Yarn Spinner source like [`to_yarn_with`], but
+    /// returns a list holding a [`Cancelled`] error as soon as `token` is
+    /// cancelled, which is checked before each passage is converted
+    ///
+    /// # Examples
+    /// ```
+    /// use tweep::{CancellationToken, ErrorKind, ExportOptions, StoryPassages};
+    /// let story = StoryPassages::from_string(":: Start\nHello".to_string()).take().0.ok().unwrap();
+    /// let options = ExportOptions::new();
+    /// let token = CancellationToken::new();
+    /// assert!(story.to_yarn_cancellable(&options, &token).is_ok());
+    /// token.cancel();
+    /// assert_eq!(story.to_yarn_cancellable(&options, &token).err().unwrap().errors[0].kind, ErrorKind::Cancelled);
+    /// ```
+    ///
+    /// [`to_yarn_with`]: #method.to_yarn_with
+    /// [`Cancelled`]: enum.ErrorKind.html#variant.Cancelled
+    pub fn to_yarn_cancellable(&self, options: &ExportOptions, token: &CancellationToken) -> Result<Output<String>, ErrorList> {
+        self.to_yarn_internal(options, Some(token))
+    }
+
+    /// Does the work of `to_yarn_with` and `to_yarn_cancellable`
+    fn to_yarn_internal(&self, export: &ExportOptions, token: Option<&CancellationToken>) -> Result<Output<String>, ErrorList> {
+        let mut warnings = Vec::new();
+        let tagged = self.tagged_passages.iter().flat_map(|(tag, passages)| passages.iter().map(move |p| (tag.as_str(), p)));
+        let specials = self
+            .scripts
+            .iter()
+            .map(|p| ("script", p))
+            .chain(self.stylesheets.iter().map(|p| ("stylesheet", p)))
+            .chain(tagged);
+        for (kind, passage) in specials {
+            warnings.push(Warning::new(
+                WarningKind::UnsupportedExport(format!("{} passage", kind)),
+                Some(passage.context.clone()),
+            ));
+        }
+
+        let mut names: Vec<&String> = self.passages.keys().collect();
+        names.sort();
+        let mut titles = node_titles(&names);
+        // Only exported passages have nodes to jump to
+        titles.retain(|name, _| self.is_exported(name, &export.filter));
+        let names = self.exported_names(export);
+
+        let mut out = String::new();
+        for name in names {
+            if let Some(token) = token {
+                token.check()?;
+            }
+
+            let passage = &self.passages[name];
+            if let PassageContent::Normal(twine) = &passage.content {
+                out.push_str(&format!("title: {}\n", titles[name.as_str()]));
+                if !passage.tags().is_empty() {
+                    out.push_str(&format!("tags: {}\n", passage.tags().join(" ")));
+                }
+                if let Some(position) = passage.metadata().get("position").and_then(|p| p.as_str()) {
+                    out.push_str(&format!("position: {}\n", position));
+                }
+                out.push_str("---\n");
+                let mut options = Vec::new();
+                for (i, line) in twine.trimmed().split('\n').enumerate() {
+                    let line_context = passage.context.subcontext(Position::rel(i + 2, 1)..);
+                    let converted = self.convert_line(passage, line, &line_context, &titles, &mut options, &mut warnings);
+                    out.push_str(&converted);
+                }
+                for (text, title) in options {
+                    out.push_str(&format!("-> {}\n    <<jump {}>>\n", escape(&text), title));
+                }
+                out.push_str("===\n");
+            }
+        }
+        Ok(Output::new(out).with_warnings(warnings))
+    }
+
+    /// Converts a single line of a passage body into Yarn, adding any links
+    /// found to `options`. `titles` holds the node titles of the exported
+    /// passages
+    fn convert_line(
+        &self,
+        passage: &Passage,
+        line: &str,
+        context: &FullContext,
+        titles: &HashMap<&str, String>,
+        options: &mut Vec<(String, String)>,
+        warnings: &mut Vec<Warning>,
+    ) -> String {
+        let mut comments = String::new();
+        let mut text = String::new();
+        let mut only_links = true;
+        let mut i = 0;
+        while i < line.len() {
+            let rest = &line[i..];
+            let span = |len: usize| context.subcontext(Position::rel(1, i + 1)..=Position::rel(1, i + len));
+            if let (true, Some(end)) = (rest.starts_with("[["), rest.find("]]")) {
+                let inner = &rest[2..end];
+                let display = display_text(inner).trim();
+                match self.resolve_link(&passage.header.name, link_target(inner)) {
+                    Some(target) if titles.contains_key(target) => {
+                        options.push((display.to_string(), titles[target].clone()))
+                    }
+                    Some(target) => warnings.push(Warning::new(
+                        WarningKind::UnexportedLink(target.to_string()),
+                        Some(span(end + 2)),
+                    )),
+                    None => warnings.push(Warning::new(
+                        WarningKind::DeadLink(link_target(inner).to_string()),
+                        Some(span(end + 2)),
+                    )),
+                }
+                text.push_str(&escape(display));
+                i += end + 2;
+            } else if let (true, Some(end)) = (rest.starts_with("<<"), rest.find(">>")) {
+                let command = &rest[..end + 2];
+                warnings.push(Warning::new(
+                    WarningKind::UnsupportedExport(format!("macro {}", command)),
+                    Some(span(end + 2)),
+                ));
+                comments.push_str(&format!("// {}\n", command));
+                i += end + 2;
+            } else if let (true, Some(end)) = (is_html_tag(rest), rest.find('>')) {
+                warnings.push(Warning::new(
+                    WarningKind::UnsupportedExport(format!("HTML tag {}", &rest[..end + 1])),
+                    Some(span(end + 1)),
+                ));
+                i += end + 1;
+            } else {
+                let c = rest.chars().next().unwrap();
+                only_links &= c.is_whitespace();
+                text.push_str(&escape(&rest[..c.len_utf8()]));
+                i += c.len_utf8();
+            }
+        }
+
+        if only_links {
+            comments
+        } else {
+            comments + text.trim() + "\n"
+        }
+    }
+}
+
+/// Returns `true` if the input begins with something that looks like an HTML
+/// tag
+fn is_html_tag(input: &str) -> bool {
+    input.starts_with('<') && input[1..].starts_with(|c: char| c.is_ascii_alphabetic() || c == '/')
+}
+
+/// Escapes characters with special meaning in Yarn lines
+fn escape(input: &str) -> String {
+    let mut out = String::with_capacity(input.len());
+    for c in input.chars() {
+        if matches!(c, '\\' | '#' | '{' | '}') {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// Creates a unique, valid Yarn node title for each of the given sorted
+/// passage names
+fn node_titles<'a>(names: &[&'a String]) -> HashMap<&'a str, String> {
+    let mut used = HashSet::new();
+    names
+        .iter()
+        .map(|name| {
+            let mut base: String = name
+                .chars()
+                .map(|c| if c.is_alphanumeric() || c == '_' { c } else { '_' })
+                .collect();
+            if !base.starts_with(|c: char| c.is_alphabetic() || c == '_') {
+                base.insert(0, '_');
+            }
+            let mut title = base.clone();
+            let mut n = 2;
+            while !used.insert(title.clone()) {
+                title = format!("{}_{}", base, n);
+                n += 1;
+            }
+            (name.as_str(), title)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn to_yarn() {
+        let input = r#":: StoryTitle
+Test
+
+:: Start
+<<set $x to 1>>Hello, <b>#1</b> [[friend|Chapter 2]]!
+[[Stay]] [[Nowhere]]
+
+:: Chapter 2
+{The end}
+
+:: Chapter-2
+Twin
+
+:: Stay
+Done
+
+:: Code [script]
+var x;
+"#
+        .to_string();
+        let story = StoryPassages::from_string(input).take().0.ok().unwrap();
+        let (yarn, warnings) = story.to_yarn().take();
+        assert_eq!(
+            yarn,
+            "title: Chapter_2\nposition: 10,10\n---\n\\{The end\\}\n===\n\
+             title: Chapter_2_2\nposition: 10,10\n---\nTwin\n===\n\
+             title: Start\nposition: 10,10\n---\n// <<set $x to 1>>\nHello, \\#1 friend!\n\
+             -> friend\n    <<jump Chapter_2>>\n-> Stay\n    <<jump Stay>>\n===\n\
+             title: Stay\nposition: 10,10\n---\nDone\n===\n"
+        );
+
+        let kinds: Vec<WarningKind> = warnings.into_iter().map(|w| w.kind).collect();
+        assert_eq!(kinds, vec![
+            WarningKind::UnsupportedExport("script passage".to_string()),
+            WarningKind::UnsupportedExport("macro <<set $x to 1>>".to_string()),
+            WarningKind::UnsupportedExport("HTML tag <b>".to_string()),
+            WarningKind::UnsupportedExport("HTML tag </b>".to_string()),
+            WarningKind::DeadLink("Nowhere".to_string()),
+        ]);
+    }
+}
@@ -0,0 +1,273 @@
+#[cfg(not(feature = "full-context"))]
+use crate::ErrorList;
+#[cfg(feature = "full-context")]
+use crate::ContextErrorList;
+use crate::Error;
+use crate::ErrorKind;
+use crate::FullContext;
+use crate::Output;
+use crate::ParseOptions;
+use crate::Story;
+use crate::StoryPassages;
+use crate::Warning;
+use crate::WarningKind;
+use crate::windows_1252;
+use std::io::Cursor;
+use std::io::Read;
+use std::path::Path;
+use std::path::PathBuf;
+use zip::ZipArchive;
+
+/// The most bytes that may be decompressed from a single archive, guarding
+/// against archives that expand to far more than their size on disk
+const MAX_DECOMPRESSED_SIZE: u64 = 64 * 1024 * 1024;
+
+/// A twee file stored in a ZIP archive
+struct ZipEntry {
+    /// The path of the file within the archive, using `/` separators
+    name: String,
+
+    /// The uncompressed contents of the file
+    data: Vec<u8>,
+}
+
+/// Reads the `.tw` and `.twee` files in the given ZIP archive, in order of
+/// name, failing if their contents would total more than `limit` bytes
+fn read_twee_entries(bytes: Vec<u8>, limit: u64) -> Result<Vec<ZipEntry>, String> {
+    let mut archive = ZipArchive::new(Cursor::new(bytes)).map_err(|e| e.to_string())?;
+    let mut remaining = limit;
+    let mut entries = Vec::new();
+    for i in 0..archive.len() {
+        let file = archive.by_index(i).map_err(|e| e.to_string())?;
+        let name = file.name().replace('\\', "/");
+        if file.is_dir() || !(name.ends_with(".tw") || name.ends_with(".twee")) {
+            continue;
+        }
+        // The declared size can't be trusted, so read at most one byte past
+        // the remaining budget to detect archives that exceed it
+        let mut data = Vec::new();
+        file.take(remaining + 1).read_to_end(&mut data).map_err(|e| format!("{}: {}", name, e))?;
+        if data.len() as u64 > remaining {
+            return Err(format!("Contents exceed the limit of {} bytes", limit));
+        }
+        remaining -= data.len() as u64;
+        entries.push(ZipEntry { name, data });
+    }
+    entries.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(entries)
+}
+
+#[cfg(not(feature = "full-context"))]
+type ParseOutput = Output<Result<StoryPassages, ErrorList>>;
+#[cfg(feature = "full-context")]
+type ParseOutput = Output<Result<StoryPassages, ContextErrorList>>;
+
+#[cfg(not(feature = "full-context"))]
+type StoryOutput = Output<Result<Story, ErrorList>>;
+#[cfg(feature = "full-context")]
+type StoryOutput = Output<Result<Story, ContextErrorList>>;
+
+impl StoryPassages {
+    /// Parses a `StoryPassages` from the `.tw` and `.twee` files in the ZIP
+    /// archive at the given [`Path`]. Each file is parsed as though it were in
+    /// a directory, in order of name, and the name of the file within the
+    /// archive (e.g., `chapter1/intro.tw`) is used as its path in contexts.
+    ///
+    /// Files in subdirectories of the archive are always included. If
+    /// [`directory_namespaces`] is enabled, their passage names are prefixed
+    /// with the subdirectory, as when parsing a directory.
+    ///
+    /// Stored and deflated files are supported, including ZIP64 archives.
+    /// To guard against decompression bombs, the twee files in the archive
+    /// may total at most 64 MiB once decompressed.
+    ///
+    /// Enabled with the "zip" feature
+    ///
+    /// # Errors
+    /// * [`BadInputPath`] - The archive couldn't be read, isn't a valid ZIP
+    ///   archive, uses an unsupported compression method or encryption, or
+    ///   its twee files are too large once decompressed, or one of its twee
+    ///   files isn't valid UTF-8 and [`lenient`] parsing is disabled
+    ///
+    /// [`Path`]: std::path::Path
+    /// [`directory_namespaces`]: struct.ParseOptions.html#structfield.directory_namespaces
+    /// [`lenient`]: struct.ParseOptions.html#structfield.lenient
+    /// [`BadInputPath`]: enum.ErrorKind.html#variant.BadInputPath
+    pub fn from_zip<P: AsRef<Path>>(input: P) -> ParseOutput {
+        StoryPassages::from_zip_with_options(input, &ParseOptions::default())
+    }
+
+    /// Parses a `StoryPassages` from the ZIP archive at the given [`Path`]
+    /// using the given [`ParseOptions`]. See `from_zip` for additional
+    /// information.
+    ///
+    /// Enabled with the "zip" feature
+    ///
+    /// [`Path`]: std::path::Path
+    /// [`ParseOptions`]: struct.ParseOptions.html
+    pub fn from_zip_with_options<P: AsRef<Path>>(input: P, options: &ParseOptions) -> ParseOutput {
+        let path = input.as_ref();
+        let bad_input = |message: String| {
+            Output::new(Err(Error::new::<FullContext>(ErrorKind::BadInputPath(path.to_path_buf(), message), None).into()))
+        };
+
+        let bytes = match std::fs::read(path) {
+            Ok(bytes) => bytes,
+            Err(e) => return bad_input(e.to_string()),
+        };
+        let entries = match read_twee_entries(bytes, MAX_DECOMPRESSED_SIZE) {
+            Ok(entries) => entries,
+            Err(e) => return bad_input(format!("Invalid ZIP archive: {}", e)),
+        };
+
+        let outputs = entries.into_iter().map(|entry| {
+            let entry_path = PathBuf::from(&entry.name);
+            options.notify_file_started(&entry_path);
+
+            let mut warnings = Vec::new();
+            let contents = match String::from_utf8(entry.data) {
+                Ok(contents) => contents,
+                Err(e) if options.lenient => {
+                    let contents = windows_1252::decode(e.as_bytes());
+                    let context = FullContext::from_file_path(Some(entry_path.clone()), contents.clone());
+                    warnings.push(Warning::new(WarningKind::LegacyEncoding, Some(context)));
+                    options.notify_warnings(&warnings);
+                    contents
+                }
+                Err(e) => {
+                    let error = Error::new::<FullContext>(ErrorKind::BadInputPath(entry_path, e.to_string()), None);
+                    return Output::new(Err(error.into()));
+                }
+            };
+
+            let context = FullContext::from_file_path(Some(entry_path), contents);
+            let (res, mut parse_warnings) = StoryPassages::from_context(context, options).take();
+            warnings.append(&mut parse_warnings);
+            let namespace = match entry.name.rfind('/') {
+                Some(pos) if options.directory_namespaces => &entry.name[..pos],
+                _ => "",
+            };
+            let res = res.map(|mut story| {
+                if !namespace.is_empty() {
+                    story.add_namespace(namespace);
+                }
+                story
+            });
+            Output::new(res).with_warnings(warnings)
+        });
+
+        let out = Output::merge_all(outputs, StoryPassages::default(), |story, sub_story| {
+            let merge_warnings = story.merge_from(sub_story);
+            options.notify_warnings(&merge_warnings);
+            merge_warnings
+        });
+        let (res, mut warnings) = out.take();
+        let res = res.and_then(|story| story.unless_cancelled(options)).and_then(|mut story| {
+            story.namespaced = options.directory_namespaces;
+            story.special_tags = options.all_special_tags();
+            story.preprocess(options, Some(path), &mut warnings);
+            let mut story_warnings = story.check();
+            options.notify_warnings(&story_warnings);
+            warnings.append(&mut story_warnings);
+            story.deny_duplicates(&mut warnings)
+        });
+        Output::new(res).with_warnings(warnings)
+    }
+}
+
+impl Story {
+    /// Parses a `Story` from the ZIP archive at the given [`Path`]. See
+    /// [`StoryPassages::from_zip`] for details.
+    ///
+    /// Enabled with the "zip" feature
+    ///
+    /// [`Path`]: std::path::Path
+    /// [`StoryPassages::from_zip`]: struct.StoryPassages.html#method.from_zip
+    pub fn from_zip<P: AsRef<Path>>(input: P) -> StoryOutput {
+        StoryPassages::from_zip(input).into_result()
+    }
+
+    /// Parses a `Story` from the ZIP archive at the given [`Path`] using the
+    /// given [`ParseOptions`]. See [`StoryPassages::from_zip`] for details.
+    ///
+    /// Enabled with the "zip" feature
+    ///
+    /// [`Path`]: std::path::Path
+    /// [`ParseOptions`]: struct.ParseOptions.html
+    /// [`StoryPassages::from_zip`]: struct.StoryPassages.html#method.from_zip
+    pub fn from_zip_with_options<P: AsRef<Path>>(input: P, options: &ParseOptions) -> StoryOutput {
+        StoryPassages::from_zip_with_options(input, options).into_result()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::tempdir;
+    use zip::write::SimpleFileOptions;
+    use zip::CompressionMethod;
+    use zip::ZipWriter;
+
+    fn archive(method: CompressionMethod, files: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        let options = SimpleFileOptions::default().compression_method(method);
+        for (name, data) in files {
+            writer.start_file(*name, options).unwrap();
+            writer.write_all(data).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn from_zip() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = tempdir()?;
+        let path = dir.path().join("story.zip");
+        let bytes = archive(CompressionMethod::Deflated, &[
+            ("game/start.tw", b":: Start\nGo [[Next]]\n"),
+            ("game/notes.txt", b":: Ignored\nx\n"),
+            ("game/more/next.twee", b":: Next\nDone [[Start]]\n"),
+        ]);
+        std::fs::write(&path, bytes)?;
+
+        let story = StoryPassages::from_zip(&path).take().0.ok().unwrap();
+        let mut names: Vec<&String> = story.passages.keys().collect();
+        names.sort();
+        assert_eq!(names, vec!["Next", "Start"]);
+        assert_eq!(story.passages["Next"].context.get_file_path(), Some(Path::new("game/more/next.twee")));
+
+        let options = ParseOptions::new().with_directory_namespaces(true);
+        let (res, warnings) = StoryPassages::from_zip_with_options(&path, &options).take();
+        let story = res.ok().unwrap();
+        assert!(story.passages.contains_key("game/Start"));
+        assert!(story.passages.contains_key("game/more/Next"));
+        assert!(warnings.iter().any(|w| w.kind == WarningKind::DeadLink("Next".to_string())));
+        Ok(())
+    }
+
+    #[test]
+    fn bad_archive() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = tempdir()?;
+        let path = dir.path().join("story.zip");
+        std::fs::write(&path, b"not a zip")?;
+        let (res, _) = StoryPassages::from_zip(&path).take();
+        assert!(res.is_err());
+
+        std::fs::write(&path, archive(CompressionMethod::Stored, &[("a.tw", b":: A\n\x92")]))?;
+        assert!(StoryPassages::from_zip(&path).take().0.is_err());
+        let options = ParseOptions::new().with_lenient(true);
+        let (res, warnings) = StoryPassages::from_zip_with_options(&path, &options).take();
+        assert!(res.is_ok());
+        assert_eq!(warnings[0].kind, WarningKind::LegacyEncoding);
+        Ok(())
+    }
+
+    #[test]
+    fn size_limit() {
+        let big = vec![b'a'; 1000];
+        let bytes = archive(CompressionMethod::Deflated, &[("a.tw", &big), ("b.tw", &big), ("c.txt", &big)]);
+        assert_eq!(read_twee_entries(bytes.clone(), 2000).unwrap().len(), 2);
+        let err = read_twee_entries(bytes, 1999).err().unwrap();
+        assert!(err.contains("limit of 1999 bytes"));
+    }
+}
@@ -0,0 +1,85 @@
+//! Macros for the optional instrumentation enabled by the `tracing` feature.
+//! Without the feature, they expand to nothing, so their arguments must not be
+//! the only use of a value
+
+/// Emits a debug event, as with `tracing::debug!`
+#[cfg(feature = "tracing")]
+macro_rules! debug {
+    ($($arg:tt)*) => {
+        tracing::debug!($($arg)*)
+    };
+}
+
+/// Emits a debug event, as with `tracing::debug!`
+#[cfg(not(feature = "tracing"))]
+macro_rules! debug {
+    ($($arg:tt)*) => {};
+}
+
+/// Creates a debug span, as with `tracing::debug_span!`, and enters it. The
+/// span is exited when the returned guard is dropped
+#[cfg(feature = "tracing")]
+macro_rules! enter_span {
+    ($($arg:tt)*) => {
+        tracing::debug_span!($($arg)*).entered()
+    };
+}
+
+/// Creates a debug span, as with `tracing::debug_span!`, and enters it. The
+/// span is exited when the returned guard is dropped
+#[cfg(not(feature = "tracing"))]
+macro_rules! enter_span {
+    ($($arg:tt)*) => {
+        ()
+    };
+}
+
+#[cfg(all(test, feature = "tracing"))]
+mod tests {
+    use crate::StoryPassages;
+    use std::sync::Mutex;
+    use tracing::span::{Attributes, Id, Record};
+    use tracing::{Event, Metadata, Subscriber};
+
+    /// Records the names of the spans created and the number of events
+    #[derive(Default)]
+    struct Recorder {
+        spans: Mutex<Vec<&'static str>>,
+        events: Mutex<usize>,
+    }
+
+    impl Subscriber for Recorder {
+        fn enabled(&self, _: &Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, span: &Attributes<'_>) -> Id {
+            let mut spans = self.spans.lock().unwrap();
+            spans.push(span.metadata().name());
+            Id::from_u64(spans.len() as u64)
+        }
+
+        fn record(&self, _: &Id, _: &Record<'_>) {}
+
+        fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+        fn event(&self, _: &Event<'_>) {
+            *self.events.lock().unwrap() += 1;
+        }
+
+        fn enter(&self, _: &Id) {}
+
+        fn exit(&self, _: &Id) {}
+    }
+
+    #[test]
+    fn instrumented() {
+        let recorder = std::sync::Arc::new(Recorder::default());
+        tracing::subscriber::with_default(recorder.clone(), || {
+            let input = ":: Start\nHello\n\n:: Start\nAgain\n".to_string();
+            assert!(StoryPassages::from_string(input).take().0.is_ok());
+        });
+        assert_eq!(*recorder.spans.lock().unwrap(), vec!["passage", "passage"]);
+        assert!(*recorder.events.lock().unwrap() >= 3);
+    }
+}
@@ -0,0 +1,174 @@
+//! Bindings for running the parser from JavaScript, such as in a browser-based
+//! twee editor. Enabled with the "wasm-bindgen" feature
+//!
+//! Build with `wasm-pack build --features wasm-bindgen` or
+//! `cargo build --target wasm32-unknown-unknown --features wasm-bindgen` and
+//! the `wasm-bindgen` CLI. The parser is then available as `parseString`:
+//!
+//! ```js
+//! import { parseString } from "tweep";
+//! const result = parseString(":: Start\nHello [[Nowhere]]");
+//! for (const d of result.diagnostics) {
+//!   console.log(`${d.line}:${d.column} ${d.severity}: ${d.message}`);
+//! }
+//! ```
+use crate::Context;
+use crate::Error;
+use crate::Severity;
+use crate::StoryPassages;
+use crate::Warning;
+use serde::Serialize;
+use wasm_bindgen::prelude::wasm_bindgen;
+use wasm_bindgen::JsValue;
+
+/// The outcome of parsing a story, as returned by [`parse_string`]
+///
+/// [`parse_string`]: fn.parse_string.html
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ParseResult {
+    /// Whether the story parsed without errors
+    pub ok: bool,
+
+    /// The title of the story, if any
+    pub title: Option<String>,
+
+    /// The name of the start passage, if any
+    pub start_passage: Option<String>,
+
+    /// The names of the story's passages, in sorted order. Special passages,
+    /// such as scripts and stylesheets, aren't included
+    pub passages: Vec<String>,
+
+    /// The errors and warnings found in the story, errors first
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+/// An error or warning, flattened for display by an editor
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Diagnostic {
+    /// The severity of the issue: "error", "warning", "note", or "hint"
+    pub severity: String,
+
+    /// The name of the issue variant, such as "DeadLink"
+    pub code: &'static str,
+
+    /// A description of the issue
+    pub message: String,
+
+    /// The file the issue is in, if any
+    pub file: Option<String>,
+
+    /// The 1-indexed line of the issue, or 0 if it has no position
+    pub line: usize,
+
+    /// The 1-indexed column of the issue, counted in UTF-16 code units as
+    /// JavaScript strings are, or 0 if it has no position
+    pub column: usize,
+}
+
+impl Diagnostic {
+    /// Creates a `Diagnostic` for an issue found in `source`
+    fn new(severity: Severity, code: &'static str, message: String, context: Option<&Context>, source: &str) -> Self {
+        let position = context.map(|c| *c.get_start_position());
+        Diagnostic {
+            severity: severity.to_string(),
+            code,
+            message,
+            file: context.and_then(|c| c.get_file_path()).map(|p| p.to_string_lossy().into_owned()),
+            line: position.map_or(0, |p| p.line),
+            column: position.map_or(0, |p| utf16_column(source, p.line, p.column)),
+        }
+    }
+
+    /// Creates a `Diagnostic` for an error found in `source`
+    fn from_error(error: &Error, source: &str) -> Self {
+        let code = error.explanation().code;
+        Diagnostic::new(Severity::Error, code, error.kind.to_string(), error.context.as_ref(), source)
+    }
+
+    /// Creates a `Diagnostic` for a warning found in `source`
+    fn from_warning(warning: &Warning, source: &str) -> Self {
+        let code = warning.explanation().code;
+        Diagnostic::new(warning.severity(), code, warning.kind.to_string(), warning.context.as_ref(), source)
+    }
+}
+
+/// Converts a 1-indexed byte column on the 1-indexed `line` of `source` into a
+/// 1-indexed column in UTF-16 code units
+fn utf16_column(source: &str, line: usize, column: usize) -> usize {
+    let text = source.split('\n').nth(line.saturating_sub(1)).unwrap_or("");
+    let end = column.saturating_sub(1).min(text.len());
+    text.get(..end).map_or(column, |before| before.encode_utf16().count() + 1)
+}
+
+impl ParseResult {
+    /// Parses the given twee source, collecting everything `parse_string`
+    /// returns. If the story parses, the warnings from checking it, such as
+    /// dead links, are included along with those from parsing
+    ///
+    /// # Examples
+    /// ```
+    /// use tweep::wasm::ParseResult;
+    /// let result = ParseResult::parse(":: StoryTitle\nCave\n\n:: Start\n[[Nowhere]]".to_string());
+    /// assert!(result.ok);
+    /// assert_eq!(result.title.as_deref(), Some("Cave"));
+    /// assert_eq!(result.passages, vec!["Start"]);
+    /// let dead = result.diagnostics.iter().find(|d| d.code == "DeadLink").unwrap();
+    /// assert_eq!((dead.line, dead.column), (5, 1));
+    /// assert_eq!(dead.severity, "warning");
+    ///
+    /// // Columns count UTF-16 code units, so "é" is one and "😀" is two
+    /// let result = ParseResult::parse(":: Start\nCafé 😀 [[Nowhere]]".to_string());
+    /// let dead = result.diagnostics.iter().find(|d| d.code == "DeadLink").unwrap();
+    /// assert_eq!((dead.line, dead.column), (2, 9));
+    /// ```
+    pub fn parse(input: String) -> Self {
+        let (res, mut warnings) = StoryPassages::from_string(input.clone()).take();
+        let mut result = ParseResult::default();
+        match res {
+            Ok(story) => {
+                result.ok = true;
+                result.title = story.title_string().map(str::to_string);
+                result.start_passage = story.get_start_passage_name().map(str::to_string);
+                result.passages = story.passages.keys().cloned().collect();
+                result.passages.sort();
+                warnings.append(&mut story.check());
+            }
+            Err(errors) => {
+                #[cfg(feature = "full-context")]
+                let errors = errors.error_list;
+                result.diagnostics.extend(errors.errors.iter().map(|e| Diagnostic::from_error(e, &input)));
+            }
+        }
+        result.diagnostics.extend(warnings.iter().map(|w| Diagnostic::from_warning(w, &input)));
+        result
+    }
+}
+
+/// Parses the given twee source, returning a [`ParseResult`] as a plain
+/// JavaScript object. Exported to JavaScript as `parseString`
+///
+/// [`ParseResult`]: struct.ParseResult.html
+#[wasm_bindgen(js_name = parseString)]
+pub fn parse_string(input: String) -> Result<JsValue, JsValue> {
+    let result = ParseResult::parse(input);
+    serde_wasm_bindgen::to_value(&result).map_err(JsValue::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diagnostics() {
+        let result = ParseResult::parse(":: Start\nHi\n\n::\nNo name".to_string());
+        assert!(!result.ok);
+        assert!(result.passages.is_empty());
+        assert_eq!(result.diagnostics[0].severity, "error");
+        assert_eq!(result.diagnostics[0].code, "EmptyName");
+        assert_eq!(result.diagnostics[0].line, 4);
+        assert_eq!(result.diagnostics[0].file, None);
+    }
+}
@@ -0,0 +1,47 @@
+//! Helpers for migrating text written in the Windows-1252 encoding, which was
+//! common in old Twine 1 projects
+
+/// The characters encoded by bytes `0x80` to `0x9F` in Windows-1252. Bytes
+/// that are undefined in Windows-1252 map to the matching C1 control character
+const HIGH_CHARS: [char; 32] = [
+    '€', '\u{81}', '‚', 'ƒ', '„', '…', '†', '‡', 'ˆ', '‰', 'Š', '‹', 'Œ', '\u{8D}', 'Ž', '\u{8F}',
+    '\u{90}', '‘', '’', '“', '”', '•', '–', '—', '˜', '™', 'š', '›', 'œ', '\u{9D}', 'ž', 'Ÿ',
+];
+
+/// Decodes bytes in the Windows-1252 encoding
+pub(crate) fn decode(bytes: &[u8]) -> String {
+    bytes.iter().map(|&b| fix_char(b as char)).collect()
+}
+
+/// Returns `true` if the string contains any C1 control characters that are
+/// likely Windows-1252 punctuation that was decoded as Latin-1
+pub(crate) fn has_misdecoded_chars(input: &str) -> bool {
+    input.chars().any(|c| fix_char(c) != c)
+}
+
+/// Replaces any C1 control characters in the string with the Windows-1252
+/// punctuation they most likely represent
+pub(crate) fn fix_misdecoded_chars(input: &str) -> String {
+    input.chars().map(fix_char).collect()
+}
+
+fn fix_char(c: char) -> char {
+    match c as u32 {
+        n @ 0x80..=0x9F => HIGH_CHARS[(n - 0x80) as usize],
+        _ => c,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn windows_1252() {
+        assert_eq!(decode(b"\x93Caf\xe9\x94 \x96 \x85"), "“Café” – …");
+        assert_eq!(decode(b"\x81"), "\u{81}");
+        assert!(has_misdecoded_chars("It\u{92}s"));
+        assert!(!has_misdecoded_chars("It’s"));
+        assert_eq!(fix_misdecoded_chars("It\u{92}s"), "It’s");
+    }
+}